@@ -0,0 +1,46 @@
+use crate::strategy::BidMode;
+use runtime::{Balance, BlockNumber};
+use structopt::StructOpt;
+
+/// A reference keeper, which liquidates unsafe CDPs and bids in collateral auctions.
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(name = "cdp-keeper")]
+pub struct Cli {
+	/// HTTP RPC endpoint of the node.
+	#[structopt(long, default_value = "http://127.0.0.1:9933")]
+	pub url: String,
+
+	/// Secret URI of the keeper account, e.g. a mnemonic phrase or `//Alice`.
+	#[structopt(long, value_name = "SURI")]
+	pub suri: String,
+
+	/// Seconds to wait between two scans.
+	#[structopt(long, default_value = "6")]
+	pub interval: u64,
+
+	/// Number of positions fetched per RPC request.
+	#[structopt(long, default_value = "100")]
+	pub page_size: u32,
+
+	/// Only liquidate the CDPs whose debit value is at least this amount,
+	/// smaller liquidations may not cover the transaction fee.
+	#[structopt(long, default_value = "0")]
+	pub min_debit_value: Balance,
+
+	/// When to bid in collateral auctions: `none`, `eager` to bid whenever the price is acceptable,
+	/// or `ending` to bid only when the auction has no bid or is about to end.
+	#[structopt(long, default_value = "ending")]
+	pub bid_mode: BidMode,
+
+	/// Minimum discount to the collateral value, in percent, at which the keeper bids.
+	#[structopt(long, default_value = "5")]
+	pub bid_discount: u32,
+
+	/// Blocks before the end of auction when the `ending` bid mode starts to bid.
+	#[structopt(long, default_value = "10")]
+	pub ending_blocks: BlockNumber,
+
+	/// Log the actions without submitting transactions.
+	#[structopt(long)]
+	pub dry_run: bool,
+}
@@ -0,0 +1,196 @@
+use codec::{Decode, Encode};
+use frame_support::storage::{StorageMap, StoragePrefixedMap};
+use jsonrpc_core_client::{transports::http, RpcChannel, RpcError};
+use module_auction_manager::{CollateralAuctionItem, CollateralAuctions};
+use module_honzon_rpc::HonzonClient;
+use module_honzon_rpc_runtime_api::{CollateralStatistics, PositionInfo, PositionsPage};
+use module_prices_rpc::PricesClient;
+use module_support::Price;
+use orml_traits::auction::AuctionInfo;
+use runtime::{
+	AccountId, AuctionId, Balance, BlockNumber, CurrencyId, Hash, Header, Index, Runtime, SignedBlock,
+	UncheckedExtrinsic,
+};
+use sc_rpc_api::{author::AuthorClient, chain::ChainClient, state::StateClient};
+use sp_core::{
+	storage::{StorageData, StorageKey},
+	Bytes,
+};
+use sp_rpc::{list::ListOrValue, number::NumberOrHex};
+use sp_runtime::traits::Header as HeaderT;
+use substrate_frame_rpc_system::SystemClient;
+use tokio::prelude::Future;
+
+/// Error of the keeper.
+#[derive(Debug, derive_more::From, derive_more::Display)]
+pub enum Error {
+	/// RPC request failed.
+	Rpc(RpcError),
+	/// Could not decode the storage.
+	Codec(codec::Error),
+	/// Could not start the runtime of RPC transport.
+	Io(std::io::Error),
+	/// Other errors.
+	Other(String),
+}
+
+/// A collateral auction in progress.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CollateralAuction {
+	pub id: AuctionId,
+	pub currency_id: CurrencyId,
+	/// The collateral amount for sale
+	pub amount: Balance,
+	/// The target amount of stable currency
+	pub target: Balance,
+	pub start_time: BlockNumber,
+	/// The end block, `None` until the first bid
+	pub end: Option<BlockNumber>,
+	/// The last bid as (bidder, price)
+	pub last_bid: Option<(AccountId, Balance)>,
+}
+
+/// The versions and genesis hash needed to sign transactions.
+#[derive(Debug, Clone)]
+pub struct SigningContext {
+	pub spec_version: u32,
+	pub transaction_version: u32,
+	pub genesis_hash: Hash,
+}
+
+type Honzon = HonzonClient<
+	Hash,
+	AccountId,
+	CurrencyId,
+	PositionInfo<CurrencyId, Balance>,
+	CollateralStatistics<CurrencyId, Balance>,
+>;
+
+/// Blocking client of the node RPCs used by the keeper.
+pub struct NodeClient {
+	runtime: tokio::runtime::Runtime,
+	author: AuthorClient<Hash, Hash>,
+	chain: ChainClient<BlockNumber, Hash, Header, SignedBlock>,
+	state: StateClient<Hash>,
+	system: SystemClient<AccountId, Index>,
+	honzon: Honzon,
+	prices: PricesClient<Hash, CurrencyId, Price>,
+}
+
+impl NodeClient {
+	/// Connect to the HTTP RPC endpoint at `url`.
+	pub fn connect(url: &str) -> Result<Self, Error> {
+		let mut runtime = tokio::runtime::Runtime::new()?;
+		let channel = runtime.block_on(http::connect::<RpcChannel>(url))?;
+		Ok(NodeClient {
+			runtime,
+			author: channel.clone().into(),
+			chain: channel.clone().into(),
+			state: channel.clone().into(),
+			system: channel.clone().into(),
+			honzon: channel.clone().into(),
+			prices: channel.into(),
+		})
+	}
+
+	fn block_on<T, F>(&mut self, future: F) -> Result<T, Error>
+	where
+		F: Future<Item = T, Error = RpcError> + Send + 'static,
+		T: Send + 'static,
+	{
+		Ok(self.runtime.block_on(future)?)
+	}
+
+	/// The hash and number of the best block.
+	pub fn best_block(&mut self) -> Result<(Hash, BlockNumber), Error> {
+		let header = self
+			.block_on(self.chain.header(None))?
+			.ok_or_else(|| Error::Other("best block not found".into()))?;
+		Ok((header.hash(), *header.number()))
+	}
+
+	/// The versions of runtime at `at` and the genesis hash.
+	pub fn signing_context(&mut self, at: Hash) -> Result<SigningContext, Error> {
+		let version = self.block_on(self.state.runtime_version(Some(at)))?;
+		let genesis_hash =
+			match self.block_on(self.chain.block_hash(Some(ListOrValue::Value(NumberOrHex::Number(0)))))? {
+				ListOrValue::Value(Some(hash)) => hash,
+				_ => return Err(Error::Other("genesis hash not found".into())),
+			};
+		Ok(SigningContext {
+			spec_version: version.spec_version,
+			transaction_version: version.transaction_version,
+			genesis_hash,
+		})
+	}
+
+	/// The next nonce of `who`, including the transactions in pool.
+	pub fn nonce(&mut self, who: &AccountId) -> Result<Index, Error> {
+		self.block_on(self.system.nonce(who.clone()))
+	}
+
+	/// The collateral types with their statistics.
+	pub fn collateral_statistics(&mut self, at: Hash) -> Result<Vec<CollateralStatistics<CurrencyId, Balance>>, Error> {
+		self.block_on(self.honzon.get_collateral_statistics(Some(at)))
+	}
+
+	/// A page of the positions under `currency_id`, starting from `start`.
+	pub fn positions_page(
+		&mut self,
+		currency_id: CurrencyId,
+		start: Option<AccountId>,
+		limit: u32,
+		at: Hash,
+	) -> Result<PositionsPage<AccountId, PositionInfo<CurrencyId, Balance>>, Error> {
+		self.block_on(self.honzon.get_positions_page(currency_id, start, limit, Some(at)))
+	}
+
+	/// The price of `currency_id` in `quote_currency_id`.
+	pub fn relative_price(
+		&mut self,
+		currency_id: CurrencyId,
+		quote_currency_id: CurrencyId,
+		at: Hash,
+	) -> Result<Option<Price>, Error> {
+		self.block_on(self.prices.get_relative_price(currency_id, quote_currency_id, Some(at)))
+	}
+
+	/// All collateral auctions in progress.
+	pub fn collateral_auctions(&mut self, at: Hash) -> Result<Vec<CollateralAuction>, Error> {
+		let prefix = CollateralAuctions::<Runtime>::final_prefix().to_vec();
+		let keys = self.block_on(self.state.storage_keys(StorageKey(prefix.clone()), Some(at)))?;
+
+		let mut auctions = Vec::with_capacity(keys.len());
+		for key in keys {
+			// the key is the prefix, followed by the twox 64 hash and the encoded auction id
+			let id = AuctionId::decode(&mut key.0.get(prefix.len() + 8..).unwrap_or_default())?;
+			let item: Option<CollateralAuctionItem<AccountId, BlockNumber>> = self.storage(key, at)?;
+			let info: Option<AuctionInfo<AccountId, Balance, BlockNumber>> =
+				self.storage(StorageKey(orml_auction::Auctions::<Runtime>::hashed_key_for(id)), at)?;
+			if let (Some(item), Some(info)) = (item, info) {
+				auctions.push(CollateralAuction {
+					id,
+					currency_id: item.currency_id,
+					amount: item.amount,
+					target: item.target,
+					start_time: item.start_time,
+					end: info.end,
+					last_bid: info.bid,
+				});
+			}
+		}
+		Ok(auctions)
+	}
+
+	/// Submit the signed extrinsic, return its hash.
+	pub fn submit(&mut self, extrinsic: UncheckedExtrinsic) -> Result<Hash, Error> {
+		self.block_on(self.author.submit_extrinsic(Bytes(extrinsic.encode())))
+	}
+
+	fn storage<T: Decode>(&mut self, key: StorageKey, at: Hash) -> Result<Option<T>, Error> {
+		match self.block_on(self.state.storage(key, Some(at)))? {
+			Some(StorageData(data)) => Ok(Some(T::decode(&mut &data[..])?)),
+			None => Ok(None),
+		}
+	}
+}
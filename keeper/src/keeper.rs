@@ -0,0 +1,166 @@
+use crate::{
+	client::{Error, NodeClient, SigningContext},
+	strategy::{BidStrategy, LiquidationStrategy},
+};
+use codec::Encode;
+use frame_support::traits::Get;
+use log::{debug, info, warn};
+use runtime::{
+	AccountId, BlockNumber, Call, GetStableCurrencyId, Hash, Index, Runtime, SignedExtra, SignedPayload,
+	UncheckedExtrinsic,
+};
+use sp_core::{sr25519, Pair};
+use sp_runtime::{generic::Era, traits::IdentifyAccount, MultiSigner};
+use std::{thread, time::Duration};
+
+/// The keeper liquidating unsafe CDPs and bidding in collateral auctions.
+pub struct Keeper {
+	client: NodeClient,
+	signer: sr25519::Pair,
+	account: AccountId,
+	liquidation: LiquidationStrategy,
+	bidding: BidStrategy,
+	page_size: u32,
+	dry_run: bool,
+}
+
+impl Keeper {
+	pub fn new(
+		client: NodeClient,
+		signer: sr25519::Pair,
+		liquidation: LiquidationStrategy,
+		bidding: BidStrategy,
+		page_size: u32,
+		dry_run: bool,
+	) -> Self {
+		let account = MultiSigner::from(signer.public()).into_account();
+		Keeper {
+			client,
+			signer,
+			account,
+			liquidation,
+			bidding,
+			page_size,
+			dry_run,
+		}
+	}
+
+	/// Scan the chain every `interval`, errors are logged and retried at next scan.
+	pub fn run(&mut self, interval: Duration) {
+		info!("Keeper account: {}", self.account);
+		loop {
+			if let Err(e) = self.scan() {
+				warn!("Scan failed: {}", e);
+			}
+			thread::sleep(interval);
+		}
+	}
+
+	/// Scan the unsafe CDPs and collateral auctions at best block, and submit the transactions.
+	fn scan(&mut self) -> Result<(), Error> {
+		let (at, now) = self.client.best_block()?;
+		debug!("Scanning at #{} ({})", now, at);
+
+		let mut calls = self.liquidation_calls(at)?;
+		calls.extend(self.bid_calls(at, now)?);
+		if calls.is_empty() || self.dry_run {
+			return Ok(());
+		}
+
+		let context = self.client.signing_context(at)?;
+		let mut nonce = self.client.nonce(&self.account)?;
+		for call in calls {
+			// keep submitting the rest, the failed one is retried at next scan if still valid
+			let extrinsic = self.sign(call, nonce, &context);
+			match self.client.submit(extrinsic) {
+				Ok(hash) => {
+					debug!("Submitted transaction {}", hash);
+					nonce += 1;
+				}
+				Err(e) => warn!("Failed to submit transaction: {}", e),
+			}
+		}
+		Ok(())
+	}
+
+	fn liquidation_calls(&mut self, at: Hash) -> Result<Vec<Call>, Error> {
+		let mut calls = vec![];
+		for statistics in self.client.collateral_statistics(at)? {
+			let currency_id = statistics.currency_id;
+			let mut start = None;
+			loop {
+				let page = self.client.positions_page(currency_id, start, self.page_size, at)?;
+				for (who, position) in page.positions {
+					if self.liquidation.should_liquidate(&position) {
+						info!(
+							"Liquidate CDP of {} under {:?}, debit value: {}",
+							who, currency_id, position.debit_value
+						);
+						calls.push(Call::CdpEngine(module_cdp_engine::Call::liquidate_by_keeper(
+							currency_id,
+							who,
+						)));
+					}
+				}
+				start = match page.next {
+					Some(next) => Some(next),
+					None => break,
+				};
+			}
+		}
+		Ok(calls)
+	}
+
+	fn bid_calls(&mut self, at: Hash, now: BlockNumber) -> Result<Vec<Call>, Error> {
+		let mut calls = vec![];
+		for auction in self.client.collateral_auctions(at)? {
+			let price = match self
+				.client
+				.relative_price(auction.currency_id, GetStableCurrencyId::get(), at)?
+			{
+				Some(price) => price,
+				None => continue,
+			};
+			if let Some(bid_price) = self.bidding.bid_price(&self.account, &auction, price, now) {
+				info!(
+					"Bid {} for collateral auction #{} of {} {:?}",
+					bid_price, auction.id, auction.amount, auction.currency_id
+				);
+				calls.push(Call::Auction(orml_auction::Call::bid(auction.id, bid_price)));
+			}
+		}
+		Ok(calls)
+	}
+
+	/// Sign the call as an immortal transaction.
+	fn sign(&self, call: Call, nonce: Index, context: &SigningContext) -> UncheckedExtrinsic {
+		let extra: SignedExtra = (
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(Era::Immortal),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			module_transaction_pause::CheckPausedTransaction::<Runtime>::new(),
+			frame_system::CheckWeight::<Runtime>::new(),
+			module_accounts::ChargeTransactionPayment::<Runtime>::from(0),
+		);
+		// the additional signed data is provided here, as it's read from storage by the runtime
+		let raw_payload = SignedPayload::from_raw(
+			call,
+			extra,
+			(
+				context.spec_version,
+				context.transaction_version,
+				context.genesis_hash,
+				context.genesis_hash,
+				(),
+				(),
+				(),
+				(),
+			),
+		);
+		let signature = raw_payload.using_encoded(|payload| self.signer.sign(payload));
+		let (call, extra, _) = raw_payload.deconstruct();
+		UncheckedExtrinsic::new_signed(call, self.account.clone().into(), signature.into(), extra)
+	}
+}
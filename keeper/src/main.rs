@@ -0,0 +1,41 @@
+//! A reference keeper bot of Honzon protocol.
+//!
+//! It connects to a node through RPC, tracks the unsafe CDPs and the collateral auctions at
+//! the best block, and submits signed transactions to liquidate the CDPs through
+//! `liquidate_by_keeper` and to bid in the auctions, according to the configured strategies.
+
+mod cli;
+mod client;
+mod keeper;
+mod strategy;
+
+use cli::Cli;
+use client::{Error, NodeClient};
+use keeper::Keeper;
+use module_support::Ratio;
+use sp_core::{sr25519, Pair};
+use sp_runtime::FixedPointNumber;
+use std::time::Duration;
+use strategy::{BidStrategy, LiquidationStrategy};
+use structopt::StructOpt;
+
+fn main() -> Result<(), Error> {
+	env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+	let cli = Cli::from_args();
+
+	let signer = sr25519::Pair::from_string(&cli.suri, None)
+		.map_err(|e| Error::Other(format!("Invalid secret URI: {:?}", e)))?;
+	let liquidation = LiquidationStrategy {
+		min_debit_value: cli.min_debit_value,
+	};
+	let bidding = BidStrategy {
+		mode: cli.bid_mode,
+		discount: Ratio::saturating_from_rational(cli.bid_discount, 100),
+		ending_blocks: cli.ending_blocks,
+	};
+	let client = NodeClient::connect(&cli.url)?;
+
+	Keeper::new(client, signer, liquidation, bidding, cli.page_size, cli.dry_run)
+		.run(Duration::from_secs(cli.interval));
+	Ok(())
+}
@@ -0,0 +1,185 @@
+use crate::client::CollateralAuction;
+use module_honzon_rpc::RiskLevel;
+use module_honzon_rpc_runtime_api::PositionInfo;
+use module_support::{Price, Ratio};
+use runtime::{AccountId, AuctionManager, Balance, BlockNumber, CurrencyId};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	FixedPointNumber,
+};
+use std::str::FromStr;
+
+/// Decide which unsafe CDPs to liquidate.
+#[derive(Debug, Clone)]
+pub struct LiquidationStrategy {
+	/// The minimum debit value worth liquidating
+	pub min_debit_value: Balance,
+}
+
+impl LiquidationStrategy {
+	/// Whether to liquidate the position.
+	pub fn should_liquidate(&self, position: &PositionInfo<CurrencyId, Balance>) -> bool {
+		RiskLevel::of(position) == RiskLevel::Liquidation && position.debit_value >= self.min_debit_value
+	}
+}
+
+/// When to bid in collateral auctions.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BidMode {
+	/// Never bid
+	None,
+	/// Bid whenever the price is acceptable
+	Eager,
+	/// Bid when the auction has no bid, or ends within the ending blocks
+	Ending,
+}
+
+impl FromStr for BidMode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"none" => Ok(BidMode::None),
+			"eager" => Ok(BidMode::Eager),
+			"ending" => Ok(BidMode::Ending),
+			_ => Err(format!("unknown bid mode: {}", s)),
+		}
+	}
+}
+
+/// Decide the bids in collateral auctions.
+#[derive(Debug, Clone)]
+pub struct BidStrategy {
+	/// When to bid
+	pub mode: BidMode,
+	/// The minimum discount to the collateral value
+	pub discount: Ratio,
+	/// The blocks before the end of auction to bid with `BidMode::Ending`
+	pub ending_blocks: BlockNumber,
+}
+
+impl BidStrategy {
+	/// The bid price for the auction at `price` of collateral in stable currency,
+	/// `None` means not to bid.
+	///
+	/// The keeper raises the last bid by the minimum increment. Above the target the auction
+	/// reduces the collateral amount instead, so a bid is acceptable as long as it is at most
+	/// the discounted collateral value in both phases.
+	pub fn bid_price(
+		&self,
+		keeper: &AccountId,
+		auction: &CollateralAuction,
+		price: Price,
+		now: BlockNumber,
+	) -> Option<Balance> {
+		match self.mode {
+			BidMode::None => return None,
+			BidMode::Eager => {}
+			BidMode::Ending => {
+				if auction
+					.end
+					.map_or(false, |end| end.saturating_sub(now) > self.ending_blocks)
+				{
+					return None;
+				}
+			}
+		}
+
+		let last_price = match &auction.last_bid {
+			Some((bidder, _)) if bidder == keeper => return None,
+			Some((_, last_price)) => *last_price,
+			None => Zero::zero(),
+		};
+		let increment = AuctionManager::get_minimum_increment_size(now, auction.start_time)
+			.saturating_mul_int(auction.target.max(last_price));
+		let bid_price = last_price.saturating_add(increment).max(1);
+		let max_bid_price = Ratio::one()
+			.saturating_sub(self.discount)
+			.saturating_mul_int(price.saturating_mul_int(auction.amount));
+
+		if bid_price <= max_bid_price {
+			Some(bid_price)
+		} else {
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn keeper() -> AccountId {
+		AccountId::from([1u8; 32])
+	}
+
+	fn bidder() -> AccountId {
+		AccountId::from([2u8; 32])
+	}
+
+	fn auction(end: Option<BlockNumber>, last_bid: Option<(AccountId, Balance)>) -> CollateralAuction {
+		CollateralAuction {
+			id: 0,
+			currency_id: CurrencyId::DOT,
+			amount: 100,
+			target: 1000,
+			start_time: 1,
+			end,
+			last_bid,
+		}
+	}
+
+	fn strategy(mode: BidMode) -> BidStrategy {
+		BidStrategy {
+			mode,
+			discount: Ratio::saturating_from_rational(5, 100),
+			ending_blocks: 10,
+		}
+	}
+
+	#[test]
+	fn bid_price_work() {
+		let price = Price::saturating_from_integer(20);
+		assert_eq!(
+			strategy(BidMode::None).bid_price(&keeper(), &auction(None, None), price, 2),
+			None
+		);
+
+		// raise the last bid by the minimum increment of target
+		let increment = AuctionManager::get_minimum_increment_size(2, 1).saturating_mul_int(1000);
+		assert_eq!(
+			strategy(BidMode::Eager).bid_price(&keeper(), &auction(Some(100), Some((bidder(), 500))), price, 2),
+			Some(500 + increment)
+		);
+		assert_eq!(
+			strategy(BidMode::Eager).bid_price(&keeper(), &auction(Some(100), Some((keeper(), 500))), price, 2),
+			None
+		);
+
+		// bid when the auction has no bid or is ending
+		assert_eq!(
+			strategy(BidMode::Ending).bid_price(&keeper(), &auction(None, None), price, 2),
+			Some(increment)
+		);
+		assert_eq!(
+			strategy(BidMode::Ending).bid_price(&keeper(), &auction(Some(100), Some((bidder(), 500))), price, 2),
+			None
+		);
+		assert_eq!(
+			strategy(BidMode::Ending).bid_price(&keeper(), &auction(Some(100), Some((bidder(), 500))), price, 90),
+			Some(500 + increment)
+		);
+
+		// the bid exceeds the discounted collateral value
+		assert_eq!(
+			strategy(BidMode::Eager).bid_price(&keeper(), &auction(Some(100), Some((bidder(), 1900))), price, 2),
+			None
+		);
+	}
+
+	#[test]
+	fn bid_mode_from_str_work() {
+		assert_eq!("ending".parse::<BidMode>(), Ok(BidMode::Ending));
+		assert!("always".parse::<BidMode>().is_err());
+	}
+}
@@ -2,47 +2,138 @@
 
 use codec::{Decode, Encode};
 use frame_support::{
-	decl_error, decl_module, decl_storage,
+	decl_error, decl_event, decl_module, decl_storage,
 	dispatch::Dispatchable,
 	ensure,
 	traits::{
-		Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency, OnKilledAccount, OnUnbalanced, Time,
-		WithdrawReason, WithdrawReasons,
+		Currency, EnsureOrigin, ExistenceRequirement, Get, LockIdentifier, LockableCurrency, OnKilledAccount,
+		OnUnbalanced, ReservableCurrency, Time, WithdrawReason, WithdrawReasons,
 	},
-	weights::{DispatchInfo, PostDispatchInfo},
-	IsSubType,
+	weights::{constants::WEIGHT_PER_MICROS, DispatchInfo, PostDispatchInfo, Weight},
+	IsSubType, IterableStorageDoubleMap,
 };
-use frame_system::{self as system, ensure_signed};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use orml_traits::MultiCurrency;
 use sp_runtime::{
 	traits::{DispatchInfoOf, SaturatedConversion, Saturating, SignedExtension, Zero},
 	transaction_validity::{
 		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
 	},
-	FixedPointOperand,
+	FixedPointNumber, FixedPointOperand, RuntimeDebug,
 };
 use sp_std::prelude::*;
+use support::{DEXManager, IsBasicOperation, IsRiskReducing, IsSponsoredCall, Ratio};
 
 mod mock;
 mod tests;
 
 const ACCOUNTS_ID: LockIdentifier = *b"ACA/acct";
+const FREE_QUOTA_ID: LockIdentifier = *b"ACA/quot";
 
 type MomentOf<T> = <<T as Trait>::Time as Time>::Moment;
 type PalletBalanceOf<T> =
 	<<T as pallet_transaction_payment::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as pallet_transaction_payment::Trait>::Currency as Currency<
+	<T as system::Trait>::AccountId,
+>>::NegativeImbalance;
 type DepositBalanceOf<T> = <<T as Trait>::DepositCurrency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type CurrencyIdOf<T> =
+	<<T as orml_currencies::Trait>::MultiCurrency as MultiCurrency<<T as system::Trait>::AccountId>>::CurrencyId;
+type BalanceOf<T> =
+	<<T as orml_currencies::Trait>::MultiCurrency as MultiCurrency<<T as system::Trait>::AccountId>>::Balance;
+
+/// The fee budget and rate limit of a sponsor
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct SponsorInfo<Balance> {
+	/// The remaining fee the sponsor is willing to pay, reserved from the sponsor
+	pub budget: Balance,
+	/// The max count of calls sponsored for every account per `SponsorPeriod`
+	pub max_count_per_period: u32,
+	/// The count of accounts offered sponsorship by the sponsor
+	pub sponsored_count: u32,
+}
 
 pub trait Trait: system::Trait + pallet_transaction_payment::Trait + orml_currencies::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
 	type FreeTransferCount: Get<u8>;
 	type FreeTransferPeriod: Get<MomentOf<Self>>;
 	type FreeTransferDeposit: Get<DepositBalanceOf<Self>>;
 	type Time: Time;
 	type DepositCurrency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+
+	/// The currencies to swap to native currency for fee in order, when the native currency is not enough
+	type AllNonNativeCurrencyIds: Get<Vec<CurrencyIdOf<Self>>>;
+
+	/// The max slippage allowed when swap for fee with DEX
+	type MaxSlippageSwapWithDEX: Get<Ratio>;
+
+	/// DEX to swap other currencies to native currency for fee
+	type DEX: DEXManager<Self::AccountId, CurrencyIdOf<Self>, BalanceOf<Self>>;
+
+	/// The calls reducing risk, whose fee is discounted in high volatility period
+	type RiskReducingCalls: IsRiskReducing<<Self as system::Trait>::Call>;
+
+	/// The origin which may declare high volatility period and update the fee discount. Root can always do this.
+	type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The count of fee-free basic operations of every account per `FreeQuotaPeriod`
+	type FreeQuotaCount: Get<u32>;
+
+	/// The period of the free transaction quota
+	type FreeQuotaPeriod: Get<Self::BlockNumber>;
+
+	/// The deposit locked to enable the free transaction quota
+	type FreeQuotaDeposit: Get<DepositBalanceOf<Self>>;
+
+	/// The basic operations which can use the free transaction quota
+	type BasicOperations: IsBasicOperation<<Self as system::Trait>::Call>;
+
+	/// The calls whose fee can be paid by the sponsor of the caller
+	type SponsoredCalls: IsSponsoredCall<<Self as system::Trait>::Call>;
+
+	/// The period of the rate limit of sponsors
+	type SponsorPeriod: Get<Self::BlockNumber>;
+
+	/// The currency to reserve the budget of sponsors, must be the currency paying fee
+	type SponsorCurrency: ReservableCurrency<Self::AccountId, Balance = PalletBalanceOf<Self>>;
+
+	/// The max count of accounts a sponsor can sponsor
+	type MaxSponsoredAccounts: Get<u32>;
 }
 
+decl_event!(
+	pub enum Event<T>
+	where
+		<T as system::Trait>::AccountId,
+		Balance = PalletBalanceOf<T>,
+	{
+		/// The sponsor registered or updated (sponsor, budget, max_count_per_period)
+		SponsorUpdated(AccountId, Balance, u32),
+		/// The sponsor removed (sponsor)
+		SponsorRemoved(AccountId),
+		/// The sponsor offered to sponsor the account (sponsor, who)
+		AccountSponsored(AccountId, AccountId),
+		/// The account accepted the sponsor (who, sponsor)
+		SponsorAccepted(AccountId, AccountId),
+		/// The sponsor stopped sponsoring the account (sponsor, who)
+		AccountUnsponsored(AccountId, AccountId),
+	}
+);
+
 decl_error! {
 	pub enum Error for Module<T: Trait> {
 		NotEnoughBalance,
+		/// The fee rate must not exceed one
+		InvalidFeeRate,
+		/// The origin is not a registered sponsor
+		NotSponsor,
+		/// The account is already offered sponsorship by the sponsor
+		AlreadySponsored,
+		/// The account is not offered sponsorship by the sponsor
+		NotSponsored,
+		/// The sponsor has sponsored `MaxSponsoredAccounts` accounts already
+		TooManySponsoredAccounts,
 	}
 }
 
@@ -50,6 +141,32 @@ decl_storage! {
 	trait Store for Module<T: Trait> as Accounts {
 		LastFreeTransfers get(fn last_free_transfers): map hasher(twox_64_concat) T::AccountId => Vec<MomentOf<T>>;
 		FreeTransferEnabledAccounts get(fn free_transfer_enabled_accounts): map hasher(twox_64_concat) T::AccountId => Option<bool>;
+
+		/// Whether it is in high volatility period declared by governance
+		IsHighVolatility get(fn is_high_volatility): bool;
+
+		/// The rate of fee charged for risk reducing calls in high volatility period, the base fee is
+		/// always charged in full, zero waives the rest of fee
+		RiskReducingFeeRate get(fn risk_reducing_fee_rate): Ratio;
+
+		/// The accounts enabled the free transaction quota by locking `FreeQuotaDeposit`
+		FreeQuotaEnabledAccounts get(fn free_quota_enabled_accounts): map hasher(twox_64_concat) T::AccountId => Option<bool>;
+
+		/// The usage of free transaction quota of the enabled accounts, removed when disabled, map from
+		/// Owner -> (PeriodIndex, UsedCount)
+		FreeQuotaUsage get(fn free_quota_usage): map hasher(twox_64_concat) T::AccountId => (T::BlockNumber, u32);
+
+		/// The fee budget and rate limit of sponsors, map from
+		/// Sponsor -> SponsorInfo
+		Sponsors get(fn sponsors): map hasher(twox_64_concat) T::AccountId => Option<SponsorInfo<PalletBalanceOf<T>>>;
+
+		/// The sponsor accepted by accounts, map from
+		/// Owner -> Sponsor
+		Sponsorships get(fn sponsorships): map hasher(twox_64_concat) T::AccountId => Option<T::AccountId>;
+
+		/// The accounts offered sponsorship by sponsors and the count of their sponsored calls, map from
+		/// Sponsor -> Owner -> (PeriodIndex, SponsoredCount)
+		SponsoredUsage get(fn sponsored_usage): double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::AccountId => Option<(T::BlockNumber, u32)>;
 	}
 }
 
@@ -57,9 +174,16 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		type Error = Error<T>;
 
+		fn deposit_event() = default;
+
 		const FreeTransferCount: u8 = T::FreeTransferCount::get();
 		const FreeTransferPeriod: MomentOf<T> = T::FreeTransferPeriod::get();
 		const FreeTransferDeposit: DepositBalanceOf<T> = T::FreeTransferDeposit::get();
+		const FreeQuotaCount: u32 = T::FreeQuotaCount::get();
+		const FreeQuotaPeriod: T::BlockNumber = T::FreeQuotaPeriod::get();
+		const FreeQuotaDeposit: DepositBalanceOf<T> = T::FreeQuotaDeposit::get();
+		const SponsorPeriod: T::BlockNumber = T::SponsorPeriod::get();
+		const MaxSponsoredAccounts: u32 = T::MaxSponsoredAccounts::get();
 
 		#[weight = 10_000]
 		fn enable_free_transfer(origin) {
@@ -78,6 +202,138 @@ decl_module! {
 			T::DepositCurrency::remove_lock(ACCOUNTS_ID, &who);
 			<FreeTransferEnabledAccounts<T>>::remove(who);
 		}
+
+		/// Lock `FreeQuotaDeposit` to enable the free transaction quota of basic operations
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		#[weight = 10_000]
+		fn enable_free_quota(origin) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(T::DepositCurrency::free_balance(&who) > T::FreeQuotaDeposit::get(), Error::<T>::NotEnoughBalance);
+
+			T::DepositCurrency::set_lock(FREE_QUOTA_ID, &who, T::FreeQuotaDeposit::get(), WithdrawReasons::all());
+			<FreeQuotaEnabledAccounts<T>>::insert(who, true);
+		}
+
+		/// Disable the free transaction quota and unlock the deposit
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		#[weight = 10_000]
+		fn disable_free_quota(origin) {
+			let who = ensure_signed(origin)?;
+
+			T::DepositCurrency::remove_lock(FREE_QUOTA_ID, &who);
+			<FreeQuotaEnabledAccounts<T>>::remove(&who);
+			<FreeQuotaUsage<T>>::remove(who);
+		}
+
+		/// Declare the start or end of high volatility period, in which the fee of risk reducing calls is discounted
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		#[weight = 10_000]
+		fn set_high_volatility(origin, is_high_volatility: bool) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			IsHighVolatility::put(is_high_volatility);
+		}
+
+		/// Update the rate of fee charged for risk reducing calls in high volatility period
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		#[weight = 10_000]
+		fn set_risk_reducing_fee_rate(origin, rate: Ratio) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(rate <= Ratio::saturating_from_integer(1), Error::<T>::InvalidFeeRate);
+			RiskReducingFeeRate::put(rate);
+		}
+
+		/// Register as a sponsor, or update the fee budget and rate limit. The budget is reserved
+		/// from the origin.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `budget`: the total fee willing to pay for sponsored accounts
+		/// - `max_count_per_period`: the max count of calls sponsored for every account per `SponsorPeriod`
+		#[weight = 10_000]
+		fn set_sponsor(origin, budget: PalletBalanceOf<T>, max_count_per_period: u32) {
+			let who = ensure_signed(origin)?;
+			let mut info = Self::sponsors(&who).unwrap_or_default();
+			if budget > info.budget {
+				T::SponsorCurrency::reserve(&who, budget - info.budget)?;
+			} else {
+				T::SponsorCurrency::unreserve(&who, info.budget - budget);
+			}
+
+			info.budget = budget;
+			info.max_count_per_period = max_count_per_period;
+			<Sponsors<T>>::insert(&who, info);
+			Self::deposit_event(RawEvent::SponsorUpdated(who, budget, max_count_per_period));
+		}
+
+		/// Stop sponsoring, unreserve the remaining budget and remove the sponsorships of the origin
+		///
+		/// The dispatch origin of this call must be _Signed_ by a registered sponsor.
+		///
+		/// # <weight>
+		/// - Complexity: `O(N)` where `N` is the count of sponsored accounts, bounded by `MaxSponsoredAccounts`
+		/// - Db reads: `Sponsors`, 1 item in `SponsorCurrency`, `N` items in `SponsoredUsage` and `Sponsorships`
+		/// - Db writes: `Sponsors`, 1 item in `SponsorCurrency`, `N` items in `SponsoredUsage` and `Sponsorships`
+		/// -------------------
+		/// Base Weight: 20 µs
+		/// # </weight>
+		#[weight = 20 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(
+			2 + 2 * T::MaxSponsoredAccounts::get() as Weight,
+			2 + 2 * T::MaxSponsoredAccounts::get() as Weight,
+		)]
+		fn remove_sponsor(origin) {
+			let who = ensure_signed(origin)?;
+			ensure!(<Sponsors<T>>::contains_key(&who), Error::<T>::NotSponsor);
+			Self::do_remove_sponsor(&who);
+			Self::deposit_event(RawEvent::SponsorRemoved(who));
+		}
+
+		/// Offer to pay the fee of sponsored calls for `who`, which takes effect after `who`
+		/// accepts it by `accept_sponsor`
+		///
+		/// The dispatch origin of this call must be _Signed_ by a registered sponsor.
+		#[weight = 10_000]
+		fn sponsor_account(origin, who: T::AccountId) {
+			let sponsor = ensure_signed(origin)?;
+			let mut info = Self::sponsors(&sponsor).ok_or(Error::<T>::NotSponsor)?;
+			ensure!(!<SponsoredUsage<T>>::contains_key(&sponsor, &who), Error::<T>::AlreadySponsored);
+			ensure!(info.sponsored_count < T::MaxSponsoredAccounts::get(), Error::<T>::TooManySponsoredAccounts);
+
+			info.sponsored_count += 1;
+			<Sponsors<T>>::insert(&sponsor, info);
+			<SponsoredUsage<T>>::insert(&sponsor, &who, (T::BlockNumber::zero(), 0));
+			Self::deposit_event(RawEvent::AccountSponsored(sponsor, who));
+		}
+
+		/// Accept the sponsorship offered by `sponsor`, replace the current sponsor if any
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		#[weight = 10_000]
+		fn accept_sponsor(origin, sponsor: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			ensure!(<SponsoredUsage<T>>::contains_key(&sponsor, &who), Error::<T>::NotSponsored);
+			<Sponsorships<T>>::insert(&who, &sponsor);
+			Self::deposit_event(RawEvent::SponsorAccepted(who, sponsor));
+		}
+
+		/// Stop paying the fee for `who`
+		///
+		/// The dispatch origin of this call must be _Signed_ by the sponsor offered sponsorship to `who`.
+		#[weight = 10_000]
+		fn unsponsor_account(origin, who: T::AccountId) {
+			let sponsor = ensure_signed(origin)?;
+			ensure!(<SponsoredUsage<T>>::contains_key(&sponsor, &who), Error::<T>::NotSponsored);
+			Self::do_unsponsor_account(&sponsor, &who);
+			Self::deposit_event(RawEvent::AccountUnsponsored(sponsor, who));
+		}
 	}
 }
 
@@ -107,9 +363,166 @@ where
 	}
 }
 
+impl<T: Trait> Module<T> {
+	/// Use the free transaction quota of `who` in current period, return false if not enabled or run out.
+	pub fn try_free_quota(who: &T::AccountId) -> bool {
+		let quota = T::FreeQuotaCount::get();
+		let period = T::FreeQuotaPeriod::get();
+		if quota.is_zero() || period.is_zero() || !<FreeQuotaEnabledAccounts<T>>::contains_key(who) {
+			return false;
+		}
+
+		let period_index = <system::Module<T>>::block_number() / period;
+		let (last_period_index, used) = Self::free_quota_usage(who);
+		let used = if last_period_index == period_index { used } else { 0 };
+		if used < quota {
+			<FreeQuotaUsage<T>>::insert(who, (period_index, used + 1));
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Remove the sponsor, unreserve the remaining budget and remove its sponsorships.
+	fn do_remove_sponsor(sponsor: &T::AccountId) {
+		if let Some(info) = <Sponsors<T>>::take(sponsor) {
+			T::SponsorCurrency::unreserve(sponsor, info.budget);
+		}
+		for (who, _) in <SponsoredUsage<T>>::iter_prefix(sponsor).collect::<Vec<_>>() {
+			if Self::sponsorships(&who).as_ref() == Some(sponsor) {
+				<Sponsorships<T>>::remove(&who);
+			}
+		}
+		<SponsoredUsage<T>>::remove_prefix(sponsor);
+	}
+
+	/// Remove the sponsorship offered by `sponsor` to `who`.
+	fn do_unsponsor_account(sponsor: &T::AccountId, who: &T::AccountId) {
+		if <SponsoredUsage<T>>::take(sponsor, who).is_some() {
+			<Sponsors<T>>::mutate(sponsor, |maybe_info| {
+				if let Some(info) = maybe_info {
+					info.sponsored_count = info.sponsored_count.saturating_sub(1);
+				}
+			});
+		}
+		if Self::sponsorships(who).as_ref() == Some(sponsor) {
+			<Sponsorships<T>>::remove(who);
+		}
+	}
+
+	/// Get the sponsor willing to pay `fee` for `who` within its budget and rate limit.
+	pub fn sponsor_of(who: &T::AccountId, fee: PalletBalanceOf<T>) -> Option<T::AccountId> {
+		let sponsor = Self::sponsorships(who)?;
+		let info = Self::sponsors(&sponsor)?;
+		let (last_period_index, count) = Self::sponsored_usage(&sponsor, who)?;
+		if info.budget < fee {
+			return None;
+		}
+
+		let period = T::SponsorPeriod::get();
+		if !period.is_zero() {
+			let period_index = <system::Module<T>>::block_number() / period;
+			let count = if last_period_index == period_index { count } else { 0 };
+			if count >= info.max_count_per_period {
+				return None;
+			}
+		}
+
+		Some(sponsor)
+	}
+
+	/// Record the `fee` paid by `sponsor` for `who`, deduct the budget and count the rate limit.
+	fn on_sponsored(sponsor: &T::AccountId, who: &T::AccountId, fee: PalletBalanceOf<T>) {
+		<Sponsors<T>>::mutate(sponsor, |maybe_info| {
+			if let Some(info) = maybe_info {
+				info.budget = info.budget.saturating_sub(fee);
+			}
+		});
+
+		let period = T::SponsorPeriod::get();
+		if !period.is_zero() {
+			let period_index = <system::Module<T>>::block_number() / period;
+			<SponsoredUsage<T>>::mutate(sponsor, who, |maybe_usage| {
+				if let Some((last_period_index, count)) = maybe_usage {
+					if *last_period_index != period_index {
+						*last_period_index = period_index;
+						*count = 0;
+					}
+					*count = count.saturating_add(1);
+				}
+			});
+		}
+	}
+
+	/// Withdraw `fee` for `who` from the reserved budget of `sponsor`, return None and keep the
+	/// budget reserved if the sponsor can't pay.
+	fn withdraw_sponsored_fee(
+		sponsor: &T::AccountId,
+		who: &T::AccountId,
+		fee: PalletBalanceOf<T>,
+		reasons: WithdrawReasons,
+	) -> Option<NegativeImbalanceOf<T>> {
+		let remaining = T::SponsorCurrency::unreserve(sponsor, fee);
+		let imbalance = if remaining.is_zero() {
+			<T as pallet_transaction_payment::Trait>::Currency::withdraw(
+				sponsor,
+				fee,
+				reasons,
+				ExistenceRequirement::KeepAlive,
+			)
+			.ok()
+		} else {
+			None
+		};
+
+		if imbalance.is_some() {
+			Self::on_sponsored(sponsor, who, fee);
+		} else {
+			let _ = T::SponsorCurrency::reserve(sponsor, fee - remaining);
+		}
+		imbalance
+	}
+
+	/// Make sure `who` has enough native currency to pay `fee` and keep alive, if not, swap the
+	/// shortfall from `AllNonNativeCurrencyIds` in order through DEX, skip the currency whose
+	/// slippage exceeds `MaxSlippageSwapWithDEX`.
+	pub fn ensure_can_pay_fee(who: &T::AccountId, fee: PalletBalanceOf<T>) {
+		let native_balance = <T as pallet_transaction_payment::Trait>::Currency::free_balance(who);
+		let required = fee.saturating_add(<T as pallet_transaction_payment::Trait>::Currency::minimum_balance());
+		if native_balance >= required {
+			return;
+		}
+
+		let native_currency_id = T::GetNativeCurrencyId::get();
+		let shortfall: BalanceOf<T> = (required - native_balance).saturated_into::<u128>().saturated_into();
+		let max_slippage = T::MaxSlippageSwapWithDEX::get();
+		for currency_id in T::AllNonNativeCurrencyIds::get() {
+			let supply_amount = T::DEX::get_supply_amount(currency_id, native_currency_id, shortfall);
+			if supply_amount.is_zero() {
+				continue;
+			}
+
+			let slippage_acceptable = T::DEX::get_exchange_slippage(currency_id, native_currency_id, supply_amount)
+				.map_or(false, |slippage| slippage <= max_slippage);
+			if slippage_acceptable
+				&& T::DEX::exchange_currency(who.clone(), currency_id, supply_amount, native_currency_id, shortfall)
+					.is_ok()
+			{
+				break;
+			}
+		}
+	}
+}
+
 impl<T: Trait> OnKilledAccount<T::AccountId> for Module<T> {
 	fn on_killed_account(who: &T::AccountId) {
 		<LastFreeTransfers<T>>::remove(who);
+		<FreeQuotaEnabledAccounts<T>>::remove(who);
+		<FreeQuotaUsage<T>>::remove(who);
+		if let Some(sponsor) = Self::sponsorships(who) {
+			Self::do_unsponsor_account(&sponsor, who);
+		}
+		Self::do_remove_sponsor(who);
 	}
 }
 
@@ -164,7 +577,7 @@ where
 		// check call type
 		let skip_pay_fee = match call.is_sub_type() {
 			Some(orml_currencies::Call::transfer(..)) => <Module<T>>::try_free_transfer(who),
-			_ => false,
+			_ => T::BasicOperations::is_basic_operation(call) && <Module<T>>::try_free_quota(who),
 		};
 
 		let pay_fee = !skip_pay_fee;
@@ -175,7 +588,21 @@ where
 			let mut reason = WithdrawReasons::none();
 			let fee = if pay_fee {
 				reason.set(WithdrawReason::TransactionPayment);
-				<pallet_transaction_payment::Module<T>>::compute_fee(len as u32, info, tip)
+				if <Module<T>>::is_high_volatility() && T::RiskReducingCalls::is_risk_reducing(call) {
+					// discount the fee except the base fee and the tip, so that risk reducing calls are never free
+					let full_fee = <pallet_transaction_payment::Module<T>>::compute_fee(len as u32, info, Zero::zero());
+					let base_fee = <pallet_transaction_payment::Module<T>>::compute_fee(
+						0,
+						&DispatchInfo { weight: 0, ..*info },
+						Zero::zero(),
+					);
+					<Module<T>>::risk_reducing_fee_rate()
+						.saturating_mul_int(full_fee.saturating_sub(base_fee))
+						.saturating_add(base_fee)
+						.saturating_add(tip)
+				} else {
+					<pallet_transaction_payment::Module<T>>::compute_fee(len as u32, info, tip)
+				}
 			//pallet_transaction_payment::ChargeTransactionPayment::<T>::compute_fee(len as u32, info, tip)
 			} else {
 				tip
@@ -183,14 +610,36 @@ where
 			if pay_tip {
 				reason.set(WithdrawReason::Tip);
 			}
-			let imbalance = match <T as pallet_transaction_payment::Trait>::Currency::withdraw(
-				who,
-				fee,
-				reason,
-				ExistenceRequirement::KeepAlive,
-			) {
-				Ok(imbalance) => imbalance,
-				Err(_) => return InvalidTransaction::Payment.into(),
+
+			// nothing to pay if the fee is waived
+			if fee.is_zero() {
+				return Ok(ValidTransaction::default());
+			}
+
+			// the sponsor pays the fee of sponsored calls without tip, fall back to `who` if the sponsor can't pay
+			let sponsored_imbalance = if pay_fee && !pay_tip && T::SponsoredCalls::is_sponsored_call(call) {
+				<Module<T>>::sponsor_of(who, fee)
+					.and_then(|sponsor| <Module<T>>::withdraw_sponsored_fee(&sponsor, who, fee, reason))
+			} else {
+				None
+			};
+
+			let imbalance = match sponsored_imbalance {
+				Some(imbalance) => imbalance,
+				None => {
+					// swap other currencies for the fee if native currency is not enough
+					<Module<T>>::ensure_can_pay_fee(who, fee);
+
+					match <T as pallet_transaction_payment::Trait>::Currency::withdraw(
+						who,
+						fee,
+						reason,
+						ExistenceRequirement::KeepAlive,
+					) {
+						Ok(imbalance) => imbalance,
+						Err(_) => return InvalidTransaction::Payment.into(),
+					}
+				}
 			};
 			<T as pallet_transaction_payment::Trait>::OnTransactionPayment::on_unbalanced(imbalance);
 			fee
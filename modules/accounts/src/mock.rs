@@ -3,9 +3,13 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{impl_outer_dispatch, impl_outer_origin, parameter_types, weights::IdentityFee};
+use frame_support::{
+	impl_outer_dispatch, impl_outer_origin, parameter_types,
+	weights::{IdentityFee, Weight},
+};
 use sp_core::H256;
-use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError, FixedPointNumber, Perbill};
+use sp_std::cell::RefCell;
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -16,6 +20,7 @@ pub type Moment = u64;
 
 pub const ALICE: AccountId = 0;
 pub const BOB: AccountId = 1;
+pub const CHARLIE: AccountId = 2;
 pub const ACA: CurrencyId = 0;
 pub const AUSD: CurrencyId = 1;
 pub const BTC: CurrencyId = 2;
@@ -62,9 +67,10 @@ impl system::Trait for Runtime {
 	type OnKilledAccount = ();
 	type DbWeight = ();
 	type BlockExecutionWeight = ();
-	type ExtrinsicBaseWeight = ();
+	type ExtrinsicBaseWeight = MockExtrinsicBaseWeight;
 	type MaximumExtrinsicWeight = ();
 }
+pub type System = system::Module<Runtime>;
 
 impl orml_tokens::Trait for Runtime {
 	type Event = ();
@@ -132,12 +138,187 @@ parameter_types! {
 	pub const FreeTransferDeposit: Balance = 200;
 }
 
+thread_local! {
+	pub static DEX_SLIPPAGE: RefCell<Option<Ratio>> = RefCell::new(Some(Ratio::saturating_from_rational(1, 100)));
+	pub static EXTRINSIC_BASE_WEIGHT: RefCell<Weight> = RefCell::new(0);
+}
+
+pub struct MockExtrinsicBaseWeight;
+impl Get<Weight> for MockExtrinsicBaseWeight {
+	fn get() -> Weight {
+		EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow())
+	}
+}
+
+/// Only AUSD has liquidity, at the price of 2 AUSD per ACA
+pub struct MockDEX;
+impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
+	fn get_target_amount(supply_currency_id: CurrencyId, _: CurrencyId, supply_currency_amount: Balance) -> Balance {
+		if supply_currency_id == AUSD {
+			supply_currency_amount / 2
+		} else {
+			0
+		}
+	}
+
+	fn get_supply_amount(supply_currency_id: CurrencyId, _: CurrencyId, target_currency_amount: Balance) -> Balance {
+		if supply_currency_id == AUSD {
+			target_currency_amount * 2
+		} else {
+			0
+		}
+	}
+
+	fn exchange_currency(
+		who: AccountId,
+		supply_currency_id: CurrencyId,
+		supply_amount: Balance,
+		target_currency_id: CurrencyId,
+		acceptable_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let target_amount = Self::get_target_amount(supply_currency_id, target_currency_id, supply_amount);
+		ensure!(
+			target_amount >= acceptable_target_amount,
+			DispatchError::Other("unacceptable target amount")
+		);
+		Currencies::withdraw(supply_currency_id, &who, supply_amount)?;
+		Currencies::deposit(target_currency_id, &who, target_amount)?;
+		Ok(target_amount)
+	}
+
+	fn get_exchange_slippage(_: CurrencyId, _: CurrencyId, _: Balance) -> Option<Ratio> {
+		DEX_SLIPPAGE.with(|v| *v.borrow())
+	}
+
+	fn get_liquidity_pool(_: CurrencyId) -> (Balance, Balance) {
+		(0, 0)
+	}
+
+	fn get_swap_target_amount(path: &[CurrencyId], supply_amount: Balance) -> Option<Balance> {
+		match path {
+			[supply_currency_id, target_currency_id] => Some(Self::get_target_amount(
+				*supply_currency_id,
+				*target_currency_id,
+				supply_amount,
+			))
+			.filter(|amount| !amount.is_zero()),
+			_ => None,
+		}
+	}
+
+	fn get_swap_supply_amount(path: &[CurrencyId], target_amount: Balance) -> Option<Balance> {
+		match path {
+			[supply_currency_id, target_currency_id] => Some(Self::get_supply_amount(
+				*supply_currency_id,
+				*target_currency_id,
+				target_amount,
+			))
+			.filter(|amount| !amount.is_zero()),
+			_ => None,
+		}
+	}
+
+	fn swap_with_exact_supply(
+		who: &AccountId,
+		path: &[CurrencyId],
+		supply_amount: Balance,
+		min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		match path {
+			[supply_currency_id, target_currency_id] => Self::exchange_currency(
+				*who,
+				*supply_currency_id,
+				supply_amount,
+				*target_currency_id,
+				min_target_amount,
+			),
+			_ => Err(DispatchError::Other("invalid path")),
+		}
+	}
+
+	fn swap_with_exact_target(
+		who: &AccountId,
+		path: &[CurrencyId],
+		target_amount: Balance,
+		max_supply_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let supply_amount =
+			Self::get_swap_supply_amount(path, target_amount).ok_or(DispatchError::Other("invalid path"))?;
+		ensure!(
+			supply_amount <= max_supply_amount,
+			DispatchError::Other("unacceptable supply amount")
+		);
+		Self::swap_with_exact_supply(who, path, supply_amount, target_amount)?;
+		Ok(supply_amount)
+	}
+}
+
+/// Treat the transfer of native currency as risk reducing for tests
+pub struct MockRiskReducingCalls;
+impl IsRiskReducing<Call> for MockRiskReducingCalls {
+	fn is_risk_reducing(call: &Call) -> bool {
+		match call {
+			Call::Currencies(orml_currencies::Call::transfer_native_currency(..)) => true,
+			_ => false,
+		}
+	}
+}
+
+/// Treat the update of balance as basic operation for tests
+pub struct MockBasicOperations;
+impl IsBasicOperation<Call> for MockBasicOperations {
+	fn is_basic_operation(call: &Call) -> bool {
+		match call {
+			Call::Currencies(orml_currencies::Call::update_balance(..)) => true,
+			_ => false,
+		}
+	}
+}
+
+/// Treat the transfer of native currency as sponsored call for tests
+pub struct MockSponsoredCalls;
+impl IsSponsoredCall<Call> for MockSponsoredCalls {
+	fn is_sponsored_call(call: &Call) -> bool {
+		match call {
+			Call::Currencies(orml_currencies::Call::transfer_native_currency(..)) => true,
+			_ => false,
+		}
+	}
+}
+
+parameter_types! {
+	pub const FreeQuotaCount: u32 = 2;
+	pub const FreeQuotaPeriod: BlockNumber = 10;
+	pub const FreeQuotaDeposit: Balance = 200;
+	pub const SponsorPeriod: BlockNumber = 10;
+	pub const MaxSponsoredAccounts: u32 = 2;
+}
+
+parameter_types! {
+	pub AllNonNativeCurrencyIds: Vec<CurrencyId> = vec![BTC, AUSD];
+	pub MaxSlippageSwapWithDEX: Ratio = Ratio::saturating_from_rational(1, 10);
+}
+
 impl Trait for Runtime {
+	type Event = ();
 	type FreeTransferCount = FreeTransferCount;
 	type FreeTransferPeriod = FreeTransferPeriod;
 	type Time = TimeModule;
 	type FreeTransferDeposit = FreeTransferDeposit;
 	type DepositCurrency = pallet_balances::Module<Self>;
+	type AllNonNativeCurrencyIds = AllNonNativeCurrencyIds;
+	type MaxSlippageSwapWithDEX = MaxSlippageSwapWithDEX;
+	type DEX = MockDEX;
+	type RiskReducingCalls = MockRiskReducingCalls;
+	type UpdateOrigin = system::EnsureRoot<AccountId>;
+	type FreeQuotaCount = FreeQuotaCount;
+	type FreeQuotaPeriod = FreeQuotaPeriod;
+	type FreeQuotaDeposit = FreeQuotaDeposit;
+	type BasicOperations = MockBasicOperations;
+	type SponsoredCalls = MockSponsoredCalls;
+	type SponsorPeriod = SponsorPeriod;
+	type SponsorCurrency = PalletBalances;
+	type MaxSponsoredAccounts = MaxSponsoredAccounts;
 }
 pub type Accounts = Module<Runtime>;
 
@@ -148,7 +329,7 @@ pub struct ExtBuilder {
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
-			endowed_accounts: vec![(ALICE, AUSD, 10000), (ALICE, BTC, 1000)],
+			endowed_accounts: vec![(ALICE, AUSD, 10000), (ALICE, BTC, 1000), (BOB, AUSD, 10000)],
 		}
 	}
 }
@@ -7,8 +7,12 @@ use frame_support::{
 	assert_noop, assert_ok,
 	weights::{DispatchClass, DispatchInfo, Pays},
 };
-use mock::{Accounts, Call, Currencies, ExtBuilder, Origin, Runtime, TimeModule, ACA, ALICE, AUSD, BOB};
+use mock::{
+	Accounts, Call, Currencies, ExtBuilder, Origin, PalletBalances, Runtime, System, TimeModule, ACA, ALICE, AUSD, BOB,
+	CHARLIE, DEX_SLIPPAGE, EXTRINSIC_BASE_WEIGHT,
+};
 use orml_traits::MultiCurrency;
+use sp_runtime::{traits::BadOrigin, transaction_validity::InvalidTransaction, FixedPointNumber};
 
 #[test]
 fn enable_free_transfer_require_deposit() {
@@ -88,6 +92,9 @@ const CALL: &<Runtime as system::Trait>::Call = &Call::Currencies(orml_currencie
 const CALL2: &<Runtime as system::Trait>::Call =
 	&Call::Currencies(orml_currencies::Call::transfer_native_currency(BOB, 12));
 
+const CALL3: &<Runtime as system::Trait>::Call =
+	&Call::Currencies(orml_currencies::Call::update_balance(BOB, AUSD, 12));
+
 const INFO: DispatchInfo = DispatchInfo {
 	weight: 1000,
 	class: DispatchClass::Normal,
@@ -184,3 +191,433 @@ fn enabled_free_transaction_charges_other_call_with_tip() {
 		assert_eq!(Currencies::free_balance(ACA, &ALICE,), 100000 - fee);
 	});
 }
+
+#[test]
+fn charges_fee_by_swapping_with_dex() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(Currencies::free_balance(ACA, &BOB), 0);
+		let fee = 23 * 2 + 1000; // len * byte + weight
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(0)
+				.validate(&BOB, CALL, &INFO, 23)
+				.unwrap()
+				.priority,
+			fee
+		);
+
+		// swap the fee and existential deposit at the price of 2 AUSD per ACA
+		assert_eq!(Currencies::free_balance(ACA, &BOB), 1);
+		assert_eq!(Currencies::free_balance(AUSD, &BOB), 10000 - (fee + 1) * 2);
+	});
+}
+
+#[test]
+fn not_swap_when_slippage_too_high() {
+	ExtBuilder::default().build().execute_with(|| {
+		DEX_SLIPPAGE.with(|v| *v.borrow_mut() = Some(Ratio::saturating_from_rational(2, 10)));
+		assert_noop!(
+			ChargeTransactionPayment::<Runtime>::from(0).validate(&BOB, CALL, &INFO, 23),
+			InvalidTransaction::Payment
+		);
+		assert_eq!(Currencies::free_balance(AUSD, &BOB), 10000);
+	});
+}
+
+#[test]
+fn set_high_volatility_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(Accounts::set_high_volatility(Origin::signed(ALICE), true), BadOrigin);
+		assert_ok!(Accounts::set_high_volatility(Origin::ROOT, true));
+		assert_eq!(Accounts::is_high_volatility(), true);
+		assert_ok!(Accounts::set_high_volatility(Origin::ROOT, false));
+		assert_eq!(Accounts::is_high_volatility(), false);
+	});
+}
+
+#[test]
+fn set_risk_reducing_fee_rate_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Accounts::set_risk_reducing_fee_rate(Origin::signed(ALICE), Ratio::saturating_from_rational(1, 2)),
+			BadOrigin
+		);
+		assert_noop!(
+			Accounts::set_risk_reducing_fee_rate(Origin::ROOT, Ratio::saturating_from_rational(3, 2)),
+			Error::<Runtime>::InvalidFeeRate
+		);
+		assert_ok!(Accounts::set_risk_reducing_fee_rate(
+			Origin::ROOT,
+			Ratio::saturating_from_rational(1, 2)
+		));
+		assert_eq!(
+			Accounts::risk_reducing_fee_rate(),
+			Ratio::saturating_from_rational(1, 2)
+		);
+	});
+}
+
+#[test]
+fn discount_fee_of_risk_reducing_calls_in_high_volatility() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Accounts::set_risk_reducing_fee_rate(
+			Origin::ROOT,
+			Ratio::saturating_from_rational(1, 2)
+		));
+
+		// not discounted before high volatility declared
+		let fee = 18 * 2 + 1000; // len * byte + weight
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(0)
+				.validate(&ALICE, CALL2, &INFO, 18)
+				.unwrap()
+				.priority,
+			fee
+		);
+
+		assert_ok!(Accounts::set_high_volatility(Origin::ROOT, true));
+		let discounted_fee = fee / 2 + 100; // the tip is not discounted
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(100)
+				.validate(&ALICE, CALL2, &INFO, 18)
+				.unwrap()
+				.priority,
+			discounted_fee
+		);
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 100000 - fee - discounted_fee);
+
+		// other calls are not discounted
+		let fee2 = 23 * 2 + 1000; // len * byte + weight
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(0)
+				.validate(&ALICE, CALL, &INFO, 23)
+				.unwrap()
+				.priority,
+			fee2
+		);
+	});
+}
+
+#[test]
+fn waive_fee_of_risk_reducing_calls_in_high_volatility_except_base_fee() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Accounts::set_high_volatility(Origin::ROOT, true));
+		assert_eq!(Accounts::risk_reducing_fee_rate(), Ratio::zero());
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(0)
+				.validate(&ALICE, CALL2, &INFO, 18)
+				.unwrap()
+				.priority,
+			0
+		);
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 100000);
+
+		// the base fee is always charged
+		EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow_mut() = 100);
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(0)
+				.validate(&ALICE, CALL2, &INFO, 18)
+				.unwrap()
+				.priority,
+			100
+		);
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 100000 - 100);
+	});
+}
+
+#[test]
+fn enable_free_quota_require_deposit() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Accounts::enable_free_quota(Origin::signed(BOB)),
+			Error::<Runtime>::NotEnoughBalance
+		);
+	});
+}
+
+#[test]
+fn enable_and_disable_free_quota_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(Accounts::free_quota_enabled_accounts(ALICE), None);
+		assert_ok!(Accounts::enable_free_quota(Origin::signed(ALICE)));
+		assert_eq!(Accounts::free_quota_enabled_accounts(ALICE), Some(true));
+		// the deposit is locked
+		assert!(<Currencies as MultiCurrency<_>>::transfer(ACA, &ALICE, &BOB, 100000 - 100).is_err());
+		assert_eq!(Accounts::try_free_quota(&ALICE), true);
+		assert_eq!(Accounts::free_quota_usage(ALICE), (0, 1));
+
+		assert_ok!(Accounts::disable_free_quota(Origin::signed(ALICE)));
+		assert_eq!(Accounts::free_quota_enabled_accounts(ALICE), None);
+		assert_eq!(Accounts::free_quota_usage(ALICE), (0, 0));
+		assert_eq!(Accounts::try_free_quota(&ALICE), false);
+		assert_ok!(<Currencies as MultiCurrency<_>>::transfer(
+			ACA,
+			&ALICE,
+			&BOB,
+			100000 - 100
+		));
+	});
+}
+
+#[test]
+fn try_free_quota_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		// not enabled
+		assert_eq!(Accounts::try_free_quota(&ALICE), false);
+		assert_eq!(Accounts::free_quota_usage(ALICE), (0, 0));
+
+		assert_ok!(Accounts::enable_free_quota(Origin::signed(ALICE)));
+		assert_eq!(Accounts::try_free_quota(&ALICE), true);
+		assert_eq!(Accounts::free_quota_usage(ALICE), (0, 1));
+		assert_eq!(Accounts::try_free_quota(&ALICE), true);
+		assert_eq!(Accounts::try_free_quota(&ALICE), false);
+		assert_eq!(Accounts::free_quota_usage(ALICE), (0, 2));
+		assert_eq!(Accounts::try_free_quota(&BOB), false);
+
+		// reset in next period
+		System::set_block_number(10);
+		assert_eq!(Accounts::try_free_quota(&ALICE), true);
+		assert_eq!(Accounts::free_quota_usage(ALICE), (1, 1));
+	});
+}
+
+#[test]
+fn basic_operations_use_free_quota() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Accounts::enable_free_quota(Origin::signed(ALICE)));
+		for _ in 0..2 {
+			assert_eq!(
+				ChargeTransactionPayment::<Runtime>::from(0)
+					.validate(&ALICE, CALL3, &INFO, 23)
+					.unwrap()
+					.priority,
+				0
+			);
+		}
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 100000);
+
+		// charged after the quota runs out
+		let fee = 23 * 2 + 1000; // len * byte + weight
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(0)
+				.validate(&ALICE, CALL3, &INFO, 23)
+				.unwrap()
+				.priority,
+			fee
+		);
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 100000 - fee);
+
+		// other calls don't use the quota
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(0)
+				.validate(&ALICE, CALL2, &INFO, 23)
+				.unwrap()
+				.priority,
+			fee
+		);
+		assert_eq!(Accounts::free_quota_usage(ALICE), (0, 2));
+	});
+}
+
+#[test]
+fn set_sponsor_reserve_budget() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(Accounts::set_sponsor(Origin::signed(BOB), 100, 2).is_err());
+		assert_eq!(Accounts::sponsors(BOB), None);
+
+		assert_ok!(Accounts::set_sponsor(Origin::signed(ALICE), 10000, 2));
+		assert_eq!(
+			Accounts::sponsors(ALICE),
+			Some(SponsorInfo {
+				budget: 10000,
+				max_count_per_period: 2,
+				sponsored_count: 0,
+			})
+		);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 10000);
+
+		assert_ok!(Accounts::set_sponsor(Origin::signed(ALICE), 4000, 3));
+		assert_eq!(Accounts::sponsors(ALICE).unwrap().budget, 4000);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 4000);
+
+		assert_noop!(
+			Accounts::remove_sponsor(Origin::signed(BOB)),
+			Error::<Runtime>::NotSponsor
+		);
+		assert_ok!(Accounts::remove_sponsor(Origin::signed(ALICE)));
+		assert_eq!(Accounts::sponsors(ALICE), None);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 0);
+	});
+}
+
+#[test]
+fn sponsor_account_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Accounts::sponsor_account(Origin::signed(ALICE), BOB),
+			Error::<Runtime>::NotSponsor
+		);
+		assert_ok!(Accounts::set_sponsor(Origin::signed(ALICE), 10000, 2));
+		assert_ok!(Accounts::sponsor_account(Origin::signed(ALICE), BOB));
+		assert_eq!(Accounts::sponsored_usage(ALICE, BOB), Some((0, 0)));
+		assert_eq!(Accounts::sponsors(ALICE).unwrap().sponsored_count, 1);
+		assert_noop!(
+			Accounts::sponsor_account(Origin::signed(ALICE), BOB),
+			Error::<Runtime>::AlreadySponsored
+		);
+
+		// not effective until accepted
+		assert_eq!(Accounts::sponsorships(BOB), None);
+		assert_noop!(
+			Accounts::accept_sponsor(Origin::signed(CHARLIE), ALICE),
+			Error::<Runtime>::NotSponsored
+		);
+		assert_ok!(Accounts::accept_sponsor(Origin::signed(BOB), ALICE));
+		assert_eq!(Accounts::sponsorships(BOB), Some(ALICE));
+
+		assert_ok!(Accounts::sponsor_account(Origin::signed(ALICE), CHARLIE));
+		assert_noop!(
+			Accounts::sponsor_account(Origin::signed(ALICE), ALICE),
+			Error::<Runtime>::TooManySponsoredAccounts
+		);
+
+		assert_noop!(
+			Accounts::unsponsor_account(Origin::signed(BOB), BOB),
+			Error::<Runtime>::NotSponsored
+		);
+		assert_ok!(Accounts::unsponsor_account(Origin::signed(ALICE), BOB));
+		assert_eq!(Accounts::sponsorships(BOB), None);
+		assert_eq!(Accounts::sponsored_usage(ALICE, BOB), None);
+		assert_eq!(Accounts::sponsors(ALICE).unwrap().sponsored_count, 1);
+	});
+}
+
+#[test]
+fn sponsored_account_can_replace_sponsor() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Currencies::deposit(ACA, &CHARLIE, 1000));
+		assert_ok!(Accounts::set_sponsor(Origin::signed(ALICE), 10000, 2));
+		assert_ok!(Accounts::set_sponsor(Origin::signed(CHARLIE), 100, 2));
+
+		// the offer of a sponsor doesn't block the others
+		assert_ok!(Accounts::sponsor_account(Origin::signed(CHARLIE), BOB));
+		assert_ok!(Accounts::sponsor_account(Origin::signed(ALICE), BOB));
+
+		assert_ok!(Accounts::accept_sponsor(Origin::signed(BOB), CHARLIE));
+		assert_eq!(Accounts::sponsorships(BOB), Some(CHARLIE));
+		assert_ok!(Accounts::accept_sponsor(Origin::signed(BOB), ALICE));
+		assert_eq!(Accounts::sponsorships(BOB), Some(ALICE));
+
+		// unsponsoring by the replaced sponsor keeps the current one
+		assert_ok!(Accounts::unsponsor_account(Origin::signed(CHARLIE), BOB));
+		assert_eq!(Accounts::sponsorships(BOB), Some(ALICE));
+	});
+}
+
+#[test]
+fn remove_sponsor_clear_sponsorships() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Accounts::set_sponsor(Origin::signed(ALICE), 10000, 2));
+		assert_ok!(Accounts::sponsor_account(Origin::signed(ALICE), BOB));
+		assert_ok!(Accounts::sponsor_account(Origin::signed(ALICE), CHARLIE));
+		assert_ok!(Accounts::accept_sponsor(Origin::signed(BOB), ALICE));
+
+		assert_ok!(Accounts::remove_sponsor(Origin::signed(ALICE)));
+		assert_eq!(Accounts::sponsorships(BOB), None);
+		assert_eq!(Accounts::sponsored_usage(ALICE, BOB), None);
+		assert_eq!(Accounts::sponsored_usage(ALICE, CHARLIE), None);
+
+		// can register and sponsor again
+		assert_ok!(Accounts::set_sponsor(Origin::signed(ALICE), 10000, 2));
+		assert_ok!(Accounts::sponsor_account(Origin::signed(ALICE), BOB));
+		assert_eq!(Accounts::sponsors(ALICE).unwrap().sponsored_count, 1);
+	});
+}
+
+#[test]
+fn sponsor_pays_fee_within_budget_and_rate_limit() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		// BOB can't pay the fee without sponsor
+		DEX_SLIPPAGE.with(|v| *v.borrow_mut() = None);
+		let fee = 23 * 2 + 1000; // len * byte + weight
+		assert_ok!(Accounts::set_sponsor(Origin::signed(ALICE), 3 * fee, 2));
+		assert_ok!(Accounts::sponsor_account(Origin::signed(ALICE), BOB));
+
+		// not accepted
+		assert!(ChargeTransactionPayment::<Runtime>::from(0)
+			.validate(&BOB, CALL2, &INFO, 23)
+			.is_err());
+		assert_ok!(Accounts::accept_sponsor(Origin::signed(BOB), ALICE));
+
+		// not sponsored call
+		assert!(ChargeTransactionPayment::<Runtime>::from(0)
+			.validate(&BOB, CALL3, &INFO, 23)
+			.is_err());
+
+		// not sponsored with tip
+		assert!(ChargeTransactionPayment::<Runtime>::from(1)
+			.validate(&BOB, CALL2, &INFO, 23)
+			.is_err());
+
+		for _ in 0..2 {
+			assert_eq!(
+				ChargeTransactionPayment::<Runtime>::from(0)
+					.validate(&BOB, CALL2, &INFO, 23)
+					.unwrap()
+					.priority,
+				fee
+			);
+		}
+		// paid from the reserved budget
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 100000 - 3 * fee);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), fee);
+		assert_eq!(Accounts::sponsors(ALICE).unwrap().budget, fee);
+		assert_eq!(Accounts::sponsored_usage(ALICE, BOB), Some((0, 2)));
+
+		// exceed the rate limit
+		assert!(ChargeTransactionPayment::<Runtime>::from(0)
+			.validate(&BOB, CALL2, &INFO, 23)
+			.is_err());
+
+		// reset in next period
+		System::set_block_number(10);
+		assert_ok!(ChargeTransactionPayment::<Runtime>::from(0).validate(&BOB, CALL2, &INFO, 23));
+		assert_eq!(Accounts::sponsors(ALICE).unwrap().budget, 0);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 0);
+
+		// exceed the budget
+		assert!(ChargeTransactionPayment::<Runtime>::from(0)
+			.validate(&BOB, CALL2, &INFO, 23)
+			.is_err());
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 100000 - 3 * fee);
+	});
+}
+
+#[test]
+fn fall_back_to_pay_by_self_when_sponsor_cannot_pay() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let fee = 23 * 2 + 1000; // len * byte + weight
+		assert_ok!(Currencies::deposit(ACA, &BOB, 10000));
+		assert_ok!(Accounts::set_sponsor(Origin::signed(ALICE), 3 * fee, 2));
+		assert_ok!(Accounts::sponsor_account(Origin::signed(ALICE), BOB));
+		assert_ok!(Accounts::accept_sponsor(Origin::signed(BOB), ALICE));
+
+		// the reserved budget is gone
+		let _ = PalletBalances::slash_reserved(&ALICE, 3 * fee);
+		assert_eq!(
+			ChargeTransactionPayment::<Runtime>::from(0)
+				.validate(&BOB, CALL2, &INFO, 23)
+				.unwrap()
+				.priority,
+			fee
+		);
+		assert_eq!(Currencies::free_balance(ACA, &BOB), 10000 - fee);
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 100000 - 3 * fee);
+		assert_eq!(Accounts::sponsors(ALICE).unwrap().budget, 3 * fee);
+		assert_eq!(Accounts::sponsored_usage(ALICE, BOB), Some((0, 0)));
+	});
+}
@@ -1,19 +1,56 @@
+//! # AirDrop Module
+//!
+//! ## Overview
+//!
+//! Besides the airdrops pushed by root for each recipient, a large distribution can be committed as
+//! a Merkle root of its `(recipient, amount)` leaves, and every recipient claims the airdrop once
+//! with the Merkle proof of its leaf. The proof is verified by hashing the sorted pair of nodes
+//! level by level, so the siblings don't need their positions.
+//!
+//! Root can also airdrop any currency from the airdrop account by vesting, which releases
+//! the airdrop to the recipient over time.
+
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{decl_event, decl_module, decl_storage};
-use frame_system::{self as system, ensure_root};
-use primitives::{AirDropCurrencyId, Balance};
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, ensure};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use primitives::{AirDropCurrencyId, Balance, CurrencyId};
+use sp_runtime::{
+	traits::{AccountIdConversion, Hash},
+	ModuleId,
+};
+use sp_std::prelude::*;
+use support::MultiCurrencyVesting;
 
 mod mock;
 mod tests;
 
+/// The maximum length of Merkle proof, which is enough for 2^32 recipients
+pub const MAX_PROOF_LENGTH: usize = 32;
+
+pub type DistributionId = u32;
+
+const MODULE_ID: ModuleId = ModuleId(*b"aca/aird");
+
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Vesting to release the vested airdrops
+	type Vesting: MultiCurrencyVesting<Self::AccountId, CurrencyId, Balance, Self::BlockNumber>;
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as AirDrop {
 		AirDrops get(fn airdrops): double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) AirDropCurrencyId => Balance;
+
+		/// The next distribution id
+		NextDistributionId get(fn next_distribution_id): DistributionId;
+
+		/// Mapping from distribution id to its airdrop currency and Merkle root
+		Distributions get(fn distributions): map hasher(twox_64_concat) DistributionId => Option<(AirDropCurrencyId, T::Hash)>;
+
+		/// Whether the recipient has claimed the airdrop of the distribution
+		Claimed get(fn claimed): double_map hasher(twox_64_concat) DistributionId, hasher(twox_64_concat) T::AccountId => bool;
 	}
 
 	add_extra_genesis {
@@ -30,16 +67,43 @@ decl_storage! {
 decl_event!(
 	pub enum Event<T> where
 		<T as system::Trait>::AccountId,
+		<T as system::Trait>::Hash,
+		<T as system::Trait>::BlockNumber,
 		AirDropCurrencyId = AirDropCurrencyId,
+		CurrencyId = CurrencyId,
 		Balance = Balance,
 	{
 		Airdrop(AccountId, AirDropCurrencyId, Balance),
 		UpdateAirdrop(AccountId, AirDropCurrencyId, Balance),
+		/// A distribution committed as Merkle root created (distribution_id, currency_id, merkle_root)
+		DistributionCreated(DistributionId, AirDropCurrencyId, Hash),
+		/// A distribution removed (distribution_id)
+		DistributionRemoved(DistributionId),
+		/// Airdrop of a distribution claimed (distribution_id, recipient, currency_id, amount)
+		Claimed(DistributionId, AccountId, AirDropCurrencyId, Balance),
+		/// Airdrop vested from the airdrop account (recipient, currency_id, amount, start, period)
+		VestedAirdrop(AccountId, CurrencyId, Balance, BlockNumber, BlockNumber),
 	}
 );
 
+decl_error! {
+	/// Error for airdrop module.
+	pub enum Error for Module<T: Trait> {
+		/// The distribution does not exist
+		DistributionNotFound,
+		/// The airdrop of the distribution has been claimed
+		AlreadyClaimed,
+		/// The Merkle proof is invalid
+		InvalidProof,
+		/// The Merkle proof is longer than `MAX_PROOF_LENGTH`
+		ProofTooLong,
+	}
+}
+
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
 		fn deposit_event() = default;
 
 		#[weight = 10_000]
@@ -65,7 +129,106 @@ decl_module! {
 			<AirDrops<T>>::insert(&to, currency_id, amount);
 			Self::deposit_event(RawEvent::UpdateAirdrop(to, currency_id, amount));
 		}
+
+		/// Create a distribution committed as the Merkle root of its `(recipient, amount)` leaves.
+		///
+		/// The dispatch origin of this call must be _Root_.
+		///
+		/// - `currency_id`: airdrop currency.
+		/// - `merkle_root`: the Merkle root of the leaves hashed by `leaf_hash`.
+		#[weight = 10_000]
+		pub fn create_distribution(origin, currency_id: AirDropCurrencyId, merkle_root: T::Hash) {
+			ensure_root(origin)?;
+			let distribution_id = Self::next_distribution_id();
+			NextDistributionId::put(distribution_id.wrapping_add(1));
+			<Distributions<T>>::insert(distribution_id, (currency_id, merkle_root));
+			Self::deposit_event(RawEvent::DistributionCreated(distribution_id, currency_id, merkle_root));
+		}
+
+		/// Remove a distribution so that its airdrop cannot be claimed anymore.
+		///
+		/// The dispatch origin of this call must be _Root_.
+		///
+		/// - `distribution_id`: the distribution to remove.
+		#[weight = 10_000]
+		pub fn remove_distribution(origin, distribution_id: DistributionId) {
+			ensure_root(origin)?;
+			ensure!(<Distributions<T>>::contains_key(distribution_id), Error::<T>::DistributionNotFound);
+			<Distributions<T>>::remove(distribution_id);
+			Self::deposit_event(RawEvent::DistributionRemoved(distribution_id));
+		}
+
+		/// Claim the airdrop of a distribution with the Merkle proof of `(origin, amount)` leaf.
+		///
+		/// The dispatch origin of this call must be _Signed_ by the recipient.
+		///
+		/// - `distribution_id`: the distribution to claim.
+		/// - `amount`: the airdrop amount of the recipient in the distribution.
+		/// - `proof`: the sibling nodes from the leaf to the Merkle root.
+		#[weight = 10_000 + 1_000 * proof.len() as u64]
+		pub fn claim(origin, distribution_id: DistributionId, amount: Balance, proof: Vec<T::Hash>) {
+			let who = ensure_signed(origin)?;
+			ensure!(proof.len() <= MAX_PROOF_LENGTH, Error::<T>::ProofTooLong);
+			let (currency_id, merkle_root) =
+				Self::distributions(distribution_id).ok_or(Error::<T>::DistributionNotFound)?;
+			ensure!(!Self::claimed(distribution_id, &who), Error::<T>::AlreadyClaimed);
+			ensure!(
+				Self::verify_proof(merkle_root, Self::leaf_hash(&who, amount), &proof),
+				Error::<T>::InvalidProof,
+			);
+
+			<Claimed<T>>::insert(distribution_id, &who, true);
+			<AirDrops<T>>::mutate(&who, currency_id, |balance| *balance += amount);
+			Self::deposit_event(RawEvent::Claimed(distribution_id, who, currency_id, amount));
+		}
+
+		/// Airdrop from the airdrop account by vesting.
+		///
+		/// The dispatch origin of this call must be _Root_.
+		///
+		/// - `to`: the recipient.
+		/// - `currency_id`: the currency to airdrop.
+		/// - `amount`: the airdrop amount.
+		/// - `start`: the block the vesting starts.
+		/// - `period`: the blocks to release the airdrop linearly.
+		#[weight = 10_000]
+		pub fn vested_airdrop(
+			origin,
+			to: T::AccountId,
+			currency_id: CurrencyId,
+			amount: Balance,
+			start: T::BlockNumber,
+			period: T::BlockNumber,
+		) {
+			ensure_root(origin)?;
+			T::Vesting::vested_transfer(currency_id, &Self::account_id(), &to, amount, start, period)?;
+			Self::deposit_event(RawEvent::VestedAirdrop(to, currency_id, amount, start, period));
+		}
 	}
 }
 
-impl<T: Trait> Module<T> {}
+impl<T: Trait> Module<T> {
+	/// The account holding the funds of vested airdrops
+	pub fn account_id() -> T::AccountId {
+		MODULE_ID.into_account()
+	}
+
+	/// The leaf hash of `(recipient, amount)` in the Merkle tree of distribution
+	pub fn leaf_hash(who: &T::AccountId, amount: Balance) -> T::Hash {
+		T::Hashing::hash_of(&(who, amount))
+	}
+
+	/// The parent hash of two nodes in the Merkle tree of distribution, the nodes are sorted before hashing
+	pub fn node_hash(a: T::Hash, b: T::Hash) -> T::Hash {
+		if a <= b {
+			T::Hashing::hash_of(&(a, b))
+		} else {
+			T::Hashing::hash_of(&(b, a))
+		}
+	}
+
+	/// Verify the Merkle proof from `leaf` to `root`
+	pub fn verify_proof(root: T::Hash, leaf: T::Hash, proof: &[T::Hash]) -> bool {
+		proof.iter().fold(leaf, |node, sibling| Self::node_hash(node, *sibling)) == root
+	}
+}
@@ -5,7 +5,8 @@
 use super::*;
 use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
 use sp_core::H256;
-use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchResult, Perbill};
+use sp_std::cell::RefCell;
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -68,8 +69,29 @@ impl system::Trait for Runtime {
 }
 pub type System = system::Module<Runtime>;
 
+thread_local! {
+	pub static VESTED: RefCell<Vec<(AccountId, AccountId, CurrencyId, Balance, BlockNumber, BlockNumber)>> = RefCell::new(vec![]);
+}
+
+/// The vesting schedules are recorded in `VESTED`
+pub struct MockVesting;
+impl MultiCurrencyVesting<AccountId, CurrencyId, Balance, BlockNumber> for MockVesting {
+	fn vested_transfer(
+		currency_id: CurrencyId,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Balance,
+		start: BlockNumber,
+		period: BlockNumber,
+	) -> DispatchResult {
+		VESTED.with(|v| v.borrow_mut().push((*from, *to, currency_id, amount, start, period)));
+		Ok(())
+	}
+}
+
 impl Trait for Runtime {
 	type Event = TestEvent;
+	type Vesting = MockVesting;
 }
 pub type Airdrop = Module<Runtime>;
 
@@ -4,7 +4,7 @@
 
 use super::*;
 use frame_support::{assert_noop, assert_ok};
-use mock::{Airdrop, ExtBuilder, Origin, System, TestEvent, ACA, ALICE, BOB, CHARLIE, KAR};
+use mock::{Airdrop, ExtBuilder, Origin, Runtime, System, TestEvent, ACA, ALICE, BOB, CHARLIE, KAR, VESTED};
 use sp_runtime::traits::BadOrigin;
 
 #[test]
@@ -43,3 +43,110 @@ fn genesis_config_work() {
 		assert_eq!(Airdrop::airdrops(CHARLIE, ACA), 80);
 	});
 }
+
+#[test]
+fn create_and_remove_distribution_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let merkle_root = Airdrop::leaf_hash(&ALICE, 100);
+		assert_noop!(
+			Airdrop::create_distribution(Origin::signed(BOB), KAR, merkle_root),
+			BadOrigin,
+		);
+		assert_ok!(Airdrop::create_distribution(Origin::ROOT, KAR, merkle_root));
+		let create_event = TestEvent::airdrop(RawEvent::DistributionCreated(0, KAR, merkle_root));
+		assert!(System::events().iter().any(|record| record.event == create_event));
+		assert_eq!(Airdrop::distributions(0), Some((KAR, merkle_root)));
+		assert_eq!(Airdrop::next_distribution_id(), 1);
+
+		assert_noop!(Airdrop::remove_distribution(Origin::signed(BOB), 0), BadOrigin);
+		assert_noop!(
+			Airdrop::remove_distribution(Origin::ROOT, 1),
+			Error::<Runtime>::DistributionNotFound
+		);
+		assert_ok!(Airdrop::remove_distribution(Origin::ROOT, 0));
+		let remove_event = TestEvent::airdrop(RawEvent::DistributionRemoved(0));
+		assert!(System::events().iter().any(|record| record.event == remove_event));
+		assert_eq!(Airdrop::distributions(0), None);
+		assert_noop!(
+			Airdrop::claim(Origin::signed(ALICE), 0, 100, vec![]),
+			Error::<Runtime>::DistributionNotFound
+		);
+	});
+}
+
+#[test]
+fn claim_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let alice_leaf = Airdrop::leaf_hash(&ALICE, 100);
+		let bob_leaf = Airdrop::leaf_hash(&BOB, 200);
+		let charlie_leaf = Airdrop::leaf_hash(&CHARLIE, 300);
+		let alice_bob_node = Airdrop::node_hash(alice_leaf, bob_leaf);
+		let merkle_root = Airdrop::node_hash(alice_bob_node, charlie_leaf);
+		assert_ok!(Airdrop::create_distribution(Origin::ROOT, ACA, merkle_root));
+
+		assert_noop!(
+			Airdrop::claim(Origin::signed(ALICE), 0, 200, vec![bob_leaf, charlie_leaf]),
+			Error::<Runtime>::InvalidProof
+		);
+		assert_noop!(
+			Airdrop::claim(Origin::signed(BOB), 0, 100, vec![bob_leaf, charlie_leaf]),
+			Error::<Runtime>::InvalidProof
+		);
+		assert_noop!(
+			Airdrop::claim(Origin::signed(ALICE), 0, 100, vec![bob_leaf; MAX_PROOF_LENGTH + 1]),
+			Error::<Runtime>::ProofTooLong
+		);
+
+		assert_ok!(Airdrop::claim(
+			Origin::signed(ALICE),
+			0,
+			100,
+			vec![bob_leaf, charlie_leaf]
+		));
+		let claim_event = TestEvent::airdrop(RawEvent::Claimed(0, ALICE, ACA, 100));
+		assert!(System::events().iter().any(|record| record.event == claim_event));
+		assert_eq!(Airdrop::airdrops(ALICE, ACA), 100);
+		assert_eq!(Airdrop::claimed(0, ALICE), true);
+		assert_noop!(
+			Airdrop::claim(Origin::signed(ALICE), 0, 100, vec![bob_leaf, charlie_leaf]),
+			Error::<Runtime>::AlreadyClaimed
+		);
+
+		assert_ok!(Airdrop::claim(
+			Origin::signed(BOB),
+			0,
+			200,
+			vec![alice_leaf, charlie_leaf]
+		));
+		assert_eq!(Airdrop::airdrops(BOB, ACA), 200);
+		assert_ok!(Airdrop::claim(Origin::signed(CHARLIE), 0, 300, vec![alice_bob_node]));
+		assert_eq!(Airdrop::airdrops(CHARLIE, ACA), 380);
+	});
+}
+
+#[test]
+fn vested_airdrop_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			Airdrop::vested_airdrop(Origin::signed(BOB), ALICE, CurrencyId::DOT, 100, 10, 100),
+			BadOrigin,
+		);
+		assert_ok!(Airdrop::vested_airdrop(
+			Origin::ROOT,
+			ALICE,
+			CurrencyId::DOT,
+			100,
+			10,
+			100
+		));
+		let vested_event = TestEvent::airdrop(RawEvent::VestedAirdrop(ALICE, CurrencyId::DOT, 100, 10, 100));
+		assert!(System::events().iter().any(|record| record.event == vested_event));
+		assert_eq!(
+			VESTED.with(|v| v.borrow().clone()),
+			vec![(Airdrop::account_id(), ALICE, CurrencyId::DOT, 100, 10, 100)]
+		);
+	});
+}
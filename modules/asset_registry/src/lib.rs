@@ -0,0 +1,200 @@
+//! # Asset Registry Module
+//!
+//! ## Overview
+//!
+//! Holds the metadata of the assets introduced by governance, e.g. bridged or locally issued tokens.
+//! Each registered asset is assigned an `AssetId` and is used as `CurrencyId::Registered(asset_id)`,
+//! so new tokens don't require adding variants to `CurrencyId` and a runtime upgrade.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{EnsureOrigin, Get},
+	weights::constants::WEIGHT_PER_MICROS,
+};
+use frame_system::{self as system, ensure_root};
+use primitives::{AssetId, Balance, CurrencyId};
+use sp_runtime::{DispatchError, DispatchResult, RuntimeDebug};
+use sp_std::prelude::*;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+mod mock;
+mod tests;
+
+/// The maximum length of asset symbol
+pub const MAX_SYMBOL_LENGTH: usize = 32;
+
+/// Where the registered asset comes from
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum AssetClass {
+	/// Issued on this chain
+	Local,
+	/// Wrapped asset backed by an external bridge
+	Bridged,
+	/// Transferred from other parachains or the relay chain
+	Foreign,
+}
+
+/// The metadata of registered asset
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct AssetMetadata {
+	/// The symbol of asset, e.g. `renBTC`
+	pub symbol: Vec<u8>,
+
+	/// The number of decimals of asset
+	pub decimals: u8,
+
+	/// The minimum balance an account should hold
+	pub existential_deposit: Balance,
+
+	/// Where the asset comes from
+	pub class: AssetClass,
+}
+
+pub trait Trait: system::Trait {
+	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// The origin which may register assets and update their metadata. Root can always do this.
+	type RegisterOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_event!(
+	pub enum Event {
+		/// A new asset registered (currency_id)
+		AssetRegistered(CurrencyId),
+		/// The metadata of asset updated (currency_id)
+		AssetUpdated(CurrencyId),
+	}
+);
+
+decl_error! {
+	/// Error for asset registry module.
+	pub enum Error for Module<T: Trait> {
+		/// The symbol is empty or too long
+		InvalidSymbol,
+		/// The asset is not registered
+		AssetNotFound,
+		/// No available asset id
+		NoAvailableAssetId,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as AssetRegistry {
+		/// The asset id to assign to the next registered asset
+		pub NextAssetId get(fn next_asset_id): AssetId;
+
+		/// Mapping from asset id to the metadata of registered asset
+		pub Assets get(fn assets): map hasher(twox_64_concat) AssetId => Option<AssetMetadata>;
+	}
+
+	add_extra_genesis {
+		// the assets registered at genesis, assigned asset ids in order from 0
+		config(assets): Vec<AssetMetadata>;
+		build(|config: &GenesisConfig| {
+			config.assets.iter().for_each(|metadata| {
+				<Module<T>>::do_register_asset(metadata.clone()).expect("genesis asset metadata should be valid");
+			});
+		});
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Register a new asset, it can be used as `CurrencyId::Registered` with the assigned asset id.
+		///
+		/// The dispatch origin of this call must be `RegisterOrigin` or _Root_.
+		///
+		/// - `metadata`: the metadata of asset.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `NextAssetId`
+		/// - Db writes: `NextAssetId`, `Assets`
+		/// -------------------
+		/// Base Weight: 20 µs
+		/// # </weight>
+		#[weight = 20 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 2)]
+		pub fn register_asset(origin, metadata: AssetMetadata) {
+			T::RegisterOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			Self::do_register_asset(metadata)?;
+		}
+
+		/// Update the metadata of registered asset.
+		///
+		/// The dispatch origin of this call must be `RegisterOrigin` or _Root_.
+		///
+		/// - `asset_id`: the asset id of registered asset.
+		/// - `metadata`: the new metadata of asset.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `Assets`
+		/// - Db writes: `Assets`
+		/// -------------------
+		/// Base Weight: 20 µs
+		/// # </weight>
+		#[weight = 20 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn update_asset(origin, asset_id: AssetId, metadata: AssetMetadata) {
+			T::RegisterOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			Self::check_metadata(&metadata)?;
+			ensure!(Assets::contains_key(asset_id), Error::<T>::AssetNotFound);
+
+			Assets::insert(asset_id, metadata);
+			Self::deposit_event(Event::AssetUpdated(CurrencyId::Registered(asset_id)));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Register a new asset and return its currency id.
+	pub fn do_register_asset(metadata: AssetMetadata) -> Result<CurrencyId, DispatchError> {
+		Self::check_metadata(&metadata)?;
+		let asset_id = Self::next_asset_id();
+		let next_asset_id = asset_id.checked_add(1).ok_or(Error::<T>::NoAvailableAssetId)?;
+
+		NextAssetId::put(next_asset_id);
+		Assets::insert(asset_id, metadata);
+		let currency_id = CurrencyId::Registered(asset_id);
+		Self::deposit_event(Event::AssetRegistered(currency_id));
+		Ok(currency_id)
+	}
+
+	/// The metadata of `currency_id`, `None` if it's not a registered asset.
+	pub fn metadata(currency_id: CurrencyId) -> Option<AssetMetadata> {
+		match currency_id {
+			CurrencyId::Registered(asset_id) => Self::assets(asset_id),
+			_ => None,
+		}
+	}
+
+	/// Whether `currency_id` is a registered asset.
+	pub fn is_registered(currency_id: CurrencyId) -> bool {
+		match currency_id {
+			CurrencyId::Registered(asset_id) => Assets::contains_key(asset_id),
+			_ => false,
+		}
+	}
+
+	fn check_metadata(metadata: &AssetMetadata) -> DispatchResult {
+		ensure!(
+			!metadata.symbol.is_empty() && metadata.symbol.len() <= MAX_SYMBOL_LENGTH,
+			Error::<T>::InvalidSymbol,
+		);
+		Ok(())
+	}
+}
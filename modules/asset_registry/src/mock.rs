@@ -0,0 +1,102 @@
+//! Mocks for the asset registry module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types};
+use frame_system::EnsureSignedBy;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod asset_registry {
+	pub use super::super::*;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		system<T>,
+		asset_registry,
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+}
+pub type System = system::Module<Runtime>;
+
+ord_parameter_types! {
+	pub const One: AccountId = 1;
+}
+
+impl Trait for Runtime {
+	type Event = TestEvent;
+	type RegisterOrigin = EnsureSignedBy<One, AccountId>;
+}
+pub type AssetRegistryModule = Module<Runtime>;
+
+pub struct ExtBuilder {
+	assets: Vec<AssetMetadata>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self { assets: vec![] }
+	}
+}
+
+impl ExtBuilder {
+	pub fn assets(mut self, assets: Vec<AssetMetadata>) -> Self {
+		self.assets = assets;
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		GenesisConfig { assets: self.assets }
+			.assimilate_storage::<Runtime>(&mut t)
+			.unwrap();
+		t.into()
+	}
+}
@@ -0,0 +1,121 @@
+//! Unit tests for the asset registry module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{AssetRegistryModule, ExtBuilder, Origin, Runtime, System, TestEvent, ALICE, BOB};
+use sp_runtime::traits::BadOrigin;
+
+fn ren_btc() -> AssetMetadata {
+	AssetMetadata {
+		symbol: b"renBTC".to_vec(),
+		decimals: 8,
+		existential_deposit: 1_000,
+		class: AssetClass::Bridged,
+	}
+}
+
+#[test]
+fn genesis_assets_registered_in_order() {
+	let local_token = AssetMetadata {
+		symbol: b"LTKN".to_vec(),
+		decimals: 12,
+		existential_deposit: 0,
+		class: AssetClass::Local,
+	};
+	ExtBuilder::default()
+		.assets(vec![ren_btc(), local_token.clone()])
+		.build()
+		.execute_with(|| {
+			assert_eq!(AssetRegistryModule::assets(0), Some(ren_btc()));
+			assert_eq!(AssetRegistryModule::assets(1), Some(local_token));
+			assert_eq!(AssetRegistryModule::next_asset_id(), 2);
+		});
+}
+
+#[test]
+fn register_asset_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			AssetRegistryModule::register_asset(Origin::signed(BOB), ren_btc()),
+			BadOrigin
+		);
+		assert_noop!(
+			AssetRegistryModule::register_asset(
+				Origin::signed(ALICE),
+				AssetMetadata {
+					symbol: vec![],
+					..ren_btc()
+				}
+			),
+			Error::<Runtime>::InvalidSymbol,
+		);
+		assert_noop!(
+			AssetRegistryModule::register_asset(
+				Origin::signed(ALICE),
+				AssetMetadata {
+					symbol: vec![b'A'; MAX_SYMBOL_LENGTH + 1],
+					..ren_btc()
+				}
+			),
+			Error::<Runtime>::InvalidSymbol,
+		);
+
+		assert_ok!(AssetRegistryModule::register_asset(Origin::signed(ALICE), ren_btc()));
+		let registered_event = TestEvent::asset_registry(Event::AssetRegistered(CurrencyId::Registered(0)));
+		assert!(System::events().iter().any(|record| record.event == registered_event));
+		assert_eq!(AssetRegistryModule::next_asset_id(), 1);
+		assert_eq!(AssetRegistryModule::assets(0), Some(ren_btc()));
+		assert_eq!(
+			AssetRegistryModule::metadata(CurrencyId::Registered(0)),
+			Some(ren_btc())
+		);
+		assert!(AssetRegistryModule::is_registered(CurrencyId::Registered(0)));
+		assert!(!AssetRegistryModule::is_registered(CurrencyId::Registered(1)));
+		assert!(!AssetRegistryModule::is_registered(CurrencyId::XBTC));
+		assert_eq!(AssetRegistryModule::metadata(CurrencyId::XBTC), None);
+
+		assert_eq!(
+			AssetRegistryModule::do_register_asset(ren_btc()),
+			Ok(CurrencyId::Registered(1))
+		);
+	});
+}
+
+#[test]
+fn register_asset_fails_when_no_available_asset_id() {
+	ExtBuilder::default().build().execute_with(|| {
+		NextAssetId::put(AssetId::max_value());
+		assert_noop!(
+			AssetRegistryModule::register_asset(Origin::ROOT, ren_btc()),
+			Error::<Runtime>::NoAvailableAssetId,
+		);
+	});
+}
+
+#[test]
+fn update_asset_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			AssetRegistryModule::update_asset(Origin::signed(ALICE), 0, ren_btc()),
+			Error::<Runtime>::AssetNotFound,
+		);
+		assert_ok!(AssetRegistryModule::register_asset(Origin::signed(ALICE), ren_btc()));
+
+		let updated = AssetMetadata {
+			existential_deposit: 2_000,
+			..ren_btc()
+		};
+		assert_noop!(
+			AssetRegistryModule::update_asset(Origin::signed(BOB), 0, updated.clone()),
+			BadOrigin
+		);
+		assert_ok!(AssetRegistryModule::update_asset(Origin::ROOT, 0, updated.clone()));
+		let updated_event = TestEvent::asset_registry(Event::AssetUpdated(CurrencyId::Registered(0)));
+		assert!(System::events().iter().any(|record| record.event == updated_event));
+		assert_eq!(AssetRegistryModule::assets(0), Some(updated));
+	});
+}
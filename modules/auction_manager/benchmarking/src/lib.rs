@@ -10,17 +10,18 @@ use sp_std::vec;
 
 use auction_manager::Module as AuctionManager;
 use auction_manager::*;
+use emergency_shutdown::Module as EmergencyShutdown;
 use frame_benchmarking::{account, benchmarks};
 use frame_support::traits::Get;
 use frame_system::RawOrigin;
 use orml_traits::{Auction, DataProviderExtended, MultiCurrency};
 use primitives::{Balance, CurrencyId};
 use sp_runtime::FixedPointNumber;
-use support::{AuctionManager as AuctionManagerTrait, CDPTreasury, OnEmergencyShutdown, Price};
+use support::{AuctionManager as AuctionManagerTrait, CDPTreasury, Price};
 
 pub struct Module<T: Trait>(auction_manager::Module<T>);
 
-pub trait Trait: auction_manager::Trait + orml_oracle::Trait + prices::Trait {}
+pub trait Trait: auction_manager::Trait + orml_oracle::Trait + prices::Trait + emergency_shutdown::Trait {}
 
 const SEED: u32 = 0;
 
@@ -63,8 +64,9 @@ benchmarks! {
 		// bid surplus auction
 		let _ = AuctionManager::<T>::surplus_auction_bid_handler(1.into(), auction_id, (bidder, dollar(1)), None);
 
-		// shutdown
-		AuctionManager::<T>::on_emergency_shutdown();
+		// shutdown and start settlement
+		EmergencyShutdown::<T>::emergency_shutdown(RawOrigin::Root.into())?;
+		EmergencyShutdown::<T>::start_settlement(RawOrigin::Root.into())?;
 	}: cancel(RawOrigin::None, auction_id)
 
 	// `cancel` a debit auction, worst case:
@@ -85,8 +87,9 @@ benchmarks! {
 		// bid debit auction
 		let _ = AuctionManager::<T>::debit_auction_bid_handler(1.into(), auction_id, (bidder, dollar(20)), None);
 
-		// shutdown
-		AuctionManager::<T>::on_emergency_shutdown();
+		// shutdown and start settlement
+		EmergencyShutdown::<T>::emergency_shutdown(RawOrigin::Root.into())?;
+		EmergencyShutdown::<T>::start_settlement(RawOrigin::Root.into())?;
 	}: cancel(RawOrigin::None, auction_id)
 
 	// `cancel` a collateral auction, worst case:
@@ -113,8 +116,9 @@ benchmarks! {
 		// bid collateral auction
 		let _ = AuctionManager::<T>::collateral_auction_bid_handler(1.into(), auction_id, (bidder, dollar(80)), None);
 
-		// shutdown
-		AuctionManager::<T>::on_emergency_shutdown();
+		// shutdown and start settlement
+		EmergencyShutdown::<T>::emergency_shutdown(RawOrigin::Root.into())?;
+		EmergencyShutdown::<T>::start_settlement(RawOrigin::Root.into())?;
 	}: cancel(RawOrigin::None, auction_id)
 }
 
@@ -9,10 +9,10 @@ use orml_oracle::DefaultCombineData;
 use primitives::{Amount, Balance, CurrencyId};
 use sp_runtime::{
 	testing::{Header, TestXt, UintAuthorityId},
-	traits::IdentityLookup,
+	traits::{Convert, IdentityLookup},
 };
 use sp_std::vec;
-use support::{ExchangeRate, ExchangeRateProvider, Price, Rate};
+use support::{ExchangeRate, ExchangeRateProvider, Price, Rate, Ratio};
 
 impl_outer_dispatch! {
 	pub enum Call for Runtime where origin: Origin {
@@ -113,6 +113,7 @@ parameter_types! {
 	pub MinimumIncrementSize: Rate = Rate::saturating_from_rational(1, 20);
 	pub const AuctionTimeToClose: u64 = 100;
 	pub const AuctionDurationSoftCap: u64 = 2000;
+	pub const MinimumRunnerUpBidDuration: u64 = 10;
 	pub const GetStableCurrencyId: CurrencyId = AUSD;
 	pub GetAmountAdjustment: Rate = Rate::saturating_from_rational(1, 2);
 	pub const UnsignedPriority: u64 = 1 << 20;
@@ -132,6 +133,9 @@ impl auction_manager::Trait for Runtime {
 	type DEX = ();
 	type PriceSource = prices::Module<Runtime>;
 	type UnsignedPriority = UnsignedPriority;
+	type EmergencyShutdown = EmergencyShutdownModule;
+	type OnCollateralAuctionDealt = ();
+	type MinimumRunnerUpBidDuration = MinimumRunnerUpBidDuration;
 }
 pub type AuctionManagerModule = auction_manager::Module<Runtime>;
 
@@ -162,9 +166,52 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = ();
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = EmergencyShutdownModule;
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
+pub struct MockConvert;
+impl Convert<(CurrencyId, Balance), Balance> for MockConvert {
+	fn convert(a: (CurrencyId, Balance)) -> Balance {
+		a.1
+	}
+}
+
+impl loans::Trait for Runtime {
+	type Event = ();
+	type Convert = MockConvert;
+	type Currency = Currencies;
+	type RiskManager = ();
+	type DebitBalance = Balance;
+	type DebitAmount = Amount;
+	type CDPTreasury = CDPTreasuryModule;
+	type EmergencyShutdown = EmergencyShutdownModule;
+	type OnUpdateLoan = ();
+	type OnUpdateCollateral = ();
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type PositionDeposit = ();
+}
+
+parameter_types! {
+	pub const ClaimsPeriod: BlockNumber = 100;
+	pub SystemCollateralRatio: Option<Ratio> = None;
+}
+
+impl emergency_shutdown::Trait for Runtime {
+	type Event = ();
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = CDPTreasuryModule;
+	type AuctionManagerHandler = AuctionManagerModule;
+	type OnShutdown = (prices::Module<Runtime>, AuctionManagerModule);
+	type ShutdownOrigin = EnsureSignedBy<One, AccountId>;
+	type CancelShutdownOrigin = EnsureSignedBy<One, AccountId>;
+	type PriceSource = prices::Module<Runtime>;
+	type SystemCollateralRatio = SystemCollateralRatio;
+	type ClaimsPeriod = ClaimsPeriod;
+}
+pub type EmergencyShutdownModule = emergency_shutdown::Module<Runtime>;
+
 parameter_types! {
 	pub const MinimumPeriod: u64 = 5;
 }
@@ -192,6 +239,13 @@ impl orml_oracle::Trait for Runtime {
 }
 pub type ModuleOracle = orml_oracle::Module<Runtime>;
 
+pub struct MockDispersion;
+impl orml_traits::DataProvider<CurrencyId, Ratio> for MockDispersion {
+	fn get(_currency_id: &CurrencyId) -> Option<Ratio> {
+		None
+	}
+}
+
 pub struct MockLiquidStakingExchangeProvider;
 impl ExchangeRateProvider for MockLiquidStakingExchangeProvider {
 	fn get_exchange_rate() -> ExchangeRate {
@@ -203,17 +257,27 @@ parameter_types! {
 	pub const GetStakingCurrencyId: CurrencyId = DOT;
 	pub const GetLiquidCurrencyId: CurrencyId = LDOT;
 	pub StableCurrencyFixedPrice: Price = Price::saturating_from_integer(1);
+	pub const MaxEmergencyPriceDuration: BlockNumber = 100;
+	pub const HeartbeatThreshold: BlockNumber = 100;
+	pub const PriceHistoryDepth: u32 = 10;
+	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![DOT];
 }
 
 impl prices::Trait for Runtime {
 	type Event = ();
 	type Source = orml_oracle::Module<Runtime>;
+	type ExternalSource = prices::NoExternalSource;
+	type Dispersion = MockDispersion;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type StableCurrencyFixedPrice = StableCurrencyFixedPrice;
-	type GetStakingCurrencyId = GetStakingCurrencyId;
-	type GetLiquidCurrencyId = GetLiquidCurrencyId;
 	type LockOrigin = EnsureSignedBy<One, AccountId>;
-	type LiquidStakingExchangeRateProvider = MockLiquidStakingExchangeProvider;
+	type DerivativeAssets =
+		prices::DerivativeAssetAdapter<GetLiquidCurrencyId, GetStakingCurrencyId, MockLiquidStakingExchangeProvider>;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type MaxEmergencyPriceDuration = MaxEmergencyPriceDuration;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type HeartbeatThreshold = HeartbeatThreshold;
+	type PriceHistoryDepth = PriceHistoryDepth;
 }
 
 impl crate::Trait for Runtime {}
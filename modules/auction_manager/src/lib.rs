@@ -23,6 +23,8 @@ use frame_system::{
 };
 use orml_traits::{Auction, AuctionHandler, Change, MultiCurrency, OnNewBidResult};
 use primitives::{Balance, CurrencyId};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_runtime::{
 	traits::{BlakeTwo256, Hash, Saturating, Zero},
 	transaction_validity::{
@@ -32,32 +34,51 @@ use sp_runtime::{
 };
 use sp_std::{
 	cmp::{Eq, PartialEq},
+	collections::btree_map::BTreeMap,
 	prelude::*,
 };
-use support::{AuctionManager, CDPTreasury, CDPTreasuryExtended, DEXManager, OnEmergencyShutdown, PriceProvider, Rate};
-use utilities::{OffchainErr, OffchainLock};
+use support::{
+	AuctionManager, CDPTreasury, CDPTreasuryExtended, DEXManager, EmergencyShutdown, OnCollateralAuctionDealt,
+	OnEmergencyShutdown, PriceProvider, Rate, ShutdownPhase,
+};
+use utilities::{OffchainErr, OffchainLock, OffchainWorkerStatus};
 
 mod mock;
 mod tests;
 
 const DB_PREFIX: &[u8] = b"acala/auction-manager-offchain-worker/";
 
+/// The offchain persistent storage key of the status of last offchain worker run
+pub const OFFCHAIN_WORKER_STATUS_KEY: &[u8] = b"acala/auction-manager-offchain-worker/status";
+
+/// The type of auctions
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum AuctionType {
+	/// Auction to sell native currency for stable currency to recover debit
+	Debit,
+	/// Auction to sell surplus stable currency for native currency
+	Surplus,
+	/// Auction to sell confiscated collateral for stable currency
+	Collateral,
+}
+
 /// Information of an collateral auction
 #[cfg_attr(feature = "std", derive(PartialEq, Eq))]
 #[derive(Encode, Decode, Clone, RuntimeDebug)]
 pub struct CollateralAuctionItem<AccountId, BlockNumber> {
 	/// Refund recipient for may receive refund
-	refund_recipient: AccountId,
+	pub refund_recipient: AccountId,
 	/// Collateral type for sale
-	currency_id: CurrencyId,
+	pub currency_id: CurrencyId,
 	/// current collateral amount for sale
 	#[codec(compact)]
-	amount: Balance,
+	pub amount: Balance,
 	/// Target sales amount want to get by this auction
 	#[codec(compact)]
-	target: Balance,
+	pub target: Balance,
 	/// Auction start time
-	start_time: BlockNumber,
+	pub start_time: BlockNumber,
 }
 
 /// Information of an debit auction
@@ -129,6 +150,15 @@ pub trait Trait: SendTransactionTypes<Call<Self>> + system::Trait {
 	/// This is exposed so that it can be tuned for particular runtime, when
 	/// multiple modules send unsigned transactions.
 	type UnsignedPriority: Get<TransactionPriority>;
+
+	/// Emergency shutdown phase, active auctions are only allowed to be cancelled when settling
+	type EmergencyShutdown: EmergencyShutdown;
+
+	/// Hook called after a collateral auction is dealt with bidders, to reward the bidders
+	type OnCollateralAuctionDealt: OnCollateralAuctionDealt<Self::AccountId, CurrencyId, Balance>;
+
+	/// The minimum blocks a bid of collateral auction must stand before outbid to be recorded as the runner-up
+	type MinimumRunnerUpBidDuration: Get<Self::BlockNumber>;
 }
 
 decl_event!(
@@ -169,6 +199,8 @@ decl_error! {
 		InvalidFeedPrice,
 		/// Must after system shutdown
 		MustAfterShutdown,
+		/// Cancel auction is not allowed in current shutdown phase
+		InvalidShutdownPhase,
 	}
 }
 
@@ -186,6 +218,15 @@ decl_storage! {
 		pub SurplusAuctions get(fn surplus_auctions): map hasher(twox_64_concat) AuctionIdOf<T> =>
 			Option<SurplusAuctionItem<T::BlockNumber>>;
 
+		/// Mapping from auction id to the last outbid bid of collateral auction from another bidder than
+		/// the current one, as (bidder, bid_price)
+		pub CollateralAuctionRunnerUp get(fn collateral_auction_runner_up): map hasher(twox_64_concat) AuctionIdOf<T> =>
+			Option<(T::AccountId, Balance)>;
+
+		/// Mapping from auction id to the block the current bidder of collateral auction placed the first bid
+		pub CollateralAuctionBidAt get(fn collateral_auction_bid_at): map hasher(twox_64_concat) AuctionIdOf<T> =>
+			Option<T::BlockNumber>;
+
 		/// Record of the total collateral amount of all ative collateral auctions under specific collateral type
 		/// CollateralType -> TotalAmount
 		pub TotalCollateralInAuction get(fn total_collateral_in_auction): map hasher(twox_64_concat) CurrencyId => Balance;
@@ -228,6 +269,9 @@ decl_module! {
 		/// The decrement of amout in debit auction when restocking
 		const GetAmountAdjustment: Rate = T::GetAmountAdjustment::get();
 
+		/// The minimum blocks a bid of collateral auction must stand before outbid to be recorded as the runner-up
+		const MinimumRunnerUpBidDuration: T::BlockNumber = T::MinimumRunnerUpBidDuration::get();
+
 		/// Cancel active auction after system shutdown
 		///
 		/// The dispatch origin of this call must be _None_.
@@ -254,40 +298,49 @@ decl_module! {
 		///		- debit auction worst case: 27.63 µs
 		///		- collateral auction worst case: 80.13 µs
 		/// # </weight>
-		#[weight = (80 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(9, 9), DispatchClass::Operational)]
+		#[weight = (80 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(10, 9), DispatchClass::Operational)]
 		pub fn cancel(origin, id: AuctionIdOf<T>) {
 			ensure_none(origin)?;
 			ensure!(Self::is_shutdown(), Error::<T>::MustAfterShutdown);
+			ensure!(Self::is_settling(), Error::<T>::InvalidShutdownPhase);
 			<Module<T> as AuctionManager<T::AccountId>>::cancel_auction(id)?;
 		}
 
 		/// Start offchain worker in order to submit unsigned tx to cancel active auction
 		/// after system shutdown.
 		fn offchain_worker(now: T::BlockNumber) {
-			if Self::is_shutdown() && sp_io::offchain::is_validator() {
-				if let Err(e) = Self::_offchain_worker(now) {
+			if Self::is_shutdown() && Self::is_settling() && sp_io::offchain::is_validator() {
+				let mut status = OffchainWorkerStatus::new(now);
+				if let Err(e) = Self::_offchain_worker(now, &mut status) {
 					debug::info!(
 						target: "auction-manager offchain worker",
 						"cannot run offchain worker at {:?}: {:?}",
 						now,
 						e,
 					);
+					status.error = Some(e);
 				}
+				status.record(OFFCHAIN_WORKER_STATUS_KEY);
 			}
 		}
 	}
 }
 
 impl<T: Trait> Module<T> {
-	fn submit_cancel_auction_tx(auction_id: AuctionIdOf<T>) {
+	fn submit_cancel_auction_tx(
+		auction_id: AuctionIdOf<T>,
+		status: &mut OffchainWorkerStatus<T::BlockNumber, AuctionType>,
+	) {
 		let call = Call::<T>::cancel(auction_id);
 		if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).is_err() {
+			status.failed += 1;
 			debug::warn!(
 				target: "auction-manager offchain worker",
 				"submit unsigned auction cancel tx for \nAuctionId {:?} failed : {:?}",
 				auction_id, OffchainErr::SubmitTransaction,
 			);
 		} else {
+			status.submitted += 1;
 			debug::debug!(
 				target: "auction-manager offchain worker",
 				"successfully submit unsigned auction cancel tx for \nAuctionId {:?}",
@@ -296,31 +349,49 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
-	fn _offchain_worker(now: T::BlockNumber) -> Result<(), OffchainErr> {
-		// Acquire offchain worker lock.
+	fn _offchain_worker(
+		now: T::BlockNumber,
+		status: &mut OffchainWorkerStatus<T::BlockNumber, AuctionType>,
+	) -> Result<(), OffchainErr> {
+		// Acquire the offchain worker lock of an auction type, starting from a random one,
+		// so that concurrent workers scan different auction types.
 		// If succeeded, update the lock, otherwise return error
-		let offchain_lock = OffchainLock::new(DB_PREFIX.to_vec());
-		offchain_lock.acquire_offchain_lock(|_: Option<()>| ())?;
-
+		let auction_types = [AuctionType::Debit, AuctionType::Surplus, AuctionType::Collateral];
 		let random_seed = sp_io::offchain::random_seed();
 		let mut rng = RandomNumberGenerator::<BlakeTwo256>::new(BlakeTwo256::hash(&random_seed[..]));
-		match rng.pick_u32(2) {
-			0 => {
+		let start = rng.pick_u32(2) as usize;
+		let (auction_type, offchain_lock) = (0..auction_types.len())
+			.map(|offset| auction_types[(start + offset) % auction_types.len()])
+			.find_map(|auction_type| {
+				let offchain_lock = OffchainLock::new_sharded(DB_PREFIX, &auction_type);
+				offchain_lock
+					.acquire_offchain_lock(|_: Option<()>| ())
+					.ok()
+					.map(|_| (auction_type, offchain_lock))
+			})
+			.ok_or(OffchainErr::OffchainLock)?;
+
+		status.scope = Some(auction_type);
+		match auction_type {
+			AuctionType::Debit => {
 				for (auction_id, _) in <DebitAuctions<T>>::iter() {
-					Self::submit_cancel_auction_tx(auction_id);
+					status.scanned += 1;
+					Self::submit_cancel_auction_tx(auction_id, status);
 					offchain_lock.extend_offchain_lock_if_needed::<()>();
 				}
 			}
-			1 => {
+			AuctionType::Surplus => {
 				for (auction_id, _) in <SurplusAuctions<T>>::iter() {
-					Self::submit_cancel_auction_tx(auction_id);
+					status.scanned += 1;
+					Self::submit_cancel_auction_tx(auction_id, status);
 					offchain_lock.extend_offchain_lock_if_needed::<()>();
 				}
 			}
-			_ => {
+			AuctionType::Collateral => {
 				for (auction_id, _) in <CollateralAuctions<T>>::iter() {
+					status.scanned += 1;
 					if !Self::collateral_auction_in_reverse_stage(auction_id) {
-						Self::submit_cancel_auction_tx(auction_id);
+						Self::submit_cancel_auction_tx(auction_id, status);
 					}
 					offchain_lock.extend_offchain_lock_if_needed::<()>();
 				}
@@ -342,6 +413,76 @@ impl<T: Trait> Module<T> {
 		<IsShutdown>::put(true);
 	}
 
+	/// Check the accounting invariants of auctions: the totals in auction equal the sum of
+	/// active auctions, and the collateral in auction is kept by CDP treasury.
+	pub fn check_invariants() -> Result<(), &'static str> {
+		let mut collaterals: BTreeMap<CurrencyId, Balance> = BTreeMap::new();
+		let mut total_target: Balance = Zero::zero();
+		for (_, collateral_auction) in <CollateralAuctions<T>>::iter() {
+			let total = collaterals.entry(collateral_auction.currency_id).or_default();
+			*total = total.saturating_add(collateral_auction.amount);
+			total_target = total_target.saturating_add(collateral_auction.target);
+		}
+		for (currency_id, total_collateral) in TotalCollateralInAuction::iter() {
+			ensure!(
+				collaterals.remove(&currency_id).unwrap_or_default() == total_collateral,
+				"total collateral in auction not equal to the sum of collateral auctions"
+			);
+			ensure!(
+				T::CDPTreasury::get_total_collaterals(currency_id) >= total_collateral,
+				"collateral in auction exceeds collaterals of CDP treasury"
+			);
+		}
+		ensure!(
+			collaterals.values().all(|collateral| collateral.is_zero()),
+			"total collateral in auction not equal to the sum of collateral auctions"
+		);
+		ensure!(
+			Self::total_target_in_auction() == total_target,
+			"total target in auction not equal to the sum of collateral auctions"
+		);
+
+		let total_debit = <DebitAuctions<T>>::iter().fold(Balance::zero(), |total, (_, debit_auction)| {
+			total.saturating_add(debit_auction.fix)
+		});
+		ensure!(
+			Self::total_debit_in_auction() == total_debit,
+			"total debit in auction not equal to the sum of debit auctions"
+		);
+
+		let total_surplus = <SurplusAuctions<T>>::iter().fold(Balance::zero(), |total, (_, surplus_auction)| {
+			total.saturating_add(surplus_auction.amount)
+		});
+		ensure!(
+			Self::total_surplus_in_auction() == total_surplus,
+			"total surplus in auction not equal to the sum of surplus auctions"
+		);
+
+		Ok(())
+	}
+
+	/// Whether emergency shutdown is in the phase of cancelling active auctions
+	pub fn is_settling() -> bool {
+		T::EmergencyShutdown::shutdown_phase() == ShutdownPhase::Settling
+	}
+
+	/// Cancel the active auctions when settlement starts, the ones failed to be
+	/// cancelled here are left to the offchain worker.
+	pub fn cancel_active_auctions() {
+		let auction_ids = <DebitAuctions<T>>::iter()
+			.map(|(id, _)| id)
+			.chain(<SurplusAuctions<T>>::iter().map(|(id, _)| id))
+			.chain(
+				<CollateralAuctions<T>>::iter()
+					.map(|(id, _)| id)
+					.filter(|id| !Self::collateral_auction_in_reverse_stage(*id)),
+			)
+			.collect::<Vec<_>>();
+		for id in auction_ids {
+			let _ = <Module<T> as AuctionManager<T::AccountId>>::cancel_auction(id);
+		}
+	}
+
 	pub fn cancel_surplus_auction(id: AuctionIdOf<T>) -> DispatchResult {
 		let surplus_auction = <SurplusAuctions<T>>::take(id).ok_or(Error::<T>::AuctionNotExsits)?;
 		if let Some(auction_info) = T::Auction::auction_info(id) {
@@ -441,6 +582,8 @@ impl<T: Trait> Module<T> {
 
 		// remove collateral auction
 		<CollateralAuctions<T>>::remove(id);
+		<CollateralAuctionRunnerUp<T>>::remove(id);
+		<CollateralAuctionBidAt<T>>::remove(id);
 		T::Auction::remove_auction(id);
 
 		<Module<T>>::deposit_event(RawEvent::CancelAuction(id));
@@ -518,6 +661,7 @@ impl<T: Trait> Module<T> {
 				system::Module::<T>::inc_ref(&new_bid.0);
 
 				// if these's bid before, return stablecoin from new bidder to last bidder
+				let mut is_new_bidder = true;
 				if let Some((last_bidder, last_price)) = last_bid {
 					let refund = sp_std::cmp::min(last_price, collateral_auction.target);
 					T::Currency::transfer(stable_currency_id, &new_bid.0, &last_bidder, refund)
@@ -527,6 +671,19 @@ impl<T: Trait> Module<T> {
 					system::Module::<T>::dec_ref(&last_bidder);
 
 					payment -= refund;
+
+					// record the outbid bid which has stood long enough, so that the bids outbid
+					// right away are not rewarded, raising own bid keeps the previous runner-up
+					is_new_bidder = last_bidder != new_bid.0;
+					let stood_long_enough = Self::collateral_auction_bid_at(id).map_or(false, |bid_at| {
+						now.saturating_sub(bid_at) >= T::MinimumRunnerUpBidDuration::get()
+					});
+					if is_new_bidder && stood_long_enough {
+						<CollateralAuctionRunnerUp<T>>::insert(id, (last_bidder, last_price));
+					}
+				}
+				if is_new_bidder {
+					<CollateralAuctionBidAt<T>>::insert(id, now);
 				}
 
 				if !payment.is_zero() {
@@ -706,10 +863,11 @@ impl<T: Trait> Module<T> {
 						collateral_auction.amount,
 					) {
 				// try trade with DEX
-				if let Ok(amount) = T::CDPTreasury::swap_collateral_to_stable(
+				if let Ok(amount) = T::CDPTreasury::swap_exact_collateral_to_stable(
 					collateral_auction.currency_id,
 					collateral_auction.amount,
 					Zero::zero(),
+					&[collateral_auction.currency_id, stable_currency_id],
 				) {
 					// swap successfully, will not deal
 					should_deal = false;
@@ -749,6 +907,14 @@ impl<T: Trait> Module<T> {
 					bidder.clone(),
 					sp_std::cmp::min(collateral_auction.target, bid_price),
 				));
+
+				T::OnCollateralAuctionDealt::on_collateral_auction_dealt(
+					&collateral_auction.refund_recipient,
+					collateral_auction.currency_id,
+					collateral_auction.target,
+					(bidder.clone(), bid_price),
+					Self::collateral_auction_runner_up(auction_id),
+				);
 			}
 
 			// decrease account ref of bidder and refund recipient
@@ -761,6 +927,8 @@ impl<T: Trait> Module<T> {
 			});
 			TotalTargetInAuction::mutate(|balance| *balance = balance.saturating_sub(collateral_auction.target));
 			<CollateralAuctions<T>>::remove(auction_id);
+			<CollateralAuctionRunnerUp<T>>::remove(auction_id);
+			<CollateralAuctionBidAt<T>>::remove(auction_id);
 		}
 	}
 
@@ -969,6 +1137,16 @@ impl<T: Trait> OnEmergencyShutdown for Module<T> {
 	fn on_emergency_shutdown() {
 		Self::emergency_shutdown();
 	}
+
+	fn on_cancel_shutdown() {
+		<IsShutdown>::put(false);
+	}
+
+	fn on_shutdown_phase_changed(phase: ShutdownPhase) {
+		if phase == ShutdownPhase::Settling {
+			Self::cancel_active_auctions();
+		}
+	}
 }
 
 #[allow(deprecated)]
@@ -977,7 +1155,7 @@ impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
 
 	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
 		if let Call::cancel(auction_id) = call {
-			if !Self::is_shutdown() {
+			if !Self::is_shutdown() || !Self::is_settling() {
 				return InvalidTransaction::Stale.into();
 			} else if <CollateralAuctions<T>>::contains_key(auction_id) {
 				if !Self::collateral_auction_in_reverse_stage(*auction_id) {
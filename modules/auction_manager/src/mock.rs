@@ -11,7 +11,8 @@ use sp_runtime::{
 	traits::IdentityLookup,
 	Perbill,
 };
-use support::Price;
+use sp_std::cell::RefCell;
+use support::{Price, ShutdownPhase};
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -123,9 +124,41 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = DEXModule;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = MockEmergencyShutdown;
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
+thread_local! {
+	pub static SHUTDOWN_PHASE: RefCell<ShutdownPhase> = RefCell::new(ShutdownPhase::Operational);
+}
+
+pub struct MockEmergencyShutdown;
+impl EmergencyShutdown for MockEmergencyShutdown {
+	fn shutdown_phase() -> ShutdownPhase {
+		SHUTDOWN_PHASE.with(|v| *v.borrow())
+	}
+}
+
+thread_local! {
+	pub static DEALT_COLLATERAL_AUCTIONS: RefCell<Vec<(AccountId, CurrencyId, Balance, (AccountId, Balance), Option<(AccountId, Balance)>)>> = RefCell::new(vec![]);
+}
+
+pub struct MockOnCollateralAuctionDealt;
+impl OnCollateralAuctionDealt<AccountId, CurrencyId, Balance> for MockOnCollateralAuctionDealt {
+	fn on_collateral_auction_dealt(
+		refund_recipient: &AccountId,
+		currency_id: CurrencyId,
+		target: Balance,
+		winner: (AccountId, Balance),
+		runner_up: Option<(AccountId, Balance)>,
+	) {
+		DEALT_COLLATERAL_AUCTIONS.with(|v| {
+			v.borrow_mut()
+				.push((*refund_recipient, currency_id, target, winner, runner_up))
+		});
+	}
+}
+
 pub struct MockPriceSource;
 impl PriceProvider<CurrencyId> for MockPriceSource {
 	fn get_relative_price(_base: CurrencyId, _quota: CurrencyId) -> Option<Price> {
@@ -155,6 +188,7 @@ impl dex::Trait for Runtime {
 	type GetExchangeFee = GetExchangeFee;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type OnUpdateLiquidity = ();
 }
 pub type DEXModule = dex::Module<Runtime>;
 
@@ -162,6 +196,7 @@ parameter_types! {
 	pub MinimumIncrementSize: Rate = Rate::saturating_from_rational(1, 20);
 	pub const AuctionTimeToClose: u64 = 100;
 	pub const AuctionDurationSoftCap: u64 = 2000;
+	pub const MinimumRunnerUpBidDuration: u64 = 10;
 	pub const GetNativeCurrencyId: CurrencyId = ACA;
 	pub GetAmountAdjustment: Rate = Rate::saturating_from_rational(1, 2);
 	pub const UnsignedPriority: u64 = 1 << 20;
@@ -181,6 +216,9 @@ impl Trait for Runtime {
 	type DEX = DEXModule;
 	type PriceSource = MockPriceSource;
 	type UnsignedPriority = UnsignedPriority;
+	type EmergencyShutdown = MockEmergencyShutdown;
+	type OnCollateralAuctionDealt = MockOnCollateralAuctionDealt;
+	type MinimumRunnerUpBidDuration = MinimumRunnerUpBidDuration;
 }
 pub type AuctionManagerModule = Module<Runtime>;
 
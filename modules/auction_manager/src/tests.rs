@@ -6,7 +6,7 @@ use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::{
 	Auction as AuctionModule, AuctionManagerModule, CDPTreasuryModule, DEXModule, ExtBuilder, Origin, Runtime, System,
-	TestEvent, Tokens, ACA, ALICE, AUSD, BOB, BTC, CAROL,
+	TestEvent, Tokens, ACA, ALICE, AUSD, BOB, BTC, CAROL, DEALT_COLLATERAL_AUCTIONS, SHUTDOWN_PHASE,
 };
 
 #[test]
@@ -246,6 +246,60 @@ fn on_auction_ended_for_collateral_auction_and_dealed() {
 	});
 }
 
+#[test]
+fn on_collateral_auction_dealt_with_runner_up() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &CAROL, 100));
+		AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 100, 200);
+		assert_eq!(
+			AuctionManagerModule::on_new_bid(1, 0, (CAROL, 100), None).accept_bid,
+			true
+		);
+		assert_eq!(AuctionManagerModule::collateral_auction_runner_up(0), None);
+		assert_eq!(AuctionManagerModule::collateral_auction_bid_at(0), Some(1));
+		assert_eq!(
+			AuctionManagerModule::on_new_bid(11, 0, (BOB, 150), Some((CAROL, 100))).accept_bid,
+			true
+		);
+		assert_eq!(
+			AuctionManagerModule::collateral_auction_runner_up(0),
+			Some((CAROL, 100))
+		);
+		assert_eq!(AuctionManagerModule::collateral_auction_bid_at(0), Some(11));
+
+		// raising own bid keeps the runner-up and the bid time
+		assert_eq!(
+			AuctionManagerModule::on_new_bid(12, 0, (BOB, 180), Some((BOB, 150))).accept_bid,
+			true
+		);
+		assert_eq!(
+			AuctionManagerModule::collateral_auction_runner_up(0),
+			Some((CAROL, 100))
+		);
+		assert_eq!(AuctionManagerModule::collateral_auction_bid_at(0), Some(11));
+
+		// the bid outbid before `MinimumRunnerUpBidDuration` is not recorded
+		assert_eq!(
+			AuctionManagerModule::on_new_bid(20, 0, (CAROL, 200), Some((BOB, 180))).accept_bid,
+			true
+		);
+		assert_eq!(
+			AuctionManagerModule::collateral_auction_runner_up(0),
+			Some((CAROL, 100))
+		);
+		assert_eq!(AuctionManagerModule::collateral_auction_bid_at(0), Some(20));
+
+		AuctionManagerModule::on_auction_ended(0, Some((CAROL, 200)));
+		assert_eq!(AuctionManagerModule::collateral_auction_runner_up(0), None);
+		assert_eq!(AuctionManagerModule::collateral_auction_bid_at(0), None);
+		assert_eq!(
+			DEALT_COLLATERAL_AUCTIONS.with(|v| v.borrow().clone()),
+			vec![(ALICE, BTC, 200, (CAROL, 200), Some((CAROL, 100)))]
+		);
+	});
+}
+
 #[test]
 fn on_auction_ended_for_collateral_auction_and_dex_take() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -373,6 +427,57 @@ fn cancel_surplus_auction_work() {
 	});
 }
 
+#[test]
+fn cancel_only_in_settling_phase() {
+	ExtBuilder::default().build().execute_with(|| {
+		AuctionManagerModule::new_surplus_auction(100);
+		assert_noop!(
+			AuctionManagerModule::cancel(Origin::NONE, 0),
+			Error::<Runtime>::MustAfterShutdown,
+		);
+
+		AuctionManagerModule::on_emergency_shutdown();
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
+		assert_noop!(
+			AuctionManagerModule::cancel(Origin::NONE, 0),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Settling);
+		assert_ok!(AuctionManagerModule::cancel(Origin::NONE, 0));
+		assert_eq!(AuctionManagerModule::surplus_auctions(0).is_some(), false);
+	});
+}
+
+#[test]
+fn cancel_active_auctions_when_settling_starts() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &CAROL, 20));
+		AuctionManagerModule::new_surplus_auction(100);
+		AuctionManagerModule::new_debit_auction(200, 100);
+		AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 10, 100);
+		AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 10, 100);
+		assert_ok!(AuctionModule::bid(Some(BOB).into(), 3, 100));
+		assert_eq!(AuctionManagerModule::collateral_auction_in_reverse_stage(3), true);
+
+		AuctionManagerModule::on_emergency_shutdown();
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
+		AuctionManagerModule::on_shutdown_phase_changed(ShutdownPhase::Frozen);
+		assert_eq!(AuctionManagerModule::surplus_auctions(0).is_some(), true);
+
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Settling);
+		AuctionManagerModule::on_shutdown_phase_changed(ShutdownPhase::Settling);
+		assert_eq!(AuctionManagerModule::surplus_auctions(0).is_some(), false);
+		assert_eq!(AuctionManagerModule::debit_auctions(1).is_some(), false);
+		assert_eq!(AuctionManagerModule::collateral_auctions(2).is_some(), false);
+		// the collateral auction in reverse stage is left to be dealt
+		assert_eq!(AuctionManagerModule::collateral_auctions(3).is_some(), true);
+		assert_eq!(AuctionManagerModule::total_surplus_in_auction(), 0);
+		assert_eq!(AuctionManagerModule::total_debit_in_auction(), 0);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 10);
+	});
+}
+
 #[test]
 fn cancel_debit_auction_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -469,3 +574,43 @@ fn cancel_collateral_auction_work() {
 		assert_eq!(AuctionModule::auction_info(0).is_some(), false);
 	});
 }
+
+#[test]
+fn check_invariants_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(AuctionManagerModule::check_invariants());
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &CAROL, 100));
+		AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 100, 200);
+		AuctionManagerModule::new_debit_auction(200, 100);
+		AuctionManagerModule::new_surplus_auction(100);
+		assert_ok!(AuctionManagerModule::check_invariants());
+
+		TotalCollateralInAuction::insert(BTC, 50);
+		assert_eq!(
+			AuctionManagerModule::check_invariants(),
+			Err("total collateral in auction not equal to the sum of collateral auctions")
+		);
+		TotalCollateralInAuction::insert(BTC, 100);
+
+		TotalDebitInAuction::put(99);
+		assert_eq!(
+			AuctionManagerModule::check_invariants(),
+			Err("total debit in auction not equal to the sum of debit auctions")
+		);
+		TotalDebitInAuction::put(100);
+
+		TotalSurplusInAuction::put(0);
+		assert_eq!(
+			AuctionManagerModule::check_invariants(),
+			Err("total surplus in auction not equal to the sum of surplus auctions")
+		);
+		TotalSurplusInAuction::put(100);
+		assert_ok!(AuctionManagerModule::check_invariants());
+
+		assert_ok!(CDPTreasuryModule::transfer_collateral_to(BTC, &CAROL, 50));
+		assert_eq!(
+			AuctionManagerModule::check_invariants(),
+			Err("collateral in auction exceeds collaterals of CDP treasury")
+		);
+	});
+}
@@ -12,20 +12,24 @@ use frame_benchmarking::{account, benchmarks};
 use frame_support::traits::Get;
 use frame_system::RawOrigin;
 use sp_runtime::{
-	traits::{CheckedDiv, UniqueSaturatedInto},
+	traits::{CheckedDiv, UniqueSaturatedInto, Zero},
 	FixedPointNumber,
 };
 
 use cdp_engine::Module as CdpEngine;
 use cdp_engine::*;
 use dex::Module as Dex;
+use emergency_shutdown::Module as EmergencyShutdown;
 use orml_traits::{Change, DataProviderExtended, MultiCurrencyExtended};
 use primitives::{Balance, CurrencyId};
-use support::{ExchangeRate, OnEmergencyShutdown, Price, Rate, Ratio};
+use support::{ExchangeRate, Price, Rate, Ratio};
 
 pub struct Module<T: Trait>(cdp_engine::Module<T>);
 
-pub trait Trait: cdp_engine::Trait + orml_oracle::Trait + prices::Trait + dex::Trait {}
+pub trait Trait:
+	cdp_engine::Trait + orml_oracle::Trait + prices::Trait + dex::Trait + emergency_shutdown::Trait
+{
+}
 
 const SEED: u32 = 0;
 
@@ -87,6 +91,52 @@ benchmarks! {
 		let u in 0 .. 1000;
 	}: _(RawOrigin::Root, Rate::saturating_from_rational(1, 1000000))
 
+	set_collateral_params_bounds {
+		let u in 0 .. 1000;
+	}: _(
+		RawOrigin::Root,
+		CurrencyId::DOT,
+		RiskManagementParamsBounds {
+			maximum_total_debit_value: Some((0, dollar(1000000))),
+			stability_fee: Some((Rate::zero(), Rate::saturating_from_rational(1, 1000))),
+			liquidation_ratio: Some((
+				Ratio::saturating_from_rational(110, 100),
+				Ratio::saturating_from_rational(300, 100),
+			)),
+			liquidation_penalty: Some((Rate::zero(), Rate::saturating_from_rational(50, 100))),
+			required_collateral_ratio: Some((
+				Ratio::saturating_from_rational(110, 100),
+				Ratio::saturating_from_rational(400, 100),
+			)),
+		}
+	)
+
+	set_global_params_bounds {
+		let u in 0 .. 1000;
+	}: _(RawOrigin::Root, Some((Rate::zero(), Rate::saturating_from_rational(1, 1000))))
+
+	ramp_collateral_ratios {
+		let u in 0 .. 1000;
+	}: _(
+		RawOrigin::Root,
+		CurrencyId::DOT,
+		Some(Ratio::saturating_from_rational(200, 100)),
+		Some(Ratio::saturating_from_rational(250, 100)),
+		100.into()
+	)
+
+	veto_collateral_ratios_ramp {
+		let u in 0 .. 1000;
+
+		CdpEngine::<T>::ramp_collateral_ratios(
+			RawOrigin::Root.into(),
+			CurrencyId::DOT,
+			Some(Ratio::saturating_from_rational(200, 100)),
+			Some(Ratio::saturating_from_rational(250, 100)),
+			100.into(),
+		)?;
+	}: _(RawOrigin::Root, CurrencyId::DOT)
+
 	// `liquidate` by_auction
 	liquidate_by_auction {
 		let u in 0 .. 1000;
@@ -221,9 +271,56 @@ benchmarks! {
 		// adjust position
 		CdpEngine::<T>::adjust_position(&owner, currency_id, collateral_amount, min_debit_amount)?;
 
-		// shutdown
-		CdpEngine::<T>::on_emergency_shutdown();
+		// shutdown and start settlement
+		EmergencyShutdown::<T>::emergency_shutdown(RawOrigin::Root.into())?;
+		EmergencyShutdown::<T>::start_settlement(RawOrigin::Root.into())?;
 	}: _(RawOrigin::None, currency_id, owner)
+
+	retire_collateral {
+		let u in 0 .. 1000;
+
+		let currency_id: CurrencyId = <T as cdp_engine::Trait>::CollateralCurrencyIds::get()[0];
+
+		// feed price
+		feed_price::<T>(currency_id, Price::saturating_from_integer(1))?;
+	}: _(RawOrigin::Root, currency_id)
+
+	redeem_retired_collateral {
+		let u in 0 .. 1000;
+
+		let owner: T::AccountId = account("owner", u, SEED);
+		let currency_id: CurrencyId = <T as cdp_engine::Trait>::CollateralCurrencyIds::get()[0];
+		let min_debit_value = <T as cdp_engine::Trait>::MinimumDebitValue::get();
+		let debit_exchange_rate = CdpEngine::<T>::get_debit_exchange_rate(currency_id);
+		let collateral_price = Price::saturating_from_integer(1);		// 1 USD
+		let min_debit_amount = ExchangeRate::saturating_from_integer(1).checked_div(&debit_exchange_rate).unwrap().saturating_mul_int(min_debit_value);
+		let min_debit_amount: T::DebitAmount = min_debit_amount.unique_saturated_into();
+		let collateral_amount = (min_debit_value * 2).unique_saturated_into();
+
+		// set balance
+		<T as loans::Trait>::Currency::update_balance(currency_id, &owner, collateral_amount)?;
+
+		// feed price
+		feed_price::<T>(currency_id, collateral_price)?;
+
+		// set risk params
+		CdpEngine::<T>::set_collateral_params(
+			RawOrigin::Root.into(),
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(min_debit_value * 100),
+		)?;
+
+		// adjust position
+		CdpEngine::<T>::adjust_position(&owner, currency_id, collateral_amount, min_debit_amount)?;
+
+		// retire collateral and settle the cdp
+		CdpEngine::<T>::retire_collateral(RawOrigin::Root.into(), currency_id)?;
+		CdpEngine::<T>::settle_cdp_has_debit(owner.clone(), currency_id)?;
+	}: _(RawOrigin::Signed(owner), currency_id, min_debit_value)
 }
 
 #[cfg(test)]
@@ -246,6 +343,34 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn set_collateral_params_bounds() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_collateral_params_bounds::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn set_global_params_bounds() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_global_params_bounds::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn ramp_collateral_ratios() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_ramp_collateral_ratios::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn veto_collateral_ratios_ramp() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_veto_collateral_ratios_ramp::<Runtime>());
+		});
+	}
+
 	#[test]
 	fn liquidate_by_auction() {
 		new_test_ext().execute_with(|| {
@@ -266,4 +391,18 @@ mod tests {
 			assert_ok!(test_benchmark_settle::<Runtime>());
 		});
 	}
+
+	#[test]
+	fn retire_collateral() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_retire_collateral::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn redeem_retired_collateral() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_redeem_retired_collateral::<Runtime>());
+		});
+	}
 }
@@ -114,6 +114,11 @@ impl loans::Trait for Runtime {
 	type DebitBalance = DebitBalance;
 	type DebitAmount = DebitAmount;
 	type CDPTreasury = CDPTreasuryModule;
+	type EmergencyShutdown = EmergencyShutdownModule;
+	type OnUpdateLoan = ();
+	type OnUpdateCollateral = ();
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type PositionDeposit = ();
 }
 
 pub struct MockAuctionManager;
@@ -172,6 +177,7 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = DexModule;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = EmergencyShutdownModule;
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
@@ -189,6 +195,7 @@ impl dex::Trait for Runtime {
 	type GetExchangeFee = GetExchangeFee;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type OnUpdateLiquidity = ();
 }
 pub type DexModule = dex::Module<Runtime>;
 
@@ -199,6 +206,7 @@ parameter_types! {
 	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(10, 100);
 	pub const MinimumDebitValue: Balance = 2;
 	pub MaxSlippageSwapWithDEX: Ratio = Ratio::saturating_from_rational(50, 100);
+	pub MinimumPriceConfidence: Ratio = Ratio::saturating_from_rational(90, 100);
 	pub const UnsignedPriority: u64 = 1 << 20;
 }
 
@@ -213,12 +221,36 @@ impl cdp_engine::Trait for Runtime {
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type LiquidationParamsUpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type EmergencyVetoOrigin = EnsureSignedBy<One, AccountId>;
 	type MaxSlippageSwapWithDEX = MaxSlippageSwapWithDEX;
+	type MinimumPriceConfidence = MinimumPriceConfidence;
 	type DEX = DexModule;
 	type UnsignedPriority = UnsignedPriority;
+	type OnKeeperAction = ();
 }
 pub type CDPEngineModule = cdp_engine::Module<Runtime>;
 
+parameter_types! {
+	pub const ClaimsPeriod: BlockNumber = 100;
+	pub SystemCollateralRatio: Option<Ratio> = None;
+}
+
+impl emergency_shutdown::Trait for Runtime {
+	type Event = ();
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = CDPTreasuryModule;
+	type AuctionManagerHandler = MockAuctionManager;
+	type OnShutdown = prices::Module<Runtime>;
+	type ShutdownOrigin = EnsureSignedBy<One, AccountId>;
+	type CancelShutdownOrigin = EnsureSignedBy<One, AccountId>;
+	type PriceSource = prices::Module<Runtime>;
+	type SystemCollateralRatio = SystemCollateralRatio;
+	type ClaimsPeriod = ClaimsPeriod;
+}
+pub type EmergencyShutdownModule = emergency_shutdown::Module<Runtime>;
+
 /// An extrinsic type used for tests.
 pub type Extrinsic = TestXt<Call, ()>;
 
@@ -257,6 +289,13 @@ impl orml_oracle::Trait for Runtime {
 }
 pub type ModuleOracle = orml_oracle::Module<Runtime>;
 
+pub struct MockDispersion;
+impl orml_traits::DataProvider<CurrencyId, Ratio> for MockDispersion {
+	fn get(_currency_id: &CurrencyId) -> Option<Ratio> {
+		None
+	}
+}
+
 pub struct MockLiquidStakingExchangeProvider;
 impl ExchangeRateProvider for MockLiquidStakingExchangeProvider {
 	fn get_exchange_rate() -> ExchangeRate {
@@ -268,17 +307,26 @@ parameter_types! {
 	pub const GetStakingCurrencyId: CurrencyId = DOT;
 	pub const GetLiquidCurrencyId: CurrencyId = LDOT;
 	pub StableCurrencyFixedPrice: Price = Price::saturating_from_integer(1);
+	pub const MaxEmergencyPriceDuration: BlockNumber = 100;
+	pub const HeartbeatThreshold: BlockNumber = 100;
+	pub const PriceHistoryDepth: u32 = 10;
 }
 
 impl prices::Trait for Runtime {
 	type Event = ();
 	type Source = orml_oracle::Module<Runtime>;
+	type ExternalSource = prices::NoExternalSource;
+	type Dispersion = MockDispersion;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type StableCurrencyFixedPrice = StableCurrencyFixedPrice;
-	type GetStakingCurrencyId = GetStakingCurrencyId;
-	type GetLiquidCurrencyId = GetLiquidCurrencyId;
 	type LockOrigin = EnsureSignedBy<One, AccountId>;
-	type LiquidStakingExchangeRateProvider = MockLiquidStakingExchangeProvider;
+	type DerivativeAssets =
+		prices::DerivativeAssetAdapter<GetLiquidCurrencyId, GetStakingCurrencyId, MockLiquidStakingExchangeProvider>;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type MaxEmergencyPriceDuration = MaxEmergencyPriceDuration;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type HeartbeatThreshold = HeartbeatThreshold;
+	type PriceHistoryDepth = PriceHistoryDepth;
 }
 
 impl crate::Trait for Runtime {}
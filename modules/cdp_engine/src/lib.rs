@@ -4,21 +4,25 @@
 //!
 //! The core module of Honzon protocol. CDP engine is responsible for handle internal processes about CDPs,
 //! including liquidation, settlement and risk management.
+//!
+//! Governance can also retire a single collateral type without shutting down the entire protocol: debit issuance
+//! under it is frozen, all its CDPs are settled at the snapshot price, and then stable coin holders can redeem
+//! against the collateral pool of that type only.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
 use frame_support::{
 	debug, decl_error, decl_event, decl_module, decl_storage, ensure,
+	storage::generator::StorageDoubleMap as StorageDoubleMapGenerator,
 	traits::{EnsureOrigin, Get},
-	weights::{constants::WEIGHT_PER_MICROS, DispatchClass},
-	IterableStorageDoubleMap,
+	weights::{constants::WEIGHT_PER_MICROS, DispatchClass, Weight},
 };
 use frame_system::{
-	self as system, ensure_none, ensure_root,
+	self as system, ensure_none, ensure_root, ensure_signed,
 	offchain::{SendTransactionTypes, SubmitTransaction},
 };
-use orml_traits::Change;
+use orml_traits::{arithmetic::Signed, Change};
 use primitives::{Amount, Balance, CurrencyId};
 use sp_runtime::{
 	traits::{BlakeTwo256, Convert, Hash, Saturating, UniqueSaturatedInto, Zero},
@@ -29,10 +33,10 @@ use sp_runtime::{
 };
 use sp_std::{marker, prelude::*};
 use support::{
-	CDPTreasury, CDPTreasuryExtended, DEXManager, ExchangeRate, OnEmergencyShutdown, Price, PriceProvider, Rate, Ratio,
-	RiskManager,
+	CDPTreasury, CDPTreasuryExtended, DEXManager, EmergencyShutdown, ExchangeRate, OnKeeperAction, Price,
+	PriceProvider, Rate, Ratio, RiskManager, ShutdownPhase,
 };
-use utilities::{LockItem, OffchainErr, OffchainLock};
+use utilities::{OffchainErr, OffchainLock, OffchainStorageIterator, OffchainWorkerStatus};
 
 mod debit_exchange_rate_convertor;
 pub use debit_exchange_rate_convertor::DebitExchangeRateConvertor;
@@ -42,12 +46,26 @@ mod tests;
 
 const DB_PREFIX: &[u8] = b"acala/cdp-engine-offchain-worker/";
 
+/// The maximum count of CDPs scanned in one offchain worker run, the next run resumes from there
+const MAX_ITERATIONS_PER_RUN: u32 = 1_000;
+
+/// The offchain persistent storage key of the status of last offchain worker run
+pub const OFFCHAIN_WORKER_STATUS_KEY: &[u8] = b"acala/cdp-engine-offchain-worker/status";
+
 pub trait Trait: SendTransactionTypes<Call<Self>> + system::Trait + loans::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
 	/// The origin which may update risk management parameters. Root can always do this.
 	type UpdateOrigin: EnsureOrigin<Self::Origin>;
 
+	/// The origin which may update liquidation related risk management parameters:
+	/// liquidation ratio, liquidation penalty and required collateral ratio,
+	/// and the bounds of all risk management parameters. Root can always do this.
+	type LiquidationParamsUpdateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The origin which may veto the pending ramps of collateral ratios. Root can always do this.
+	type EmergencyVetoOrigin: EnsureOrigin<Self::Origin>;
+
 	/// The list of valid collateral currency types
 	type CollateralCurrencyIds: Get<Vec<CurrencyId>>;
 
@@ -75,6 +93,10 @@ pub trait Trait: SendTransactionTypes<Call<Self>> + system::Trait + loans::Trait
 	/// The price source of all types of currencies related to CDP
 	type PriceSource: PriceProvider<CurrencyId>;
 
+	/// The minimum confidence of feed price required to judge whether a CDP is unsafe
+	/// and to issue new debit, the last confident price is used to judge instead
+	type MinimumPriceConfidence: Get<Ratio>;
+
 	/// The DEX participating in liquidation
 	type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
 
@@ -83,6 +105,9 @@ pub trait Trait: SendTransactionTypes<Call<Self>> + system::Trait + loans::Trait
 	/// This is exposed so that it can be tuned for particular runtime, when
 	/// multiple modules send unsigned transactions.
 	type UnsignedPriority: Get<TransactionPriority>;
+
+	/// Hook to reward the keepers whose transactions triggered successful liquidations or settlements
+	type OnKeeperAction: OnKeeperAction<Self::AccountId, CurrencyId, Balance>;
 }
 
 /// Liquidation strategy available
@@ -120,6 +145,70 @@ pub struct RiskManagementParams {
 	pub required_collateral_ratio: Option<Ratio>,
 }
 
+/// Inclusive bounds `(min, max)` of risk management params, which updates must stay within.
+/// `None` value means not bounded
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct RiskManagementParamsBounds {
+	/// Bounds of maximum total debit value
+	pub maximum_total_debit_value: Option<(Balance, Balance)>,
+
+	/// Bounds of extra stability fee rate
+	pub stability_fee: Option<(Rate, Rate)>,
+
+	/// Bounds of liquidation ratio
+	pub liquidation_ratio: Option<(Ratio, Ratio)>,
+
+	/// Bounds of liquidation penalty rate
+	pub liquidation_penalty: Option<(Rate, Rate)>,
+
+	/// Bounds of required collateral ratio
+	pub required_collateral_ratio: Option<(Ratio, Ratio)>,
+}
+
+impl RiskManagementParamsBounds {
+	/// Whether the min of every bounds is not greater than its max
+	pub fn is_valid(&self) -> bool {
+		is_valid_bounds(&self.maximum_total_debit_value)
+			&& is_valid_bounds(&self.stability_fee)
+			&& is_valid_bounds(&self.liquidation_ratio)
+			&& is_valid_bounds(&self.liquidation_penalty)
+			&& is_valid_bounds(&self.required_collateral_ratio)
+	}
+
+	/// Whether the params are within the bounds, the params which are not set are not checked
+	pub fn contains(&self, params: &RiskManagementParams) -> bool {
+		within_bounds(&Some(params.maximum_total_debit_value), &self.maximum_total_debit_value)
+			&& within_bounds(&params.stability_fee, &self.stability_fee)
+			&& within_bounds(&params.liquidation_ratio, &self.liquidation_ratio)
+			&& within_bounds(&params.liquidation_penalty, &self.liquidation_penalty)
+			&& within_bounds(&params.required_collateral_ratio, &self.required_collateral_ratio)
+	}
+}
+
+fn is_valid_bounds<T: PartialOrd>(bounds: &Option<(T, T)>) -> bool {
+	bounds.as_ref().map_or(true, |(min, max)| min <= max)
+}
+
+fn within_bounds<T: PartialOrd>(value: &Option<T>, bounds: &Option<(T, T)>) -> bool {
+	match (value, bounds) {
+		(Some(value), Some((min, max))) => min <= value && value <= max,
+		_ => true,
+	}
+}
+
+/// Linear ramp of a ratio from `from` at block `start` to `to` at block `end`
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct RatioRamp<BlockNumber> {
+	/// The ratio when ramp starts
+	pub from: Ratio,
+	/// The ratio when ramp ends
+	pub to: Ratio,
+	/// The block number when ramp starts
+	pub start: BlockNumber,
+	/// The block number when ramp ends
+	pub end: BlockNumber,
+}
+
 // typedef to help polkadot.js disambiguate Change with different generic parameters
 type ChangeOptionRate = Change<Option<Rate>>;
 type ChangeOptionRatio = Change<Option<Ratio>>;
@@ -129,6 +218,7 @@ decl_event!(
 	pub enum Event<T>
 	where
 		<T as system::Trait>::AccountId,
+		<T as system::Trait>::BlockNumber,
 		CurrencyId = CurrencyId,
 		Balance = Balance,
 	{
@@ -148,6 +238,22 @@ decl_event!(
 		MaximumTotalDebitValueUpdated(CurrencyId, Balance),
 		/// The global stability fee for all types of collateral updated (new_global_stability_fee)
 		GlobalStabilityFeeUpdated(Rate),
+		/// The collateral type is retired (collateral_type, settle_price)
+		CollateralRetired(CurrencyId, Price),
+		/// Redeem stable coin for retired collateral (collateral_type, redeemer, stable_amount, collateral_amount)
+		RedeemRetiredCollateral(CurrencyId, AccountId, Balance, Balance),
+		/// The bounds of risk management params for specific collateral type updated (collateral_type, new_bounds)
+		CollateralParamsBoundsUpdated(CurrencyId, RiskManagementParamsBounds),
+		/// The bounds of global stability fee updated (new_bounds)
+		GlobalStabilityFeeBoundsUpdated(Option<(Rate, Rate)>),
+		/// The ramp of collateral ratios for specific collateral type started
+		/// (collateral_type, target_liquidation_ratio, target_required_collateral_ratio, end_block)
+		CollateralRatiosRampStarted(CurrencyId, Option<Ratio>, Option<Ratio>, BlockNumber),
+		/// The ramp of collateral ratios for specific collateral type vetoed, `None` vetoer means not signed
+		/// (collateral_type, vetoer, vetoed_liquidation_ratio, vetoed_required_collateral_ratio)
+		CollateralRatiosRampVetoed(CurrencyId, Option<AccountId>, Option<Ratio>, Option<Ratio>),
+		/// The stability fee accrued for specific collateral type at the end of block (collateral_type, issued_amount)
+		StabilityFeeAccrued(CurrencyId, Balance),
 	}
 );
 
@@ -168,28 +274,71 @@ decl_error! {
 		RemainDebitValueTooSmall,
 		/// Feed price is invalid
 		InvalidFeedPrice,
+		/// The confidence of feed price is below the minimum, new debit is halted
+		PriceConfidenceTooLow,
 		/// No debit value in CDP so that it cannot be settled
 		NoDebitValue,
 		/// System has already been shutdown
 		AlreadyShutdown,
 		/// Must after system shutdown
 		MustAfterShutdown,
+		/// Settlement is not allowed in current shutdown phase
+		InvalidShutdownPhase,
+		/// The collateral type has been retired
+		CollateralRetired,
+		/// The collateral type has not been retired
+		CollateralNotRetired,
+		/// There are still CDPs with debit under the retired collateral type
+		ExistUnsettledDebit,
+		/// The retired collateral in CDP treasury is not enough to redeem
+		CollateralNotEnough,
+		/// The min of bounds is greater than its max
+		InvalidBounds,
+		/// The updated risk management param is out of its bounds
+		ParamOutOfBounds,
+		/// The ramp duration must be greater than zero
+		InvalidRampDuration,
+		/// There is no pending ramp of collateral ratios to veto
+		NoPendingRamp,
+		/// No target collateral ratio to ramp
+		NoRampTarget,
 	}
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as CDPEngine {
-		/// System shutdown flag
-		pub IsShutdown get(fn is_shutdown): bool;
-
 		/// Mapping from collateral type to its exchange rate of debit units and debit value
 		pub DebitExchangeRate get(fn debit_exchange_rate): map hasher(twox_64_concat) CurrencyId => Option<ExchangeRate>;
 
+		/// Mapping from collateral type to its debit exchange rate before the last stability fee accrual
+		pub PreviousDebitExchangeRate get(fn previous_debit_exchange_rate): map hasher(twox_64_concat) CurrencyId => Option<ExchangeRate>;
+
 		/// Global stability fee rate for all types of collateral
 		pub GlobalStabilityFee get(fn global_stability_fee) config(): Rate;
 
 		/// Mapping from collateral type to its risk management params
 		pub CollateralParams get(fn collateral_params): map hasher(twox_64_concat) CurrencyId => RiskManagementParams;
+
+		/// Bounds of global stability fee rate
+		pub GlobalStabilityFeeBounds get(fn global_stability_fee_bounds): Option<(Rate, Rate)>;
+
+		/// Mapping from collateral type to the bounds of its risk management params
+		pub CollateralParamsBounds get(fn collateral_params_bounds): map hasher(twox_64_concat) CurrencyId => RiskManagementParamsBounds;
+
+		/// Mapping from collateral type to the ongoing ramp of its liquidation ratio
+		pub LiquidationRatioRamps get(fn liquidation_ratio_ramps): map hasher(twox_64_concat) CurrencyId => Option<RatioRamp<T::BlockNumber>>;
+
+		/// Mapping from collateral type to the ongoing ramp of its required collateral ratio
+		pub RequiredCollateralRatioRamps get(fn required_collateral_ratio_ramps): map hasher(twox_64_concat) CurrencyId => Option<RatioRamp<T::BlockNumber>>;
+
+		/// Mapping from retired collateral type to its snapshot settle price
+		pub RetiredCollaterals get(fn retired_collaterals): map hasher(twox_64_concat) CurrencyId => Option<Price>;
+
+		/// Mapping from collateral type to its last feed price with enough confidence
+		pub LastConfidentPrices get(fn last_confident_prices): map hasher(twox_64_concat) CurrencyId => Option<Price>;
+
+		/// System shutdown flag of previous version, derived from the phase of emergency shutdown now
+		IsShutdown: bool;
 	}
 
 	add_extra_genesis {
@@ -233,6 +382,9 @@ decl_module! {
 		/// The max slippage allowed when liquidate an unsafe CDP by swap with DEX
 		const MaxSlippageSwapWithDEX: Ratio = T::MaxSlippageSwapWithDEX::get();
 
+		/// The minimum confidence of feed price required to judge whether a CDP is unsafe
+		const MinimumPriceConfidence: Ratio = T::MinimumPriceConfidence::get();
+
 		/// The default liquidation ratio for all collateral types of CDP,
 		/// if the liquidation ratio for specific collateral is `None`, it works.
 		const DefaultLiquidationRatio: Ratio = T::DefaultLiquidationRatio::get();
@@ -258,8 +410,8 @@ decl_module! {
 		/// 	- T::DEX is module_dex
 		/// - Complexity: `O(1)`
 		/// - Db reads:
-		///		- liquidate by auction: `IsShutdown`, (4 + 2 + 3 + 2 + 1 + 3 + 2) items of modules related to module_cdp_engine
-		///		- liquidate by dex: `IsShutdown`, (4 + 5 + 3 + 2 + 2 + 0 + 2) items of modules related to module_cdp_engine
+		///		- liquidate by auction: 1 item of module_emergency_shutdown, (4 + 2 + 3 + 2 + 1 + 3 + 2) items of modules related to module_cdp_engine
+		///		- liquidate by dex: 1 item of module_emergency_shutdown, (4 + 5 + 3 + 2 + 2 + 0 + 2) items of modules related to module_cdp_engine
 		/// - Db writes:
 		///		- liquidate by auction: (4 + 2 + 0 + 2 + 0 + 5) items of modules related to module_cdp_engine
 		///		- liquidate by dex: (4 + 5 + 0 + 2 + 1 + 0) items of modules related to module_cdp_engine
@@ -279,7 +431,7 @@ decl_module! {
 			Self::liquidate_unsafe_cdp(who, currency_id)?;
 		}
 
-		/// Settle CDP has debit after system shutdown
+		/// Settle CDP has debit after system shutdown or its collateral type is retired
 		///
 		/// The dispatch origin of this call must be _None_.
 		///
@@ -291,20 +443,165 @@ decl_module! {
 		/// 	- T::CDPTreasury is module_cdp_treasury
 		/// 	- T::DEX is module_dex
 		/// - Complexity: `O(1)`
-		/// - Db reads: `IsShutdown`, 9 items of modules related to module_cdp_engine
+		/// - Db reads: 1 item of module_emergency_shutdown, `RetiredCollaterals`, 10 items of modules related to module_cdp_engine
 		/// - Db writes: 8 items of modules related to module_cdp_engine
 		/// -------------------
 		/// Base Weight: 76.54 µs
 		/// # </weight>
-		#[weight = (77 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(10, 8), DispatchClass::Operational)]
+		#[weight = (77 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(12, 8), DispatchClass::Operational)]
 		pub fn settle(
 			origin,
 			currency_id: CurrencyId,
 			who: T::AccountId,
 		) {
 			ensure_none(origin)?;
-			ensure!(Self::is_shutdown(), Error::<T>::MustAfterShutdown);
+			ensure!(
+				Self::is_shutdown() || Self::retired_collaterals(currency_id).is_some(),
+				Error::<T>::MustAfterShutdown,
+			);
+			ensure!(Self::can_settle(currency_id), Error::<T>::InvalidShutdownPhase);
+			Self::settle_cdp_has_debit(who, currency_id)?;
+		}
+
+		/// Liquidate unsafe CDP by keeper, the keeper gets rewarded by `OnKeeperAction`
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `currency_id`: CDP's collateral type.
+		/// - `who`: CDP's owner.
+		///
+		/// # <weight>
+		/// - Preconditions:
+		/// 	- same as `liquidate`
+		/// 	- T::OnKeeperAction is module_incentives
+		/// - Complexity: `O(1)`
+		/// - Db reads: same as `liquidate`, 3 items of module_incentives
+		/// - Db writes: same as `liquidate`, 2 items of module_incentives
+		/// -------------------
+		/// Base Weight: 135 µs
+		/// # </weight>
+		#[weight = 135 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(21, 15)]
+		pub fn liquidate_by_keeper(
+			origin,
+			currency_id: CurrencyId,
+			who: T::AccountId,
+		) {
+			let keeper = ensure_signed(origin)?;
+			ensure!(!Self::is_shutdown(), Error::<T>::AlreadyShutdown);
+			let debit_value = Self::get_debit_value(currency_id, <loans::Module<T>>::debits(currency_id, &who));
+			Self::liquidate_unsafe_cdp(who, currency_id)?;
+			T::OnKeeperAction::on_keeper_action(&keeper, currency_id, debit_value);
+		}
+
+		/// Settle CDP has debit by keeper, the keeper gets rewarded by `OnKeeperAction`
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `currency_id`: CDP's collateral type.
+		/// - `who`: CDP's owner.
+		///
+		/// # <weight>
+		/// - Preconditions:
+		/// 	- same as `settle`
+		/// 	- T::OnKeeperAction is module_incentives
+		/// - Complexity: `O(1)`
+		/// - Db reads: same as `settle`, 3 items of module_incentives
+		/// - Db writes: same as `settle`, 2 items of module_incentives
+		/// -------------------
+		/// Base Weight: 87 µs
+		/// # </weight>
+		#[weight = 87 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(15, 10)]
+		pub fn settle_by_keeper(
+			origin,
+			currency_id: CurrencyId,
+			who: T::AccountId,
+		) {
+			let keeper = ensure_signed(origin)?;
+			ensure!(
+				Self::is_shutdown() || Self::retired_collaterals(currency_id).is_some(),
+				Error::<T>::MustAfterShutdown,
+			);
+			ensure!(Self::can_settle(currency_id), Error::<T>::InvalidShutdownPhase);
+			let debit_value = Self::get_debit_value(currency_id, <loans::Module<T>>::debits(currency_id, &who));
 			Self::settle_cdp_has_debit(who, currency_id)?;
+			T::OnKeeperAction::on_keeper_action(&keeper, currency_id, debit_value);
+		}
+
+		/// Retire a collateral type: freeze debit issuance under it, snapshot and lock its settle price,
+		/// so that all its CDPs can be settled without shutting down the entire protocol.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `currency_id`: collateral type.
+		///
+		/// # <weight>
+		/// - Preconditions:
+		/// 	- T::PriceSource is module_prices
+		/// - Complexity: `O(1)`
+		/// - Db reads: 1 item of module_emergency_shutdown, `RetiredCollaterals`, 3 items of module_prices
+		/// - Db writes: `RetiredCollaterals`, 1 items of module_prices
+		/// -------------------
+		/// Base Weight: 35 µs
+		/// # </weight>
+		#[weight = 35 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(5, 2)]
+		pub fn retire_collateral(origin, currency_id: CurrencyId) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(!Self::is_shutdown(), Error::<T>::AlreadyShutdown);
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
+			ensure!(Self::retired_collaterals(currency_id).is_none(), Error::<T>::CollateralRetired);
+
+			let settle_price: Price = T::PriceSource::get_relative_price(T::GetStableCurrencyId::get(), currency_id)
+				.ok_or(Error::<T>::InvalidFeedPrice)?;
+			T::PriceSource::lock_price(currency_id);
+			RetiredCollaterals::insert(currency_id, settle_price);
+
+			Self::deposit_event(RawEvent::CollateralRetired(currency_id, settle_price));
+		}
+
+		/// Redeem stable coin for the collateral of a retired collateral type at its snapshot settle price,
+		/// only after all CDPs under it have been settled. Not allowed after emergency shutdown, when the
+		/// collateral in CDP treasury is refunded by the emergency shutdown module.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `currency_id`: retired collateral type.
+		/// - `amount`: stable coin amount to redeem.
+		///
+		/// # <weight>
+		/// - Preconditions:
+		/// 	- T::CDPTreasury is module_cdp_treasury
+		/// - Complexity: `O(1)`
+		/// - Db reads: `RetiredCollaterals`, 1 item of module_emergency_shutdown, 7 items of modules related to module_cdp_engine
+		/// - Db writes: 6 items of modules related to module_cdp_engine
+		/// -------------------
+		/// Base Weight: 80 µs
+		/// # </weight>
+		#[weight = 80 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(9, 6)]
+		pub fn redeem_retired_collateral(origin, currency_id: CurrencyId, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_shutdown(), Error::<T>::AlreadyShutdown);
+			let settle_price = Self::retired_collaterals(currency_id).ok_or(Error::<T>::CollateralNotRetired)?;
+			ensure!(
+				<loans::Module<T>>::total_debits(currency_id).is_zero(),
+				Error::<T>::ExistUnsettledDebit,
+			);
+
+			let collateral_amount = settle_price.saturating_mul_int(amount);
+			ensure!(
+				collateral_amount <= <T as Trait>::CDPTreasury::get_total_collaterals(currency_id),
+				Error::<T>::CollateralNotEnough,
+			);
+
+			// the redeemed stable coin goes to surplus pool to offset the settled debit
+			<T as Trait>::CDPTreasury::transfer_surplus_from(&who, amount)?;
+			<T as Trait>::CDPTreasury::transfer_collateral_to(currency_id, &who, collateral_amount)?;
+
+			Self::deposit_event(RawEvent::RedeemRetiredCollateral(currency_id, who, amount, collateral_amount));
 		}
 
 		/// Update global parameters related to risk management of CDP
@@ -315,12 +612,12 @@ decl_module! {
 		///
 		/// # <weight>
 		/// - Complexity: `O(1)`
-		/// - Db reads:
+		/// - Db reads: `GlobalStabilityFeeBounds`
 		/// - Db writes: `GlobalStabilityFee`
 		/// -------------------
 		/// Base Weight: 21.04 µs
 		/// # </weight>
-		#[weight = 21 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		#[weight = 21 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 1)]
 		pub fn set_global_params(
 			origin,
 			global_stability_fee: Rate,
@@ -328,13 +625,18 @@ decl_module! {
 			T::UpdateOrigin::try_origin(origin)
 				.map(|_| ())
 				.or_else(ensure_root)?;
+			ensure!(
+				within_bounds(&Some(global_stability_fee), &Self::global_stability_fee_bounds()),
+				Error::<T>::ParamOutOfBounds,
+			);
 			GlobalStabilityFee::put(global_stability_fee);
 			Self::deposit_event(RawEvent::GlobalStabilityFeeUpdated(global_stability_fee));
 		}
 
 		/// Update parameters related to risk management of CDP under specific collateral type
 		///
-		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		/// The dispatch origin of this call must be `LiquidationParamsUpdateOrigin` or _Root_ when updating
+		/// any of the liquidation related params, otherwise it must be `UpdateOrigin` or _Root_.
 		///
 		/// - `currency_id`: collateral type.
 		/// - `stability_fee`: extra stability fee rate, `None` means do not update, `Some(None)` means update it to `None`.
@@ -345,12 +647,12 @@ decl_module! {
 		///
 		/// # <weight>
 		/// - Complexity: `O(1)`
-		/// - Db reads:	`CollateralParams`
-		/// - Db writes: `CollateralParams`
+		/// - Db reads:	`CollateralParams`, `CollateralParamsBounds`
+		/// - Db writes: `CollateralParams`, `LiquidationRatioRamps`, `RequiredCollateralRatioRamps`
 		/// -------------------
 		/// Base Weight: 32.81 µs
 		/// # </weight>
-		#[weight = 33 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 1)]
+		#[weight = 33 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 3)]
 		pub fn set_collateral_params(
 			origin,
 			currency_id: CurrencyId,
@@ -360,9 +662,18 @@ decl_module! {
 			required_collateral_ratio: ChangeOptionRatio,
 			maximum_total_debit_value: ChangeBalance,
 		) {
-			T::UpdateOrigin::try_origin(origin)
-				.map(|_| ())
-				.or_else(ensure_root)?;
+			let update_liquidation_params = matches!(liquidation_ratio, Change::NewValue(_))
+				|| matches!(liquidation_penalty, Change::NewValue(_))
+				|| matches!(required_collateral_ratio, Change::NewValue(_));
+			if update_liquidation_params {
+				T::LiquidationParamsUpdateOrigin::try_origin(origin)
+					.map(|_| ())
+					.or_else(ensure_root)?;
+			} else {
+				T::UpdateOrigin::try_origin(origin)
+					.map(|_| ())
+					.or_else(ensure_root)?;
+			}
 			ensure!(
 				T::CollateralCurrencyIds::get().contains(&currency_id),
 				Error::<T>::InvalidCollateralType,
@@ -371,46 +682,257 @@ decl_module! {
 			let mut collateral_params = Self::collateral_params(currency_id);
 			if let Change::NewValue(update) = stability_fee {
 				collateral_params.stability_fee = update;
-				Self::deposit_event(RawEvent::StabilityFeeUpdated(currency_id, update));
 			}
 			if let Change::NewValue(update) = liquidation_ratio {
 				collateral_params.liquidation_ratio = update;
-				Self::deposit_event(RawEvent::LiquidationRatioUpdated(currency_id, update));
 			}
 			if let Change::NewValue(update) = liquidation_penalty {
 				collateral_params.liquidation_penalty = update;
-				Self::deposit_event(RawEvent::LiquidationPenaltyUpdated(currency_id, update));
 			}
 			if let Change::NewValue(update) = required_collateral_ratio {
 				collateral_params.required_collateral_ratio = update;
-				Self::deposit_event(RawEvent::RequiredCollateralRatioUpdated(currency_id, update));
 			}
 			if let Change::NewValue(val) = maximum_total_debit_value {
 				collateral_params.maximum_total_debit_value = val;
+			}
+			ensure!(
+				Self::collateral_params_bounds(currency_id).contains(&collateral_params),
+				Error::<T>::ParamOutOfBounds,
+			);
+
+			if let Change::NewValue(update) = stability_fee {
+				Self::deposit_event(RawEvent::StabilityFeeUpdated(currency_id, update));
+			}
+			if let Change::NewValue(update) = liquidation_ratio {
+				// updating directly cancels the ongoing ramp
+				<LiquidationRatioRamps<T>>::remove(currency_id);
+				Self::deposit_event(RawEvent::LiquidationRatioUpdated(currency_id, update));
+			}
+			if let Change::NewValue(update) = liquidation_penalty {
+				Self::deposit_event(RawEvent::LiquidationPenaltyUpdated(currency_id, update));
+			}
+			if let Change::NewValue(update) = required_collateral_ratio {
+				<RequiredCollateralRatioRamps<T>>::remove(currency_id);
+				Self::deposit_event(RawEvent::RequiredCollateralRatioUpdated(currency_id, update));
+			}
+			if let Change::NewValue(val) = maximum_total_debit_value {
 				Self::deposit_event(RawEvent::MaximumTotalDebitValueUpdated(currency_id, val));
 			}
 			CollateralParams::insert(currency_id, collateral_params);
 		}
 
+		/// Update the bounds of global stability fee, `set_global_params` cannot exceed them.
+		///
+		/// The dispatch origin of this call must be `LiquidationParamsUpdateOrigin` or _Root_.
+		///
+		/// - `bounds`: inclusive bounds `(min, max)` of global stability fee rate, `None` means not bounded.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `GlobalStabilityFeeBounds`
+		/// -------------------
+		/// Base Weight: 21 µs
+		/// # </weight>
+		#[weight = 21 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_global_params_bounds(origin, bounds: Option<(Rate, Rate)>) {
+			T::LiquidationParamsUpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(is_valid_bounds(&bounds), Error::<T>::InvalidBounds);
+			GlobalStabilityFeeBounds::set(bounds);
+			Self::deposit_event(RawEvent::GlobalStabilityFeeBoundsUpdated(bounds));
+		}
+
+		/// Update the bounds of risk management params under specific collateral type,
+		/// `set_collateral_params` cannot exceed them.
+		///
+		/// The dispatch origin of this call must be `LiquidationParamsUpdateOrigin` or _Root_.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `bounds`: inclusive bounds `(min, max)` of every risk management param, `None` means not bounded.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `CollateralParamsBounds`
+		/// -------------------
+		/// Base Weight: 25 µs
+		/// # </weight>
+		#[weight = 25 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_collateral_params_bounds(
+			origin,
+			currency_id: CurrencyId,
+			bounds: RiskManagementParamsBounds,
+		) {
+			T::LiquidationParamsUpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
+			ensure!(bounds.is_valid(), Error::<T>::InvalidBounds);
+			CollateralParamsBounds::insert(currency_id, bounds.clone());
+			Self::deposit_event(RawEvent::CollateralParamsBoundsUpdated(currency_id, bounds));
+		}
+
+		/// Change the liquidation ratio and the required collateral ratio under specific collateral type
+		/// linearly from their current effective values over `duration` blocks rather than stepwise,
+		/// so that existing CDPs get a predictable window to adjust.
+		///
+		/// The dispatch origin of this call must be `LiquidationParamsUpdateOrigin` or _Root_.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `liquidation_ratio`: target liquidation ratio, `None` means do not ramp it.
+		/// - `required_collateral_ratio`: target required collateral ratio, `None` means do not ramp it.
+		/// - `duration`: the number of blocks the ramp lasts.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `CollateralParams`, `CollateralParamsBounds`, `LiquidationRatioRamps`,
+		///   `RequiredCollateralRatioRamps`
+		/// - Db writes: `LiquidationRatioRamps`, `RequiredCollateralRatioRamps`
+		/// -------------------
+		/// Base Weight: 35 µs
+		/// # </weight>
+		#[weight = 35 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(4, 2)]
+		pub fn ramp_collateral_ratios(
+			origin,
+			currency_id: CurrencyId,
+			liquidation_ratio: Option<Ratio>,
+			required_collateral_ratio: Option<Ratio>,
+			duration: T::BlockNumber,
+		) {
+			T::LiquidationParamsUpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
+			ensure!(!duration.is_zero(), Error::<T>::InvalidRampDuration);
+			ensure!(
+				liquidation_ratio.is_some() || required_collateral_ratio.is_some(),
+				Error::<T>::NoRampTarget,
+			);
+
+			let mut target_params = Self::collateral_params(currency_id);
+			if liquidation_ratio.is_some() {
+				target_params.liquidation_ratio = liquidation_ratio;
+			}
+			if required_collateral_ratio.is_some() {
+				target_params.required_collateral_ratio = required_collateral_ratio;
+			}
+			ensure!(
+				Self::collateral_params_bounds(currency_id).contains(&target_params),
+				Error::<T>::ParamOutOfBounds,
+			);
+
+			let start = <system::Module<T>>::block_number();
+			let end = start.saturating_add(duration);
+			let current_liquidation_ratio = Self::get_liquidation_ratio(currency_id);
+			if let Some(to) = liquidation_ratio {
+				<LiquidationRatioRamps<T>>::insert(currency_id, RatioRamp {
+					from: current_liquidation_ratio,
+					to,
+					start,
+					end,
+				});
+			}
+			if let Some(to) = required_collateral_ratio {
+				// CDPs below the liquidation ratio are already unsafe, ramp the unset required collateral ratio from it
+				let from = Self::required_collateral_ratio(currency_id).unwrap_or(current_liquidation_ratio);
+				<RequiredCollateralRatioRamps<T>>::insert(currency_id, RatioRamp { from, to, start, end });
+			}
+
+			Self::deposit_event(RawEvent::CollateralRatiosRampStarted(
+				currency_id,
+				liquidation_ratio,
+				required_collateral_ratio,
+				end,
+			));
+		}
+
+		/// Veto the pending ramps of collateral ratios under specific collateral type before they finish,
+		/// the collateral ratios fall back to the values before the ramps.
+		///
+		/// The dispatch origin of this call must be `EmergencyVetoOrigin` or _Root_.
+		///
+		/// - `currency_id`: collateral type.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `LiquidationRatioRamps`, `RequiredCollateralRatioRamps`
+		/// - Db writes: `LiquidationRatioRamps`, `RequiredCollateralRatioRamps`
+		/// -------------------
+		/// Base Weight: 25 µs
+		/// # </weight>
+		#[weight = 25 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 2)]
+		pub fn veto_collateral_ratios_ramp(origin, currency_id: CurrencyId) {
+			let vetoer = ensure_signed(origin.clone()).ok();
+			T::EmergencyVetoOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			let liquidation_ratio_ramp = <LiquidationRatioRamps<T>>::take(currency_id);
+			let required_collateral_ratio_ramp = <RequiredCollateralRatioRamps<T>>::take(currency_id);
+			ensure!(
+				liquidation_ratio_ramp.is_some() || required_collateral_ratio_ramp.is_some(),
+				Error::<T>::NoPendingRamp,
+			);
+
+			Self::deposit_event(RawEvent::CollateralRatiosRampVetoed(
+				currency_id,
+				vetoer,
+				liquidation_ratio_ramp.map(|ramp| ramp.to),
+				required_collateral_ratio_ramp.map(|ramp| ramp.to),
+			));
+		}
+
+		/// Remove the shutdown flag of previous version
+		fn on_runtime_upgrade() -> Weight {
+			IsShutdown::kill();
+			T::DbWeight::get().writes(1)
+		}
+
 		/// Issue interest in stable coin for all types of collateral has debit when block end,
 		/// and update their debit exchange rate
-		fn on_finalize(_now: T::BlockNumber) {
+		fn on_finalize(now: T::BlockNumber) {
+			// settle the finished ramps of collateral ratios into risk management params
+			// and record the confident prices
+			for currency_id in T::CollateralCurrencyIds::get() {
+				Self::settle_finished_ramps(currency_id, now);
+				if let Some(price) = Self::confident_feed_price(currency_id) {
+					LastConfidentPrices::insert(currency_id, price);
+				}
+			}
+
 			// collect stability fee for all types of collateral
 			if !Self::is_shutdown() {
 				for currency_id in T::CollateralCurrencyIds::get() {
+					// retired collateral type does not accrue stability fee anymore
+					if Self::retired_collaterals(currency_id).is_some() {
+						continue;
+					}
+
 					let debit_exchange_rate = Self::get_debit_exchange_rate(currency_id);
 					let stability_fee_rate = Self::get_stability_fee(currency_id);
 					let total_debits = <loans::Module<T>>::total_debits(currency_id);
 					if !stability_fee_rate.is_zero() && !total_debits.is_zero() {
 						let debit_exchange_rate_increment = debit_exchange_rate.saturating_mul(stability_fee_rate);
 						let total_debit_value = Self::get_debit_value(currency_id, total_debits);
-						let issued_stable_coin_balance = debit_exchange_rate_increment.saturating_mul_int(total_debit_value);
+						let issued_stable_coin_balance = stability_fee_rate.saturating_mul_int(total_debit_value);
 
 						// issue stablecoin to surplus pool
 						if <T as Trait>::CDPTreasury::on_system_surplus(issued_stable_coin_balance).is_ok() {
 							// update exchange rate when issue success
 							let new_debit_exchange_rate = debit_exchange_rate.saturating_add(debit_exchange_rate_increment);
+							PreviousDebitExchangeRate::insert(currency_id, debit_exchange_rate);
 							DebitExchangeRate::insert(currency_id, new_debit_exchange_rate);
+							if !issued_stable_coin_balance.is_zero() {
+								Self::deposit_event(RawEvent::StabilityFeeAccrued(currency_id, issued_stable_coin_balance));
+							}
 						}
 					}
 				}
@@ -420,14 +942,17 @@ decl_module! {
 		/// Runs after every block. Start offchain worker to check CDP and
 		/// submit unsigned tx to trigger liquidation or settlement.
 		fn offchain_worker(now: T::BlockNumber) {
-			if let Err(e) = Self::_offchain_worker(now) {
+			let mut status = OffchainWorkerStatus::new(now);
+			if let Err(e) = Self::_offchain_worker(now, &mut status) {
 				debug::info!(
 					target: "cdp-engine offchain worker",
 					"cannot run offchain worker at {:?}: {:?}",
 					now,
 					e,
 				);
+				status.error = Some(e);
 			}
+			status.record(OFFCHAIN_WORKER_STATUS_KEY);
 		}
 	}
 }
@@ -447,7 +972,10 @@ impl<T: Trait> Module<T> {
 		Ok(())
 	}
 
-	fn _offchain_worker(block_number: T::BlockNumber) -> Result<(), OffchainErr> {
+	fn _offchain_worker(
+		block_number: T::BlockNumber,
+		status: &mut OffchainWorkerStatus<T::BlockNumber, CurrencyId>,
+	) -> Result<(), OffchainErr> {
 		let collateral_currency_ids = T::CollateralCurrencyIds::get();
 		if collateral_currency_ids.len().is_zero() {
 			return Ok(());
@@ -458,40 +986,69 @@ impl<T: Trait> Module<T> {
 			return Err(OffchainErr::NotValidator);
 		}
 
-		let collateral_currency_ids = T::CollateralCurrencyIds::get();
-		let offchain_lock = OffchainLock::new(DB_PREFIX.to_vec());
-
-		// Acquire offchain worker lock.
+		// Acquire the offchain worker lock of a collateral type, starting from a random one,
+		// so that concurrent workers scan different collateral types.
 		// If succeeded, update the lock, otherwise return error
-		let LockItem {
-			extra_data: position, ..
-		} = offchain_lock.acquire_offchain_lock(|val: Option<u32>| {
-			if let Some(previous_position) = val {
-				if previous_position < collateral_currency_ids.len().saturating_sub(1) as u32 {
-					previous_position + 1
-				} else {
-					0
-				}
-			} else {
-				let random_seed = sp_io::offchain::random_seed();
-				let mut rng = RandomNumberGenerator::<BlakeTwo256>::new(BlakeTwo256::hash(&random_seed[..]));
-
-				rng.pick_u32(collateral_currency_ids.len().saturating_sub(1) as u32)
-			}
-		})?;
-
-		let currency_id = collateral_currency_ids[(position as usize)];
-
-		if !Self::is_shutdown() {
-			for (account_id, _) in <loans::Debits<T>>::iter_prefix(currency_id) {
-				if Self::is_cdp_unsafe(currency_id, &account_id) {
+		let random_seed = sp_io::offchain::random_seed();
+		let mut rng = RandomNumberGenerator::<BlakeTwo256>::new(BlakeTwo256::hash(&random_seed[..]));
+		let start = rng.pick_u32(collateral_currency_ids.len().saturating_sub(1) as u32) as usize;
+		let (currency_id, offchain_lock) = (0..collateral_currency_ids.len())
+			.map(|offset| collateral_currency_ids[(start + offset) % collateral_currency_ids.len()])
+			.find_map(|currency_id| {
+				let offchain_lock = OffchainLock::new_sharded(DB_PREFIX, &currency_id);
+				offchain_lock
+					.acquire_offchain_lock(|_: Option<()>| ())
+					.ok()
+					.map(|_| (currency_id, offchain_lock))
+			})
+			.ok_or(OffchainErr::OffchainLock)?;
+		status.scope = Some(currency_id);
+
+		// resume scanning the CDPs of the collateral type from the cursor of last run
+		let prefix =
+			<loans::Debits<T> as StorageDoubleMapGenerator<_, _, _>>::storage_double_map_final_key1(currency_id);
+		let mut cursor_key = DB_PREFIX.to_vec();
+		cursor_key.extend_from_slice(b"cursor/");
+		currency_id.using_encoded(|encoded| cursor_key.extend_from_slice(encoded));
+		let iterator = OffchainStorageIterator::new(prefix, cursor_key, MAX_ITERATIONS_PER_RUN);
+
+		let can_settle = Self::can_settle(currency_id);
+		if can_settle || !Self::is_shutdown() {
+			let scanned = iterator.iterate(|key, debit: T::DebitBalance| {
+				// the key of owner is twox_64_concat encoded, skip the 8 bytes hash
+				let account_id = match key.get(8..).and_then(|mut raw| T::AccountId::decode(&mut raw).ok()) {
+					Some(account_id) => account_id,
+					None => return,
+				};
+
+				if can_settle {
+					if !debit.is_zero() {
+						if let Err(e) = Self::submit_unsigned_settle_tx(currency_id, account_id.clone()) {
+							status.failed += 1;
+							debug::warn!(
+								target: "cdp-engine offchain worker",
+								"submit unsigned settlement tx for \nCDP - AccountId {:?} CurrencyId {:?} \nfailed : {:?}",
+								account_id, currency_id, e,
+							);
+						} else {
+							status.submitted += 1;
+							debug::debug!(
+								target: "cdp-engine offchain worker",
+								"successfully submit unsigned settlement tx for \nCDP - AccountId {:?} CurrencyId {:?}",
+								account_id, currency_id,
+							);
+						}
+					}
+				} else if Self::is_cdp_unsafe(currency_id, &account_id) {
 					if let Err(e) = Self::submit_unsigned_liquidation_tx(currency_id, account_id.clone()) {
+						status.failed += 1;
 						debug::warn!(
 							target: "cdp-engine offchain worker",
 							"submit unsigned liquidation tx for \nCDP - AccountId {:?} CurrencyId {:?} \nfailed : {:?}",
 							account_id, currency_id, e,
 						);
 					} else {
+						status.submitted += 1;
 						debug::debug!(
 							target: "cdp-engine offchain worker",
 							"successfully submit unsigned liquidation tx for \nCDP - AccountId {:?} CurrencyId {:?}",
@@ -501,33 +1058,13 @@ impl<T: Trait> Module<T> {
 				}
 
 				// check the expire timestamp of lock that is needed to extend
-				offchain_lock.extend_offchain_lock_if_needed::<u32>();
-			}
-		} else {
-			for (account_id, debit) in <loans::Debits<T>>::iter_prefix(currency_id) {
-				if !debit.is_zero() {
-					if let Err(e) = Self::submit_unsigned_settle_tx(currency_id, account_id.clone()) {
-						debug::warn!(
-							target: "cdp-engine offchain worker",
-							"submit unsigned settlement tx for \nCDP - AccountId {:?} CurrencyId {:?} \nfailed : {:?}",
-							account_id, currency_id, e,
-						);
-					} else {
-						debug::debug!(
-							target: "cdp-engine offchain worker",
-							"successfully submit unsigned settlement tx for \nCDP - AccountId {:?} CurrencyId {:?}",
-							account_id, currency_id,
-						);
-					}
-				}
-
-				// check the expire timestamp of lock that is needed to extend
-				offchain_lock.extend_offchain_lock_if_needed::<u32>();
-			}
+				offchain_lock.extend_offchain_lock_if_needed::<()>();
+			});
+			status.scanned += scanned;
 		}
 
 		// finally, reset the expire timestamp to now in order to release lock in advance.
-		offchain_lock.release_offchain_lock(|current_position: u32| current_position == position);
+		offchain_lock.release_offchain_lock(|_: ()| true);
 		debug::debug!(
 			target: "cdp-engine offchain worker",
 			"offchain worker start at block: {:?} already done!",
@@ -540,11 +1077,14 @@ impl<T: Trait> Module<T> {
 	pub fn is_cdp_unsafe(currency_id: CurrencyId, who: &T::AccountId) -> bool {
 		let debit_balance = <loans::Module<T>>::debits(currency_id, who);
 		let collateral_balance = <loans::Module<T>>::collaterals(who, currency_id);
-		let stable_currency_id = T::GetStableCurrencyId::get();
 
-		if debit_balance.is_zero() {
+		// CDPs under retired collateral type are settled rather than liquidated
+		if debit_balance.is_zero() || Self::retired_collaterals(currency_id).is_some() {
 			false
-		} else if let Some(feed_price) = T::PriceSource::get_relative_price(currency_id, stable_currency_id) {
+		} else if let Some(feed_price) = Self::confident_feed_price(currency_id)
+			// if the confidence of feed_price is too low, judge with the last confident price
+			.or_else(|| Self::last_confident_prices(currency_id))
+		{
 			let collateral_ratio =
 				Self::calculate_collateral_ratio(currency_id, collateral_balance, debit_balance, feed_price);
 			collateral_ratio < Self::get_liquidation_ratio(currency_id)
@@ -554,12 +1094,37 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
+	/// The feed price of collateral type in stable currency, `None` if its confidence is too low
+	pub fn confident_feed_price(currency_id: CurrencyId) -> Option<Price> {
+		T::PriceSource::get_relative_price_with_confidence(currency_id, T::GetStableCurrencyId::get())
+			.filter(|(_, confidence)| *confidence >= T::MinimumPriceConfidence::get())
+			.map(|(price, _)| price)
+	}
+
+	/// Check the accounting invariants of CDP engine: the debit exchange rate never falls below
+	/// the one before the last accrual, as it only accrues the stability fee.
+	pub fn check_invariants() -> Result<(), &'static str> {
+		for currency_id in T::CollateralCurrencyIds::get() {
+			let previous_debit_exchange_rate =
+				Self::previous_debit_exchange_rate(currency_id).unwrap_or_else(T::DefaultDebitExchangeRate::get);
+			ensure!(
+				Self::get_debit_exchange_rate(currency_id) >= previous_debit_exchange_rate,
+				"debit exchange rate decreased"
+			);
+		}
+
+		Ok(())
+	}
+
 	pub fn maximum_total_debit_value(currency_id: CurrencyId) -> Balance {
 		Self::collateral_params(currency_id).maximum_total_debit_value
 	}
 
 	pub fn required_collateral_ratio(currency_id: CurrencyId) -> Option<Ratio> {
-		Self::collateral_params(currency_id).required_collateral_ratio
+		match Self::required_collateral_ratio_ramps(currency_id) {
+			Some(ramp) => Some(Self::ramp_value(&ramp)),
+			None => Self::collateral_params(currency_id).required_collateral_ratio,
+		}
 	}
 
 	pub fn get_stability_fee(currency_id: CurrencyId) -> Rate {
@@ -570,9 +1135,47 @@ impl<T: Trait> Module<T> {
 	}
 
 	pub fn get_liquidation_ratio(currency_id: CurrencyId) -> Ratio {
-		Self::collateral_params(currency_id)
-			.liquidation_ratio
-			.unwrap_or_else(T::DefaultLiquidationRatio::get)
+		match Self::liquidation_ratio_ramps(currency_id) {
+			Some(ramp) => Self::ramp_value(&ramp),
+			None => Self::collateral_params(currency_id)
+				.liquidation_ratio
+				.unwrap_or_else(T::DefaultLiquidationRatio::get),
+		}
+	}
+
+	/// The effective value of the ratio ramp at current block
+	pub fn ramp_value(ramp: &RatioRamp<T::BlockNumber>) -> Ratio {
+		let now = <system::Module<T>>::block_number();
+		if now >= ramp.end {
+			return ramp.to;
+		}
+		if now <= ramp.start {
+			return ramp.from;
+		}
+
+		let elapsed: u128 = now.saturating_sub(ramp.start).unique_saturated_into();
+		let duration: u128 = ramp.end.saturating_sub(ramp.start).unique_saturated_into();
+		let progress = Ratio::saturating_from_rational(elapsed, duration);
+		if ramp.to >= ramp.from {
+			ramp.from
+				.saturating_add(ramp.to.saturating_sub(ramp.from).saturating_mul(progress))
+		} else {
+			ramp.from
+				.saturating_sub(ramp.from.saturating_sub(ramp.to).saturating_mul(progress))
+		}
+	}
+
+	fn settle_finished_ramps(currency_id: CurrencyId, now: T::BlockNumber) {
+		if let Some(ramp) = Self::liquidation_ratio_ramps(currency_id).filter(|ramp| now >= ramp.end) {
+			CollateralParams::mutate(currency_id, |params| params.liquidation_ratio = Some(ramp.to));
+			<LiquidationRatioRamps<T>>::remove(currency_id);
+			Self::deposit_event(RawEvent::LiquidationRatioUpdated(currency_id, Some(ramp.to)));
+		}
+		if let Some(ramp) = Self::required_collateral_ratio_ramps(currency_id).filter(|ramp| now >= ramp.end) {
+			CollateralParams::mutate(currency_id, |params| params.required_collateral_ratio = Some(ramp.to));
+			<RequiredCollateralRatioRamps<T>>::remove(currency_id);
+			Self::deposit_event(RawEvent::RequiredCollateralRatioUpdated(currency_id, Some(ramp.to)));
+		}
 	}
 
 	pub fn get_liquidation_penalty(currency_id: CurrencyId) -> Rate {
@@ -601,6 +1204,19 @@ impl<T: Trait> Module<T> {
 		Ratio::checked_from_rational(locked_collateral_value, debit_value).unwrap_or_default()
 	}
 
+	/// The collateral price at which the position reaches the liquidation ratio,
+	/// `None` if there's no collateral.
+	pub fn calculate_liquidation_price(
+		currency_id: CurrencyId,
+		collateral_balance: Balance,
+		debit_balance: T::DebitBalance,
+	) -> Option<Price> {
+		let debit_value = Self::get_debit_value(currency_id, debit_balance);
+		let liquidation_value = Self::get_liquidation_ratio(currency_id).saturating_mul_int(debit_value);
+
+		Price::checked_from_rational(liquidation_value, collateral_balance)
+	}
+
 	pub fn adjust_position(
 		who: &T::AccountId,
 		currency_id: CurrencyId,
@@ -611,11 +1227,44 @@ impl<T: Trait> Module<T> {
 			T::CollateralCurrencyIds::get().contains(&currency_id),
 			Error::<T>::InvalidCollateralType,
 		);
+		if Self::retired_collaterals(currency_id).is_some() {
+			ensure!(
+				!collateral_adjustment.is_positive() && !debit_adjustment.is_positive(),
+				Error::<T>::CollateralRetired,
+			);
+		}
+		if debit_adjustment.is_positive() {
+			ensure!(
+				Self::confident_feed_price(currency_id).is_some(),
+				Error::<T>::PriceConfidenceTooLow,
+			);
+		}
 		<loans::Module<T>>::adjust_position(who, currency_id, collateral_adjustment, debit_adjustment)?;
 		Ok(())
 	}
 
-	// settle cdp has debit when emergency shutdown
+	/// Whether the system is shut down, derived from the phase of emergency shutdown
+	pub fn is_shutdown() -> bool {
+		<T as loans::Trait>::EmergencyShutdown::is_shutdown()
+	}
+
+	/// Whether emergency shutdown is in the phase of settling CDPs
+	pub fn is_settling() -> bool {
+		<T as loans::Trait>::EmergencyShutdown::shutdown_phase() == ShutdownPhase::Settling
+	}
+
+	/// Whether CDPs under the collateral type can be settled, that is emergency
+	/// shutdown is settling or the collateral type is retired. Loans are frozen
+	/// in the other phases of emergency shutdown, so retired CDPs cannot be settled then.
+	pub fn can_settle(currency_id: CurrencyId) -> bool {
+		if Self::is_shutdown() {
+			Self::is_settling()
+		} else {
+			Self::retired_collaterals(currency_id).is_some()
+		}
+	}
+
+	// settle cdp has debit when emergency shutdown or its collateral type is retired
 	pub fn settle_cdp_has_debit(who: T::AccountId, currency_id: CurrencyId) -> DispatchResult {
 		let debit_balance = <loans::Module<T>>::debits(currency_id, &who);
 		ensure!(!debit_balance.is_zero(), Error::<T>::NoDebitValue);
@@ -623,7 +1272,8 @@ impl<T: Trait> Module<T> {
 		// confiscate collateral in cdp to cdp treasury
 		// and decrease cdp's debit to zero
 		let collateral_balance = <loans::Module<T>>::collaterals(&who, currency_id);
-		let settle_price: Price = T::PriceSource::get_relative_price(T::GetStableCurrencyId::get(), currency_id)
+		let settle_price: Price = Self::retired_collaterals(currency_id)
+			.or_else(|| T::PriceSource::get_relative_price(T::GetStableCurrencyId::get(), currency_id))
 			.ok_or(Error::<T>::InvalidFeedPrice)?;
 		let bad_debt_value = Self::get_debit_value(currency_id, debit_balance);
 		let confiscate_collateral_amount =
@@ -656,7 +1306,9 @@ impl<T: Trait> Module<T> {
 		let bad_debt_value = Self::get_debit_value(currency_id, debit_balance);
 		let target_stable_amount = bad_debt_value
 			.saturating_add(Self::get_liquidation_penalty(currency_id).saturating_mul_int(bad_debt_value));
-		let supply_collateral_amount = T::DEX::get_supply_amount(currency_id, stable_currency_id, target_stable_amount);
+		let swap_path = [currency_id, stable_currency_id];
+		let supply_collateral_amount =
+			T::DEX::get_swap_supply_amount(&swap_path, target_stable_amount).unwrap_or_default();
 		let exchange_slippage =
 			T::DEX::get_exchange_slippage(currency_id, stable_currency_id, supply_collateral_amount);
 		let slippage_limit = T::MaxSlippageSwapWithDEX::get();
@@ -675,15 +1327,15 @@ impl<T: Trait> Module<T> {
 
 		match liquidation_strategy {
 			LiquidationStrategy::Exchange => {
-				if <T as Trait>::CDPTreasury::swap_collateral_to_stable(
+				// swap for the exact target, the actual supply amount is at most the collateral of CDP
+				if let Ok(actual_supply_collateral_amount) = <T as Trait>::CDPTreasury::swap_collateral_to_exact_stable(
 					currency_id,
-					supply_collateral_amount,
+					collateral_balance,
 					target_stable_amount,
-				)
-				.is_ok()
-				{
+					&swap_path,
+				) {
 					// refund remain collateral to CDP owner
-					let refund_collateral_amount = collateral_balance - supply_collateral_amount;
+					let refund_collateral_amount = collateral_balance.saturating_sub(actual_supply_collateral_amount);
 					if !refund_collateral_amount.is_zero() {
 						<T as Trait>::CDPTreasury::transfer_collateral_to(currency_id, &who, refund_collateral_amount)
 							.expect("never failed");
@@ -764,12 +1416,6 @@ impl<T: Trait> RiskManager<T::AccountId, CurrencyId, Balance, T::DebitBalance> f
 	}
 }
 
-impl<T: Trait> OnEmergencyShutdown for Module<T> {
-	fn on_emergency_shutdown() {
-		<IsShutdown>::put(true);
-	}
-}
-
 #[allow(deprecated)]
 impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
 	type Call = Call<T>;
@@ -790,7 +1436,7 @@ impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
 			}
 			Call::settle(currency_id, who) => {
 				let debit_balance = <loans::Module<T>>::debits(currency_id, who);
-				if debit_balance.is_zero() || !Self::is_shutdown() {
+				if debit_balance.is_zero() || !Self::can_settle(*currency_id) {
 					return InvalidTransaction::Stale.into();
 				}
 
@@ -3,7 +3,9 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{impl_outer_dispatch, impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types};
+use frame_support::{
+	impl_outer_dispatch, impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types, traits::Contains,
+};
 use frame_system::EnsureSignedBy;
 use sp_core::H256;
 use sp_runtime::{
@@ -11,7 +13,8 @@ use sp_runtime::{
 	traits::IdentityLookup,
 	Perbill,
 };
-use support::AuctionManager;
+use sp_std::cell::RefCell;
+use support::{AuctionManager, ShutdownPhase};
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -136,9 +139,34 @@ impl loans::Trait for Runtime {
 	type DebitBalance = DebitBalance;
 	type DebitAmount = DebitAmount;
 	type CDPTreasury = CDPTreasuryModule;
+	type EmergencyShutdown = MockEmergencyShutdown;
+	type OnUpdateLoan = ();
+	type OnUpdateCollateral = ();
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type PositionDeposit = ();
 }
 pub type LoansModule = loans::Module<Runtime>;
 
+thread_local! {
+	pub static PRICE_CONFIDENCE: RefCell<Ratio> = RefCell::new(Ratio::one());
+	pub static SHUTDOWN_PHASE: RefCell<ShutdownPhase> = RefCell::new(ShutdownPhase::Operational);
+	pub static KEEPER_ACTIONS: RefCell<Vec<(AccountId, CurrencyId, Balance)>> = RefCell::new(vec![]);
+}
+
+pub struct MockOnKeeperAction;
+impl OnKeeperAction<AccountId, CurrencyId, Balance> for MockOnKeeperAction {
+	fn on_keeper_action(keeper: &AccountId, currency_id: CurrencyId, debit_value: Balance) {
+		KEEPER_ACTIONS.with(|v| v.borrow_mut().push((*keeper, currency_id, debit_value)));
+	}
+}
+
+pub struct MockEmergencyShutdown;
+impl EmergencyShutdown for MockEmergencyShutdown {
+	fn shutdown_phase() -> ShutdownPhase {
+		SHUTDOWN_PHASE.with(|v| *v.borrow())
+	}
+}
+
 pub struct MockPriceSource;
 impl PriceProvider<CurrencyId> for MockPriceSource {
 	fn get_relative_price(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
@@ -156,6 +184,10 @@ impl PriceProvider<CurrencyId> for MockPriceSource {
 	fn lock_price(_currency_id: CurrencyId) {}
 
 	fn unlock_price(_currency_id: CurrencyId) {}
+
+	fn get_price_with_confidence(currency_id: CurrencyId) -> Option<(Price, Ratio)> {
+		Self::get_price(currency_id).map(|price| (price, PRICE_CONFIDENCE.with(|v| *v.borrow())))
+	}
 }
 
 pub struct MockAuctionManager;
@@ -210,6 +242,7 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = DEXModule;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = MockEmergencyShutdown;
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
@@ -227,6 +260,7 @@ impl dex::Trait for Runtime {
 	type GetExchangeFee = GetExchangeFee;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type OnUpdateLiquidity = ();
 }
 pub type DEXModule = dex::Module<Runtime>;
 
@@ -234,6 +268,20 @@ ord_parameter_types! {
 	pub const One: AccountId = 1;
 }
 
+pub struct FinancialCouncil;
+impl Contains<AccountId> for FinancialCouncil {
+	fn sorted_members() -> Vec<AccountId> {
+		vec![1, 2]
+	}
+}
+
+pub struct TechnicalCommittee;
+impl Contains<AccountId> for TechnicalCommittee {
+	fn sorted_members() -> Vec<AccountId> {
+		vec![1, 3]
+	}
+}
+
 parameter_types! {
 	pub DefaultLiquidationRatio: Ratio = Ratio::saturating_from_rational(3, 2);
 	pub DefaultDebitExchangeRate: ExchangeRate = ExchangeRate::saturating_from_integer(1);
@@ -241,11 +289,13 @@ parameter_types! {
 	pub const MinimumDebitValue: Balance = 2;
 	pub MaxSlippageSwapWithDEX: Ratio = Ratio::saturating_from_rational(50, 100);
 	pub const UnsignedPriority: u64 = 1 << 20;
+	pub MinimumPriceConfidence: Ratio = Ratio::saturating_from_rational(90, 100);
 }
 
 impl Trait for Runtime {
 	type Event = TestEvent;
 	type PriceSource = MockPriceSource;
+	type MinimumPriceConfidence = MinimumPriceConfidence;
 	type CollateralCurrencyIds = CollateralCurrencyIds;
 	type DefaultLiquidationRatio = DefaultLiquidationRatio;
 	type DefaultDebitExchangeRate = DefaultDebitExchangeRate;
@@ -253,10 +303,13 @@ impl Trait for Runtime {
 	type MinimumDebitValue = MinimumDebitValue;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CDPTreasuryModule;
-	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type UpdateOrigin = EnsureSignedBy<FinancialCouncil, AccountId>;
+	type LiquidationParamsUpdateOrigin = EnsureSignedBy<TechnicalCommittee, AccountId>;
+	type EmergencyVetoOrigin = EnsureSignedBy<One, AccountId>;
 	type MaxSlippageSwapWithDEX = MaxSlippageSwapWithDEX;
 	type DEX = DEXModule;
 	type UnsignedPriority = UnsignedPriority;
+	type OnKeeperAction = MockOnKeeperAction;
 }
 pub type CDPEngineModule = Module<Runtime>;
 
@@ -3,10 +3,15 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok, traits::OnFinalize};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OnFinalize, OnRuntimeUpgrade},
+	unsigned::ValidateUnsigned,
+};
 use mock::{
-	CDPEngineModule, CDPTreasuryModule, Currencies, DefaultDebitExchangeRate, DefaultLiquidationPenalty,
-	DefaultLiquidationRatio, ExtBuilder, LoansModule, Origin, Runtime, System, TestEvent, ACA, ALICE, AUSD, BTC, DOT,
+	CDPEngineModule, CDPTreasuryModule, Currencies, DEXModule, DefaultDebitExchangeRate, DefaultLiquidationPenalty,
+	DefaultLiquidationRatio, ExtBuilder, LoansModule, Origin, Runtime, System, TestEvent, ACA, ALICE, AUSD, BOB, BTC,
+	CAROL, DOT, KEEPER_ACTIONS, PRICE_CONFIDENCE, SHUTDOWN_PHASE,
 };
 use orml_traits::MultiCurrency;
 use sp_runtime::traits::BadOrigin;
@@ -39,6 +44,60 @@ fn is_cdp_unsafe_work() {
 	});
 }
 
+#[test]
+fn is_cdp_unsafe_require_minimum_price_confidence() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_eq!(CDPEngineModule::is_cdp_unsafe(BTC, &ALICE), true);
+
+		// no confident price to judge
+		PRICE_CONFIDENCE.with(|v| *v.borrow_mut() = Ratio::saturating_from_rational(80, 100));
+		assert_eq!(CDPEngineModule::is_cdp_unsafe(BTC, &ALICE), false);
+
+		PRICE_CONFIDENCE.with(|v| *v.borrow_mut() = Ratio::saturating_from_rational(90, 100));
+		assert_eq!(CDPEngineModule::is_cdp_unsafe(BTC, &ALICE), true);
+		CDPEngineModule::on_finalize(1);
+		assert_eq!(
+			CDPEngineModule::last_confident_prices(BTC),
+			Some(Price::saturating_from_integer(1))
+		);
+
+		// judge with the last confident price
+		PRICE_CONFIDENCE.with(|v| *v.borrow_mut() = Ratio::saturating_from_rational(80, 100));
+		assert_eq!(CDPEngineModule::is_cdp_unsafe(BTC, &ALICE), true);
+	});
+}
+
+#[test]
+fn adjust_position_halt_new_debit_when_price_confidence_too_low() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		PRICE_CONFIDENCE.with(|v| *v.borrow_mut() = Ratio::saturating_from_rational(80, 100));
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50),
+			Error::<Runtime>::PriceConfidenceTooLow,
+		);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 0));
+	});
+}
+
 #[test]
 fn get_debit_exchange_rate_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -194,23 +253,446 @@ fn set_collateral_params_work() {
 
 		let new_collateral_params = CDPEngineModule::collateral_params(BTC);
 
-		assert_eq!(
-			new_collateral_params.stability_fee,
-			Some(Rate::saturating_from_rational(1, 100000))
+		assert_eq!(
+			new_collateral_params.stability_fee,
+			Some(Rate::saturating_from_rational(1, 100000))
+		);
+		assert_eq!(
+			new_collateral_params.liquidation_ratio,
+			Some(Ratio::saturating_from_rational(3, 2))
+		);
+		assert_eq!(
+			new_collateral_params.liquidation_penalty,
+			Some(Rate::saturating_from_rational(2, 10))
+		);
+		assert_eq!(
+			new_collateral_params.required_collateral_ratio,
+			Some(Ratio::saturating_from_rational(9, 5))
+		);
+		assert_eq!(new_collateral_params.maximum_total_debit_value, 10000);
+	});
+}
+
+#[test]
+fn set_collateral_params_require_liquidation_params_update_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(2),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(10000),
+		));
+		assert_noop!(
+			CDPEngineModule::set_collateral_params(
+				Origin::signed(2),
+				BTC,
+				Change::NoChange,
+				Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_collateral_params(
+				Origin::signed(2),
+				BTC,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+				Change::NoChange,
+				Change::NoChange,
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_collateral_params(
+				Origin::signed(2),
+				BTC,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+				Change::NoChange,
+			),
+			BadOrigin
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_eq!(
+			CDPEngineModule::collateral_params(BTC).liquidation_ratio,
+			Some(Ratio::saturating_from_rational(3, 2))
+		);
+		assert_eq!(
+			CDPEngineModule::collateral_params(BTC).stability_fee,
+			Some(Rate::saturating_from_rational(1, 100000))
+		);
+	});
+}
+
+#[test]
+fn set_collateral_params_with_different_origins() {
+	ExtBuilder::default().build().execute_with(|| {
+		// 2 is only in `UpdateOrigin`, 3 is only in `LiquidationParamsUpdateOrigin`
+		assert_noop!(
+			CDPEngineModule::set_collateral_params(
+				Origin::signed(3),
+				BTC,
+				Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+			),
+			BadOrigin
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(3),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NoChange,
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(2),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(10000),
+		));
+
+		let collateral_params = CDPEngineModule::collateral_params(BTC);
+		assert_eq!(
+			collateral_params.stability_fee,
+			Some(Rate::saturating_from_rational(1, 100000))
+		);
+		assert_eq!(
+			collateral_params.liquidation_ratio,
+			Some(Ratio::saturating_from_rational(3, 2))
+		);
+		assert_eq!(
+			collateral_params.liquidation_penalty,
+			Some(Rate::saturating_from_rational(2, 10))
+		);
+		assert_eq!(
+			collateral_params.required_collateral_ratio,
+			Some(Ratio::saturating_from_rational(9, 5))
+		);
+		assert_eq!(collateral_params.maximum_total_debit_value, 10000);
+	});
+}
+
+#[test]
+fn set_collateral_params_bounds_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let bounds = RiskManagementParamsBounds {
+			maximum_total_debit_value: Some((0, 10000)),
+			stability_fee: None,
+			liquidation_ratio: Some((
+				Ratio::saturating_from_rational(3, 2),
+				Ratio::saturating_from_rational(3, 1),
+			)),
+			liquidation_penalty: None,
+			required_collateral_ratio: None,
+		};
+		assert_noop!(
+			CDPEngineModule::set_collateral_params_bounds(Origin::signed(2), BTC, bounds.clone()),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_collateral_params_bounds(Origin::signed(1), AUSD, bounds.clone()),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_noop!(
+			CDPEngineModule::set_collateral_params_bounds(
+				Origin::signed(1),
+				BTC,
+				RiskManagementParamsBounds {
+					maximum_total_debit_value: Some((10000, 0)),
+					..Default::default()
+				}
+			),
+			Error::<Runtime>::InvalidBounds
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params_bounds(
+			Origin::signed(1),
+			BTC,
+			bounds.clone()
+		));
+		let update_bounds_event = TestEvent::cdp_engine(RawEvent::CollateralParamsBoundsUpdated(BTC, bounds.clone()));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == update_bounds_event));
+		assert_eq!(CDPEngineModule::collateral_params_bounds(BTC), bounds);
+
+		assert_noop!(
+			CDPEngineModule::set_collateral_params(
+				Origin::signed(1),
+				BTC,
+				Change::NoChange,
+				Change::NewValue(Some(Ratio::saturating_from_rational(4, 1))),
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+			),
+			Error::<Runtime>::ParamOutOfBounds
+		);
+		assert_noop!(
+			CDPEngineModule::set_collateral_params(
+				Origin::signed(1),
+				BTC,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NewValue(10001),
+			),
+			Error::<Runtime>::ParamOutOfBounds
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(2, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(10000),
+		));
+		assert_eq!(
+			CDPEngineModule::collateral_params(BTC).liquidation_ratio,
+			Some(Ratio::saturating_from_rational(2, 1))
+		);
+	});
+}
+
+#[test]
+fn set_global_params_bounds_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let bounds = Some((Rate::zero(), Rate::saturating_from_rational(1, 1000)));
+		assert_noop!(
+			CDPEngineModule::set_global_params_bounds(Origin::signed(2), bounds),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_global_params_bounds(
+				Origin::signed(1),
+				Some((Rate::saturating_from_rational(1, 1000), Rate::zero()))
+			),
+			Error::<Runtime>::InvalidBounds
+		);
+		assert_ok!(CDPEngineModule::set_global_params_bounds(Origin::signed(1), bounds));
+		let update_bounds_event = TestEvent::cdp_engine(RawEvent::GlobalStabilityFeeBoundsUpdated(bounds));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == update_bounds_event));
+
+		assert_noop!(
+			CDPEngineModule::set_global_params(Origin::signed(1), Rate::saturating_from_rational(1, 100)),
+			Error::<Runtime>::ParamOutOfBounds
+		);
+		assert_ok!(CDPEngineModule::set_global_params(
+			Origin::signed(1),
+			Rate::saturating_from_rational(1, 1000)
+		));
+		assert_eq!(
+			CDPEngineModule::global_stability_fee(),
+			Rate::saturating_from_rational(1, 1000)
+		);
+	});
+}
+
+#[test]
+fn ramp_collateral_ratios_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CDPEngineModule::ramp_collateral_ratios(
+				Origin::signed(2),
+				BTC,
+				Some(Ratio::saturating_from_rational(2, 1)),
+				None,
+				10
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::ramp_collateral_ratios(
+				Origin::signed(1),
+				BTC,
+				Some(Ratio::saturating_from_rational(2, 1)),
+				None,
+				0
+			),
+			Error::<Runtime>::InvalidRampDuration
+		);
+		assert_noop!(
+			CDPEngineModule::ramp_collateral_ratios(Origin::signed(1), BTC, None, None, 10),
+			Error::<Runtime>::NoRampTarget
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params_bounds(
+			Origin::signed(1),
+			BTC,
+			RiskManagementParamsBounds {
+				liquidation_ratio: Some((
+					Ratio::saturating_from_rational(1, 1),
+					Ratio::saturating_from_rational(2, 1)
+				)),
+				..Default::default()
+			}
+		));
+		assert_noop!(
+			CDPEngineModule::ramp_collateral_ratios(
+				Origin::signed(1),
+				BTC,
+				Some(Ratio::saturating_from_rational(3, 1)),
+				None,
+				10
+			),
+			Error::<Runtime>::ParamOutOfBounds
+		);
+
+		assert_eq!(
+			CDPEngineModule::get_liquidation_ratio(BTC),
+			Ratio::saturating_from_rational(3, 2)
+		);
+		assert_eq!(CDPEngineModule::required_collateral_ratio(BTC), None);
+		assert_ok!(CDPEngineModule::ramp_collateral_ratios(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::saturating_from_rational(2, 1)),
+			Some(Ratio::saturating_from_rational(5, 2)),
+			10
+		));
+		let ramp_event = TestEvent::cdp_engine(RawEvent::CollateralRatiosRampStarted(
+			BTC,
+			Some(Ratio::saturating_from_rational(2, 1)),
+			Some(Ratio::saturating_from_rational(5, 2)),
+			11,
+		));
+		assert!(System::events().iter().any(|record| record.event == ramp_event));
+		assert_eq!(
+			CDPEngineModule::get_liquidation_ratio(BTC),
+			Ratio::saturating_from_rational(3, 2)
+		);
+
+		System::set_block_number(6);
+		assert_eq!(
+			CDPEngineModule::get_liquidation_ratio(BTC),
+			Ratio::saturating_from_rational(7, 4)
+		);
+		assert_eq!(
+			CDPEngineModule::required_collateral_ratio(BTC),
+			Some(Ratio::saturating_from_rational(2, 1))
+		);
+		CDPEngineModule::on_finalize(6);
+		assert!(CDPEngineModule::liquidation_ratio_ramps(BTC).is_some());
+
+		System::set_block_number(11);
+		assert_eq!(
+			CDPEngineModule::get_liquidation_ratio(BTC),
+			Ratio::saturating_from_rational(2, 1)
+		);
+		CDPEngineModule::on_finalize(11);
+		assert_eq!(CDPEngineModule::liquidation_ratio_ramps(BTC), None);
+		assert_eq!(CDPEngineModule::required_collateral_ratio_ramps(BTC), None);
+		assert_eq!(
+			CDPEngineModule::collateral_params(BTC).liquidation_ratio,
+			Some(Ratio::saturating_from_rational(2, 1))
+		);
+		assert_eq!(
+			CDPEngineModule::collateral_params(BTC).required_collateral_ratio,
+			Some(Ratio::saturating_from_rational(5, 2))
+		);
+
+		// ramp down and cancel it by updating directly
+		assert_ok!(CDPEngineModule::ramp_collateral_ratios(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::saturating_from_rational(1, 1)),
+			None,
+			4
+		));
+		System::set_block_number(13);
+		assert_eq!(
+			CDPEngineModule::get_liquidation_ratio(BTC),
+			Ratio::saturating_from_rational(3, 2)
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_eq!(CDPEngineModule::liquidation_ratio_ramps(BTC), None);
+		assert_eq!(
+			CDPEngineModule::get_liquidation_ratio(BTC),
+			Ratio::saturating_from_rational(9, 5)
+		);
+	});
+}
+
+#[test]
+fn veto_collateral_ratios_ramp_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CDPEngineModule::veto_collateral_ratios_ramp(Origin::signed(1), BTC),
+			Error::<Runtime>::NoPendingRamp
 		);
+		assert_ok!(CDPEngineModule::ramp_collateral_ratios(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::saturating_from_rational(2, 1)),
+			None,
+			10
+		));
+		System::set_block_number(6);
 		assert_eq!(
-			new_collateral_params.liquidation_ratio,
-			Some(Ratio::saturating_from_rational(3, 2))
+			CDPEngineModule::get_liquidation_ratio(BTC),
+			Ratio::saturating_from_rational(7, 4)
 		);
-		assert_eq!(
-			new_collateral_params.liquidation_penalty,
-			Some(Rate::saturating_from_rational(2, 10))
+
+		assert_noop!(
+			CDPEngineModule::veto_collateral_ratios_ramp(Origin::signed(2), BTC),
+			BadOrigin
 		);
+		assert_ok!(CDPEngineModule::veto_collateral_ratios_ramp(Origin::signed(1), BTC));
+		let veto_event = TestEvent::cdp_engine(RawEvent::CollateralRatiosRampVetoed(
+			BTC,
+			Some(1),
+			Some(Ratio::saturating_from_rational(2, 1)),
+			None,
+		));
+		assert!(System::events().iter().any(|record| record.event == veto_event));
+		assert_eq!(CDPEngineModule::liquidation_ratio_ramps(BTC), None);
 		assert_eq!(
-			new_collateral_params.required_collateral_ratio,
-			Some(Ratio::saturating_from_rational(9, 5))
+			CDPEngineModule::get_liquidation_ratio(BTC),
+			Ratio::saturating_from_rational(3, 2)
 		);
-		assert_eq!(new_collateral_params.maximum_total_debit_value, 10000);
+
+		System::set_block_number(11);
+		CDPEngineModule::on_finalize(11);
+		assert_eq!(CDPEngineModule::collateral_params(BTC).liquidation_ratio, None);
 	});
 }
 
@@ -233,6 +715,30 @@ fn calculate_collateral_ratio_work() {
 	});
 }
 
+#[test]
+fn calculate_liquidation_price_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPEngineModule::calculate_liquidation_price(BTC, 0, 50), None);
+		assert_eq!(
+			CDPEngineModule::calculate_liquidation_price(BTC, 100, 50),
+			Some(Price::saturating_from_rational(75, 100))
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(2, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_eq!(
+			CDPEngineModule::calculate_liquidation_price(BTC, 100, 50),
+			Some(Price::saturating_from_integer(1))
+		);
+	});
+}
+
 #[test]
 fn check_debit_cap_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -411,6 +917,54 @@ fn liquidate_unsafe_cdp_by_collateral_auction() {
 	});
 }
 
+#[test]
+fn liquidate_unsafe_cdp_by_swap() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(Origin::signed(CAROL), BTC, 100, 1000));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+
+		let liquidate_unsafe_cdp_event = TestEvent::cdp_engine(RawEvent::LiquidateUnsafeCDP(
+			BTC,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::Exchange,
+		));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == liquidate_unsafe_cdp_event));
+
+		// swap 7 BTC for exactly 60 aUSD, and refund the rest collateral
+		assert_eq!(DEXModule::liquidity_pool(BTC), (107, 940));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 60);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 993);
+		assert_eq!(LoansModule::debits(BTC, ALICE), 0);
+		assert_eq!(LoansModule::collaterals(ALICE, BTC), 0);
+	});
+}
+
 #[test]
 fn on_finalize_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -463,7 +1017,30 @@ fn on_finalize_work() {
 }
 
 #[test]
-fn on_emergency_shutdown_work() {
+fn on_finalize_emit_stability_fee_accrued_event() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 30));
+		CDPEngineModule::on_finalize(1);
+
+		let stability_fee_accrued_event = TestEvent::cdp_engine(RawEvent::StabilityFeeAccrued(BTC, 3));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == stability_fee_accrued_event));
+	});
+}
+
+#[test]
+fn is_shutdown_follows_shutdown_phase() {
 	ExtBuilder::default().build().execute_with(|| {
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::ROOT,
@@ -481,8 +1058,14 @@ fn on_emergency_shutdown_work() {
 			Some(ExchangeRate::saturating_from_rational(101, 100))
 		);
 		assert_eq!(CDPEngineModule::is_shutdown(), false);
-		CDPEngineModule::on_emergency_shutdown();
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
 		assert_eq!(CDPEngineModule::is_shutdown(), true);
+		assert_eq!(CDPEngineModule::is_settling(), false);
+
+		// the shutdown flag of previous version is removed
+		IsShutdown::put(true);
+		CDPEngineModule::on_runtime_upgrade();
+		assert_eq!(IsShutdown::exists(), false);
 		CDPEngineModule::on_finalize(2);
 		assert_eq!(
 			CDPEngineModule::debit_exchange_rate(BTC),
@@ -528,3 +1111,235 @@ fn settle_cdp_has_debit_work() {
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 50);
 	});
 }
+
+#[test]
+fn settle_only_in_settling_phase() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_noop!(
+			CDPEngineModule::settle(Origin::NONE, BTC, ALICE),
+			Error::<Runtime>::MustAfterShutdown,
+		);
+
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
+		assert_noop!(
+			CDPEngineModule::settle(Origin::NONE, BTC, ALICE),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Settling);
+		assert_ok!(CDPEngineModule::settle(Origin::NONE, BTC, ALICE));
+		assert_eq!(LoansModule::debits(BTC, ALICE), 0);
+	});
+}
+
+#[test]
+fn liquidate_and_settle_by_keeper_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 50));
+		assert_noop!(
+			CDPEngineModule::liquidate_by_keeper(Origin::NONE, BTC, ALICE),
+			BadOrigin,
+		);
+		assert_noop!(
+			CDPEngineModule::liquidate_by_keeper(Origin::signed(CAROL), BTC, ALICE),
+			Error::<Runtime>::MustBeUnsafe,
+		);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(CDPEngineModule::liquidate_by_keeper(Origin::signed(CAROL), BTC, ALICE));
+		assert_eq!(LoansModule::debits(BTC, ALICE), 0);
+		assert_eq!(KEEPER_ACTIONS.with(|v| v.borrow().clone()), vec![(CAROL, BTC, 50)]);
+
+		assert_noop!(
+			CDPEngineModule::settle_by_keeper(Origin::signed(CAROL), BTC, BOB),
+			Error::<Runtime>::MustAfterShutdown,
+		);
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Settling);
+		assert_ok!(CDPEngineModule::settle_by_keeper(Origin::signed(CAROL), BTC, BOB));
+		assert_eq!(LoansModule::debits(BTC, BOB), 0);
+		assert_eq!(
+			KEEPER_ACTIONS.with(|v| v.borrow().clone()),
+			vec![(CAROL, BTC, 50), (CAROL, BTC, 50)]
+		);
+	});
+}
+
+#[test]
+fn retire_collateral_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(CDPEngineModule::retire_collateral(Origin::signed(5), BTC), BadOrigin);
+		assert_noop!(
+			CDPEngineModule::retire_collateral(Origin::signed(1), ACA),
+			Error::<Runtime>::InvalidCollateralType,
+		);
+		assert_ok!(CDPEngineModule::retire_collateral(Origin::signed(1), BTC));
+
+		let collateral_retired_event =
+			TestEvent::cdp_engine(RawEvent::CollateralRetired(BTC, Price::saturating_from_integer(1)));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == collateral_retired_event));
+		assert_eq!(
+			CDPEngineModule::retired_collaterals(BTC),
+			Some(Price::saturating_from_integer(1))
+		);
+		assert_noop!(
+			CDPEngineModule::retire_collateral(Origin::ROOT, BTC),
+			Error::<Runtime>::CollateralRetired,
+		);
+
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
+		assert_noop!(
+			CDPEngineModule::retire_collateral(Origin::ROOT, DOT),
+			Error::<Runtime>::AlreadyShutdown,
+		);
+	});
+}
+
+#[test]
+fn retired_collateral_settle_and_redeem_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_noop!(
+			CDPEngineModule::redeem_retired_collateral(Origin::signed(ALICE), BTC, 10),
+			Error::<Runtime>::CollateralNotRetired,
+		);
+
+		assert_ok!(CDPEngineModule::retire_collateral(Origin::ROOT, BTC));
+		assert_eq!(CDPEngineModule::is_cdp_unsafe(BTC, &ALICE), false);
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, BTC, 10, 0),
+			Error::<Runtime>::CollateralRetired,
+		);
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, BTC, 0, 10),
+			Error::<Runtime>::CollateralRetired,
+		);
+		assert_noop!(
+			CDPEngineModule::redeem_retired_collateral(Origin::signed(ALICE), BTC, 10),
+			Error::<Runtime>::ExistUnsettledDebit,
+		);
+
+		// loans are frozen during emergency shutdown, the settlement is invalid before submitted
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
+		assert_eq!(CDPEngineModule::can_settle(BTC), false);
+		assert_eq!(
+			CDPEngineModule::validate_unsigned(TransactionSource::External, &Call::settle(BTC, ALICE)),
+			InvalidTransaction::Stale.into(),
+		);
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Operational);
+
+		// retired collateral can be settled without shutdown
+		assert_ok!(CDPEngineModule::settle(Origin::NONE, BTC, ALICE));
+		assert_eq!(LoansModule::debits(BTC, ALICE), 0);
+		assert_eq!(LoansModule::collaterals(ALICE, BTC), 50);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 50);
+
+		// the collateral is refunded by emergency shutdown after shutdown
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Settling);
+		assert_noop!(
+			CDPEngineModule::redeem_retired_collateral(Origin::signed(ALICE), BTC, 20),
+			Error::<Runtime>::AlreadyShutdown,
+		);
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Operational);
+
+		assert_ok!(CDPEngineModule::redeem_retired_collateral(
+			Origin::signed(ALICE),
+			BTC,
+			20
+		));
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 30);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 920);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 30);
+		assert_noop!(
+			CDPEngineModule::redeem_retired_collateral(Origin::signed(ALICE), BTC, 40),
+			Error::<Runtime>::CollateralNotEnough,
+		);
+
+		// owner can withdraw the remaining collateral
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, -50, 0));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 970);
+	});
+}
+
+#[test]
+fn check_invariants_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::check_invariants());
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::ROOT,
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+
+		// the debit exchange rate only grows by the stability fee
+		CDPEngineModule::on_finalize(1);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(BTC),
+			ExchangeRate::saturating_from_rational(101, 100)
+		);
+		assert_ok!(CDPEngineModule::check_invariants());
+
+		// still above the default rate, but below the rate before the last accrual
+		CDPEngineModule::on_finalize(2);
+		assert_eq!(
+			CDPEngineModule::previous_debit_exchange_rate(BTC),
+			Some(ExchangeRate::saturating_from_rational(101, 100))
+		);
+		DebitExchangeRate::insert(BTC, ExchangeRate::saturating_from_rational(1005, 1000));
+		assert_eq!(
+			CDPEngineModule::check_invariants(),
+			Err("debit exchange rate decreased")
+		);
+
+		DebitExchangeRate::insert(BTC, ExchangeRate::saturating_from_rational(1, 2));
+		assert_eq!(
+			CDPEngineModule::check_invariants(),
+			Err("debit exchange rate decreased")
+		);
+	});
+}
@@ -11,16 +11,18 @@
 use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage, ensure,
 	traits::{EnsureOrigin, Get},
-	weights::constants::WEIGHT_PER_MICROS,
+	weights::{constants::WEIGHT_PER_MICROS, Weight},
+	IterableStorageMap,
 };
 use frame_system::{self as system, ensure_root};
 use orml_traits::{MultiCurrency, MultiCurrencyExtended};
 use primitives::{Balance, CurrencyId};
 use sp_runtime::{
-	traits::{AccountIdConversion, Zero},
+	traits::{AccountIdConversion, Saturating, Zero},
 	DispatchError, DispatchResult, FixedPointNumber, ModuleId,
 };
-use support::{AuctionManager, CDPTreasury, CDPTreasuryExtended, DEXManager, OnEmergencyShutdown, Ratio};
+use sp_std::marker;
+use support::{AuctionManager, CDPTreasury, CDPTreasuryExtended, DEXManager, EmergencyShutdown, Ratio};
 
 mod benchmarking;
 mod mock;
@@ -50,6 +52,9 @@ pub trait Trait: system::Trait {
 	/// or to create debit/surplus auction on block end.
 	/// If set to 0, does not work.
 	type MaxAuctionsCount: Get<u32>;
+
+	/// Emergency shutdown phase, collateral auctions are not allowed to create after shutdown
+	type EmergencyShutdown: EmergencyShutdown;
 }
 
 decl_event!(
@@ -80,6 +85,8 @@ decl_error! {
 		SurplusPoolOverflow,
 		/// debit pool overflow
 		DebitPoolOverflow,
+		/// The swap path does not start with the collateral or end with stable currency
+		InvalidSwapPath,
 	}
 }
 
@@ -111,8 +118,8 @@ decl_storage! {
 		/// Mapping from collateral type to collateral assets amount kept in CDP treasury
 		pub TotalCollaterals get(fn total_collaterals): map hasher(twox_64_concat) CurrencyId => Balance;
 
-		/// System shutdown flag
-		pub IsShutdown get(fn is_shutdown): bool;
+		/// System shutdown flag of previous version, derived from the phase of emergency shutdown now
+		IsShutdown: bool;
 	}
 
 	add_extra_genesis {
@@ -205,6 +212,12 @@ decl_module! {
 			Self::deposit_event(Event::CollateralAuctionMaximumSizeUpdated(currency_id, size));
 		}
 
+		/// Remove the shutdown flag of previous version
+		fn on_runtime_upgrade() -> Weight {
+			IsShutdown::kill();
+			T::DbWeight::get().writes(1)
+		}
+
 		/// Handle excessive surplus or debits of system when block end
 		fn on_finalize(_now: T::BlockNumber) {
 			// offset the same amount between debit pool and surplus pool
@@ -261,6 +274,40 @@ impl<T: Trait> Module<T> {
 		MODULE_ID.into_account()
 	}
 
+	fn ensure_swap_path(currency_id: CurrencyId, path: &[CurrencyId]) -> DispatchResult {
+		ensure!(
+			path.first() == Some(&currency_id) && path.last() == Some(&T::GetStableCurrencyId::get()),
+			Error::<T>::InvalidSwapPath,
+		);
+		Ok(())
+	}
+
+	/// Check the accounting invariants of CDP treasury: the treasury account holds
+	/// the surplus pool and the total collaterals.
+	pub fn check_invariants() -> Result<(), &'static str> {
+		ensure!(
+			T::Currency::free_balance(T::GetStableCurrencyId::get(), &Self::account_id()) >= Self::surplus_pool(),
+			"treasury account not holding surplus pool"
+		);
+		for (currency_id, total_collateral) in TotalCollaterals::iter() {
+			ensure!(
+				T::Currency::free_balance(currency_id, &Self::account_id()) >= total_collateral,
+				"treasury account not holding total collaterals"
+			);
+		}
+
+		Ok(())
+	}
+
+	/// The surplus which is not needed to offset the debit pool, not in surplus auctions
+	/// and above the surplus buffer, it can be spent without affecting the system.
+	pub fn spendable_surplus() -> Balance {
+		Self::surplus_pool()
+			.saturating_sub(Self::debit_pool())
+			.saturating_sub(T::AuctionManagerHandler::get_total_surplus_in_auction())
+			.saturating_sub(Self::surplus_buffer_size())
+	}
+
 	pub fn offset_surplus_and_debit() {
 		let offset_amount = sp_std::cmp::min(Self::debit_pool(), Self::surplus_pool());
 
@@ -273,8 +320,9 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
-	pub fn emergency_shutdown() {
-		<IsShutdown>::put(true);
+	/// Whether the system is shut down, derived from the phase of emergency shutdown
+	pub fn is_shutdown() -> bool {
+		T::EmergencyShutdown::is_shutdown()
 	}
 }
 
@@ -333,6 +381,15 @@ impl<T: Trait> CDPTreasury<T::AccountId> for Module<T> {
 		Ok(())
 	}
 
+	fn transfer_surplus_to(to: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		let new_surplus_pool = Self::surplus_pool()
+			.checked_sub(amount)
+			.ok_or(Error::<T>::SurplusPoolNotEnough)?;
+		T::Currency::transfer(T::GetStableCurrencyId::get(), &Self::account_id(), to, amount)?;
+		SurplusPool::put(new_surplus_pool);
+		Ok(())
+	}
+
 	fn transfer_collateral_to(
 		currency_id: Self::CurrencyId,
 		to: &T::AccountId,
@@ -359,6 +416,15 @@ impl<T: Trait> CDPTreasury<T::AccountId> for Module<T> {
 		Ok(())
 	}
 
+	fn burn_collateral(currency_id: Self::CurrencyId, amount: Self::Balance) -> DispatchResult {
+		let new_total_collateral = Self::total_collaterals(currency_id)
+			.checked_sub(amount)
+			.ok_or(Error::<T>::CollateralNotEnough)?;
+		T::Currency::withdraw(currency_id, &Self::account_id(), amount)?;
+		TotalCollaterals::insert(currency_id, new_total_collateral);
+		Ok(())
+	}
+
 	fn get_debit_proportion(amount: Self::Balance) -> Ratio {
 		let stable_total_supply = T::Currency::total_issuance(T::GetStableCurrencyId::get());
 		Ratio::checked_from_rational(amount, stable_total_supply).unwrap_or_default()
@@ -366,24 +432,20 @@ impl<T: Trait> CDPTreasury<T::AccountId> for Module<T> {
 }
 
 impl<T: Trait> CDPTreasuryExtended<T::AccountId> for Module<T> {
-	fn swap_collateral_to_stable(
+	fn swap_exact_collateral_to_stable(
 		currency_id: CurrencyId,
 		supply_amount: Balance,
-		target_amount: Balance,
+		min_target_amount: Balance,
+		path: &[CurrencyId],
 	) -> sp_std::result::Result<Balance, DispatchError> {
+		Self::ensure_swap_path(currency_id, path)?;
 		ensure!(
 			Self::total_collaterals(currency_id) >= supply_amount,
 			Error::<T>::CollateralNotEnough,
 		);
 		T::Currency::ensure_can_withdraw(currency_id, &Self::account_id(), supply_amount)?;
 
-		let amount = T::DEX::exchange_currency(
-			Self::account_id(),
-			currency_id,
-			supply_amount,
-			T::GetStableCurrencyId::get(),
-			target_amount,
-		)?;
+		let amount = T::DEX::swap_with_exact_supply(&Self::account_id(), path, supply_amount, min_target_amount)?;
 
 		TotalCollaterals::mutate(currency_id, |balance| *balance -= supply_amount);
 		SurplusPool::mutate(|surplus| *surplus += amount);
@@ -391,12 +453,38 @@ impl<T: Trait> CDPTreasuryExtended<T::AccountId> for Module<T> {
 		Ok(amount)
 	}
 
+	fn swap_collateral_to_exact_stable(
+		currency_id: CurrencyId,
+		max_supply_amount: Balance,
+		target_amount: Balance,
+		path: &[CurrencyId],
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Self::ensure_swap_path(currency_id, path)?;
+		ensure!(
+			Self::total_collaterals(currency_id) >= max_supply_amount,
+			Error::<T>::CollateralNotEnough,
+		);
+		T::Currency::ensure_can_withdraw(currency_id, &Self::account_id(), max_supply_amount)?;
+
+		let supply_amount =
+			T::DEX::swap_with_exact_target(&Self::account_id(), path, target_amount, max_supply_amount)?;
+
+		TotalCollaterals::mutate(currency_id, |balance| *balance -= supply_amount);
+		SurplusPool::mutate(|surplus| *surplus += target_amount);
+
+		Ok(supply_amount)
+	}
+
 	fn create_collateral_auctions(
 		currency_id: CurrencyId,
 		amount: Balance,
 		target: Balance,
 		refund_receiver: T::AccountId,
 	) {
+		if Self::is_shutdown() {
+			return;
+		}
+
 		if Self::total_collaterals(currency_id)
 			>= amount + T::AuctionManagerHandler::get_total_collateral_in_auction(currency_id)
 		{
@@ -437,8 +525,11 @@ impl<T: Trait> CDPTreasuryExtended<T::AccountId> for Module<T> {
 	}
 }
 
-impl<T: Trait> OnEmergencyShutdown for Module<T> {
-	fn on_emergency_shutdown() {
-		Self::emergency_shutdown();
+/// The spendable surplus of CDP treasury
+pub struct SpendableSurplus<T>(marker::PhantomData<T>);
+
+impl<T: Trait> Get<Balance> for SpendableSurplus<T> {
+	fn get() -> Balance {
+		<Module<T>>::spendable_surplus()
 	}
 }
@@ -8,7 +8,7 @@ use frame_system::EnsureSignedBy;
 use sp_core::H256;
 use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
 use sp_std::cell::RefCell;
-use support::Rate;
+use support::{Rate, ShutdownPhase};
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -129,6 +129,7 @@ impl dex::Trait for Runtime {
 	type GetExchangeFee = GetExchangeFee;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type OnUpdateLiquidity = ();
 }
 pub type DEXModule = dex::Module<Runtime>;
 
@@ -136,6 +137,14 @@ thread_local! {
 	pub static TOTAL_COLLATERAL_AUCTION: RefCell<u32> = RefCell::new(0);
 	pub static TOTAL_DEBIT_AUCTION: RefCell<u32> = RefCell::new(0);
 	pub static TOTAL_SURPLUS_AUCTION: RefCell<u32> = RefCell::new(0);
+	pub static SHUTDOWN_PHASE: RefCell<ShutdownPhase> = RefCell::new(ShutdownPhase::Operational);
+}
+
+pub struct MockEmergencyShutdown;
+impl EmergencyShutdown for MockEmergencyShutdown {
+	fn shutdown_phase() -> ShutdownPhase {
+		SHUTDOWN_PHASE.with(|v| *v.borrow())
+	}
 }
 
 pub struct MockAuctionManager;
@@ -195,6 +204,7 @@ impl Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = DEXModule;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = MockEmergencyShutdown;
 }
 pub type CDPTreasuryModule = Module<Runtime>;
 
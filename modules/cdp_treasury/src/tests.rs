@@ -3,12 +3,16 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok, traits::OnFinalize};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OnFinalize, OnRuntimeUpgrade},
+};
 use mock::{
 	CDPTreasuryModule, Currencies, DEXModule, ExtBuilder, Origin, Runtime, System, TestEvent, ALICE, AUSD, BOB, BTC,
-	TOTAL_COLLATERAL_AUCTION, TOTAL_DEBIT_AUCTION, TOTAL_SURPLUS_AUCTION,
+	SHUTDOWN_PHASE, TOTAL_COLLATERAL_AUCTION, TOTAL_DEBIT_AUCTION, TOTAL_SURPLUS_AUCTION,
 };
 use sp_runtime::traits::BadOrigin;
+use support::ShutdownPhase;
 
 #[test]
 fn set_collateral_auction_maximum_size_work() {
@@ -166,11 +170,18 @@ fn withdraw_backed_debit_from_work() {
 }
 
 #[test]
-fn emergency_shutdown_work() {
+fn is_shutdown_follows_shutdown_phase() {
 	ExtBuilder::default().build().execute_with(|| {
 		assert_eq!(CDPTreasuryModule::is_shutdown(), false);
-		CDPTreasuryModule::emergency_shutdown();
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
 		assert_eq!(CDPTreasuryModule::is_shutdown(), true);
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Operational);
+		assert_eq!(CDPTreasuryModule::is_shutdown(), false);
+
+		// the shutdown flag of previous version is removed
+		IsShutdown::put(true);
+		CDPTreasuryModule::on_runtime_upgrade();
+		assert_eq!(IsShutdown::exists(), false);
 	});
 }
 
@@ -209,6 +220,36 @@ fn transfer_collateral_to_work() {
 	});
 }
 
+#[test]
+fn transfer_surplus_to_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::on_system_surplus(500));
+		assert_noop!(
+			CDPTreasuryModule::transfer_surplus_to(&BOB, 501),
+			Error::<Runtime>::SurplusPoolNotEnough,
+		);
+		assert_ok!(CDPTreasuryModule::transfer_surplus_to(&BOB, 200));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 300);
+		assert_eq!(Currencies::free_balance(AUSD, &CDPTreasuryModule::account_id()), 300);
+		assert_eq!(Currencies::free_balance(AUSD, &BOB), 1200);
+	});
+}
+
+#[test]
+fn burn_collateral_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &ALICE, 500));
+		assert_noop!(
+			CDPTreasuryModule::burn_collateral(BTC, 501),
+			Error::<Runtime>::CollateralNotEnough,
+		);
+		assert_ok!(CDPTreasuryModule::burn_collateral(BTC, 400));
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 100);
+		assert_eq!(Currencies::total_issuance(BTC), 1600);
+	});
+}
+
 #[test]
 fn get_total_collaterals_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -226,6 +267,27 @@ fn get_surplus_pool_work() {
 	});
 }
 
+#[test]
+fn spendable_surplus_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_eq!(CDPTreasuryModule::spendable_surplus(), 1000);
+		assert_ok!(CDPTreasuryModule::on_system_debit(300));
+		assert_eq!(CDPTreasuryModule::spendable_surplus(), 700);
+		assert_ok!(CDPTreasuryModule::set_debit_and_surplus_handle_params(
+			Origin::ROOT,
+			None,
+			Some(500),
+			None,
+			None,
+		));
+		assert_eq!(CDPTreasuryModule::spendable_surplus(), 200);
+		assert_eq!(SpendableSurplus::<Runtime>::get(), 200);
+		assert_ok!(CDPTreasuryModule::on_system_debit(300));
+		assert_eq!(CDPTreasuryModule::spendable_surplus(), 0);
+	});
+}
+
 #[test]
 fn get_debit_proportion_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -237,19 +299,52 @@ fn get_debit_proportion_work() {
 }
 
 #[test]
-fn swap_collateral_to_stable_work() {
+fn swap_exact_collateral_to_stable_work() {
 	ExtBuilder::default().build().execute_with(|| {
 		assert_ok!(DEXModule::add_liquidity(Origin::signed(ALICE), BTC, 100, 1000));
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
 		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &BOB, 100));
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
-		assert_ok!(CDPTreasuryModule::swap_collateral_to_stable(BTC, 100, 500));
+		assert_noop!(
+			CDPTreasuryModule::swap_exact_collateral_to_stable(BTC, 100, 500, &[AUSD, BTC]),
+			Error::<Runtime>::InvalidSwapPath,
+		);
+		assert_noop!(
+			CDPTreasuryModule::swap_exact_collateral_to_stable(BTC, 101, 500, &[BTC, AUSD]),
+			Error::<Runtime>::CollateralNotEnough,
+		);
+		assert_ok!(CDPTreasuryModule::swap_exact_collateral_to_stable(
+			BTC,
+			100,
+			500,
+			&[BTC, AUSD]
+		));
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 500);
 	});
 }
 
+#[test]
+fn swap_collateral_to_exact_stable_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DEXModule::add_liquidity(Origin::signed(ALICE), BTC, 100, 1000));
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &BOB, 100));
+		assert_noop!(
+			CDPTreasuryModule::swap_collateral_to_exact_stable(BTC, 100, 400, &[BTC]),
+			Error::<Runtime>::InvalidSwapPath,
+		);
+		let supply_amount = DEXModule::get_swap_supply_amount(&[BTC, AUSD], 400).unwrap();
+		assert!(supply_amount < 100);
+		assert_eq!(
+			CDPTreasuryModule::swap_collateral_to_exact_stable(BTC, 100, 400, &[BTC, AUSD]),
+			Ok(supply_amount)
+		);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100 - supply_amount);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 400);
+	});
+}
+
 #[test]
 fn create_collateral_auctions_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -277,6 +372,16 @@ fn create_collateral_auctions_work() {
 	});
 }
 
+#[test]
+fn create_collateral_auctions_not_work_after_shutdown() {
+	ExtBuilder::default().build().execute_with(|| {
+		TotalCollaterals::mutate(BTC, |balance| *balance += 10000);
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
+		CDPTreasuryModule::create_collateral_auctions(BTC, 1000, 1000, ALICE);
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 0);
+	});
+}
+
 #[test]
 fn create_surplus_auction_when_on_finalize() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -322,3 +427,26 @@ fn create_debit_auction_when_on_finalize() {
 		assert_eq!(TOTAL_DEBIT_AUCTION.with(|v| *v.borrow_mut()), 8);
 	});
 }
+
+#[test]
+fn check_invariants_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::check_invariants());
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &ALICE, 500));
+		assert_ok!(CDPTreasuryModule::check_invariants());
+
+		SurplusPool::put(1001);
+		assert_eq!(
+			CDPTreasuryModule::check_invariants(),
+			Err("treasury account not holding surplus pool")
+		);
+		SurplusPool::put(1000);
+
+		TotalCollaterals::insert(BTC, 501);
+		assert_eq!(
+			CDPTreasuryModule::check_invariants(),
+			Err("treasury account not holding total collaterals")
+		);
+	});
+}
@@ -0,0 +1,280 @@
+//! # Collateral Onboarding Module
+//!
+//! ## Overview
+//!
+//! Anyone can propose a new collateral type for CDPs by reserving `ProposalBond` of native currency
+//! together with the proposed risk management params. Governance approves or rejects the proposal:
+//! approval requires the currency to have liquidity paired with stable currency in DEX, adds it to the
+//! collateral types returned by `CollateralCurrencyIds`, configures it by `OnCollateralOnboarded`
+//! (risk management params, incentive pool, etc.) and returns the bond; rejection slashes the bond.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{EnsureOrigin, Get},
+	weights::constants::WEIGHT_PER_MICROS,
+};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use orml_traits::MultiReservableCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	DispatchResult, RuntimeDebug,
+};
+use sp_std::{marker, prelude::*};
+use support::{DEXManager, Rate, Ratio};
+
+mod mock;
+mod tests;
+
+/// Risk management params and incentives proposed for a new collateral type
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct CollateralOnboardingParams {
+	/// Maximum total debit value generated from the collateral type
+	pub maximum_total_debit_value: Balance,
+
+	/// Extra stability fee rate, `None` value means not set
+	pub stability_fee: Option<Rate>,
+
+	/// Liquidation ratio, `None` value means not set
+	pub liquidation_ratio: Option<Ratio>,
+
+	/// Liquidation penalty rate, `None` value means not set
+	pub liquidation_penalty: Option<Rate>,
+
+	/// Required collateral ratio, `None` value means not set
+	pub required_collateral_ratio: Option<Ratio>,
+
+	/// Weight of the loans of the collateral type in incentive emission, zero means no rewards
+	pub loans_incentive_weight: u32,
+}
+
+/// Pending proposal of a new collateral type
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct CollateralProposal<AccountId> {
+	/// The proposer who reserved the bond
+	pub proposer: AccountId,
+
+	/// The amount of reserved bond
+	pub bond: Balance,
+
+	/// The proposed params
+	pub params: CollateralOnboardingParams,
+}
+
+/// Hook to configure the approved collateral type in other modules
+pub trait OnCollateralOnboarded {
+	/// Check the collateral type can be configured with `params`, before anything is written
+	fn ensure_can_onboard(currency_id: CurrencyId, params: &CollateralOnboardingParams) -> DispatchResult;
+
+	fn on_collateral_onboarded(currency_id: CurrencyId, params: &CollateralOnboardingParams) -> DispatchResult;
+}
+
+impl OnCollateralOnboarded for () {
+	fn ensure_can_onboard(_currency_id: CurrencyId, _params: &CollateralOnboardingParams) -> DispatchResult {
+		Ok(())
+	}
+
+	fn on_collateral_onboarded(_currency_id: CurrencyId, _params: &CollateralOnboardingParams) -> DispatchResult {
+		Ok(())
+	}
+}
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Currency to reserve the proposal bond
+	type Currency: MultiReservableCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+	/// Native currency id, the proposal bond is reserved in it
+	type GetNativeCurrencyId: Get<CurrencyId>;
+
+	/// Stable currency id
+	type GetStableCurrencyId: Get<CurrencyId>;
+
+	/// The amount of bond to reserve when propose a new collateral type
+	type ProposalBond: Get<Balance>;
+
+	/// The collateral types configured in runtime, the approved collateral types are appended to them
+	type BaseCollateralCurrencyIds: Get<Vec<CurrencyId>>;
+
+	/// The origin which may approve or reject the proposals. Root can always do this.
+	type ApproveOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The DEX to check the liquidity of the proposed collateral type
+	type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+	/// Hook to configure the approved collateral type
+	type OnCollateralOnboarded: OnCollateralOnboarded;
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		<T as system::Trait>::AccountId,
+		CurrencyId = CurrencyId,
+		Balance = Balance,
+	{
+		/// A new collateral type proposed (proposer, collateral_type, bond)
+		CollateralProposed(AccountId, CurrencyId, Balance),
+		/// The proposal of collateral type approved and it's onboarded (collateral_type)
+		CollateralApproved(CurrencyId),
+		/// The proposal of collateral type rejected and its bond slashed (collateral_type, slashed_bond)
+		CollateralRejected(CurrencyId, Balance),
+	}
+);
+
+decl_error! {
+	/// Error for collateral onboarding module.
+	pub enum Error for Module<T: Trait> {
+		/// The currency is already a collateral type
+		AlreadyCollateral,
+		/// The currency cannot be collateral
+		InvalidCurrencyId,
+		/// There is already a pending proposal of the currency
+		ProposalExists,
+		/// The proposal does not exist
+		ProposalNotFound,
+		/// The currency has no liquidity paired with stable currency in DEX
+		NoDEXLiquidity,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as CollateralOnboarding {
+		/// Mapping from proposed currency to its pending proposal
+		pub Proposals get(fn proposals): map hasher(twox_64_concat) CurrencyId => Option<CollateralProposal<T::AccountId>>;
+
+		/// The collateral types onboarded by approved proposals
+		pub OnboardedCollaterals get(fn onboarded_collaterals): Vec<CurrencyId>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// The amount of bond to reserve when propose a new collateral type
+		const ProposalBond: Balance = T::ProposalBond::get();
+
+		/// Propose a new collateral type with its risk management params by reserving `ProposalBond`.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `currency_id`: the proposed collateral type.
+		/// - `params`: the proposed risk management params and incentives.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `OnboardedCollaterals`, `Proposals`, 1 items of orml_tokens
+		/// - Db writes: `Proposals`, 1 items of orml_tokens
+		/// -------------------
+		/// Base Weight: 40 µs
+		/// # </weight>
+		#[weight = 40 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(3, 2)]
+		pub fn propose(origin, currency_id: CurrencyId, params: CollateralOnboardingParams) {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				currency_id != T::GetStableCurrencyId::get() && currency_id != T::GetNativeCurrencyId::get(),
+				Error::<T>::InvalidCurrencyId,
+			);
+			ensure!(
+				!CollateralCurrencyIds::<T>::get().contains(&currency_id),
+				Error::<T>::AlreadyCollateral,
+			);
+			ensure!(!<Proposals<T>>::contains_key(currency_id), Error::<T>::ProposalExists);
+
+			let bond = T::ProposalBond::get();
+			T::Currency::reserve(T::GetNativeCurrencyId::get(), &who, bond)?;
+			<Proposals<T>>::insert(currency_id, CollateralProposal {
+				proposer: who.clone(),
+				bond,
+				params,
+			});
+
+			Self::deposit_event(RawEvent::CollateralProposed(who, currency_id, bond));
+		}
+
+		/// Approve the proposal of collateral type, onboard it and return the bond to the proposer.
+		///
+		/// The dispatch origin of this call must be `ApproveOrigin` or _Root_.
+		///
+		/// - `currency_id`: the proposed collateral type.
+		///
+		/// # <weight>
+		/// - Preconditions:
+		/// 	- T::OnCollateralOnboarded configures module_cdp_engine and module_incentives
+		/// - Complexity: `O(N)` where N is the number of collateral types
+		/// - Db reads: `Proposals`, `OnboardedCollaterals`, 1 items of module_dex, 2 items of module_cdp_engine
+		/// - Db writes: `Proposals`, `OnboardedCollaterals`, 1 items of orml_tokens, 1 items of module_cdp_engine,
+		///   1 items of module_incentives
+		/// -------------------
+		/// Base Weight: 80 µs
+		/// # </weight>
+		#[weight = 80 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(5, 5)]
+		pub fn approve(origin, currency_id: CurrencyId) {
+			T::ApproveOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			let proposal = Self::proposals(currency_id).ok_or(Error::<T>::ProposalNotFound)?;
+			let (collateral_pool, stable_pool) = T::DEX::get_liquidity_pool(currency_id);
+			ensure!(
+				!collateral_pool.is_zero() && !stable_pool.is_zero(),
+				Error::<T>::NoDEXLiquidity,
+			);
+			T::OnCollateralOnboarded::ensure_can_onboard(currency_id, &proposal.params)?;
+
+			// the collateral type must be valid before configuring it in other modules
+			OnboardedCollaterals::mutate(|collaterals| collaterals.push(currency_id));
+			if let Err(e) = T::OnCollateralOnboarded::on_collateral_onboarded(currency_id, &proposal.params) {
+				OnboardedCollaterals::mutate(|collaterals| collaterals.retain(|c| *c != currency_id));
+				return Err(e);
+			}
+
+			<Proposals<T>>::remove(currency_id);
+			T::Currency::unreserve(T::GetNativeCurrencyId::get(), &proposal.proposer, proposal.bond);
+
+			Self::deposit_event(RawEvent::CollateralApproved(currency_id));
+		}
+
+		/// Reject the proposal of collateral type and slash the bond.
+		///
+		/// The dispatch origin of this call must be `ApproveOrigin` or _Root_.
+		///
+		/// - `currency_id`: the proposed collateral type.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `Proposals`, 1 items of orml_tokens
+		/// - Db writes: `Proposals`, 1 items of orml_tokens
+		/// -------------------
+		/// Base Weight: 35 µs
+		/// # </weight>
+		#[weight = 35 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 2)]
+		pub fn reject(origin, currency_id: CurrencyId) {
+			T::ApproveOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			let proposal = <Proposals<T>>::take(currency_id).ok_or(Error::<T>::ProposalNotFound)?;
+			let remaining = T::Currency::slash_reserved(T::GetNativeCurrencyId::get(), &proposal.proposer, proposal.bond);
+			let slashed = proposal.bond.saturating_sub(remaining);
+
+			Self::deposit_event(RawEvent::CollateralRejected(currency_id, slashed));
+		}
+	}
+}
+
+/// All valid collateral types: the ones configured in runtime and the onboarded ones
+pub struct CollateralCurrencyIds<T>(marker::PhantomData<T>);
+
+impl<T: Trait> Get<Vec<CurrencyId>> for CollateralCurrencyIds<T> {
+	fn get() -> Vec<CurrencyId> {
+		let mut currency_ids = T::BaseCollateralCurrencyIds::get();
+		currency_ids.extend(<Module<T>>::onboarded_collaterals());
+		currency_ids
+	}
+}
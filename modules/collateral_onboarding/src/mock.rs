@@ -0,0 +1,218 @@
+//! Mocks for the collateral onboarding module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types};
+use frame_system::EnsureSignedBy;
+use primitives::Amount;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError, Perbill};
+use sp_std::cell::RefCell;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const ACA: CurrencyId = CurrencyId::ACA;
+pub const AUSD: CurrencyId = CurrencyId::AUSD;
+pub const BTC: CurrencyId = CurrencyId::XBTC;
+pub const DOT: CurrencyId = CurrencyId::DOT;
+pub const LDOT: CurrencyId = CurrencyId::LDOT;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod collateral_onboarding {
+	pub use super::super::*;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		system<T>,
+		orml_tokens<T>,
+		collateral_onboarding<T>,
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+}
+pub type System = system::Module<Runtime>;
+
+impl orml_tokens::Trait for Runtime {
+	type Event = TestEvent;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type DustRemoval = ();
+	type OnReceived = ();
+}
+pub type Tokens = orml_tokens::Module<Runtime>;
+
+thread_local! {
+	static DEX_LIQUIDITY: RefCell<Vec<CurrencyId>> = RefCell::new(vec![]);
+	pub static ONBOARDED: RefCell<Vec<(CurrencyId, CollateralOnboardingParams)>> = RefCell::new(vec![]);
+	pub static ONBOARDING_FAILS: RefCell<bool> = RefCell::new(false);
+}
+
+pub fn add_dex_liquidity(currency_id: CurrencyId) {
+	DEX_LIQUIDITY.with(|v| v.borrow_mut().push(currency_id));
+}
+
+/// Only the currencies added by `add_dex_liquidity` have liquidity
+pub struct MockDEX;
+impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
+	fn get_target_amount(_: CurrencyId, _: CurrencyId, _: Balance) -> Balance {
+		Default::default()
+	}
+
+	fn get_supply_amount(_: CurrencyId, _: CurrencyId, _: Balance) -> Balance {
+		Default::default()
+	}
+
+	fn exchange_currency(
+		_: AccountId,
+		_: CurrencyId,
+		_: Balance,
+		_: CurrencyId,
+		_: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(Default::default())
+	}
+
+	fn get_exchange_slippage(_: CurrencyId, _: CurrencyId, _: Balance) -> Option<Ratio> {
+		None
+	}
+
+	fn get_liquidity_pool(currency_id: CurrencyId) -> (Balance, Balance) {
+		if DEX_LIQUIDITY.with(|v| v.borrow().contains(&currency_id)) {
+			(1000, 1000)
+		} else {
+			(0, 0)
+		}
+	}
+
+	fn get_swap_target_amount(_: &[CurrencyId], _: Balance) -> Option<Balance> {
+		None
+	}
+
+	fn get_swap_supply_amount(_: &[CurrencyId], _: Balance) -> Option<Balance> {
+		None
+	}
+
+	fn swap_with_exact_supply(
+		_: &AccountId,
+		_: &[CurrencyId],
+		_: Balance,
+		_: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(Default::default())
+	}
+
+	fn swap_with_exact_target(
+		_: &AccountId,
+		_: &[CurrencyId],
+		_: Balance,
+		_: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(Default::default())
+	}
+}
+
+pub struct MockOnCollateralOnboarded;
+impl OnCollateralOnboarded for MockOnCollateralOnboarded {
+	fn ensure_can_onboard(_currency_id: CurrencyId, _params: &CollateralOnboardingParams) -> DispatchResult {
+		if ONBOARDING_FAILS.with(|v| *v.borrow()) {
+			return Err(DispatchError::Other("onboarding failed"));
+		}
+		Ok(())
+	}
+
+	fn on_collateral_onboarded(currency_id: CurrencyId, params: &CollateralOnboardingParams) -> DispatchResult {
+		ONBOARDED.with(|v| v.borrow_mut().push((currency_id, params.clone())));
+		Ok(())
+	}
+}
+
+ord_parameter_types! {
+	pub const One: AccountId = 1;
+}
+
+parameter_types! {
+	pub const GetNativeCurrencyId: CurrencyId = ACA;
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	pub const ProposalBond: Balance = 100;
+	pub BaseCollateralCurrencyIds: Vec<CurrencyId> = vec![BTC];
+}
+
+impl Trait for Runtime {
+	type Event = TestEvent;
+	type Currency = Tokens;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type ProposalBond = ProposalBond;
+	type BaseCollateralCurrencyIds = BaseCollateralCurrencyIds;
+	type ApproveOrigin = EnsureSignedBy<One, AccountId>;
+	type DEX = MockDEX;
+	type OnCollateralOnboarded = MockOnCollateralOnboarded;
+}
+pub type CollateralOnboardingModule = Module<Runtime>;
+
+pub struct ExtBuilder {
+	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			endowed_accounts: vec![(ALICE, ACA, 1000), (BOB, ACA, 50)],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		orml_tokens::GenesisConfig::<Runtime> {
+			endowed_accounts: self.endowed_accounts,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		t.into()
+	}
+}
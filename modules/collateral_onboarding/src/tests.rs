@@ -0,0 +1,138 @@
+//! Unit tests for the collateral onboarding module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{
+	add_dex_liquidity, CollateralOnboardingModule, ExtBuilder, Origin, Runtime, System, TestEvent, Tokens, ACA, ALICE,
+	AUSD, BOB, BTC, DOT, LDOT, ONBOARDED, ONBOARDING_FAILS,
+};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use sp_runtime::{traits::BadOrigin, DispatchError, FixedPointNumber};
+
+fn proposed_params() -> CollateralOnboardingParams {
+	CollateralOnboardingParams {
+		maximum_total_debit_value: 10000,
+		stability_fee: Some(Rate::saturating_from_rational(1, 100000)),
+		liquidation_ratio: Some(Ratio::saturating_from_rational(3, 2)),
+		liquidation_penalty: Some(Rate::saturating_from_rational(2, 10)),
+		required_collateral_ratio: Some(Ratio::saturating_from_rational(9, 5)),
+		loans_incentive_weight: 50,
+	}
+}
+
+#[test]
+fn propose_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CollateralOnboardingModule::propose(Origin::signed(ALICE), AUSD, proposed_params()),
+			Error::<Runtime>::InvalidCurrencyId,
+		);
+		assert_noop!(
+			CollateralOnboardingModule::propose(Origin::signed(ALICE), ACA, proposed_params()),
+			Error::<Runtime>::InvalidCurrencyId,
+		);
+		assert_noop!(
+			CollateralOnboardingModule::propose(Origin::signed(ALICE), BTC, proposed_params()),
+			Error::<Runtime>::AlreadyCollateral,
+		);
+		assert!(CollateralOnboardingModule::propose(Origin::signed(BOB), DOT, proposed_params()).is_err());
+
+		assert_ok!(CollateralOnboardingModule::propose(
+			Origin::signed(ALICE),
+			DOT,
+			proposed_params()
+		));
+		let proposed_event = TestEvent::collateral_onboarding(RawEvent::CollateralProposed(ALICE, DOT, 100));
+		assert!(System::events().iter().any(|record| record.event == proposed_event));
+		assert_eq!(Tokens::reserved_balance(ACA, &ALICE), 100);
+		assert_eq!(
+			CollateralOnboardingModule::proposals(DOT),
+			Some(CollateralProposal {
+				proposer: ALICE,
+				bond: 100,
+				params: proposed_params(),
+			})
+		);
+
+		assert_noop!(
+			CollateralOnboardingModule::propose(Origin::signed(ALICE), DOT, proposed_params()),
+			Error::<Runtime>::ProposalExists,
+		);
+	});
+}
+
+#[test]
+fn approve_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CollateralOnboardingModule::propose(
+			Origin::signed(ALICE),
+			DOT,
+			proposed_params()
+		));
+		assert_noop!(CollateralOnboardingModule::approve(Origin::signed(BOB), DOT), BadOrigin);
+		assert_noop!(
+			CollateralOnboardingModule::approve(Origin::signed(1), LDOT),
+			Error::<Runtime>::ProposalNotFound
+		);
+		assert_noop!(
+			CollateralOnboardingModule::approve(Origin::signed(1), DOT),
+			Error::<Runtime>::NoDEXLiquidity
+		);
+
+		add_dex_liquidity(DOT);
+		ONBOARDING_FAILS.with(|v| *v.borrow_mut() = true);
+		assert_noop!(
+			CollateralOnboardingModule::approve(Origin::signed(1), DOT),
+			DispatchError::Other("onboarding failed")
+		);
+		assert_eq!(CollateralOnboardingModule::onboarded_collaterals(), vec![]);
+		assert!(CollateralOnboardingModule::proposals(DOT).is_some());
+		assert_eq!(ONBOARDED.with(|v| v.borrow().len()), 0);
+
+		ONBOARDING_FAILS.with(|v| *v.borrow_mut() = false);
+		assert_eq!(CollateralCurrencyIds::<Runtime>::get(), vec![BTC]);
+		assert_ok!(CollateralOnboardingModule::approve(Origin::signed(1), DOT));
+		let approved_event = TestEvent::collateral_onboarding(RawEvent::CollateralApproved(DOT));
+		assert!(System::events().iter().any(|record| record.event == approved_event));
+		assert_eq!(CollateralOnboardingModule::proposals(DOT), None);
+		assert_eq!(CollateralOnboardingModule::onboarded_collaterals(), vec![DOT]);
+		assert_eq!(CollateralCurrencyIds::<Runtime>::get(), vec![BTC, DOT]);
+		assert_eq!(ONBOARDED.with(|v| v.borrow().clone()), vec![(DOT, proposed_params())]);
+		assert_eq!(Tokens::reserved_balance(ACA, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 1000);
+
+		assert_noop!(
+			CollateralOnboardingModule::propose(Origin::signed(ALICE), DOT, proposed_params()),
+			Error::<Runtime>::AlreadyCollateral,
+		);
+	});
+}
+
+#[test]
+fn reject_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CollateralOnboardingModule::propose(
+			Origin::signed(ALICE),
+			DOT,
+			proposed_params()
+		));
+		assert_noop!(CollateralOnboardingModule::reject(Origin::signed(BOB), DOT), BadOrigin);
+		assert_noop!(
+			CollateralOnboardingModule::reject(Origin::signed(1), LDOT),
+			Error::<Runtime>::ProposalNotFound
+		);
+
+		assert_ok!(CollateralOnboardingModule::reject(Origin::ROOT, DOT));
+		let rejected_event = TestEvent::collateral_onboarding(RawEvent::CollateralRejected(DOT, 100));
+		assert!(System::events().iter().any(|record| record.event == rejected_event));
+		assert_eq!(CollateralOnboardingModule::proposals(DOT), None);
+		assert_eq!(CollateralOnboardingModule::onboarded_collaterals(), vec![]);
+		assert_eq!(Tokens::reserved_balance(ACA, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 900);
+	});
+}
@@ -0,0 +1,149 @@
+//! # Currencies Transfer Module
+//!
+//! ## Overview
+//!
+//! Transfer utilities on top of the multi-currency layer: transfer the whole withdrawable balance of a currency,
+//! which is read at execution so the balance changed after signing is transferred without leaving dust,
+//! and transfer multiple currencies to multiple destinations in one call.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{decl_error, decl_event, decl_module, ensure, traits::Get, weights::constants::WEIGHT_PER_MICROS};
+use frame_system::{self as system, ensure_signed};
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{StaticLookup, Zero},
+	DispatchResult,
+};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+mod mock;
+mod tests;
+
+type SourceOf<T> = <<T as system::Trait>::Lookup as StaticLookup>::Source;
+
+/// The balance of an account which can be transferred out
+pub trait WithdrawableBalance<AccountId> {
+	/// The free balance not frozen by locks, less the existential deposit if the account must be kept alive
+	fn withdrawable_balance(currency_id: CurrencyId, who: &AccountId) -> Balance;
+}
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The multi-currency to transfer
+	type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+	/// The maximum number of transfers in a batch
+	type MaxBatchTransfers: Get<u32>;
+
+	/// The withdrawable balance of the currencies
+	type WithdrawableBalance: WithdrawableBalance<Self::AccountId>;
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		<T as system::Trait>::AccountId,
+		CurrencyId = CurrencyId,
+		Balance = Balance,
+	{
+		/// Currency transferred (currency_id, from, to, amount)
+		Transferred(CurrencyId, AccountId, AccountId, Balance),
+	}
+);
+
+decl_error! {
+	/// Error for currencies transfer module.
+	pub enum Error for Module<T: Trait> {
+		/// The batch is empty or has more than `MaxBatchTransfers` transfers
+		InvalidBatchSize,
+		/// The total amount of a currency overflows
+		AmountOverflow,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// The maximum number of transfers in a batch
+		const MaxBatchTransfers: u32 = T::MaxBatchTransfers::get();
+
+		/// Transfer the whole withdrawable balance of `currency_id` to `dest`, the locked balance stays.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `currency_id`: the currency to transfer.
+		/// - `dest`: the destination.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: 2 items of the currency
+		/// - Db writes: 2 items of the currency
+		/// -------------------
+		/// Base Weight: 60 µs
+		/// # </weight>
+		#[weight = 60 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 2)]
+		pub fn transfer_all(origin, currency_id: CurrencyId, dest: <T::Lookup as StaticLookup>::Source) {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			let amount = T::WithdrawableBalance::withdrawable_balance(currency_id, &from);
+			if !amount.is_zero() {
+				Self::do_transfer(currency_id, &from, &to, amount)?;
+			}
+		}
+
+		/// Execute the transfers in order.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `transfers`: the list of (dest, currency_id, amount).
+		///
+		/// The free balances are checked to cover the total amount of each currency before transferring,
+		/// so that the batch is not partially executed for insufficient balance.
+		///
+		/// # <weight>
+		/// - Complexity: `O(N)` where N is the number of transfers
+		/// - Db reads: 2 * N items of the currencies
+		/// - Db writes: 2 * N items of the currencies
+		/// -------------------
+		/// Base Weight: 60 * N µs
+		/// # </weight>
+		#[weight = (60 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 2)).saturating_mul(transfers.len() as u64)]
+		pub fn transfer_batch(origin, transfers: Vec<(SourceOf<T>, CurrencyId, Balance)>) {
+			let from = ensure_signed(origin)?;
+			ensure!(
+				!transfers.is_empty() && transfers.len() as u32 <= T::MaxBatchTransfers::get(),
+				Error::<T>::InvalidBatchSize,
+			);
+
+			let mut totals: BTreeMap<CurrencyId, Balance> = BTreeMap::new();
+			let mut resolved = Vec::with_capacity(transfers.len());
+			for (dest, currency_id, amount) in transfers {
+				let to = T::Lookup::lookup(dest)?;
+				let total = totals.entry(currency_id).or_default();
+				*total = total.checked_add(amount).ok_or(Error::<T>::AmountOverflow)?;
+				resolved.push((to, currency_id, amount));
+			}
+			for (currency_id, total) in totals {
+				T::Currency::ensure_can_withdraw(currency_id, &from, total)?;
+			}
+
+			for (to, currency_id, amount) in resolved {
+				Self::do_transfer(currency_id, &from, &to, amount)?;
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn do_transfer(currency_id: CurrencyId, from: &T::AccountId, to: &T::AccountId, amount: Balance) -> DispatchResult {
+		T::Currency::transfer(currency_id, from, to, amount)?;
+		Self::deposit_event(RawEvent::Transferred(currency_id, from.clone(), to.clone(), amount));
+		Ok(())
+	}
+}
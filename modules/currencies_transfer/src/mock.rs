@@ -0,0 +1,125 @@
+//! Mocks for the currencies transfer module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use primitives::Amount;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const AUSD: CurrencyId = CurrencyId::AUSD;
+pub const BTC: CurrencyId = CurrencyId::XBTC;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod currencies_transfer {
+	pub use super::super::*;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		system<T>,
+		orml_tokens<T>,
+		currencies_transfer<T>,
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+}
+pub type System = system::Module<Runtime>;
+
+impl orml_tokens::Trait for Runtime {
+	type Event = TestEvent;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type DustRemoval = ();
+	type OnReceived = ();
+}
+pub type Tokens = orml_tokens::Module<Runtime>;
+
+parameter_types! {
+	pub const MaxBatchTransfers: u32 = 3;
+}
+
+pub struct MockWithdrawableBalance;
+impl WithdrawableBalance<AccountId> for MockWithdrawableBalance {
+	fn withdrawable_balance(currency_id: CurrencyId, who: &AccountId) -> Balance {
+		let account = Tokens::accounts(who, currency_id);
+		account.free.saturating_sub(account.frozen)
+	}
+}
+
+impl Trait for Runtime {
+	type Event = TestEvent;
+	type Currency = Tokens;
+	type MaxBatchTransfers = MaxBatchTransfers;
+	type WithdrawableBalance = MockWithdrawableBalance;
+}
+pub type CurrenciesTransferModule = Module<Runtime>;
+
+pub struct ExtBuilder {
+	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			endowed_accounts: vec![(ALICE, AUSD, 1000), (ALICE, BTC, 100)],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		orml_tokens::GenesisConfig::<Runtime> {
+			endowed_accounts: self.endowed_accounts,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		t.into()
+	}
+}
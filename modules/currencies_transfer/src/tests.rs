@@ -0,0 +1,82 @@
+//! Unit tests for the currencies transfer module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{
+	CurrenciesTransferModule, ExtBuilder, Origin, Runtime, System, TestEvent, Tokens, ALICE, AUSD, BOB, BTC, CAROL,
+};
+use orml_traits::MultiLockableCurrency;
+
+#[test]
+fn transfer_all_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CurrenciesTransferModule::transfer_all(Origin::signed(ALICE), AUSD, BOB));
+		let transferred_event = TestEvent::currencies_transfer(RawEvent::Transferred(AUSD, ALICE, BOB, 1000));
+		assert!(System::events().iter().any(|record| record.event == transferred_event));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 1000);
+		assert_eq!(Tokens::free_balance(BTC, &ALICE), 100);
+
+		// nothing to transfer
+		assert_ok!(CurrenciesTransferModule::transfer_all(Origin::signed(ALICE), AUSD, BOB));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 1000);
+	});
+}
+
+#[test]
+fn transfer_all_leaves_locked_balance() {
+	ExtBuilder::default().build().execute_with(|| {
+		Tokens::set_lock(*b"testlock", AUSD, &ALICE, 300);
+		assert_ok!(CurrenciesTransferModule::transfer_all(Origin::signed(ALICE), AUSD, BOB));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 300);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 1700);
+	});
+}
+
+#[test]
+fn transfer_batch_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CurrenciesTransferModule::transfer_batch(Origin::signed(ALICE), vec![]),
+			Error::<Runtime>::InvalidBatchSize,
+		);
+		assert_noop!(
+			CurrenciesTransferModule::transfer_batch(
+				Origin::signed(ALICE),
+				vec![(BOB, AUSD, 1), (BOB, AUSD, 1), (BOB, AUSD, 1), (BOB, AUSD, 1)]
+			),
+			Error::<Runtime>::InvalidBatchSize,
+		);
+		assert_noop!(
+			CurrenciesTransferModule::transfer_batch(
+				Origin::signed(ALICE),
+				vec![(BOB, AUSD, 1), (CAROL, AUSD, Balance::max_value())]
+			),
+			Error::<Runtime>::AmountOverflow,
+		);
+		// the total amount of AUSD exceeds the free balance, nothing is transferred
+		assert!(CurrenciesTransferModule::transfer_batch(
+			Origin::signed(ALICE),
+			vec![(BOB, AUSD, 600), (CAROL, BTC, 50), (CAROL, AUSD, 600)]
+		)
+		.is_err());
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 1000);
+		assert_eq!(Tokens::free_balance(BTC, &ALICE), 100);
+
+		assert_ok!(CurrenciesTransferModule::transfer_batch(
+			Origin::signed(ALICE),
+			vec![(BOB, AUSD, 600), (CAROL, BTC, 50), (CAROL, AUSD, 400)]
+		));
+		let transferred_event = TestEvent::currencies_transfer(RawEvent::Transferred(BTC, ALICE, CAROL, 50));
+		assert!(System::events().iter().any(|record| record.event == transferred_event));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(BTC, &ALICE), 50);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 600);
+		assert_eq!(Tokens::free_balance(AUSD, &CAROL), 400);
+		assert_eq!(Tokens::free_balance(BTC, &CAROL), 50);
+	});
+}
@@ -6,6 +6,8 @@
 //! the trading mechanism refers to the design of Uniswap. In addition to being used for trading, DEX also participates
 //! in CDP liquidation, which is faster than liquidation by auction when the liquidity is sufficient. And providing market
 //! making liquidity for DEX will also receive stable coin as additional reward for its participation in the CDP liquidation.
+//!
+//! During the `Frozen` phase of emergency shutdown, the liquidity pools are frozen, no swap or liquidity change is allowed.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -26,7 +28,7 @@ use sp_runtime::{
 	DispatchError, DispatchResult, FixedPointNumber, FixedPointOperand, ModuleId,
 };
 use sp_std::prelude::Vec;
-use support::{CDPTreasury, DEXManager, OnEmergencyShutdown, Price, Rate, Ratio};
+use support::{CDPTreasury, DEXManager, OnEmergencyShutdown, OnUpdateLiquidity, Price, Rate, Ratio, ShutdownPhase};
 
 mod benchmarking;
 mod mock;
@@ -34,6 +36,9 @@ mod tests;
 
 const MODULE_ID: ModuleId = ModuleId(*b"aca/dexm");
 
+/// The maximum number of currencies in a trading path
+pub const TRADING_PATH_LIMIT: usize = 3;
+
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
@@ -57,6 +62,9 @@ pub trait Trait: system::Trait {
 
 	/// Trading fee rate
 	type GetExchangeFee: Get<Rate>;
+
+	/// Hook called after the share of liquidity provider updated, e.g. to track the shares of liquidity mining
+	type OnUpdateLiquidity: OnUpdateLiquidity<Self::AccountId, CurrencyId, Self::Share>;
 }
 
 decl_event!(
@@ -92,6 +100,12 @@ decl_error! {
 		InacceptablePrice,
 		/// The increament of liquidity is invalid
 		InvalidLiquidityIncrement,
+		/// The trading path is invalid
+		InvalidTradingPath,
+		/// The liquidity along the trading path is not enough
+		InsufficientLiquidity,
+		/// The liquidity pools are frozen by emergency shutdown
+		PoolsFrozen,
 	}
 }
 
@@ -123,6 +137,9 @@ decl_storage! {
 
 		/// System shutdown flag
 		IsShutdown get(fn is_shutdown): bool;
+
+		/// Whether the liquidity pools are frozen, during the `Frozen` phase of emergency shutdown
+		IsFrozen get(fn is_frozen): bool;
 	}
 
 	add_extra_genesis {
@@ -231,6 +248,7 @@ decl_module! {
 			#[compact] acceptable_target_amount: Balance,
 		) {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(), Error::<T>::PoolsFrozen);
 			let base_currency_id = T::GetBaseCurrencyId::get();
 			ensure!(
 				supply_currency_id != target_currency_id,
@@ -277,6 +295,7 @@ decl_module! {
 			#[compact] max_base_currency_amount: Balance,
 		) {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(), Error::<T>::PoolsFrozen);
 			let base_currency_id = T::GetBaseCurrencyId::get();
 			ensure!(
 				T::EnabledCurrencyIds::get().contains(&other_currency_id),
@@ -336,6 +355,7 @@ decl_module! {
 			Self::deposit_calculate_interest(other_currency_id, &who, share_increment);
 			<TotalShares<T>>::mutate(other_currency_id, |share| *share = share.saturating_add(share_increment));
 			<Shares<T>>::mutate(other_currency_id, &who, |share| *share = share.saturating_add(share_increment));
+			T::OnUpdateLiquidity::on_update_liquidity(&who, other_currency_id, Self::shares(other_currency_id, &who));
 			LiquidityPool::mutate(other_currency_id, |pool| {
 				*pool = (pool.0.saturating_add(other_currency_increment), pool.1.saturating_add(base_currency_increment));
 			});
@@ -368,6 +388,7 @@ decl_module! {
 		#[weight = 72 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(9, 9)]
 		pub fn withdraw_liquidity(origin, currency_id: CurrencyId, #[compact] share_amount: T::Share) {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(), Error::<T>::PoolsFrozen);
 			let base_currency_id = T::GetBaseCurrencyId::get();
 			ensure!(
 				T::EnabledCurrencyIds::get().contains(&currency_id),
@@ -394,6 +415,7 @@ decl_module! {
 			Self::withdraw_calculate_interest(currency_id, &who, share_amount)?;
 			<TotalShares<T>>::mutate(currency_id, |share| *share = share.saturating_sub(share_amount));
 			<Shares<T>>::mutate(currency_id, &who, |share| *share = share.saturating_sub(share_amount));
+			T::OnUpdateLiquidity::on_update_liquidity(&who, currency_id, Self::shares(currency_id, &who));
 			LiquidityPool::mutate(currency_id, |pool| {
 				*pool = (pool.0.saturating_sub(withdraw_other_currency_amount), pool.1.saturating_sub(withdraw_base_currency_amount));
 			});
@@ -712,6 +734,119 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
+	/// The pool of trading pair as (supply_pool, target_pool), `None` means no such trading pair
+	fn get_trading_pool(supply_currency_id: CurrencyId, target_currency_id: CurrencyId) -> Option<(Balance, Balance)> {
+		let base_currency_id = T::GetBaseCurrencyId::get();
+		if supply_currency_id == target_currency_id {
+			None
+		} else if target_currency_id == base_currency_id {
+			Some(Self::liquidity_pool(supply_currency_id))
+		} else if supply_currency_id == base_currency_id {
+			let (other_currency_pool, base_currency_pool) = Self::liquidity_pool(target_currency_id);
+			Some((base_currency_pool, other_currency_pool))
+		} else {
+			None
+		}
+	}
+
+	/// Each step of trading path must be a trading pair, and the path can not pass a currency twice
+	/// because the amounts are calculated with the pools before swap.
+	pub fn is_valid_trading_path(path: &[CurrencyId]) -> bool {
+		path.len() >= 2
+			&& path.len() <= TRADING_PATH_LIMIT
+			&& path
+				.iter()
+				.enumerate()
+				.all(|(i, currency_id)| !path[i + 1..].contains(currency_id))
+			&& path
+				.windows(2)
+				.all(|pair| Self::get_trading_pool(pair[0], pair[1]).is_some())
+	}
+
+	/// The amounts of each step along the trading path to swap the exact supply amount,
+	/// return `None` if the path is invalid or any step can not be swapped
+	pub fn get_target_amounts(path: &[CurrencyId], supply_amount: Balance) -> Option<Vec<Balance>> {
+		if !Self::is_valid_trading_path(path) || supply_amount.is_zero() {
+			return None;
+		}
+
+		let mut amounts = Vec::with_capacity(path.len());
+		amounts.push(supply_amount);
+		for pair in path.windows(2) {
+			let (supply_pool, target_pool) = Self::get_trading_pool(pair[0], pair[1])?;
+			let target_amount = Self::calculate_swap_target_amount(supply_pool, target_pool, *amounts.last()?);
+			if target_amount.is_zero() {
+				return None;
+			}
+			amounts.push(target_amount);
+		}
+		Some(amounts)
+	}
+
+	/// The amounts of each step along the trading path to swap for the exact target amount,
+	/// return `None` if the path is invalid or any step can not be swapped
+	pub fn get_supply_amounts(path: &[CurrencyId], target_amount: Balance) -> Option<Vec<Balance>> {
+		if !Self::is_valid_trading_path(path) || target_amount.is_zero() {
+			return None;
+		}
+
+		let mut amounts = Vec::with_capacity(path.len());
+		amounts.push(target_amount);
+		for pair in path.windows(2).rev() {
+			let (supply_pool, target_pool) = Self::get_trading_pool(pair[0], pair[1])?;
+			let supply_amount = Self::calculate_swap_supply_amount(supply_pool, target_pool, *amounts.last()?);
+			if supply_amount.is_zero() {
+				return None;
+			}
+			amounts.push(supply_amount);
+		}
+		amounts.reverse();
+		Some(amounts)
+	}
+
+	/// Swap along the valid trading path with the amounts of each step
+	fn do_swap(who: &T::AccountId, path: &[CurrencyId], amounts: &[Balance]) -> DispatchResult {
+		let base_currency_id = T::GetBaseCurrencyId::get();
+		let (supply_currency_id, supply_amount) = (path[0], amounts[0]);
+		let (target_currency_id, target_amount) = (path[path.len() - 1], amounts[amounts.len() - 1]);
+		ensure!(
+			T::Currency::ensure_can_withdraw(supply_currency_id, who, supply_amount).is_ok(),
+			Error::<T>::AmountNotEnough,
+		);
+
+		T::Currency::transfer(supply_currency_id, who, &Self::account_id(), supply_amount)
+			.expect("never failed because after checks");
+		T::Currency::transfer(target_currency_id, &Self::account_id(), who, target_amount)
+			.expect("never failed because after checks");
+		for (i, pair) in path.windows(2).enumerate() {
+			let (supply_increment, target_decrement) = (amounts[i], amounts[i + 1]);
+			if pair[1] == base_currency_id {
+				LiquidityPool::mutate(pair[0], |pool| {
+					*pool = (
+						pool.0.saturating_add(supply_increment),
+						pool.1.saturating_sub(target_decrement),
+					);
+				});
+			} else {
+				LiquidityPool::mutate(pair[1], |pool| {
+					*pool = (
+						pool.0.saturating_sub(target_decrement),
+						pool.1.saturating_add(supply_increment),
+					);
+				});
+			}
+		}
+
+		Self::deposit_event(RawEvent::Swap(
+			who.clone(),
+			supply_currency_id,
+			supply_amount,
+			target_currency_id,
+			target_amount,
+		));
+		Ok(())
+	}
+
 	pub fn deposit_calculate_interest(currency_id: CurrencyId, who: &T::AccountId, share_amount: T::Share) {
 		let total_shares = Self::total_shares(currency_id);
 		if total_shares.is_zero() {
@@ -887,10 +1022,62 @@ impl<T: Trait> DEXManager<T::AccountId, CurrencyId, Balance> for Module<T> {
 			Some(final_slippage)
 		}
 	}
+
+	fn get_liquidity_pool(currency_id: CurrencyId) -> (Balance, Balance) {
+		Self::liquidity_pool(currency_id)
+	}
+
+	fn get_swap_target_amount(path: &[CurrencyId], supply_amount: Balance) -> Option<Balance> {
+		Self::get_target_amounts(path, supply_amount).and_then(|amounts| amounts.last().copied())
+	}
+
+	fn get_swap_supply_amount(path: &[CurrencyId], target_amount: Balance) -> Option<Balance> {
+		Self::get_supply_amounts(path, target_amount).and_then(|amounts| amounts.first().copied())
+	}
+
+	fn swap_with_exact_supply(
+		who: &T::AccountId,
+		path: &[CurrencyId],
+		supply_amount: Balance,
+		min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		ensure!(!Self::is_frozen(), Error::<T>::PoolsFrozen);
+		ensure!(Self::is_valid_trading_path(path), Error::<T>::InvalidTradingPath);
+		let amounts = Self::get_target_amounts(path, supply_amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+		let target_amount = amounts[amounts.len() - 1];
+		ensure!(target_amount >= min_target_amount, Error::<T>::InacceptablePrice);
+
+		Self::do_swap(who, path, &amounts)?;
+		Ok(target_amount)
+	}
+
+	fn swap_with_exact_target(
+		who: &T::AccountId,
+		path: &[CurrencyId],
+		target_amount: Balance,
+		max_supply_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		ensure!(!Self::is_frozen(), Error::<T>::PoolsFrozen);
+		ensure!(Self::is_valid_trading_path(path), Error::<T>::InvalidTradingPath);
+		let amounts = Self::get_supply_amounts(path, target_amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+		let supply_amount = amounts[0];
+		ensure!(supply_amount <= max_supply_amount, Error::<T>::InacceptablePrice);
+
+		Self::do_swap(who, path, &amounts)?;
+		Ok(supply_amount)
+	}
 }
 
 impl<T: Trait> OnEmergencyShutdown for Module<T> {
 	fn on_emergency_shutdown() {
 		<IsShutdown>::put(true);
 	}
+
+	fn on_cancel_shutdown() {
+		<IsShutdown>::put(false);
+	}
+
+	fn on_shutdown_phase_changed(phase: ShutdownPhase) {
+		<IsFrozen>::put(phase == ShutdownPhase::Frozen);
+	}
 }
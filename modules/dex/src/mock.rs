@@ -7,6 +7,7 @@ use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, pa
 use frame_system::EnsureSignedBy;
 use sp_core::H256;
 use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_std::cell::RefCell;
 use support::{AuctionManager, Rate};
 
 pub type AccountId = u128;
@@ -104,6 +105,7 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = ();
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = ();
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
@@ -150,6 +152,17 @@ parameter_types! {
 	pub EnabledCurrencyIds : Vec<CurrencyId> = vec![BTC, DOT];
 }
 
+thread_local! {
+	pub static LIQUIDITY_UPDATES: RefCell<Vec<(AccountId, CurrencyId, Share)>> = RefCell::new(vec![]);
+}
+
+pub struct MockOnUpdateLiquidity;
+impl OnUpdateLiquidity<AccountId, CurrencyId, Share> for MockOnUpdateLiquidity {
+	fn on_update_liquidity(who: &AccountId, currency_id: CurrencyId, new_share: Share) {
+		LIQUIDITY_UPDATES.with(|v| v.borrow_mut().push((*who, currency_id, new_share)));
+	}
+}
+
 impl Trait for Runtime {
 	type Event = TestEvent;
 	type Currency = Tokens;
@@ -159,6 +172,7 @@ impl Trait for Runtime {
 	type GetExchangeFee = GetExchangeFee;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type OnUpdateLiquidity = MockOnUpdateLiquidity;
 }
 pub type DexModule = Module<Runtime>;
 
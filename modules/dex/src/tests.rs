@@ -4,7 +4,10 @@
 
 use super::*;
 use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
-use mock::{DexModule, ExtBuilder, Origin, Runtime, System, TestEvent, Tokens, ACA, ALICE, AUSD, BOB, BTC, CAROL, DOT};
+use mock::{
+	DexModule, ExtBuilder, Origin, Runtime, System, TestEvent, Tokens, ACA, ALICE, AUSD, BOB, BTC, CAROL, DOT,
+	LIQUIDITY_UPDATES,
+};
 use sp_runtime::traits::BadOrigin;
 
 #[test]
@@ -464,6 +467,96 @@ fn get_supply_amount_work() {
 	});
 }
 
+#[test]
+fn get_swap_amount_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DexModule::add_liquidity(Origin::signed(ALICE), BTC, 100, 10000));
+		assert_ok!(DexModule::add_liquidity(Origin::signed(ALICE), DOT, 1000, 10000));
+		assert_eq!(DexModule::get_swap_target_amount(&[DOT, BTC], 1000), None);
+		assert_eq!(DexModule::get_swap_target_amount(&[DOT, AUSD, DOT], 1000), None);
+		assert_eq!(DexModule::get_swap_target_amount(&[DOT, AUSD, ACA], 1000), None);
+		assert_eq!(DexModule::get_swap_target_amount(&[DOT, AUSD], 1000), Some(4950));
+		assert_eq!(DexModule::get_swap_target_amount(&[DOT, AUSD, BTC], 1000), Some(34));
+		assert_eq!(
+			DexModule::get_target_amounts(&[DOT, AUSD, BTC], 1000),
+			Some(vec![1000, 4950, 34])
+		);
+		assert_eq!(DexModule::get_swap_supply_amount(&[DOT, AUSD, ACA], 1000), None);
+		let supply_amount = DexModule::get_swap_supply_amount(&[DOT, AUSD, BTC], 34).unwrap();
+		assert!(DexModule::get_swap_target_amount(&[DOT, AUSD, BTC], supply_amount).unwrap() >= 34);
+	});
+}
+
+#[test]
+fn swap_with_exact_supply_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DexModule::add_liquidity(Origin::signed(ALICE), BTC, 100, 10000));
+		assert_ok!(DexModule::add_liquidity(Origin::signed(ALICE), DOT, 1000, 10000));
+		assert_ok!(Tokens::transfer(Origin::signed(BOB), CAROL, DOT, 1000));
+		assert_noop!(
+			DexModule::swap_with_exact_supply(&CAROL, &[DOT, BTC], 1000, 0),
+			Error::<Runtime>::InvalidTradingPath,
+		);
+		assert_noop!(
+			DexModule::swap_with_exact_supply(&CAROL, &[DOT, AUSD, ACA], 1000, 0),
+			Error::<Runtime>::InsufficientLiquidity,
+		);
+		assert_noop!(
+			DexModule::swap_with_exact_supply(&CAROL, &[DOT, AUSD, BTC], 1000, 35),
+			Error::<Runtime>::InacceptablePrice,
+		);
+		assert_noop!(
+			DexModule::swap_with_exact_supply(&CAROL, &[DOT, AUSD, BTC], 1001, 0),
+			Error::<Runtime>::AmountNotEnough,
+		);
+		assert_eq!(
+			DexModule::swap_with_exact_supply(&CAROL, &[DOT, AUSD, BTC], 1000, 34),
+			Ok(34)
+		);
+
+		let swap_event = TestEvent::dex(RawEvent::Swap(CAROL, DOT, 1000, BTC, 34));
+		assert!(System::events().iter().any(|record| record.event == swap_event));
+
+		assert_eq!(Tokens::free_balance(BTC, &CAROL), 34);
+		assert_eq!(Tokens::free_balance(DOT, &CAROL), 0);
+		assert_eq!(DexModule::liquidity_pool(BTC), (66, 14950));
+		assert_eq!(DexModule::liquidity_pool(DOT), (2000, 5050));
+	});
+}
+
+#[test]
+fn swap_with_exact_target_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DexModule::add_liquidity(Origin::signed(ALICE), BTC, 10000, 10000));
+		assert_ok!(Tokens::transfer(Origin::signed(BOB), CAROL, BTC, 20000));
+		assert_noop!(
+			DexModule::swap_with_exact_target(&CAROL, &[BTC], 4950, 20000),
+			Error::<Runtime>::InvalidTradingPath,
+		);
+		assert_noop!(
+			DexModule::swap_with_exact_target(&CAROL, &[BTC, AUSD], 10000, 20000),
+			Error::<Runtime>::InsufficientLiquidity,
+		);
+		assert_noop!(
+			DexModule::swap_with_exact_target(&CAROL, &[BTC, AUSD], 4950, 10004),
+			Error::<Runtime>::InacceptablePrice,
+		);
+		assert_eq!(
+			DexModule::swap_with_exact_target(&CAROL, &[BTC, AUSD], 4950, 20000),
+			Ok(10005)
+		);
+
+		let swap_event = TestEvent::dex(RawEvent::Swap(CAROL, BTC, 10005, AUSD, 4950));
+		assert!(System::events().iter().any(|record| record.event == swap_event));
+
+		assert_eq!(Tokens::free_balance(BTC, &CAROL), 9995);
+		assert_eq!(Tokens::free_balance(AUSD, &CAROL), 4950);
+		assert_eq!(DexModule::liquidity_pool(BTC), (20005, 5050));
+	});
+}
+
 #[test]
 fn get_exchange_slippage_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -492,3 +585,45 @@ fn get_exchange_slippage_work() {
 		);
 	});
 }
+
+#[test]
+fn update_liquidity_notify_new_share() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DexModule::add_liquidity(Origin::signed(ALICE), BTC, 10000, 10000000));
+		assert_ok!(DexModule::withdraw_liquidity(Origin::signed(ALICE), BTC, 10000));
+		assert_eq!(
+			LIQUIDITY_UPDATES.with(|v| v.borrow().clone()),
+			vec![(ALICE, BTC, 10000000), (ALICE, BTC, 9990000)]
+		);
+	});
+}
+
+#[test]
+fn pools_frozen_in_frozen_phase_of_shutdown() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DexModule::add_liquidity(Origin::signed(ALICE), BTC, 100, 10000));
+		DexModule::on_emergency_shutdown();
+		DexModule::on_shutdown_phase_changed(ShutdownPhase::Frozen);
+		assert_eq!(DexModule::is_frozen(), true);
+		assert_noop!(
+			DexModule::add_liquidity(Origin::signed(ALICE), BTC, 100, 10000),
+			Error::<Runtime>::PoolsFrozen,
+		);
+		assert_noop!(
+			DexModule::withdraw_liquidity(Origin::signed(ALICE), BTC, 100),
+			Error::<Runtime>::PoolsFrozen,
+		);
+		assert_noop!(
+			DexModule::swap_currency(Origin::signed(BOB), BTC, 10, AUSD, 0),
+			Error::<Runtime>::PoolsFrozen,
+		);
+		assert_noop!(
+			DexModule::swap_with_exact_supply(&BOB, &[BTC, AUSD], 10, 0),
+			Error::<Runtime>::PoolsFrozen,
+		);
+
+		DexModule::on_shutdown_phase_changed(ShutdownPhase::Settling);
+		assert_eq!(DexModule::is_frozen(), false);
+		assert_ok!(DexModule::swap_currency(Origin::signed(BOB), BTC, 10, AUSD, 0));
+	});
+}
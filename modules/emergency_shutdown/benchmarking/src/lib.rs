@@ -17,7 +17,7 @@ use emergency_shutdown::Module as EmergencyShutdown;
 use emergency_shutdown::*;
 use orml_traits::{DataProviderExtended, MultiCurrencyExtended};
 use primitives::{Balance, CurrencyId};
-use support::{CDPTreasury, Price};
+use support::{CDPTreasury, Price, Ratio};
 
 pub struct Module<T: Trait>(emergency_shutdown::Module<T>);
 
@@ -48,10 +48,43 @@ benchmarks! {
 		feed_price::<T>(currency_id, Price::saturating_from_integer(1))?;
 	}: emergency_shutdown(RawOrigin::Root)
 
+	set_shutdown_triggers {
+		let u in 0 .. 1000;
+
+		let triggers = ShutdownTriggers {
+			max_price_unavailable_blocks: Some(100u32.into()),
+			min_system_collateral_ratio: Some(Ratio::saturating_from_rational(110, 100)),
+			below_collateral_ratio_blocks: 10u32.into(),
+			max_bad_debt: Some(dollar(10000)),
+		};
+	}: _(RawOrigin::Root, triggers)
+
+	set_unclaimed_collateral_receiver {
+		let u in 0 .. 1000;
+
+		let receiver: T::AccountId = account("receiver", u, SEED);
+	}: _(RawOrigin::Root, Some(receiver))
+
+	start_settlement {
+		let u in 0 .. 1000;
+
+		EmergencyShutdown::<T>::emergency_shutdown(RawOrigin::Root.into())?;
+	}: _(RawOrigin::Root)
+
+	cancel_shutdown {
+		let u in 0 .. 1000;
+
+		let currency_id = <T as emergency_shutdown::Trait>::CollateralCurrencyIds::get()[0];
+		feed_price::<T>(currency_id, Price::saturating_from_integer(1))?;
+		EmergencyShutdown::<T>::emergency_shutdown(RawOrigin::Root.into())?;
+		EmergencyShutdown::<T>::start_settlement(RawOrigin::Root.into())?;
+	}: _(RawOrigin::Root)
+
 	open_collateral_refund {
 		let u in 0 .. 1000;
 
 		EmergencyShutdown::<T>::emergency_shutdown(RawOrigin::Root.into())?;
+		EmergencyShutdown::<T>::start_settlement(RawOrigin::Root.into())?;
 	}: _(RawOrigin::Root)
 
 	refund_collaterals {
@@ -68,6 +101,7 @@ benchmarks! {
 		<T as emergency_shutdown::Trait>::CDPTreasury::deposit_backed_debit_to(&funder, dollar(9000))?;
 
 		EmergencyShutdown::<T>::emergency_shutdown(RawOrigin::Root.into())?;
+		EmergencyShutdown::<T>::start_settlement(RawOrigin::Root.into())?;
 		EmergencyShutdown::<T>::open_collateral_refund(RawOrigin::Root.into())?;
 	}: _(RawOrigin::Signed(caller),  dollar(1000))
 }
@@ -85,6 +119,34 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn set_shutdown_triggers() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_shutdown_triggers::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn set_unclaimed_collateral_receiver() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_unclaimed_collateral_receiver::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn start_settlement() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_start_settlement::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn cancel_shutdown() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_cancel_shutdown::<Runtime>());
+		});
+	}
+
 	#[test]
 	fn open_collateral_refund() {
 		new_test_ext().execute_with(|| {
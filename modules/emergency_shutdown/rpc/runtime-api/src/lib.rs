@@ -0,0 +1,13 @@
+//! Runtime API definition for emergency shutdown module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+
+pub use module_emergency_shutdown::{CollateralSolvency, SolvencyReport};
+
+sp_api::decl_runtime_apis! {
+	pub trait EmergencyShutdownApi {
+		fn solvency_report() -> Option<SolvencyReport>;
+	}
+}
@@ -0,0 +1,66 @@
+//! RPC interface for the emergency shutdown module.
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use module_emergency_shutdown_rpc_runtime_api::SolvencyReport;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::sync::Arc;
+
+pub use self::gen_client::Client as EmergencyShutdownClient;
+pub use module_emergency_shutdown_rpc_runtime_api::EmergencyShutdownApi as EmergencyShutdownRuntimeApi;
+
+#[rpc]
+pub trait EmergencyShutdownApi<BlockHash> {
+	#[rpc(name = "emergencyShutdown_solvencyReport")]
+	fn solvency_report(&self, at: Option<BlockHash>) -> Result<Option<SolvencyReport>>;
+}
+
+/// A struct that implements the [`EmergencyShutdownApi`].
+pub struct EmergencyShutdown<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> EmergencyShutdown<C, B> {
+	/// Create new `EmergencyShutdown` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		EmergencyShutdown {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+pub enum Error {
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block> EmergencyShutdownApi<<Block as BlockT>::Hash> for EmergencyShutdown<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: EmergencyShutdownRuntimeApi<Block>,
+{
+	fn solvency_report(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Option<SolvencyReport>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.solvency_report(&at).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to get solvency report.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
@@ -8,19 +8,46 @@
 //! operations including close some user entry, freeze feed prices, run offchain worker to settle
 //! CDPs has debit, cancel all active auctions module, when debits and gaps are settled,
 //! the stable coin holder are allowed to refund a basket of remaining collateral assets.
+//! The redemption rate of each collateral is fixed when the final redemption opens, so every
+//! stable coin is redeemed for the same basket regardless of the order of redemptions.
+//!
+//! Shutdown proceeds through the phases `Operational -> Frozen -> Settling -> ClaimsOpen -> Finalized`,
+//! other modules query the current phase by `EmergencyShutdown` to decide which operations are allowed,
+//! or get notified of every phase transition by `OnEmergencyShutdown::on_shutdown_phase_changed`.
+//! Before the claims open, a supermajority can cancel a shutdown triggered by false alarm, operations are
+//! unfrozen and locked prices are released, but CDPs already settled and auctions already cancelled stay as they are.
+//! The claims window lasts `ClaimsPeriod` blocks, after the deadline the unclaimed collaterals are swept to
+//! `UnclaimedCollateralReceiver` (or burned if it's not set) and the shutdown is finalized.
+//!
+//! Besides the manual governance call, emergency shutdown can be triggered automatically at the beginning of
+//! a block when one of the configured `ShutdownTriggers` fires.
+//!
+//! `solvency_report` dry-runs the shutdown settlement at current prices (locked prices after shutdown),
+//! which helps governance to decide whether shutdown or debit auctions are the right response.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
 use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage, ensure,
 	traits::{EnsureOrigin, Get},
-	weights::constants::WEIGHT_PER_MICROS,
+	weights::{constants::WEIGHT_PER_MICROS, Weight},
+	IterableStorageDoubleMap,
 };
 use frame_system::{self as system, ensure_root, ensure_signed};
+use orml_traits::MultiCurrency;
 use primitives::{Balance, CurrencyId};
-use sp_runtime::{traits::Zero, FixedPointNumber};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{
+	traits::{Convert, Saturating, Zero},
+	DispatchResult, FixedPointNumber, RuntimeDebug,
+};
 use sp_std::prelude::*;
-use support::{AuctionManager, CDPTreasury, OnEmergencyShutdown, PriceProvider, Ratio};
+use support::{
+	AuctionManager, CDPTreasury, EmergencyShutdown, ExchangeRate, OnEmergencyShutdown, PriceProvider, Ratio,
+	ShutdownPhase,
+};
 
 mod mock;
 mod tests;
@@ -31,8 +58,8 @@ pub trait Trait: system::Trait + loans::Trait {
 	/// The list of valid collateral currency types
 	type CollateralCurrencyIds: Get<Vec<CurrencyId>>;
 
-	/// Price source to freeze currencies' price
-	type PriceSource: PriceProvider<CurrencyId>;
+	/// The stable currency id
+	type GetStableCurrencyId: Get<CurrencyId>;
 
 	/// CDP treasury to escrow collateral assets after settlement
 	type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
@@ -45,6 +72,85 @@ pub trait Trait: system::Trait + loans::Trait {
 
 	/// The origin which may trigger emergency shutdown. Root can always do this.
 	type ShutdownOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The origin which may cancel emergency shutdown before the claims open. Root can always do this.
+	type CancelShutdownOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The price source to check the automatic shutdown triggers
+	type PriceSource: PriceProvider<CurrencyId>;
+
+	/// The system collateral ratio tracked by system collateralization, `None` if there's no debit
+	type SystemCollateralRatio: Get<Option<Ratio>>;
+
+	/// The period of the claims window after the final redemption opens
+	type ClaimsPeriod: Get<Self::BlockNumber>;
+}
+
+/// Conditions to trigger emergency shutdown automatically, `None` value means the trigger is disabled
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct ShutdownTriggers<BlockNumber> {
+	/// Shutdown if prices of all collaterals have been unavailable for this number of blocks
+	pub max_price_unavailable_blocks: Option<BlockNumber>,
+
+	/// Shutdown if the collateral ratio of all CDPs in system is below it
+	pub min_system_collateral_ratio: Option<Ratio>,
+
+	/// Shutdown only if the system collateral ratio has been below `min_system_collateral_ratio`
+	/// for this number of blocks
+	pub below_collateral_ratio_blocks: BlockNumber,
+
+	/// Shutdown if the bad debt in CDP treasury, net of the surplus, is above it
+	pub max_bad_debt: Option<Balance>,
+}
+
+/// Dry-run result of shutdown settlement for a collateral type
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct CollateralSolvency {
+	/// Collateral type
+	pub currency_id: CurrencyId,
+	/// Collateral amount in CDPs, CDP treasury and collateral auctions
+	pub collateral_amount: Balance,
+	/// Value of `collateral_amount` in stable currency
+	pub collateral_value: Balance,
+	/// Total debit value of CDPs under this collateral type
+	pub debit_value: Balance,
+	/// Collateral value left to stable coin holders after settlement,
+	/// the excess collateral of CDPs belongs to their owners
+	pub backing_value: Balance,
+	/// Debit value not covered by the collateral of CDPs after settlement
+	pub shortfall: Balance,
+}
+
+/// Dry-run result of shutdown settlement for the whole system
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SolvencyReport {
+	/// Total collateral value of all collateral types
+	pub total_collateral_value: Balance,
+	/// Total debit value of all CDPs
+	pub total_debit_value: Balance,
+	/// Bad debt in CDP treasury
+	pub bad_debt: Balance,
+	/// Total issuance of stable coin
+	pub total_stable_issuance: Balance,
+	/// Collateral value backing each stable coin after settlement, `None` if no stable coin issued
+	pub backing_per_stable: Option<Ratio>,
+	/// Report of every collateral type
+	pub collaterals: Vec<CollateralSolvency>,
+}
+
+/// The trigger which fired automatic emergency shutdown
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub enum ShutdownTrigger {
+	/// Prices of all collaterals have been unavailable for too long
+	PriceUnavailable,
+	/// System collateral ratio is below the threshold (system_collateral_ratio)
+	BelowSystemCollateralRatio(Ratio),
+	/// Bad debt net of the surplus is above the cap (bad_debt)
+	ExceedBadDebtCap(Balance),
 }
 
 decl_event!(
@@ -56,10 +162,24 @@ decl_event!(
 	{
 		/// Emergency shutdown occurs (block_number)
 		Shutdown(BlockNumber),
+		/// Emergency shutdown is triggered automatically (block_number, trigger)
+		AutoShutdown(BlockNumber, ShutdownTrigger),
+		/// The automatic shutdown triggers updated (new_triggers)
+		ShutdownTriggersUpdated(ShutdownTriggers<BlockNumber>),
+		/// Emergency shutdown cancelled and system resumed (block_number)
+		CancelShutdown(BlockNumber),
+		/// Settlement of CDPs and auctions started (block_number)
+		StartSettlement(BlockNumber),
 		/// The final redemption opened (block_number)
 		OpenRefund(BlockNumber),
 		/// Refund info (caller, stable_coin_amount, refund_list)
 		Refund(AccountId, Balance, Vec<(CurrencyId, Balance)>),
+		/// The receiver of unclaimed collaterals updated, `None` means burn them (new_receiver)
+		UnclaimedCollateralReceiverUpdated(Option<AccountId>),
+		/// Unclaimed collaterals swept after the claims deadline, `None` receiver means burned (receiver, swept_list)
+		SweepUnclaimedCollaterals(Option<AccountId>, Vec<(CurrencyId, Balance)>),
+		/// Emergency shutdown finalized (block_number)
+		Finalize(BlockNumber),
 	}
 );
 
@@ -76,15 +196,40 @@ decl_error! {
 		ExistPotentialSurplus,
 		/// Exist unhandled debit, means settlement has not been completed
 		ExistUnhandleDebit,
+		/// The operation is not allowed in current shutdown phase
+		InvalidShutdownPhase,
 	}
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as EmergencyShutdown {
-		/// Emergency shutdown flag
-		pub IsShutdown get(fn is_shutdown): bool;
-		/// Open final redemption flag
-		pub CanRefund get(fn can_refund): bool;
+		/// Current phase of emergency shutdown
+		pub Phase get(fn phase): ShutdownPhase;
+
+		/// Mapping from collateral type to the collateral amount redeemed per stable coin,
+		/// fixed when the final redemption opens
+		pub RedemptionRates get(fn redemption_rates): map hasher(twox_64_concat) CurrencyId => Option<ExchangeRate>;
+
+		/// Conditions to trigger emergency shutdown automatically
+		pub Triggers get(fn triggers): ShutdownTriggers<T::BlockNumber>;
+
+		/// The block number since which prices of all collaterals are unavailable
+		pub PriceUnavailableSince get(fn price_unavailable_since): Option<T::BlockNumber>;
+
+		/// The block number since which the system collateral ratio is below the threshold
+		pub BelowCollateralRatioSince get(fn below_collateral_ratio_since): Option<T::BlockNumber>;
+
+		/// The block number after which unclaimed collaterals are swept
+		pub ClaimsDeadline get(fn claims_deadline): Option<T::BlockNumber>;
+
+		/// The account receives unclaimed collaterals, `None` means burn them
+		pub UnclaimedCollateralReceiver get(fn unclaimed_collateral_receiver): Option<T::AccountId>;
+
+		/// Emergency shutdown flag of previous version, migrated into `Phase`
+		IsShutdown: bool;
+
+		/// Open final redemption flag of previous version, migrated into `Phase`
+		CanRefund: bool;
 	}
 }
 
@@ -96,6 +241,9 @@ decl_module! {
 		/// The list of valid collateral currency types
 		const CollateralCurrencyIds: Vec<CurrencyId> = T::CollateralCurrencyIds::get();
 
+		/// The period of the claims window after the final redemption opens
+		const ClaimsPeriod: T::BlockNumber = T::ClaimsPeriod::get();
+
 		/// Start emergency shutdown
 		///
 		/// The dispatch origin of this call must be `ShutdownOrigin` or _Root_.
@@ -104,10 +252,10 @@ decl_module! {
 		/// - Preconditions:
 		/// 	- T::CDPTreasury is module_cdp_treasury
 		/// 	- T::AuctionManagerHandler is module_auction_manager
-		/// 	- T::OnShutdown is (module_cdp_treasury, module_cdp_engine, module_honzon, module_dex)
+		/// 	- T::OnShutdown is (module_prices, module_cdp_treasury, module_cdp_engine, module_honzon, module_dex, module_auction_manager, module_incentives)
 		/// - Complexity: `O(1)`
-		/// - Db reads: `IsShutdown`, (length of collateral_ids) items in modules related to module_emergency_shutdown
-		/// - Db writes: `IsShutdown`, (4 + length of collateral_ids) items in modules related to module_emergency_shutdown
+		/// - Db reads: `Phase`, (length of collateral_ids) items in modules related to module_emergency_shutdown
+		/// - Db writes: `Phase`, (4 + length of collateral_ids) items in modules related to module_emergency_shutdown
 		/// -------------------
 		/// Base Weight: 47.4 µs
 		/// # </weight>
@@ -120,20 +268,111 @@ decl_module! {
 				.map(|_| ())
 				.or_else(ensure_root)?;
 			ensure!(!Self::is_shutdown(), Error::<T>::AlreadyShutdown);
+			Self::do_shutdown();
+		}
 
-			// trigger shutdown in other related modules
-			T::OnShutdown::on_emergency_shutdown();
+		/// Cancel emergency shutdown before the final redemption opens, and resume the system.
+		///
+		/// The dispatch origin of this call must be `CancelShutdownOrigin` or _Root_.
+		///
+		/// # <weight>
+		/// - Preconditions:
+		/// 	- T::OnShutdown is (module_prices, module_cdp_treasury, module_cdp_engine, module_honzon, module_dex, module_auction_manager, module_incentives)
+		/// - Complexity: `O(1)`
+		/// - Db reads: `Phase`
+		/// - Db writes: `Phase`, `PriceUnavailableSince`, `BelowCollateralRatioSince`, (4 + length of collateral_ids) items in modules related to module_emergency_shutdown
+		/// -------------------
+		/// Base Weight: 45 µs
+		/// # </weight>
+		#[weight = 45 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(
+			1,
+			7 + (T::CollateralCurrencyIds::get().len() as u64)
+		)]
+		pub fn cancel_shutdown(origin) {
+			T::CancelShutdownOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			let phase = Self::phase();
+			ensure!(phase != ShutdownPhase::Operational, Error::<T>::MustAfterShutdown);
+			ensure!(
+				phase == ShutdownPhase::Frozen || phase == ShutdownPhase::Settling,
+				Error::<T>::InvalidShutdownPhase,
+			);
 
-			// get all collateral types
-			let collateral_currency_ids = T::CollateralCurrencyIds::get();
+			// revert shutdown in other related modules, prices of collaterals are unlocked by them
+			T::OnShutdown::on_cancel_shutdown();
 
-			// lock price for every collateral
-			for currency_id in collateral_currency_ids {
-				<T as Trait>::PriceSource::lock_price(currency_id);
-			}
+			// restart counting for the automatic trigger
+			<PriceUnavailableSince<T>>::kill();
+			<BelowCollateralRatioSince<T>>::kill();
+			Self::set_phase(ShutdownPhase::Operational);
+			Self::deposit_event(RawEvent::CancelShutdown(<system::Module<T>>::block_number()));
+		}
+
+		/// Update the account to receive the collaterals unclaimed after the claims deadline
+		///
+		/// The dispatch origin of this call must be `ShutdownOrigin` or _Root_.
+		///
+		/// - `receiver`: the receiver of unclaimed collaterals, `None` means burn them.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `UnclaimedCollateralReceiver`
+		/// -------------------
+		/// Base Weight: 18 µs
+		/// # </weight>
+		#[weight = 18 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_unclaimed_collateral_receiver(origin, receiver: Option<T::AccountId>) {
+			T::ShutdownOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			<UnclaimedCollateralReceiver<T>>::set(receiver.clone());
+			Self::deposit_event(RawEvent::UnclaimedCollateralReceiverUpdated(receiver));
+		}
 
-			<IsShutdown>::put(true);
-			Self::deposit_event(RawEvent::Shutdown(<system::Module<T>>::block_number()));
+		/// Update the conditions to trigger emergency shutdown automatically
+		///
+		/// The dispatch origin of this call must be `ShutdownOrigin` or _Root_.
+		///
+		/// - `triggers`: the new automatic shutdown triggers.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `Triggers`
+		/// -------------------
+		/// Base Weight: 20 µs
+		/// # </weight>
+		#[weight = 20 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_shutdown_triggers(origin, triggers: ShutdownTriggers<T::BlockNumber>) {
+			T::ShutdownOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			<Triggers<T>>::put(triggers.clone());
+			Self::deposit_event(RawEvent::ShutdownTriggersUpdated(triggers));
+		}
+
+		/// Start to settle CDPs and cancel active auctions after emergency shutdown.
+		///
+		/// The dispatch origin of this call must be `ShutdownOrigin` or _Root_.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `Phase`
+		/// - Db writes: `Phase`
+		/// -------------------
+		/// Base Weight: 15 µs
+		/// # </weight>
+		#[weight = 15 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn start_settlement(origin) {
+			T::ShutdownOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			Self::ensure_phase(ShutdownPhase::Frozen)?;
+
+			Self::set_phase(ShutdownPhase::Settling);
+			Self::deposit_event(RawEvent::StartSettlement(<system::Module<T>>::block_number()));
 		}
 
 		/// Open final redemption if settlement is completed.
@@ -144,22 +383,22 @@ decl_module! {
 		/// - Preconditions:
 		/// 	- T::CDPTreasury is module_cdp_treasury
 		/// 	- T::AuctionManagerHandler is module_auction_manager
-		/// 	- T::OnShutdown is (module_cdp_treasury, module_cdp_engine, module_honzon, module_dex)
+		/// 	- T::OnShutdown is (module_prices, module_cdp_treasury, module_cdp_engine, module_honzon, module_dex, module_auction_manager, module_incentives)
 		/// - Complexity: `O(1)`
-		/// - Db reads: `IsShutdown`, (2 + 2 * length of collateral_ids) items in modules related to module_emergency_shutdown
-		/// - Db writes: `CanRefund`
+		/// - Db reads: `Phase`, (3 + 3 * length of collateral_ids) items in modules related to module_emergency_shutdown
+		/// - Db writes: `Phase`, `RedemptionRates`, `ClaimsDeadline`
 		/// -------------------
 		/// Base Weight: 47.4 µs
 		/// # </weight>
 		#[weight = 48 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(
-			2 + 2 * (T::CollateralCurrencyIds::get().len() as u64),
-			1
+			4 + 3 * (T::CollateralCurrencyIds::get().len() as u64),
+			2 + (T::CollateralCurrencyIds::get().len() as u64)
 		)]
 		pub fn open_collateral_refund(origin) {
 			T::ShutdownOrigin::try_origin(origin)
 				.map(|_| ())
 				.or_else(ensure_root)?;
-			ensure!(Self::is_shutdown(), Error::<T>::MustAfterShutdown);	// must after shutdown
+			Self::ensure_phase(ShutdownPhase::Settling)?;
 
 			// Ensure there's no debit and surplus auction now, these maybe bring uncertain surplus to system.
 			// Cancel all surplus auctions and debit auctions to pass the check!
@@ -186,9 +425,13 @@ decl_module! {
 				);
 			}
 
-			// Open refund stage
-			<CanRefund>::put(true);
-			Self::deposit_event(RawEvent::OpenRefund(<system::Module<T>>::block_number()));
+			Self::fix_redemption_rates();
+
+			// Open refund stage, the claims window closes after `ClaimsPeriod`
+			let now = <system::Module<T>>::block_number();
+			<ClaimsDeadline<T>>::put(now.saturating_add(T::ClaimsPeriod::get()));
+			Self::set_phase(ShutdownPhase::ClaimsOpen);
+			Self::deposit_event(RawEvent::OpenRefund(now));
 		}
 
 		/// Refund a basket of remaining collateral assets to caller
@@ -199,9 +442,9 @@ decl_module! {
 		/// - Preconditions:
 		/// 	- T::CDPTreasury is module_cdp_treasury
 		/// 	- T::AuctionManagerHandler is module_auction_manager
-		/// 	- T::OnShutdown is (module_cdp_treasury, module_cdp_engine, module_honzon, module_dex)
+		/// 	- T::OnShutdown is (module_prices, module_cdp_treasury, module_cdp_engine, module_honzon, module_dex, module_auction_manager, module_incentives)
 		/// - Complexity: `O(1)`
-		/// - Db reads: `CanRefund`, (2 + 3 * length of collateral_ids) items in modules related to module_emergency_shutdown
+		/// - Db reads: `Phase`, `RedemptionRates`, (2 + 2 * length of collateral_ids) items in modules related to module_emergency_shutdown
 		/// - Db writes: (3 * length of collateral_ids) items in modules related to module_emergency_shutdown
 		/// -------------------
 		/// Base Weight: 95.86 µs
@@ -214,17 +457,21 @@ decl_module! {
 			let who = ensure_signed(origin)?;
 			ensure!(Self::can_refund(), Error::<T>::CanNotRefund);
 
-			let refund_ratio: Ratio = <T as Trait>::CDPTreasury::get_debit_proportion(amount);
 			let collateral_currency_ids = T::CollateralCurrencyIds::get();
 
 			// burn caller's stable currency by cdp treasury
 			<T as Trait>::CDPTreasury::withdraw_backed_debit_from(&who, amount)?;
 
 			let mut refund_assets: Vec<(CurrencyId, Balance)> = vec![];
-			// refund collaterals to caller by cdp treasury
+			// refund collaterals to caller by cdp treasury at the fixed redemption rates,
+			// never exceed the remaining collaterals because of rounding
 			for currency_id in collateral_currency_ids {
-				let refund_amount = refund_ratio
-					.saturating_mul_int(<T as Trait>::CDPTreasury::get_total_collaterals(currency_id));
+				let refund_amount = sp_std::cmp::min(
+					Self::redemption_rates(currency_id)
+						.unwrap_or_default()
+						.saturating_mul_int(amount),
+					<T as Trait>::CDPTreasury::get_total_collaterals(currency_id),
+				);
 
 				if !refund_amount.is_zero() {
 					<T as Trait>::CDPTreasury::transfer_collateral_to(currency_id, &who, refund_amount)?;
@@ -234,5 +481,252 @@ decl_module! {
 
 			Self::deposit_event(RawEvent::Refund(who, amount, refund_assets));
 		}
+
+		/// Check the automatic shutdown triggers at the beginning of block, shutdown the system if one of them fired.
+		/// After the claims deadline, sweep unclaimed collaterals and finalize the shutdown.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			match Self::phase() {
+				ShutdownPhase::Operational => Self::try_auto_shutdown(now),
+				ShutdownPhase::ClaimsOpen => Self::try_finalize(now),
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_shutdown_flags()
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether emergency shutdown has been triggered
+	pub fn is_shutdown() -> bool {
+		Self::phase() != ShutdownPhase::Operational
+	}
+
+	/// Whether the final redemption is opened
+	pub fn can_refund() -> bool {
+		Self::phase() == ShutdownPhase::ClaimsOpen
+	}
+
+	/// Dry-run the shutdown settlement at current prices, `None` if the
+	/// price of any collateral is unavailable
+	pub fn solvency_report() -> Option<SolvencyReport> {
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let mut collaterals: Vec<CollateralSolvency> = vec![];
+
+		for currency_id in T::CollateralCurrencyIds::get() {
+			let price = T::PriceSource::get_relative_price(currency_id, stable_currency_id)?;
+
+			// collaterals already escrowed by cdp treasury and auctions go to stable coin holders
+			let escrowed_amount = <T as Trait>::CDPTreasury::get_total_collaterals(currency_id).saturating_add(
+				<T as Trait>::AuctionManagerHandler::get_total_collateral_in_auction(currency_id),
+			);
+			let mut report = CollateralSolvency {
+				currency_id,
+				collateral_amount: <loans::Module<T>>::total_collaterals(currency_id).saturating_add(escrowed_amount),
+				collateral_value: Zero::zero(),
+				debit_value: Zero::zero(),
+				backing_value: price.saturating_mul_int(escrowed_amount),
+				shortfall: Zero::zero(),
+			};
+			report.collateral_value = price.saturating_mul_int(report.collateral_amount);
+
+			// settle every CDP which has debit, confiscated collaterals go to stable coin holders
+			for (who, debit_balance) in <loans::Debits<T>>::iter_prefix(currency_id) {
+				let debit_value = <T as loans::Trait>::Convert::convert((currency_id, debit_balance));
+				let cdp_collateral_value = price.saturating_mul_int(<loans::Module<T>>::collaterals(&who, currency_id));
+				report.debit_value = report.debit_value.saturating_add(debit_value);
+				report.backing_value = report
+					.backing_value
+					.saturating_add(sp_std::cmp::min(debit_value, cdp_collateral_value));
+				report.shortfall = report
+					.shortfall
+					.saturating_add(debit_value.saturating_sub(cdp_collateral_value));
+			}
+
+			collaterals.push(report);
+		}
+
+		let total_stable_issuance = <T as loans::Trait>::Currency::total_issuance(stable_currency_id);
+		let total_backing_value = collaterals
+			.iter()
+			.fold(Balance::zero(), |acc, report| acc.saturating_add(report.backing_value));
+
+		Some(SolvencyReport {
+			total_collateral_value: collaterals.iter().fold(Balance::zero(), |acc, report| {
+				acc.saturating_add(report.collateral_value)
+			}),
+			total_debit_value: collaterals
+				.iter()
+				.fold(Balance::zero(), |acc, report| acc.saturating_add(report.debit_value)),
+			bad_debt: <T as Trait>::CDPTreasury::get_debit_pool(),
+			total_stable_issuance,
+			backing_per_stable: Ratio::checked_from_rational(total_backing_value, total_stable_issuance),
+			collaterals,
+		})
+	}
+
+	fn check_shutdown_triggers(now: T::BlockNumber) -> Option<ShutdownTrigger> {
+		let triggers = Self::triggers();
+
+		if let Some(max_price_unavailable_blocks) = triggers.max_price_unavailable_blocks {
+			let collateral_currency_ids = T::CollateralCurrencyIds::get();
+			let all_unavailable = !collateral_currency_ids.is_empty()
+				&& collateral_currency_ids
+					.into_iter()
+					.all(|currency_id| T::PriceSource::get_price(currency_id).is_none());
+
+			if all_unavailable {
+				let since = Self::price_unavailable_since().unwrap_or_else(|| {
+					<PriceUnavailableSince<T>>::put(now);
+					now
+				});
+				if now.saturating_sub(since) >= max_price_unavailable_blocks {
+					return Some(ShutdownTrigger::PriceUnavailable);
+				}
+			} else {
+				<PriceUnavailableSince<T>>::kill();
+			}
+		}
+
+		if let Some(min_system_collateral_ratio) = triggers.min_system_collateral_ratio {
+			match T::SystemCollateralRatio::get() {
+				Some(system_collateral_ratio) if system_collateral_ratio < min_system_collateral_ratio => {
+					let since = Self::below_collateral_ratio_since().unwrap_or_else(|| {
+						<BelowCollateralRatioSince<T>>::put(now);
+						now
+					});
+					if now.saturating_sub(since) >= triggers.below_collateral_ratio_blocks {
+						return Some(ShutdownTrigger::BelowSystemCollateralRatio(system_collateral_ratio));
+					}
+				}
+				_ => <BelowCollateralRatioSince<T>>::kill(),
+			}
+		}
+
+		if let Some(max_bad_debt) = triggers.max_bad_debt {
+			let bad_debt = <T as Trait>::CDPTreasury::get_debit_pool()
+				.saturating_sub(<T as Trait>::CDPTreasury::get_surplus_pool());
+			if bad_debt > max_bad_debt {
+				return Some(ShutdownTrigger::ExceedBadDebtCap(bad_debt));
+			}
+		}
+
+		None
+	}
+
+	fn try_auto_shutdown(now: T::BlockNumber) -> Weight {
+		let collateral_len = T::CollateralCurrencyIds::get().len() as u64;
+		let mut weight = T::DbWeight::get().reads_writes(6 + collateral_len, 2);
+
+		if let Some(trigger) = Self::check_shutdown_triggers(now) {
+			Self::deposit_event(RawEvent::AutoShutdown(now, trigger));
+			Self::do_shutdown();
+			weight = weight.saturating_add(
+				48 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(collateral_len, 5 + collateral_len),
+			);
+		}
+		weight
+	}
+
+	fn try_finalize(now: T::BlockNumber) -> Weight {
+		if Self::claims_deadline().map_or(true, |deadline| now <= deadline) {
+			return T::DbWeight::get().reads(2);
+		}
+
+		Self::sweep_unclaimed_collaterals();
+		Self::set_phase(ShutdownPhase::Finalized);
+		Self::deposit_event(RawEvent::Finalize(now));
+
+		let collateral_len = T::CollateralCurrencyIds::get().len() as u64;
+		T::DbWeight::get().reads_writes(3 + 2 * collateral_len, 1 + 3 * collateral_len)
+	}
+
+	fn sweep_unclaimed_collaterals() {
+		let receiver = Self::unclaimed_collateral_receiver();
+		let mut swept_assets: Vec<(CurrencyId, Balance)> = vec![];
+
+		for currency_id in T::CollateralCurrencyIds::get() {
+			let amount = <T as Trait>::CDPTreasury::get_total_collaterals(currency_id);
+			if amount.is_zero() {
+				continue;
+			}
+
+			let result = match receiver {
+				Some(ref receiver) => <T as Trait>::CDPTreasury::transfer_collateral_to(currency_id, receiver, amount),
+				None => <T as Trait>::CDPTreasury::burn_collateral(currency_id, amount),
+			};
+			if result.is_ok() {
+				swept_assets.push((currency_id, amount));
+			}
+		}
+
+		Self::deposit_event(RawEvent::SweepUnclaimedCollaterals(receiver, swept_assets));
+	}
+
+	fn do_shutdown() {
+		// trigger shutdown in other related modules, prices of collaterals are locked by them
+		T::OnShutdown::on_emergency_shutdown();
+
+		Self::set_phase(ShutdownPhase::Frozen);
+		Self::deposit_event(RawEvent::Shutdown(<system::Module<T>>::block_number()));
+	}
+
+	/// Fix the redemption rate of every collateral by the remaining collaterals in cdp treasury
+	/// and the total issuance of stable coin.
+	fn fix_redemption_rates() {
+		let total_stable_issuance = <T as loans::Trait>::Currency::total_issuance(T::GetStableCurrencyId::get());
+		for currency_id in T::CollateralCurrencyIds::get() {
+			let redemption_rate = ExchangeRate::checked_from_rational(
+				<T as Trait>::CDPTreasury::get_total_collaterals(currency_id),
+				total_stable_issuance,
+			)
+			.unwrap_or_default();
+			RedemptionRates::insert(currency_id, redemption_rate);
+		}
+	}
+
+	/// Migrate the shutdown flags of previous version into `Phase`. The shutdown of previous version
+	/// allowed settlement right away, and refund without fixed redemption rates, so they are fixed here.
+	fn migrate_shutdown_flags() -> Weight {
+		if !IsShutdown::exists() && !CanRefund::exists() {
+			return T::DbWeight::get().reads(2);
+		}
+
+		let is_shutdown = IsShutdown::take();
+		let can_refund = CanRefund::take();
+		if can_refund {
+			Self::fix_redemption_rates();
+			let now = <system::Module<T>>::block_number();
+			<ClaimsDeadline<T>>::put(now.saturating_add(T::ClaimsPeriod::get()));
+			<Phase>::put(ShutdownPhase::ClaimsOpen);
+		} else if is_shutdown {
+			<Phase>::put(ShutdownPhase::Settling);
+		}
+
+		let collaterals_count = T::CollateralCurrencyIds::get().len() as Weight;
+		T::DbWeight::get().reads_writes(3 + collaterals_count, 4 + collaterals_count)
+	}
+
+	fn set_phase(phase: ShutdownPhase) {
+		<Phase>::put(phase);
+		T::OnShutdown::on_shutdown_phase_changed(phase);
+	}
+
+	fn ensure_phase(phase: ShutdownPhase) -> DispatchResult {
+		let current_phase = Self::phase();
+		ensure!(
+			current_phase != ShutdownPhase::Operational,
+			Error::<T>::MustAfterShutdown
+		);
+		ensure!(current_phase == phase, Error::<T>::InvalidShutdownPhase);
+		Ok(())
+	}
+}
+
+impl<T: Trait> EmergencyShutdown for Module<T> {
+	fn shutdown_phase() -> ShutdownPhase {
+		Self::phase()
 	}
 }
@@ -12,7 +12,8 @@ use sp_runtime::{
 	traits::{Convert, IdentityLookup},
 	DispatchResult, Perbill,
 };
-use support::{AuctionManager, Price, PriceProvider};
+use std::cell::RefCell;
+use support::{AuctionManager, Price};
 
 pub type AccountId = u128;
 pub type AuctionId = u64;
@@ -136,16 +137,39 @@ impl loans::Trait for Runtime {
 	type DebitBalance = DebitBalance;
 	type DebitAmount = DebitAmount;
 	type CDPTreasury = CDPTreasuryModule;
+	type EmergencyShutdown = EmergencyShutdownModule;
+	type OnUpdateLoan = ();
+	type OnUpdateCollateral = ();
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type PositionDeposit = ();
+}
+pub type LoansModule = loans::Module<Runtime>;
+
+thread_local! {
+	pub static PRICE: RefCell<Option<Price>> = RefCell::new(Some(Price::one()));
+	pub static SHUTDOWN_PHASES: RefCell<Vec<ShutdownPhase>> = RefCell::new(vec![]);
+	pub static SYSTEM_COLLATERAL_RATIO: RefCell<Option<Ratio>> = RefCell::new(None);
+}
+
+pub struct MockOnShutdown;
+impl OnEmergencyShutdown for MockOnShutdown {
+	fn on_emergency_shutdown() {}
+
+	fn on_cancel_shutdown() {}
+
+	fn on_shutdown_phase_changed(phase: ShutdownPhase) {
+		SHUTDOWN_PHASES.with(|v| v.borrow_mut().push(phase));
+	}
 }
 
 pub struct MockPriceSource;
 impl PriceProvider<CurrencyId> for MockPriceSource {
 	fn get_relative_price(_base: CurrencyId, _quote: CurrencyId) -> Option<Price> {
-		Some(Price::saturating_from_integer(1))
+		PRICE.with(|v| *v.borrow())
 	}
 
 	fn get_price(_currency_id: CurrencyId) -> Option<Price> {
-		Some(Price::saturating_from_integer(1))
+		PRICE.with(|v| *v.borrow())
 	}
 
 	fn lock_price(_currency_id: CurrencyId) {}
@@ -153,6 +177,13 @@ impl PriceProvider<CurrencyId> for MockPriceSource {
 	fn unlock_price(_currency_id: CurrencyId) {}
 }
 
+pub struct MockSystemCollateralRatio;
+impl Get<Option<Ratio>> for MockSystemCollateralRatio {
+	fn get() -> Option<Ratio> {
+		SYSTEM_COLLATERAL_RATIO.with(|v| *v.borrow())
+	}
+}
+
 pub struct MockAuctionManager;
 impl AuctionManager<AccountId> for MockAuctionManager {
 	type Balance = Balance;
@@ -209,6 +240,7 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = ();
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = EmergencyShutdownModule;
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
@@ -216,14 +248,22 @@ ord_parameter_types! {
 	pub const CollateralCurrencyIds: Vec<CurrencyId> = vec![BTC, DOT];
 }
 
+parameter_types! {
+	pub const ClaimsPeriod: BlockNumber = 10;
+}
+
 impl Trait for Runtime {
 	type Event = TestEvent;
 	type CollateralCurrencyIds = CollateralCurrencyIds;
-	type PriceSource = MockPriceSource;
+	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CDPTreasuryModule;
 	type AuctionManagerHandler = MockAuctionManager;
-	type OnShutdown = CDPTreasuryModule;
+	type OnShutdown = MockOnShutdown;
 	type ShutdownOrigin = EnsureSignedBy<One, AccountId>;
+	type CancelShutdownOrigin = EnsureSignedBy<One, AccountId>;
+	type PriceSource = MockPriceSource;
+	type SystemCollateralRatio = MockSystemCollateralRatio;
+	type ClaimsPeriod = ClaimsPeriod;
 }
 pub type EmergencyShutdownModule = Module<Runtime>;
 
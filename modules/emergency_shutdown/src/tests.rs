@@ -3,9 +3,16 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
-use mock::{CDPTreasuryModule, EmergencyShutdownModule, ExtBuilder, Origin, Runtime, System, TestEvent, ALICE};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OnInitialize, OnRuntimeUpgrade},
+};
+use mock::{
+	CDPTreasuryModule, Currencies, EmergencyShutdownModule, ExtBuilder, LoansModule, Origin, Runtime, System,
+	TestEvent, ALICE, BOB, BTC, DOT, PRICE, SHUTDOWN_PHASES, SYSTEM_COLLATERAL_RATIO,
+};
 use sp_runtime::traits::BadOrigin;
+use support::Price;
 
 #[test]
 fn emergency_shutdown_work() {
@@ -23,6 +30,7 @@ fn emergency_shutdown_work() {
 		assert!(System::events().iter().any(|record| record.event == shutdown_event));
 
 		assert_eq!(EmergencyShutdownModule::is_shutdown(), true);
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::Frozen);
 		assert_eq!(CDPTreasuryModule::is_shutdown(), true);
 		assert_noop!(
 			EmergencyShutdownModule::emergency_shutdown(Origin::ROOT),
@@ -31,6 +39,69 @@ fn emergency_shutdown_work() {
 	});
 }
 
+#[test]
+fn start_settlement_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			EmergencyShutdownModule::start_settlement(Origin::ROOT),
+			Error::<Runtime>::MustAfterShutdown,
+		);
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_noop!(EmergencyShutdownModule::start_settlement(Origin::signed(5)), BadOrigin,);
+		assert_ok!(EmergencyShutdownModule::start_settlement(Origin::signed(1)));
+
+		let start_settlement_event = TestEvent::emergency_shutdown(RawEvent::StartSettlement(1));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == start_settlement_event));
+
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::Settling);
+		assert_eq!(EmergencyShutdownModule::shutdown_phase(), ShutdownPhase::Settling);
+		assert_noop!(
+			EmergencyShutdownModule::start_settlement(Origin::ROOT),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+	});
+}
+
+#[test]
+fn cancel_shutdown_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			EmergencyShutdownModule::cancel_shutdown(Origin::ROOT),
+			Error::<Runtime>::MustAfterShutdown,
+		);
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_eq!(CDPTreasuryModule::is_shutdown(), true);
+		assert_noop!(EmergencyShutdownModule::cancel_shutdown(Origin::signed(5)), BadOrigin);
+		assert_ok!(EmergencyShutdownModule::cancel_shutdown(Origin::signed(1)));
+
+		let cancel_shutdown_event = TestEvent::emergency_shutdown(RawEvent::CancelShutdown(1));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == cancel_shutdown_event));
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::Operational);
+		assert_eq!(CDPTreasuryModule::is_shutdown(), false);
+
+		// can be cancelled when settling
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::start_settlement(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::cancel_shutdown(Origin::ROOT));
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), false);
+
+		// cannot be cancelled after the claims open
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::start_settlement(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(Origin::ROOT));
+		assert_noop!(
+			EmergencyShutdownModule::cancel_shutdown(Origin::ROOT),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+	});
+}
+
 #[test]
 fn open_collateral_refund_fail() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -40,6 +111,10 @@ fn open_collateral_refund_fail() {
 			Error::<Runtime>::MustAfterShutdown,
 		);
 		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(Origin::ROOT),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
 	});
 }
 
@@ -49,6 +124,7 @@ fn open_collateral_refund_work() {
 		System::set_block_number(1);
 		assert_eq!(EmergencyShutdownModule::can_refund(), false);
 		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::start_settlement(Origin::ROOT));
 		assert_noop!(
 			EmergencyShutdownModule::open_collateral_refund(Origin::signed(5)),
 			BadOrigin,
@@ -59,6 +135,7 @@ fn open_collateral_refund_work() {
 		assert!(System::events().iter().any(|record| record.event == open_refund_event));
 
 		assert_eq!(EmergencyShutdownModule::can_refund(), true);
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::ClaimsOpen);
 	});
 }
 
@@ -71,3 +148,358 @@ fn refund_collaterals_fail() {
 		);
 	});
 }
+
+#[test]
+fn refund_collaterals_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::deposit_backed_debit_to(&ALICE, 1000));
+		assert_ok!(CDPTreasuryModule::deposit_backed_debit_to(&BOB, 3000));
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &BOB, 400));
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(DOT, &BOB, 800));
+
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::start_settlement(Origin::ROOT));
+		assert_eq!(EmergencyShutdownModule::redemption_rates(BTC), None);
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(Origin::ROOT));
+		assert_eq!(
+			EmergencyShutdownModule::redemption_rates(BTC),
+			Some(ExchangeRate::saturating_from_rational(1, 10))
+		);
+		assert_eq!(
+			EmergencyShutdownModule::redemption_rates(DOT),
+			Some(ExchangeRate::saturating_from_rational(2, 10))
+		);
+
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(Origin::signed(ALICE), 1000));
+		let refund_event = TestEvent::emergency_shutdown(RawEvent::Refund(ALICE, 1000, vec![(BTC, 100), (DOT, 200)]));
+		assert!(System::events().iter().any(|record| record.event == refund_event));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 1100);
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 1200);
+
+		// redemption rates are fixed, the later redemption gets the same basket
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(Origin::signed(BOB), 3000));
+		assert_eq!(Currencies::free_balance(BTC, &BOB), 900);
+		assert_eq!(Currencies::free_balance(DOT, &BOB), 800);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(CDPTreasuryModule::total_collaterals(DOT), 0);
+	});
+}
+
+#[test]
+fn set_unclaimed_collateral_receiver_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			EmergencyShutdownModule::set_unclaimed_collateral_receiver(Origin::signed(5), Some(3)),
+			BadOrigin,
+		);
+		assert_ok!(EmergencyShutdownModule::set_unclaimed_collateral_receiver(
+			Origin::signed(1),
+			Some(3)
+		));
+
+		let update_receiver_event =
+			TestEvent::emergency_shutdown(RawEvent::UnclaimedCollateralReceiverUpdated(Some(3)));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == update_receiver_event));
+		assert_eq!(EmergencyShutdownModule::unclaimed_collateral_receiver(), Some(3));
+	});
+}
+
+#[test]
+fn sweep_unclaimed_collaterals_after_claims_deadline() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::deposit_backed_debit_to(&ALICE, 1000));
+		assert_ok!(CDPTreasuryModule::deposit_backed_debit_to(&BOB, 3000));
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &BOB, 400));
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(DOT, &BOB, 800));
+		assert_ok!(EmergencyShutdownModule::set_unclaimed_collateral_receiver(
+			Origin::ROOT,
+			Some(3)
+		));
+
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::start_settlement(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(Origin::ROOT));
+		assert_eq!(EmergencyShutdownModule::claims_deadline(), Some(11));
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(Origin::signed(ALICE), 1000));
+
+		EmergencyShutdownModule::on_initialize(11);
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::ClaimsOpen);
+
+		EmergencyShutdownModule::on_initialize(12);
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::Finalized);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(CDPTreasuryModule::total_collaterals(DOT), 0);
+		assert_eq!(Currencies::free_balance(BTC, &3), 300);
+		assert_eq!(Currencies::free_balance(DOT, &3), 600);
+
+		let sweep_event = TestEvent::emergency_shutdown(RawEvent::SweepUnclaimedCollaterals(
+			Some(3),
+			vec![(BTC, 300), (DOT, 600)],
+		));
+		assert!(System::events().iter().any(|record| record.event == sweep_event));
+		let finalize_event = TestEvent::emergency_shutdown(RawEvent::Finalize(12));
+		assert!(System::events().iter().any(|record| record.event == finalize_event));
+
+		assert_noop!(
+			EmergencyShutdownModule::refund_collaterals(Origin::signed(BOB), 3000),
+			Error::<Runtime>::CanNotRefund,
+		);
+	});
+}
+
+#[test]
+fn burn_unclaimed_collaterals_without_receiver() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::deposit_backed_debit_to(&ALICE, 1000));
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &BOB, 400));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::start_settlement(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(Origin::ROOT));
+		assert_eq!(Currencies::total_issuance(BTC), 2000);
+
+		EmergencyShutdownModule::on_initialize(12);
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::Finalized);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(Currencies::total_issuance(BTC), 1600);
+
+		let sweep_event = TestEvent::emergency_shutdown(RawEvent::SweepUnclaimedCollaterals(None, vec![(BTC, 400)]));
+		assert!(System::events().iter().any(|record| record.event == sweep_event));
+	});
+}
+
+#[test]
+fn set_shutdown_triggers_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let triggers = ShutdownTriggers {
+			max_price_unavailable_blocks: Some(10),
+			min_system_collateral_ratio: Some(Ratio::saturating_from_rational(11, 10)),
+			below_collateral_ratio_blocks: 10,
+			max_bad_debt: Some(1000),
+		};
+		assert_noop!(
+			EmergencyShutdownModule::set_shutdown_triggers(Origin::signed(5), triggers.clone()),
+			BadOrigin,
+		);
+		assert_ok!(EmergencyShutdownModule::set_shutdown_triggers(
+			Origin::signed(1),
+			triggers.clone()
+		));
+
+		let update_triggers_event = TestEvent::emergency_shutdown(RawEvent::ShutdownTriggersUpdated(triggers.clone()));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == update_triggers_event));
+		assert_eq!(EmergencyShutdownModule::triggers(), triggers);
+	});
+}
+
+#[test]
+fn auto_shutdown_when_price_unavailable() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(EmergencyShutdownModule::set_shutdown_triggers(
+			Origin::ROOT,
+			ShutdownTriggers {
+				max_price_unavailable_blocks: Some(10),
+				..Default::default()
+			}
+		));
+		PRICE.with(|v| *v.borrow_mut() = None);
+		EmergencyShutdownModule::on_initialize(1);
+		assert_eq!(EmergencyShutdownModule::price_unavailable_since(), Some(1));
+		EmergencyShutdownModule::on_initialize(10);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), false);
+
+		// recovered price resets the counter
+		PRICE.with(|v| *v.borrow_mut() = Some(Price::one()));
+		EmergencyShutdownModule::on_initialize(11);
+		assert_eq!(EmergencyShutdownModule::price_unavailable_since(), None);
+
+		PRICE.with(|v| *v.borrow_mut() = None);
+		EmergencyShutdownModule::on_initialize(12);
+		EmergencyShutdownModule::on_initialize(21);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), false);
+		System::set_block_number(22);
+		EmergencyShutdownModule::on_initialize(22);
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::Frozen);
+		assert_eq!(CDPTreasuryModule::is_shutdown(), true);
+
+		let auto_shutdown_event =
+			TestEvent::emergency_shutdown(RawEvent::AutoShutdown(22, ShutdownTrigger::PriceUnavailable));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == auto_shutdown_event));
+	});
+}
+
+#[test]
+fn auto_shutdown_when_below_system_collateral_ratio() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		SYSTEM_COLLATERAL_RATIO.with(|v| *v.borrow_mut() = Some(Ratio::saturating_from_rational(200, 80)));
+		assert_ok!(EmergencyShutdownModule::set_shutdown_triggers(
+			Origin::ROOT,
+			ShutdownTriggers {
+				min_system_collateral_ratio: Some(Ratio::saturating_from_rational(250, 100)),
+				below_collateral_ratio_blocks: 10,
+				..Default::default()
+			}
+		));
+		EmergencyShutdownModule::on_initialize(1);
+		assert_eq!(EmergencyShutdownModule::below_collateral_ratio_since(), None);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), false);
+
+		SYSTEM_COLLATERAL_RATIO.with(|v| *v.borrow_mut() = Some(Ratio::saturating_from_rational(100, 80)));
+		EmergencyShutdownModule::on_initialize(2);
+		assert_eq!(EmergencyShutdownModule::below_collateral_ratio_since(), Some(2));
+		EmergencyShutdownModule::on_initialize(11);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), false);
+
+		// recovered ratio resets the counter
+		SYSTEM_COLLATERAL_RATIO.with(|v| *v.borrow_mut() = None);
+		EmergencyShutdownModule::on_initialize(12);
+		assert_eq!(EmergencyShutdownModule::below_collateral_ratio_since(), None);
+
+		SYSTEM_COLLATERAL_RATIO.with(|v| *v.borrow_mut() = Some(Ratio::saturating_from_rational(100, 80)));
+		EmergencyShutdownModule::on_initialize(13);
+		EmergencyShutdownModule::on_initialize(22);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), false);
+		System::set_block_number(23);
+		EmergencyShutdownModule::on_initialize(23);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), true);
+
+		let auto_shutdown_event = TestEvent::emergency_shutdown(RawEvent::AutoShutdown(
+			23,
+			ShutdownTrigger::BelowSystemCollateralRatio(Ratio::saturating_from_rational(100, 80)),
+		));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == auto_shutdown_event));
+	});
+}
+
+#[test]
+fn auto_shutdown_when_exceed_bad_debt_cap() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(EmergencyShutdownModule::set_shutdown_triggers(
+			Origin::ROOT,
+			ShutdownTriggers {
+				max_bad_debt: Some(100),
+				..Default::default()
+			}
+		));
+		assert_ok!(CDPTreasuryModule::on_system_debit(100));
+		EmergencyShutdownModule::on_initialize(1);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), false);
+
+		// the bad debt is net of the surplus
+		assert_ok!(CDPTreasuryModule::on_system_surplus(50));
+		assert_ok!(CDPTreasuryModule::on_system_debit(50));
+		EmergencyShutdownModule::on_initialize(1);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), false);
+
+		assert_ok!(CDPTreasuryModule::on_system_debit(1));
+		EmergencyShutdownModule::on_initialize(1);
+		assert_eq!(EmergencyShutdownModule::is_shutdown(), true);
+
+		let auto_shutdown_event =
+			TestEvent::emergency_shutdown(RawEvent::AutoShutdown(1, ShutdownTrigger::ExceedBadDebtCap(101)));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == auto_shutdown_event));
+	});
+}
+
+#[test]
+fn solvency_report_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_ok!(LoansModule::adjust_position(&BOB, DOT, 100, 30));
+		assert_ok!(CDPTreasuryModule::transfer_collateral_from(BTC, &BOB, 20));
+		PRICE.with(|v| *v.borrow_mut() = Some(Price::saturating_from_rational(1, 4)));
+
+		assert_eq!(
+			EmergencyShutdownModule::solvency_report(),
+			Some(SolvencyReport {
+				total_collateral_value: 55,
+				total_debit_value: 80,
+				bad_debt: 0,
+				total_stable_issuance: 80,
+				backing_per_stable: Some(Ratio::saturating_from_rational(55, 80)),
+				collaterals: vec![
+					CollateralSolvency {
+						currency_id: BTC,
+						collateral_amount: 120,
+						collateral_value: 30,
+						debit_value: 50,
+						backing_value: 30,
+						shortfall: 25,
+					},
+					CollateralSolvency {
+						currency_id: DOT,
+						collateral_amount: 100,
+						collateral_value: 25,
+						debit_value: 30,
+						backing_value: 25,
+						shortfall: 5,
+					},
+				],
+			})
+		);
+
+		PRICE.with(|v| *v.borrow_mut() = None);
+		assert_eq!(EmergencyShutdownModule::solvency_report(), None);
+	});
+}
+
+#[test]
+fn notify_every_shutdown_phase_change() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::cancel_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::start_settlement(Origin::ROOT));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(Origin::ROOT));
+		EmergencyShutdownModule::on_initialize(100);
+
+		assert_eq!(
+			SHUTDOWN_PHASES.with(|v| v.borrow().clone()),
+			vec![
+				ShutdownPhase::Frozen,
+				ShutdownPhase::Operational,
+				ShutdownPhase::Frozen,
+				ShutdownPhase::Settling,
+				ShutdownPhase::ClaimsOpen,
+				ShutdownPhase::Finalized,
+			]
+		);
+	});
+}
+
+#[test]
+fn migrate_shutdown_flags_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		EmergencyShutdownModule::on_runtime_upgrade();
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::Operational);
+
+		IsShutdown::put(true);
+		EmergencyShutdownModule::on_runtime_upgrade();
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::Settling);
+		assert_eq!(IsShutdown::exists(), false);
+
+		IsShutdown::put(true);
+		CanRefund::put(true);
+		EmergencyShutdownModule::on_runtime_upgrade();
+		assert_eq!(EmergencyShutdownModule::phase(), ShutdownPhase::ClaimsOpen);
+		assert_eq!(EmergencyShutdownModule::redemption_rates(BTC).is_some(), true);
+		assert_eq!(EmergencyShutdownModule::claims_deadline().is_some(), true);
+		assert_eq!(CanRefund::exists(), false);
+	});
+}
@@ -1,11 +1,18 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
-use frame_support::decl_module;
+use frame_support::{decl_error, decl_event, decl_module, decl_storage, ensure, traits::Get};
 use frame_system::{self as system, ensure_signed};
-use primitives::{Balance, EraIndex};
-use sp_runtime::RuntimeDebug;
-use support::HomaProtocol;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId, EraIndex};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	FixedPointNumber, RuntimeDebug,
+};
+use support::{HomaProtocol, Rate};
+
+mod mock;
+mod tests;
 
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
 pub enum RedeemStrategy {
@@ -14,12 +21,62 @@ pub enum RedeemStrategy {
 	WaitForUnbonding,
 }
 
+/// An offer to sell the claim on unbonding at a specific era for staking currency at a discount.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct ExitOffer {
+	/// The amount of claimed unbonding for sale
+	pub amount: Balance,
+	/// The discount off the claim amount that buyer pays
+	pub discount: Rate,
+}
+
 pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
 	type Homa: HomaProtocol<Self::AccountId, Balance, EraIndex>;
+	type StakingCurrencyId: Get<CurrencyId>;
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		<T as system::Trait>::AccountId,
+		Balance = Balance,
+	{
+		/// Offer the claim on unbonding for early exit (seller, era, amount, discount)
+		OfferEarlyExit(AccountId, EraIndex, Balance, Rate),
+		/// Cancel the early exit offer (seller, era)
+		CancelEarlyExit(AccountId, EraIndex),
+		/// Buy the claim on unbonding of an early exit offer (buyer, seller, era, claim_amount, payment)
+		TakeEarlyExit(AccountId, AccountId, EraIndex, Balance, Balance),
+	}
+);
+
+decl_error! {
+	/// Error for homa module.
+	pub enum Error for Module<T: Trait> {
+		InvalidDiscount,
+		ClaimedUnbondingNotEnough,
+		ExitOfferNotExisted,
+		ExceedExitOfferAmount,
+		StakingCurrencyNotEnough,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Homa {
+		/// The early exit offers of claimed unbonding, keyed by seller and unbonded era
+		pub ExitOffers get(fn exit_offers): double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) EraIndex => Option<ExitOffer>;
+	}
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+		fn deposit_event() = default;
+
+		const StakingCurrencyId: CurrencyId = T::StakingCurrencyId::get();
+
 		#[weight = 10_000]
 		pub fn mint(origin, #[compact] amount: Balance) {
 			let who = ensure_signed(origin)?;
@@ -47,6 +104,64 @@ decl_module! {
 			let who = ensure_signed(origin)?;
 			T::Homa::withdraw_redemption(&who)?;
 		}
+
+		/// Offer the claim on unbonding at `era` for sale, buyer pays `amount * (1 - discount)` staking currency.
+		/// Replace the existing offer of the same era.
+		#[weight = 10_000]
+		pub fn offer_early_exit(origin, era: EraIndex, #[compact] amount: Balance, discount: Rate) {
+			let who = ensure_signed(origin)?;
+			ensure!(discount <= Rate::one(), Error::<T>::InvalidDiscount);
+			ensure!(
+				T::Homa::claimed_unbonding(&who, era) >= amount,
+				Error::<T>::ClaimedUnbondingNotEnough,
+			);
+
+			if amount.is_zero() {
+				<ExitOffers<T>>::remove(&who, era);
+			} else {
+				<ExitOffers<T>>::insert(&who, era, ExitOffer { amount, discount });
+			}
+			Self::deposit_event(RawEvent::OfferEarlyExit(who, era, amount, discount));
+		}
+
+		#[weight = 10_000]
+		pub fn cancel_early_exit(origin, era: EraIndex) {
+			let who = ensure_signed(origin)?;
+			ensure!(<ExitOffers<T>>::contains_key(&who, era), Error::<T>::ExitOfferNotExisted);
+			<ExitOffers<T>>::remove(&who, era);
+			Self::deposit_event(RawEvent::CancelEarlyExit(who, era));
+		}
+
+		/// Buy `amount` claim on unbonding at `era` from the early exit offer of `seller`.
+		#[weight = 10_000]
+		pub fn take_early_exit(origin, seller: T::AccountId, era: EraIndex, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			let mut offer = Self::exit_offers(&seller, era).ok_or(Error::<T>::ExitOfferNotExisted)?;
+			ensure!(amount <= offer.amount, Error::<T>::ExceedExitOfferAmount);
+
+			ensure!(
+				T::Homa::claimed_unbonding(&seller, era) >= amount,
+				Error::<T>::ClaimedUnbondingNotEnough,
+			);
+
+			let payment = Rate::one().saturating_sub(offer.discount).saturating_mul_int(amount);
+			let staking_currency_id = T::StakingCurrencyId::get();
+			T::Currency::ensure_can_withdraw(staking_currency_id, &who, payment)
+				.map_err(|_| Error::<T>::StakingCurrencyNotEnough)?;
+
+			// pay before moving the claim, the claim is checked above so the transfer of it never fails
+			T::Currency::transfer(staking_currency_id, &who, &seller, payment)?;
+			T::Homa::transfer_claimed_unbonding(&seller, &who, era, amount)
+				.map_err(|_| Error::<T>::ClaimedUnbondingNotEnough)?;
+
+			offer.amount -= amount;
+			if offer.amount.is_zero() {
+				<ExitOffers<T>>::remove(&seller, era);
+			} else {
+				<ExitOffers<T>>::insert(&seller, era, offer);
+			}
+			Self::deposit_event(RawEvent::TakeEarlyExit(who, seller, era, amount, payment));
+		}
 	}
 }
 
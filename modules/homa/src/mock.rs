@@ -0,0 +1,167 @@
+//! Mocks for homa module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use primitives::Amount;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError, DispatchResult, Perbill};
+use std::{cell::RefCell, collections::BTreeMap};
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 0;
+pub const BOB: AccountId = 1;
+pub const DOT: CurrencyId = CurrencyId::DOT;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod homa {
+	pub use super::super::*;
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		system<T>,
+		homa<T>,
+		orml_tokens<T>,
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+}
+pub type System = system::Module<Runtime>;
+
+impl orml_tokens::Trait for Runtime {
+	type Event = TestEvent;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type DustRemoval = ();
+	type OnReceived = ();
+}
+pub type TokensModule = orml_tokens::Module<Runtime>;
+
+thread_local! {
+	pub static CLAIMED_UNBONDING: RefCell<BTreeMap<(AccountId, EraIndex), Balance>> = RefCell::new(BTreeMap::new());
+}
+
+pub struct MockHoma;
+impl MockHoma {
+	pub fn set_claimed_unbonding(who: AccountId, era: EraIndex, amount: Balance) {
+		CLAIMED_UNBONDING.with(|v| v.borrow_mut().insert((who, era), amount));
+	}
+}
+impl HomaProtocol<AccountId, Balance, EraIndex> for MockHoma {
+	type Balance = Balance;
+
+	fn mint(_who: &AccountId, _amount: Balance) -> Result<Balance, DispatchError> {
+		Ok(Zero::zero())
+	}
+
+	fn redeem_by_unbond(_who: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn redeem_by_free_unbonded(_who: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn redeem_by_claim_unbonding(_who: &AccountId, _amount: Balance, _target_era: EraIndex) -> DispatchResult {
+		Ok(())
+	}
+
+	fn withdraw_redemption(_who: &AccountId) -> Result<Balance, DispatchError> {
+		Ok(Zero::zero())
+	}
+
+	fn claimed_unbonding(who: &AccountId, era: EraIndex) -> Balance {
+		CLAIMED_UNBONDING.with(|v| v.borrow().get(&(*who, era)).copied().unwrap_or_default())
+	}
+
+	fn transfer_claimed_unbonding(from: &AccountId, to: &AccountId, era: EraIndex, amount: Balance) -> DispatchResult {
+		let from_claimed = Self::claimed_unbonding(from, era)
+			.checked_sub(amount)
+			.ok_or("claimed unbonding not enough")?;
+		let to_claimed = Self::claimed_unbonding(to, era);
+		Self::set_claimed_unbonding(*from, era, from_claimed);
+		Self::set_claimed_unbonding(*to, era, to_claimed + amount);
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const StakingCurrencyId: CurrencyId = DOT;
+}
+
+impl Trait for Runtime {
+	type Event = TestEvent;
+	type Currency = TokensModule;
+	type Homa = MockHoma;
+	type StakingCurrencyId = StakingCurrencyId;
+}
+pub type HomaModule = Module<Runtime>;
+
+pub struct ExtBuilder {
+	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			endowed_accounts: vec![(ALICE, DOT, 1000), (BOB, DOT, 1000)],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		CLAIMED_UNBONDING.with(|v| v.borrow_mut().clear());
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			endowed_accounts: self.endowed_accounts,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		t.into()
+	}
+}
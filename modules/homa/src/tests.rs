@@ -0,0 +1,132 @@
+//! Unit tests for homa module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{ExtBuilder, HomaModule, MockHoma, Origin, Runtime, System, TestEvent, TokensModule, ALICE, BOB, DOT};
+
+#[test]
+fn offer_early_exit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		MockHoma::set_claimed_unbonding(ALICE, 3, 500);
+
+		assert_noop!(
+			HomaModule::offer_early_exit(Origin::signed(ALICE), 3, 500, Rate::saturating_from_rational(11, 10)),
+			Error::<Runtime>::InvalidDiscount,
+		);
+		assert_noop!(
+			HomaModule::offer_early_exit(Origin::signed(ALICE), 3, 501, Rate::saturating_from_rational(1, 10)),
+			Error::<Runtime>::ClaimedUnbondingNotEnough,
+		);
+		assert_ok!(HomaModule::offer_early_exit(
+			Origin::signed(ALICE),
+			3,
+			400,
+			Rate::saturating_from_rational(1, 10)
+		));
+		assert_eq!(
+			HomaModule::exit_offers(ALICE, 3),
+			Some(ExitOffer {
+				amount: 400,
+				discount: Rate::saturating_from_rational(1, 10)
+			})
+		);
+
+		let offer_event = TestEvent::homa(RawEvent::OfferEarlyExit(
+			ALICE,
+			3,
+			400,
+			Rate::saturating_from_rational(1, 10),
+		));
+		assert!(System::events().iter().any(|record| record.event == offer_event));
+	});
+}
+
+#[test]
+fn cancel_early_exit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		MockHoma::set_claimed_unbonding(ALICE, 3, 500);
+		assert_noop!(
+			HomaModule::cancel_early_exit(Origin::signed(ALICE), 3),
+			Error::<Runtime>::ExitOfferNotExisted,
+		);
+		assert_ok!(HomaModule::offer_early_exit(
+			Origin::signed(ALICE),
+			3,
+			500,
+			Rate::saturating_from_rational(1, 10)
+		));
+		assert_ok!(HomaModule::cancel_early_exit(Origin::signed(ALICE), 3));
+		assert_eq!(HomaModule::exit_offers(ALICE, 3), None);
+	});
+}
+
+#[test]
+fn take_early_exit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		MockHoma::set_claimed_unbonding(ALICE, 3, 500);
+		assert_noop!(
+			HomaModule::take_early_exit(Origin::signed(BOB), ALICE, 3, 100),
+			Error::<Runtime>::ExitOfferNotExisted,
+		);
+		assert_ok!(HomaModule::offer_early_exit(
+			Origin::signed(ALICE),
+			3,
+			500,
+			Rate::saturating_from_rational(1, 10)
+		));
+		assert_noop!(
+			HomaModule::take_early_exit(Origin::signed(BOB), ALICE, 3, 501),
+			Error::<Runtime>::ExceedExitOfferAmount,
+		);
+
+		assert_ok!(HomaModule::take_early_exit(Origin::signed(BOB), ALICE, 3, 200));
+		assert_eq!(TokensModule::free_balance(DOT, &ALICE), 1180);
+		assert_eq!(TokensModule::free_balance(DOT, &BOB), 820);
+		assert_eq!(MockHoma::claimed_unbonding(&ALICE, 3), 300);
+		assert_eq!(MockHoma::claimed_unbonding(&BOB, 3), 200);
+		assert_eq!(HomaModule::exit_offers(ALICE, 3).map(|offer| offer.amount), Some(300));
+
+		let take_event = TestEvent::homa(RawEvent::TakeEarlyExit(BOB, ALICE, 3, 200, 180));
+		assert!(System::events().iter().any(|record| record.event == take_event));
+
+		assert_ok!(HomaModule::take_early_exit(Origin::signed(BOB), ALICE, 3, 300));
+		assert_eq!(HomaModule::exit_offers(ALICE, 3), None);
+		assert_eq!(MockHoma::claimed_unbonding(&BOB, 3), 500);
+	});
+}
+
+#[test]
+fn take_early_exit_fails_when_claim_or_payment_not_enough() {
+	ExtBuilder::default().build().execute_with(|| {
+		MockHoma::set_claimed_unbonding(ALICE, 3, 500);
+		assert_ok!(HomaModule::offer_early_exit(
+			Origin::signed(ALICE),
+			3,
+			500,
+			Rate::saturating_from_rational(1, 10)
+		));
+
+		// the seller has withdrawn or transferred the claim after offering
+		MockHoma::set_claimed_unbonding(ALICE, 3, 100);
+		assert_noop!(
+			HomaModule::take_early_exit(Origin::signed(BOB), ALICE, 3, 200),
+			Error::<Runtime>::ClaimedUnbondingNotEnough,
+		);
+
+		MockHoma::set_claimed_unbonding(ALICE, 3, 5000);
+		assert_ok!(HomaModule::offer_early_exit(
+			Origin::signed(ALICE),
+			3,
+			5000,
+			Rate::saturating_from_rational(1, 10)
+		));
+		assert_noop!(
+			HomaModule::take_early_exit(Origin::signed(BOB), ALICE, 3, 2000),
+			Error::<Runtime>::StakingCurrencyNotEnough,
+		);
+	});
+}
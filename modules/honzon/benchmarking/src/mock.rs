@@ -113,6 +113,11 @@ impl loans::Trait for Runtime {
 	type DebitBalance = DebitBalance;
 	type DebitAmount = DebitAmount;
 	type CDPTreasury = CDPTreasuryModule;
+	type EmergencyShutdown = ();
+	type OnUpdateLoan = ();
+	type OnUpdateCollateral = ();
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type PositionDeposit = ();
 }
 
 pub struct MockAuctionManager;
@@ -171,6 +176,7 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = ();
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = ();
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
@@ -181,6 +187,7 @@ parameter_types! {
 	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(10, 100);
 	pub const MinimumDebitValue: Balance = 2;
 	pub MaxSlippageSwapWithDEX: Ratio = Ratio::saturating_from_rational(50, 100);
+	pub MinimumPriceConfidence: Ratio = Ratio::saturating_from_rational(90, 100);
 	pub const UnsignedPriority: u64 = 1 << 20;
 }
 
@@ -195,9 +202,13 @@ impl cdp_engine::Trait for Runtime {
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type LiquidationParamsUpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type EmergencyVetoOrigin = EnsureSignedBy<One, AccountId>;
 	type MaxSlippageSwapWithDEX = MaxSlippageSwapWithDEX;
+	type MinimumPriceConfidence = MinimumPriceConfidence;
 	type DEX = ();
 	type UnsignedPriority = UnsignedPriority;
+	type OnKeeperAction = ();
 }
 pub type CDPEngineModule = cdp_engine::Module<Runtime>;
 
@@ -243,6 +254,13 @@ impl orml_oracle::Trait for Runtime {
 }
 pub type ModuleOracle = orml_oracle::Module<Runtime>;
 
+pub struct MockDispersion;
+impl orml_traits::DataProvider<CurrencyId, Ratio> for MockDispersion {
+	fn get(_currency_id: &CurrencyId) -> Option<Ratio> {
+		None
+	}
+}
+
 pub struct MockLiquidStakingExchangeProvider;
 impl ExchangeRateProvider for MockLiquidStakingExchangeProvider {
 	fn get_exchange_rate() -> ExchangeRate {
@@ -254,17 +272,26 @@ parameter_types! {
 	pub const GetStakingCurrencyId: CurrencyId = DOT;
 	pub const GetLiquidCurrencyId: CurrencyId = LDOT;
 	pub StableCurrencyFixedPrice: Price = Price::saturating_from_integer(1);
+	pub const MaxEmergencyPriceDuration: BlockNumber = 100;
+	pub const HeartbeatThreshold: BlockNumber = 100;
+	pub const PriceHistoryDepth: u32 = 10;
 }
 
 impl prices::Trait for Runtime {
 	type Event = ();
 	type Source = orml_oracle::Module<Runtime>;
+	type ExternalSource = prices::NoExternalSource;
+	type Dispersion = MockDispersion;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type StableCurrencyFixedPrice = StableCurrencyFixedPrice;
-	type GetStakingCurrencyId = GetStakingCurrencyId;
-	type GetLiquidCurrencyId = GetLiquidCurrencyId;
 	type LockOrigin = EnsureSignedBy<One, AccountId>;
-	type LiquidStakingExchangeRateProvider = MockLiquidStakingExchangeProvider;
+	type DerivativeAssets =
+		prices::DerivativeAssetAdapter<GetLiquidCurrencyId, GetStakingCurrencyId, MockLiquidStakingExchangeProvider>;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type MaxEmergencyPriceDuration = MaxEmergencyPriceDuration;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type HeartbeatThreshold = HeartbeatThreshold;
+	type PriceHistoryDepth = PriceHistoryDepth;
 }
 
 impl crate::Trait for Runtime {}
@@ -0,0 +1,124 @@
+//! Runtime API definition for honzon module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sp_runtime::traits::{MaybeDisplay, MaybeFromStr};
+use sp_std::prelude::*;
+use support::{Price, Ratio};
+
+#[derive(Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PositionInfo<CurrencyId, Balance> {
+	/// The collateral type of the position
+	pub currency_id: CurrencyId,
+	/// The collateral amount
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub collateral: Balance,
+	/// The debit amount
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub debit: Balance,
+	/// The debt in stable currency, including the accrued stability fee
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub debit_value: Balance,
+	/// The collateral ratio at current price, `None` if there's no debit or the price is unavailable
+	pub collateral_ratio: Option<Ratio>,
+	/// The liquidation ratio of the collateral type
+	pub liquidation_ratio: Ratio,
+	/// The required collateral ratio of the collateral type, positions below it are at risk
+	pub required_collateral_ratio: Option<Ratio>,
+	/// The collateral price at which the position will be liquidated
+	pub liquidation_price: Option<Price>,
+}
+
+#[derive(Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PositionsPage<AccountId, Position> {
+	/// The positions with their owners
+	pub positions: Vec<(AccountId, Position)>,
+	/// The start of next page, `None` if there's no more positions
+	pub next: Option<AccountId>,
+}
+
+#[derive(Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct CollateralStatistics<CurrencyId, Balance> {
+	/// The collateral type
+	pub currency_id: CurrencyId,
+	/// The total collateral amount locked in positions
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub total_collateral: Balance,
+	/// The value of total collateral in stable currency, `None` if the price is unavailable
+	pub total_locked_value: Option<Balance>,
+	/// The total debt in stable currency, including the accrued stability fee
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub total_debit_value: Balance,
+	/// The cap of total debt in stable currency
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub maximum_total_debit_value: Balance,
+	/// The ratio of total debt to the cap, `None` if the cap is zero
+	pub utilization: Option<Ratio>,
+	/// The ratio of total locked value to total debt, `None` if there's no debt or the price is unavailable
+	pub average_collateral_ratio: Option<Ratio>,
+	/// The count of positions with debit
+	pub positions: u32,
+	/// The count of positions below the required collateral ratio
+	pub positions_at_risk: u32,
+}
+
+#[cfg(feature = "std")]
+fn serialize_as_string<S: Serializer, T: std::fmt::Display>(t: &T, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&t.to_string())
+}
+
+#[cfg(feature = "std")]
+fn deserialize_from_string<'de, D: Deserializer<'de>, T: std::str::FromStr>(deserializer: D) -> Result<T, D::Error> {
+	let s = String::deserialize(deserializer)?;
+	s.parse::<T>()
+		.map_err(|_| serde::de::Error::custom("Parse from string failed"))
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait HonzonApi<AccountId, CurrencyId, Balance> where
+		AccountId: Codec,
+		CurrencyId: Codec,
+		Balance: Codec + MaybeDisplay + MaybeFromStr,
+	{
+		fn get_positions(who: AccountId) -> Vec<PositionInfo<CurrencyId, Balance>>;
+
+		fn get_positions_page(
+			currency_id: CurrencyId,
+			start: Option<AccountId>,
+			limit: u32,
+		) -> PositionsPage<AccountId, PositionInfo<CurrencyId, Balance>>;
+
+		fn get_collateral_statistics() -> Vec<CollateralStatistics<CurrencyId, Balance>>;
+
+		fn check_invariants() -> Result<(), Vec<u8>>;
+	}
+}
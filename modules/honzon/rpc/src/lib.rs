@@ -0,0 +1,373 @@
+//! RPC interface for the honzon module.
+
+use codec::{Codec, Decode, Encode};
+use futures::{future, StreamExt, TryStreamExt};
+use jsonrpc_core::{
+	futures::{Future, Sink},
+	Error as RpcError, ErrorCode, Result,
+};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use log::warn;
+use module_auction_manager::AuctionType;
+use module_honzon_rpc_runtime_api::{CollateralStatistics, PositionInfo, PositionsPage};
+use sc_client_api::BlockchainEvents;
+use sc_rpc_api::{DenyUnsafe, Subscriptions};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::offchain::OffchainStorage;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay, MaybeFromStr},
+};
+use std::{collections::HashMap, sync::Arc};
+use utilities::OffchainWorkerStatus;
+
+pub use self::gen_client::Client as HonzonClient;
+pub use module_honzon_rpc_runtime_api::HonzonApi as HonzonRuntimeApi;
+
+/// The max number of accounts watched by a risk alert subscription
+pub const MAX_RISK_ALERT_ACCOUNTS: usize = 100;
+
+/// The risk level of a position
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RiskLevel {
+	/// The collateral ratio is above the required collateral ratio
+	Safe,
+	/// The collateral ratio is below the required collateral ratio
+	Warning,
+	/// The collateral ratio is below the liquidation ratio
+	Liquidation,
+}
+
+impl RiskLevel {
+	/// Evaluate the risk level of `position`.
+	pub fn of<CurrencyId, Balance>(position: &PositionInfo<CurrencyId, Balance>) -> Self {
+		match position.collateral_ratio {
+			Some(ratio) if ratio < position.liquidation_ratio => RiskLevel::Liquidation,
+			Some(ratio)
+				if position
+					.required_collateral_ratio
+					.map_or(false, |required| ratio < required) =>
+			{
+				RiskLevel::Warning
+			}
+			_ => RiskLevel::Safe,
+		}
+	}
+}
+
+/// The notification of a position whose risk level changed
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskAlert<BlockHash, AccountId, PositionInfo> {
+	/// The best block at which the risk level changed
+	pub block_hash: BlockHash,
+	/// The owner of the position
+	pub account: AccountId,
+	/// The new risk level
+	pub level: RiskLevel,
+	/// The position at `block_hash`
+	pub position: PositionInfo,
+}
+
+#[rpc]
+pub trait HonzonApi<BlockHash, AccountId, CurrencyId, ResponseType, StatisticsType> {
+	#[rpc(name = "honzon_getPositions")]
+	fn get_positions(&self, who: AccountId, at: Option<BlockHash>) -> Result<Vec<ResponseType>>;
+
+	#[rpc(name = "honzon_getPositionsPage")]
+	fn get_positions_page(
+		&self,
+		currency_id: CurrencyId,
+		start: Option<AccountId>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<PositionsPage<AccountId, ResponseType>>;
+
+	#[rpc(name = "honzon_getCollateralStatistics")]
+	fn get_collateral_statistics(&self, at: Option<BlockHash>) -> Result<Vec<StatisticsType>>;
+}
+
+#[rpc]
+pub trait HonzonRiskAlertApi<BlockHash, AccountId, ResponseType> {
+	/// RPC metadata
+	type Metadata;
+
+	/// Subscribe to the risk level changes of the positions of `accounts`, evaluated against each new best block.
+	/// At most `MAX_RISK_ALERT_ACCOUNTS` accounts can be watched by a subscription.
+	#[pubsub(subscription = "honzon_riskAlert", subscribe, name = "honzon_subscribeRiskAlerts")]
+	fn subscribe_risk_alerts(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<Vec<RiskAlert<BlockHash, AccountId, ResponseType>>>,
+		accounts: Vec<AccountId>,
+	);
+
+	/// Unsubscribe from the risk alerts.
+	#[pubsub(
+		subscription = "honzon_riskAlert",
+		unsubscribe,
+		name = "honzon_unsubscribeRiskAlerts"
+	)]
+	fn unsubscribe_risk_alerts(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+}
+
+#[rpc]
+pub trait OffchainWorkerStatusApi<BlockNumber, CurrencyId> {
+	/// The status of last cdp engine offchain worker run of this node
+	#[rpc(name = "honzon_cdpEngineWorkerStatus")]
+	fn cdp_engine_worker_status(&self) -> Result<Option<OffchainWorkerStatus<BlockNumber, CurrencyId>>>;
+
+	/// The status of last auction manager offchain worker run of this node
+	#[rpc(name = "honzon_auctionManagerWorkerStatus")]
+	fn auction_manager_worker_status(&self) -> Result<Option<OffchainWorkerStatus<BlockNumber, AuctionType>>>;
+}
+
+/// A struct that implements the [`HonzonApi`].
+pub struct Honzon<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Honzon<C, B> {
+	/// Create new `Honzon` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Honzon {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+pub enum Error {
+	RuntimeError,
+	TooManyAccounts,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::TooManyAccounts => 2,
+		}
+	}
+}
+
+impl<C, Block, AccountId, CurrencyId, Balance>
+	HonzonApi<
+		<Block as BlockT>::Hash,
+		AccountId,
+		CurrencyId,
+		PositionInfo<CurrencyId, Balance>,
+		CollateralStatistics<CurrencyId, Balance>,
+	> for Honzon<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: HonzonRuntimeApi<Block, AccountId, CurrencyId, Balance>,
+	AccountId: Codec,
+	CurrencyId: Codec,
+	Balance: Codec + MaybeDisplay + MaybeFromStr,
+{
+	fn get_positions(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<PositionInfo<CurrencyId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.get_positions(&at, who).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to get positions.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+
+	fn get_positions_page(
+		&self,
+		currency_id: CurrencyId,
+		start: Option<AccountId>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<PositionsPage<AccountId, PositionInfo<CurrencyId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.get_positions_page(&at, currency_id, start, limit)
+			.map_err(|e| RpcError {
+				code: ErrorCode::ServerError(Error::RuntimeError.into()),
+				message: "Unable to get positions page.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})
+	}
+
+	fn get_collateral_statistics(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<CollateralStatistics<CurrencyId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.get_collateral_statistics(&at).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to get collateral statistics.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
+
+/// A struct that implements the [`HonzonRiskAlertApi`].
+pub struct HonzonRiskAlert<C, B> {
+	client: Arc<C>,
+	subscriptions: Subscriptions,
+	deny_unsafe: DenyUnsafe,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> HonzonRiskAlert<C, B> {
+	/// Create new `HonzonRiskAlert` with the given reference to the client and the subscriptions manager.
+	pub fn new(client: Arc<C>, subscriptions: Subscriptions, deny_unsafe: DenyUnsafe) -> Self {
+		HonzonRiskAlert {
+			client,
+			subscriptions,
+			deny_unsafe,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, AccountId, CurrencyId, Balance>
+	HonzonRiskAlertApi<<Block as BlockT>::Hash, AccountId, PositionInfo<CurrencyId, Balance>> for HonzonRiskAlert<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	C::Api: HonzonRuntimeApi<Block, AccountId, CurrencyId, Balance>,
+	AccountId: Codec + Clone + Send + Sync + Serialize + 'static,
+	CurrencyId: Codec + Send + Serialize + 'static,
+	Balance: Codec + MaybeDisplay + MaybeFromStr + Send + 'static,
+{
+	type Metadata = sc_rpc_api::Metadata;
+
+	fn subscribe_risk_alerts(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<Vec<RiskAlert<<Block as BlockT>::Hash, AccountId, PositionInfo<CurrencyId, Balance>>>>,
+		accounts: Vec<AccountId>,
+	) {
+		if let Err(e) = self.deny_unsafe.check_if_safe() {
+			let _ = subscriber.reject(e.into());
+			return;
+		}
+		if accounts.len() > MAX_RISK_ALERT_ACCOUNTS {
+			let _ = subscriber.reject(RpcError {
+				code: ErrorCode::ServerError(Error::TooManyAccounts.into()),
+				message: format!("At most {} accounts can be watched.", MAX_RISK_ALERT_ACCOUNTS),
+				data: None,
+			});
+			return;
+		}
+
+		let client = self.client.clone();
+		// the last notified risk level of positions, keyed by encoded (account, currency_id)
+		let mut levels: HashMap<Vec<u8>, RiskLevel> = HashMap::new();
+
+		let stream = self
+			.client
+			.import_notification_stream()
+			.filter(|notification| future::ready(notification.is_new_best))
+			.filter_map(move |notification| {
+				let api = client.runtime_api();
+				let at = BlockId::hash(notification.hash);
+				let mut alerts = vec![];
+
+				for account in accounts.iter() {
+					let positions = match api.get_positions(&at, account.clone()) {
+						Ok(positions) => positions,
+						Err(e) => {
+							warn!("Unable to get positions at {:?}: {:?}", notification.hash, e);
+							continue;
+						}
+					};
+
+					for position in positions {
+						let level = RiskLevel::of(&position);
+						let key = (account, &position.currency_id).encode();
+						if levels.insert(key, level).unwrap_or(RiskLevel::Safe) != level {
+							alerts.push(RiskAlert {
+								block_hash: notification.hash,
+								account: account.clone(),
+								level,
+								position,
+							});
+						}
+					}
+				}
+
+				future::ready(if alerts.is_empty() { None } else { Some(alerts) })
+			})
+			.map(|alerts| Ok::<_, ()>(Ok(alerts)))
+			.compat();
+
+		self.subscriptions.add(subscriber, |sink| {
+			sink.sink_map_err(|e| warn!("Error sending risk alerts: {:?}", e))
+				.send_all(stream)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_risk_alerts(&self, _metadata: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+}
+
+/// A struct that implements the [`OffchainWorkerStatusApi`].
+pub struct OffchainWorkers<S> {
+	storage: S,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<S: OffchainStorage> OffchainWorkers<S> {
+	/// Create new `OffchainWorkers` with the given offchain storage of the node.
+	pub fn new(storage: S, deny_unsafe: DenyUnsafe) -> Self {
+		OffchainWorkers { storage, deny_unsafe }
+	}
+
+	fn status<T: Decode>(&self, key: &[u8]) -> Result<Option<T>> {
+		self.deny_unsafe.check_if_safe()?;
+		self.storage
+			.get(sp_offchain::STORAGE_PREFIX, key)
+			.map(|raw| {
+				T::decode(&mut &raw[..]).map_err(|e| RpcError {
+					code: ErrorCode::ServerError(Error::RuntimeError.into()),
+					message: "Unable to decode offchain worker status.".into(),
+					data: Some(format!("{:?}", e).into()),
+				})
+			})
+			.transpose()
+	}
+}
+
+impl<S, BlockNumber, CurrencyId> OffchainWorkerStatusApi<BlockNumber, CurrencyId> for OffchainWorkers<S>
+where
+	S: OffchainStorage + 'static,
+	BlockNumber: Codec + Serialize + Send + Sync + 'static,
+	CurrencyId: Codec + Serialize + Send + Sync + 'static,
+{
+	fn cdp_engine_worker_status(&self) -> Result<Option<OffchainWorkerStatus<BlockNumber, CurrencyId>>> {
+		self.status(module_cdp_engine::OFFCHAIN_WORKER_STATUS_KEY)
+	}
+
+	fn auction_manager_worker_status(&self) -> Result<Option<OffchainWorkerStatus<BlockNumber, AuctionType>>> {
+		self.status(module_auction_manager::OFFCHAIN_WORKER_STATUS_KEY)
+	}
+}
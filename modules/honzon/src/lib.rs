@@ -203,4 +203,8 @@ impl<T: Trait> OnEmergencyShutdown for Module<T> {
 	fn on_emergency_shutdown() {
 		<IsShutdown>::put(true);
 	}
+
+	fn on_cancel_shutdown() {
+		<IsShutdown>::put(false);
+	}
 }
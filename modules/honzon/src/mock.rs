@@ -136,6 +136,11 @@ impl loans::Trait for Runtime {
 	type DebitBalance = DebitBalance;
 	type DebitAmount = DebitAmount;
 	type CDPTreasury = CDPTreasuryModule;
+	type EmergencyShutdown = ();
+	type OnUpdateLoan = ();
+	type OnUpdateCollateral = ();
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type PositionDeposit = ();
 }
 pub type LoansModule = loans::Module<Runtime>;
 
@@ -210,6 +215,7 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = ();
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = ();
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
@@ -220,6 +226,7 @@ parameter_types! {
 	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(10, 100);
 	pub const MinimumDebitValue: Balance = 2;
 	pub MaxSlippageSwapWithDEX: Ratio = Ratio::saturating_from_rational(50, 100);
+	pub MinimumPriceConfidence: Ratio = Ratio::saturating_from_rational(90, 100);
 	pub const UnsignedPriority: u64 = 1 << 20;
 }
 
@@ -234,9 +241,13 @@ impl cdp_engine::Trait for Runtime {
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type LiquidationParamsUpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type EmergencyVetoOrigin = EnsureSignedBy<One, AccountId>;
 	type MaxSlippageSwapWithDEX = MaxSlippageSwapWithDEX;
+	type MinimumPriceConfidence = MinimumPriceConfidence;
 	type DEX = ();
 	type UnsignedPriority = UnsignedPriority;
+	type OnKeeperAction = ();
 }
 pub type CDPEngineModule = cdp_engine::Module<Runtime>;
 
@@ -0,0 +1,1292 @@
+//! # Incentives Module
+//!
+//! ## Overview
+//!
+//! Incentives module distributes rewards of native token to the participants of the system.
+//! Governance controls the overall emission by an emission schedule, which emits a total reward amount
+//! every `AccumulatePeriod` blocks that decays by a ratio every decay period (e.g. halving), and distributes
+//! it to all pools by their weights. Both the schedule and the weights are storage, so the tokenomics can be
+//! changed without runtime upgrade. The rewards of a pool are shared by its accounts in proportion to their shares.
+//!
+//! For loans incentive, there is a pool per collateral type, and the share of an account
+//! is its debit amount under this collateral type, which is kept updated by the `OnUpdateLoan` hook of loans.
+//! The accrued rewards can be claimed at any time, and are claimed automatically when the share is reduced.
+//!
+//! For DEX liquidity mining, there is a pool per trading pair, and the share of an account is its liquidity
+//! share of the trading pair, which is kept updated by the `OnUpdateLiquidity` hook of DEX.
+//!
+//! Pools can pay rewards in other currencies at the same time, e.g. a partner token. The extra rewards of a
+//! currency are funded by anyone, accumulated at the amount per period set by governance until the funds run
+//! out, and accounted separately per currency. Claiming the rewards of a pool pays out all currencies.
+//!
+//! The early adopter program weights the participation by duration, the score of an account is the sum of its
+//! debit amount multiplied by the blocks it is outstanding until the program ends, the debit of a loan is tracked
+//! since its first update after the program starts. After the end, the rewards of the program are shared by
+//! scores and released linearly over `VestingPeriod` by a vesting schedule of `Vesting` instead of paid at once, so that a loan opened shortly
+//! before the end earns little and farmed rewards can't be dumped immediately.
+//!
+//! The keeper incentive pool holds stable coin funded from the surplus of CDP treasury (which collects
+//! the liquidation penalties) or transferred by anyone, and rewards the accounts whose transactions
+//! triggered successful liquidations or settlements. Every reward is recorded on-chain and claimable.
+//!
+//! The deposit program is a pool whose share is the stable coin locked by accounts. Unlike the other pools, its
+//! rewards are not minted but paid out of a pot funded with native token by governance, the reward amount per
+//! period is capped by the pot. The stable coin unlocked has to wait for `DepositWithdrawalDelay` blocks before
+//! withdrawal, and earns no rewards in the meantime.
+//!
+//! To bootstrap the demand of collateral auctions when it matters most, the winning bidder and the runner-up
+//! (the last outbid bidder) of a dealt collateral auction are rewarded in proportion to their bids while the
+//! system is under stress, which is when the bad debit of CDP treasury reaches the threshold. The rewards of an
+//! auction are capped and paid out of a budget set by governance, and the bidders who are the owner of the
+//! liquidated CDP or the CDP treasury get nothing.
+//!
+//! After emergency shutdown, the rewards stop accumulating to pools, the accrued rewards can still be claimed.
+//! The accumulation resumes if the shutdown is cancelled.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{EnsureOrigin, Get},
+	weights::{constants::WEIGHT_PER_MICROS, Weight},
+	IterableStorageDoubleMap, IterableStorageMap,
+};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use orml_traits::MultiCurrency;
+use primitives::{Amount, Balance, CurrencyId};
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, UniqueSaturatedInto, Zero},
+	DispatchResult, FixedPointNumber, ModuleId, RuntimeDebug,
+};
+use sp_std::{collections::btree_map::BTreeMap, convert::TryInto, prelude::*};
+use support::{
+	CDPTreasury, MultiCurrencyVesting, OnCollateralAuctionDealt, OnEmergencyShutdown, OnKeeperAction,
+	OnUpdateLiquidity, OnUpdateLoan, Rate, Ratio, ShutdownPhase,
+};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+mod mock;
+mod tests;
+
+const MODULE_ID: ModuleId = ModuleId(*b"aca/inct");
+const DEPOSIT_MODULE_ID: ModuleId = ModuleId(*b"aca/depo");
+const EXTRA_REWARDS_MODULE_ID: ModuleId = ModuleId(*b"aca/exrw");
+
+/// The incentive pools
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum PoolId {
+	/// Rewards for the debit of loans under specific collateral type
+	Loans(CurrencyId),
+	/// Rewards for the liquidity share of DEX under specific trading pair (other currency of the pair)
+	DexIncentive(CurrencyId),
+	/// Rewards for the stable coin deposited, paid from the funded pot
+	Deposit,
+}
+
+/// The accounting of an incentive pool
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct PoolInfo {
+	/// Sum of the shares of all accounts
+	pub total_shares: Balance,
+	/// Total rewards accumulated to the pool, including the virtual rewards
+	/// inflated for the shares added later
+	pub total_rewards: Balance,
+	/// Total rewards withdrawn from the pool, including the virtual rewards
+	pub total_withdrawn_rewards: Balance,
+}
+
+/// The accounting of the rewards of a pool in other currency than the incentive currency
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct ExtraRewardInfo {
+	/// The reward amount accumulated every `AccumulatePeriod`
+	pub reward_per_period: Balance,
+	/// The funds not accumulated to the pool yet
+	pub remaining_funds: Balance,
+	/// Total rewards accumulated to the pool, including the virtual rewards
+	pub total_rewards: Balance,
+	/// Total rewards withdrawn from the pool, including the virtual rewards
+	pub total_withdrawn_rewards: Balance,
+}
+
+/// The participation of the early adopter program
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct ParticipationScore<BlockNumber> {
+	/// The debit amount currently outstanding
+	pub debit: Balance,
+	/// The accumulated score, debit amount multiplied by blocks
+	pub score: Balance,
+	/// The block of the last accumulation
+	pub last_update: BlockNumber,
+}
+
+/// The early adopter program
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct EarlyAdopterProgramInfo<BlockNumber> {
+	/// The block the program ends, scores stop accumulating afterwards
+	pub end: BlockNumber,
+	/// The total rewards shared by scores
+	pub total_rewards: Balance,
+}
+
+/// The emission schedule of incentive rewards
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct EmissionScheduleInfo<BlockNumber> {
+	/// The block the emission starts
+	pub start: BlockNumber,
+	/// The total reward amount per period at the start
+	pub emission_per_period: Balance,
+	/// The blocks between decays, zero means no decay
+	pub decay_period: BlockNumber,
+	/// The ratio the emission is multiplied by at every decay, e.g. 1/2 for halving
+	pub decay_ratio: Ratio,
+}
+
+/// The configuration of collateral auction rewards
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct AuctionRewardConfig {
+	/// The rate of the winning bid paid to the winner
+	pub winner_rate: Rate,
+	/// The rate of the outbid bid paid to the runner-up
+	pub runner_up_rate: Rate,
+	/// The maximum rewards of a single auction
+	pub max_rewards_per_auction: Balance,
+	/// The bad debit of CDP treasury from which the system is under stress
+	pub stress_threshold: Balance,
+}
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The origin which may update incentive parameters. Root can always do this.
+	type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Currency for paying incentive rewards
+	type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+	/// The currency id of incentive rewards
+	type IncentiveCurrencyId: Get<CurrencyId>;
+
+	/// The period to accumulate rewards to the pools
+	type AccumulatePeriod: Get<Self::BlockNumber>;
+
+	/// Stablecoin currency id, the currency of keeper rewards
+	type GetStableCurrencyId: Get<CurrencyId>;
+
+	/// CDP treasury to fund the keeper incentive pool from its surplus
+	type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+	/// The period to release the rewards of early adopter program linearly
+	type VestingPeriod: Get<Self::BlockNumber>;
+
+	/// The account of CDP treasury, which gets no auction rewards
+	type TreasuryAccount: Get<Self::AccountId>;
+
+	/// Vesting to pay out the early adopter rewards and extra rewards over time
+	type Vesting: MultiCurrencyVesting<Self::AccountId, CurrencyId, Balance, Self::BlockNumber>;
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		<T as system::Trait>::BlockNumber,
+		PoolId = PoolId,
+		Balance = Balance,
+		CurrencyId = CurrencyId,
+		Rate = Rate,
+		AuctionRewardConfig = AuctionRewardConfig,
+		EmissionScheduleInfo = EmissionScheduleInfo<<T as system::Trait>::BlockNumber>,
+	{
+		/// Rewards claimed (who, pool_id, reward_amount)
+		ClaimRewards(AccountId, PoolId, Balance),
+		/// The reward rate of keeper updated (new_rate)
+		KeeperRewardRateUpdated(Rate),
+		/// Keeper incentive pool funded from the surplus of CDP treasury (amount)
+		FundKeeperPool(Balance),
+		/// Keeper rewarded for a successful liquidation or settlement (keeper, collateral_type, reward_amount)
+		KeeperRewardAccrued(AccountId, CurrencyId, Balance),
+		/// Keeper rewards claimed (keeper, reward_amount)
+		ClaimKeeperRewards(AccountId, Balance),
+		/// The early adopter program updated (end, total_rewards)
+		EarlyAdopterProgramUpdated(BlockNumber, Balance),
+		/// The rewards of early adopter program start vesting (who, reward_amount)
+		EarlyAdopterRewardsVested(AccountId, Balance),
+		/// The configuration of collateral auction rewards updated (new_config)
+		AuctionRewardConfigUpdated(AuctionRewardConfig),
+		/// The budget of collateral auction rewards updated (new_budget)
+		AuctionRewardBudgetUpdated(Balance),
+		/// Bidder of collateral auction rewarded (bidder, collateral_type, reward_amount)
+		AuctionRewardPaid(AccountId, CurrencyId, Balance),
+		/// The pot of deposit rewards funded (funder, amount)
+		FundDepositPot(AccountId, Balance),
+		/// Stable coin deposited (who, amount)
+		DepositStable(AccountId, Balance),
+		/// Deposit unlocked, can be withdrawn after the block (who, amount, unlock_at)
+		UnlockDeposit(AccountId, Balance, BlockNumber),
+		/// Unlocked deposit withdrawn (who, amount)
+		WithdrawDeposit(AccountId, Balance),
+		/// The delay of deposit withdrawal updated (new_delay)
+		DepositWithdrawalDelayUpdated(BlockNumber),
+		/// The emission schedule updated (new_schedule)
+		EmissionScheduleUpdated(EmissionScheduleInfo),
+		/// The weight of pool in emission updated (pool_id, new_weight)
+		PoolWeightUpdated(PoolId, u32),
+		/// The extra reward amount per period of pool updated (pool_id, currency_id, reward_amount_per_period)
+		ExtraRewardAmountUpdated(PoolId, CurrencyId, Balance),
+		/// The extra rewards of pool funded (funder, pool_id, currency_id, amount)
+		FundExtraRewards(AccountId, PoolId, CurrencyId, Balance),
+		/// Extra rewards claimed (who, pool_id, currency_id, reward_amount)
+		ClaimExtraRewards(AccountId, PoolId, CurrencyId, Balance),
+		/// The vesting period of extra rewards in currency updated (currency_id, new_period)
+		ExtraRewardVestingPeriodUpdated(CurrencyId, BlockNumber),
+	}
+);
+
+decl_error! {
+	/// Error for incentives module.
+	pub enum Error for Module<T: Trait> {
+		/// The end of early adopter program must be in the future
+		InvalidProgramEnd,
+		/// The early adopter program has ended already
+		ProgramEnded,
+		/// The early adopter program has not ended yet
+		ProgramNotEnded,
+		/// No early adopter program
+		NoEarlyAdopterProgram,
+		/// No rewards of early adopter program to claim
+		NoEarlyAdopterRewards,
+		/// The deposit is not enough to unlock
+		DepositNotEnough,
+		/// No unlocked deposit to withdraw
+		NoUnlockedDeposit,
+		/// The unlocked deposit is still in withdrawal delay
+		DepositStillLocked,
+		/// The incentive currency can't be extra rewards
+		InvalidExtraRewardCurrency,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Incentives {
+		/// The accounting info of pools
+		pub Pools get(fn pools): map hasher(twox_64_concat) PoolId => PoolInfo;
+
+		/// The share and withdrawn rewards of account in pool, map from
+		/// Pool -> Owner -> (Share, WithdrawnRewards)
+		pub ShareAndWithdrawnReward get(fn share_and_withdrawn_reward): double_map hasher(twox_64_concat) PoolId, hasher(twox_64_concat) T::AccountId => (Balance, Balance);
+
+		/// The rate of the debit value of liquidated or settled CDP paid to keeper
+		pub KeeperRewardRate get(fn keeper_reward_rate): Rate;
+
+		/// The unclaimed rewards of keepers
+		pub PendingKeeperRewards get(fn pending_keeper_rewards): map hasher(twox_64_concat) T::AccountId => Balance;
+
+		/// Total unclaimed rewards of keepers, reserved in the keeper incentive pool
+		pub TotalPendingKeeperRewards get(fn total_pending_keeper_rewards): Balance;
+
+		/// The early adopter program
+		pub EarlyAdopterProgram get(fn early_adopter_program): Option<EarlyAdopterProgramInfo<T::BlockNumber>>;
+
+		/// The participation of accounts in early adopter program, map from
+		/// Owner -> CollateralType -> ParticipationScore
+		pub EarlyAdopterScores get(fn early_adopter_scores): double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) CurrencyId => ParticipationScore<T::BlockNumber>;
+
+		/// The participation of all accounts in early adopter program
+		pub TotalEarlyAdopterScore get(fn total_early_adopter_score): ParticipationScore<T::BlockNumber>;
+
+		/// The configuration of collateral auction rewards
+		pub AuctionRewards get(fn auction_rewards): AuctionRewardConfig;
+
+		/// The remaining budget of collateral auction rewards
+		pub AuctionRewardBudget get(fn auction_reward_budget): Balance;
+
+		/// The blocks the unlocked deposit has to wait before withdrawal
+		pub DepositWithdrawalDelay get(fn deposit_withdrawal_delay): T::BlockNumber;
+
+		/// The unlocked deposit of accounts waiting for withdrawal, map from
+		/// Owner -> (Amount, UnlockAt)
+		pub DepositUnlocking get(fn deposit_unlocking): map hasher(twox_64_concat) T::AccountId => Option<(Balance, T::BlockNumber)>;
+
+		/// The emission schedule distributed to pools by weights
+		pub EmissionSchedule get(fn emission_schedule): EmissionScheduleInfo<T::BlockNumber>;
+
+		/// The weights of pools in emission, map from
+		/// Pool -> Weight
+		pub PoolWeights get(fn pool_weights): map hasher(twox_64_concat) PoolId => u32;
+
+		/// The rewards of pools in other currencies, map from
+		/// Pool -> Currency -> ExtraRewardInfo
+		pub ExtraRewards get(fn extra_rewards): double_map hasher(twox_64_concat) PoolId, hasher(twox_64_concat) CurrencyId => ExtraRewardInfo;
+
+		/// The withdrawn extra rewards of account in pool, map from
+		/// (Pool, Owner) -> Currency -> WithdrawnRewards
+		pub ExtraWithdrawnRewards get(fn extra_withdrawn_rewards): double_map hasher(twox_64_concat) (PoolId, T::AccountId), hasher(twox_64_concat) CurrencyId => Balance;
+
+		/// The period to release the claimed extra rewards in currency, zero means paid at once
+		pub ExtraRewardVestingPeriods get(fn extra_reward_vesting_periods): map hasher(twox_64_concat) CurrencyId => T::BlockNumber;
+
+		/// Whether the accumulation of rewards is paused by emergency shutdown
+		pub RewardsPaused get(fn rewards_paused): bool;
+
+		/// The reward amounts per period of pools of previous version, migrated into `EmissionSchedule`
+		IncentiveRewardAmount: map hasher(twox_64_concat) PoolId => Balance;
+
+		/// The weights of trading pairs in liquidity mining of previous version, migrated into `PoolWeights`
+		DexIncentiveWeights: map hasher(twox_64_concat) CurrencyId => u32;
+
+		/// The emission schedule of liquidity mining of previous version, migrated into `EmissionSchedule`
+		DexRewardSchedule: Vec<(T::BlockNumber, Balance)>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+		fn deposit_event() = default;
+
+		/// The currency id of incentive rewards
+		const IncentiveCurrencyId: CurrencyId = T::IncentiveCurrencyId::get();
+
+		/// The period to accumulate rewards
+		const AccumulatePeriod: T::BlockNumber = T::AccumulatePeriod::get();
+
+		/// The period to release the rewards of early adopter program
+		const VestingPeriod: T::BlockNumber = T::VestingPeriod::get();
+
+		/// Claim the accrued rewards of caller in the pool, including the extra rewards in all currencies
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `pool_id`: the incentive pool
+		///
+		/// # <weight>
+		/// - Complexity: `O(C)` where C is the number of extra reward currencies of the pool, which is small
+		/// - Db reads: `ShareAndWithdrawnReward`, `Pools`, 1 item in orml_currencies,
+		///		C * (`ExtraRewards`, `ExtraWithdrawnRewards`, 2 items in orml_currencies)
+		/// - Db writes: `ShareAndWithdrawnReward`, `Pools`, 1 item in orml_currencies,
+		///		C * (`ExtraRewards`, `ExtraWithdrawnRewards`, 2 items in orml_currencies)
+		/// -------------------
+		/// Base Weight: 40 µs + 35 µs * C
+		/// # </weight>
+		#[weight = 110 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(11, 11)]
+		pub fn claim_rewards(origin, pool_id: PoolId) {
+			let who = ensure_signed(origin)?;
+			Self::payout_rewards(&who, pool_id);
+		}
+
+		/// Update the reward rate of keeper
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `rate`: the rate of the debit value of liquidated or settled CDP paid to keeper
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `KeeperRewardRate`
+		/// -------------------
+		/// Base Weight: 10 µs
+		/// # </weight>
+		#[weight = 10 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_keeper_reward_rate(origin, rate: Rate) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			KeeperRewardRate::put(rate);
+			Self::deposit_event(RawEvent::KeeperRewardRateUpdated(rate));
+		}
+
+		/// Fund the keeper incentive pool from the surplus of CDP treasury
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `amount`: the amount of stable coin
+		///
+		/// # <weight>
+		/// - Preconditions:
+		/// 	- T::CDPTreasury is module_cdp_treasury
+		/// - Complexity: `O(1)`
+		/// - Db reads: 3 items of module_cdp_treasury and orml_currencies
+		/// - Db writes: 3 items of module_cdp_treasury and orml_currencies
+		/// -------------------
+		/// Base Weight: 35 µs
+		/// # </weight>
+		#[weight = 35 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(3, 3)]
+		pub fn fund_keeper_pool(origin, #[compact] amount: Balance) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			T::CDPTreasury::transfer_surplus_to(&Self::account_id(), amount)?;
+			Self::deposit_event(RawEvent::FundKeeperPool(amount));
+		}
+
+		/// Claim the rewards of caller as keeper
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `PendingKeeperRewards`, `TotalPendingKeeperRewards`, 2 items of orml_currencies
+		/// - Db writes: `PendingKeeperRewards`, `TotalPendingKeeperRewards`, 2 items of orml_currencies
+		/// -------------------
+		/// Base Weight: 35 µs
+		/// # </weight>
+		#[weight = 35 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(4, 4)]
+		pub fn claim_keeper_rewards(origin) {
+			let who = ensure_signed(origin)?;
+			let rewards = Self::pending_keeper_rewards(&who);
+			if !rewards.is_zero() {
+				T::Currency::transfer(T::GetStableCurrencyId::get(), &Self::account_id(), &who, rewards)?;
+				<PendingKeeperRewards<T>>::remove(&who);
+				TotalPendingKeeperRewards::mutate(|total| *total = total.saturating_sub(rewards));
+				Self::deposit_event(RawEvent::ClaimKeeperRewards(who, rewards));
+			}
+		}
+
+		/// Start the early adopter program, or update it before it ends
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `end`: the block the program ends
+		/// - `total_rewards`: the total rewards of the program
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `EarlyAdopterProgram`
+		/// - Db writes: `EarlyAdopterProgram`
+		/// -------------------
+		/// Base Weight: 15 µs
+		/// # </weight>
+		#[weight = 15 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn set_early_adopter_program(origin, end: T::BlockNumber, #[compact] total_rewards: Balance) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			let now = <system::Module<T>>::block_number();
+			ensure!(end > now, Error::<T>::InvalidProgramEnd);
+			if let Some(program) = Self::early_adopter_program() {
+				ensure!(program.end > now, Error::<T>::ProgramEnded);
+			}
+
+			<EarlyAdopterProgram<T>>::put(EarlyAdopterProgramInfo { end, total_rewards });
+			Self::deposit_event(RawEvent::EarlyAdopterProgramUpdated(end, total_rewards));
+		}
+
+		/// Claim the rewards of caller in early adopter program after it ends,
+		/// the rewards are released linearly over `VestingPeriod` from the end of program by `Vesting`.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// # <weight>
+		/// - Preconditions:
+		/// 	- T::Vesting is module_multi_currency_vesting
+		/// - Complexity: `O(C)` where C is the number of collateral types, which is small
+		/// - Db reads: `EarlyAdopterProgram`, `TotalEarlyAdopterScore`, C * `EarlyAdopterScores`, 4 items in orml_currencies, 1 item in module_multi_currency_vesting
+		/// - Db writes: C * `EarlyAdopterScores`, 4 items in orml_currencies, 1 item in module_multi_currency_vesting
+		/// -------------------
+		/// Base Weight: 100 µs
+		/// # </weight>
+		#[weight = 100 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(13, 11)]
+		pub fn claim_early_adopter_rewards(origin) {
+			let who = ensure_signed(origin)?;
+			let program = Self::early_adopter_program().ok_or(Error::<T>::NoEarlyAdopterProgram)?;
+			let now = <system::Module<T>>::block_number();
+			ensure!(now >= program.end, Error::<T>::ProgramNotEnded);
+
+			let mut total = Self::total_early_adopter_score();
+			Self::accrue_score(&mut total, program.end);
+			let score = <EarlyAdopterScores<T>>::iter_prefix(&who).fold(0u128, |score, (_, mut participation)| {
+				Self::accrue_score(&mut participation, program.end);
+				score.saturating_add(participation.score)
+			});
+			let rewards = Ratio::checked_from_rational(score, total.score)
+				.unwrap_or_default()
+				.saturating_mul_int(program.total_rewards);
+			ensure!(!rewards.is_zero(), Error::<T>::NoEarlyAdopterRewards);
+
+			// the rewards are minted to the module account to fund the vesting schedule
+			let currency_id = T::IncentiveCurrencyId::get();
+			let module_account = Self::account_id();
+			T::Currency::deposit(currency_id, &module_account, rewards)?;
+			if let Err(e) = T::Vesting::vested_transfer(
+				currency_id,
+				&module_account,
+				&who,
+				rewards,
+				program.end,
+				T::VestingPeriod::get(),
+			) {
+				T::Currency::withdraw(currency_id, &module_account, rewards)?;
+				return Err(e);
+			}
+
+			<EarlyAdopterScores<T>>::remove_prefix(&who);
+			Self::deposit_event(RawEvent::EarlyAdopterRewardsVested(who, rewards));
+		}
+
+		/// Update the configuration of collateral auction rewards
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `config`: the new configuration, zero rates stop the rewards
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `AuctionRewards`
+		/// -------------------
+		/// Base Weight: 10 µs
+		/// # </weight>
+		#[weight = 10 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_auction_reward_config(origin, config: AuctionRewardConfig) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			AuctionRewards::put(config.clone());
+			Self::deposit_event(RawEvent::AuctionRewardConfigUpdated(config));
+		}
+
+		/// Update the remaining budget of collateral auction rewards
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `budget`: the new budget
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `AuctionRewardBudget`
+		/// -------------------
+		/// Base Weight: 10 µs
+		/// # </weight>
+		#[weight = 10 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_auction_reward_budget(origin, #[compact] budget: Balance) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			AuctionRewardBudget::put(budget);
+			Self::deposit_event(RawEvent::AuctionRewardBudgetUpdated(budget));
+		}
+
+		/// Fund the pot of deposit rewards with native token
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `amount`: the amount of native token
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: 2 items of orml_currencies
+		/// - Db writes: 2 items of orml_currencies
+		/// -------------------
+		/// Base Weight: 30 µs
+		/// # </weight>
+		#[weight = 30 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 2)]
+		pub fn fund_deposit_pot(origin, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			T::Currency::transfer(T::IncentiveCurrencyId::get(), &who, &Self::deposit_account_id(), amount)?;
+			Self::deposit_event(RawEvent::FundDepositPot(who, amount));
+		}
+
+		/// Deposit stable coin to earn the rewards of deposit program
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `amount`: the amount of stable coin
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `ShareAndWithdrawnReward`, `Pools`, 2 items of orml_currencies
+		/// - Db writes: `ShareAndWithdrawnReward`, `Pools`, 2 items of orml_currencies
+		/// -------------------
+		/// Base Weight: 45 µs
+		/// # </weight>
+		#[weight = 45 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(4, 4)]
+		pub fn deposit_stable(origin, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			T::Currency::transfer(T::GetStableCurrencyId::get(), &who, &Self::deposit_account_id(), amount)?;
+			Self::add_share(&who, PoolId::Deposit, amount);
+			Self::deposit_event(RawEvent::DepositStable(who, amount));
+		}
+
+		/// Unlock deposit, the pending rewards are claimed and the unlocked amount can be
+		/// withdrawn after `DepositWithdrawalDelay`. The unlocked amount is merged into the
+		/// previous unlocking one, whose delay restarts.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `amount`: the amount of stable coin
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `ShareAndWithdrawnReward`, `Pools`, `DepositWithdrawalDelay`, `DepositUnlocking`, 2 items of orml_currencies
+		/// - Db writes: `ShareAndWithdrawnReward`, `Pools`, `DepositUnlocking`, 2 items of orml_currencies
+		/// -------------------
+		/// Base Weight: 60 µs
+		/// # </weight>
+		#[weight = 60 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(6, 5)]
+		pub fn unlock_deposit(origin, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			let (share, _) = Self::share_and_withdrawn_reward(PoolId::Deposit, &who);
+			ensure!(share >= amount, Error::<T>::DepositNotEnough);
+
+			Self::remove_share(&who, PoolId::Deposit, amount);
+			let unlock_at = <system::Module<T>>::block_number() + Self::deposit_withdrawal_delay();
+			<DepositUnlocking<T>>::mutate(&who, |maybe_unlocking| {
+				let unlocking_amount = maybe_unlocking.map_or(Zero::zero(), |(unlocking_amount, _)| unlocking_amount);
+				*maybe_unlocking = Some((unlocking_amount.saturating_add(amount), unlock_at));
+			});
+			Self::deposit_event(RawEvent::UnlockDeposit(who, amount, unlock_at));
+		}
+
+		/// Withdraw the unlocked deposit after the withdrawal delay
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `DepositUnlocking`, 2 items of orml_currencies
+		/// - Db writes: `DepositUnlocking`, 2 items of orml_currencies
+		/// -------------------
+		/// Base Weight: 35 µs
+		/// # </weight>
+		#[weight = 35 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(3, 3)]
+		pub fn withdraw_deposit(origin) {
+			let who = ensure_signed(origin)?;
+			let (amount, unlock_at) = Self::deposit_unlocking(&who).ok_or(Error::<T>::NoUnlockedDeposit)?;
+			ensure!(
+				<system::Module<T>>::block_number() >= unlock_at,
+				Error::<T>::DepositStillLocked,
+			);
+
+			T::Currency::transfer(T::GetStableCurrencyId::get(), &Self::deposit_account_id(), &who, amount)?;
+			<DepositUnlocking<T>>::remove(&who);
+			Self::deposit_event(RawEvent::WithdrawDeposit(who, amount));
+		}
+
+		/// Update the delay of deposit withdrawal
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `delay`: the blocks the unlocked deposit has to wait, only affects the deposit unlocked afterwards
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `DepositWithdrawalDelay`
+		/// -------------------
+		/// Base Weight: 10 µs
+		/// # </weight>
+		#[weight = 10 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_deposit_withdrawal_delay(origin, delay: T::BlockNumber) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			<DepositWithdrawalDelay<T>>::put(delay);
+			Self::deposit_event(RawEvent::DepositWithdrawalDelayUpdated(delay));
+		}
+
+		/// Update the emission schedule
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `schedule`: the new schedule, zero emission stops it
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `EmissionSchedule`
+		/// -------------------
+		/// Base Weight: 10 µs
+		/// # </weight>
+		#[weight = 10 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_emission_schedule(origin, schedule: EmissionScheduleInfo<T::BlockNumber>) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			<EmissionSchedule<T>>::put(schedule.clone());
+			Self::deposit_event(RawEvent::EmissionScheduleUpdated(schedule));
+		}
+
+		/// Update the weights of pools in emission
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `updates`: vec of (pool_id, weight), zero weight removes the pool from emission
+		///
+		/// # <weight>
+		/// - Complexity: `O(N)` where N is the length of `updates`
+		/// - Db reads:
+		/// - Db writes: `PoolWeights`
+		/// -------------------
+		/// Base Weight: 10 µs + 3 µs * N
+		/// # </weight>
+		#[weight = (10 + 3 * updates.len() as u64) * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, updates.len() as u64)]
+		pub fn set_pool_weights(origin, updates: Vec<(PoolId, u32)>) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			for (pool_id, weight) in updates {
+				if weight.is_zero() {
+					PoolWeights::remove(pool_id);
+				} else {
+					PoolWeights::insert(pool_id, weight);
+				}
+				Self::deposit_event(RawEvent::PoolWeightUpdated(pool_id, weight));
+			}
+		}
+
+		/// Update the extra reward amount accumulated every `AccumulatePeriod` of pool
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `pool_id`: the incentive pool
+		/// - `currency_id`: the currency of extra rewards, can't be the incentive currency
+		/// - `amount`: the reward amount per period, zero stops the extra rewards
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `ExtraRewards`
+		/// - Db writes: `ExtraRewards`
+		/// -------------------
+		/// Base Weight: 15 µs
+		/// # </weight>
+		#[weight = 15 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn set_extra_reward_amount(origin, pool_id: PoolId, currency_id: CurrencyId, #[compact] amount: Balance) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(
+				currency_id != T::IncentiveCurrencyId::get(),
+				Error::<T>::InvalidExtraRewardCurrency,
+			);
+			ExtraRewards::mutate(pool_id, currency_id, |info| info.reward_per_period = amount);
+			Self::deposit_event(RawEvent::ExtraRewardAmountUpdated(pool_id, currency_id, amount));
+		}
+
+		/// Fund the extra rewards of pool
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `pool_id`: the incentive pool
+		/// - `currency_id`: the currency of extra rewards, can't be the incentive currency
+		/// - `amount`: the amount to fund
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `ExtraRewards`, 2 items of orml_currencies
+		/// - Db writes: `ExtraRewards`, 2 items of orml_currencies
+		/// -------------------
+		/// Base Weight: 35 µs
+		/// # </weight>
+		#[weight = 35 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(3, 3)]
+		pub fn fund_extra_rewards(origin, pool_id: PoolId, currency_id: CurrencyId, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				currency_id != T::IncentiveCurrencyId::get(),
+				Error::<T>::InvalidExtraRewardCurrency,
+			);
+			T::Currency::transfer(currency_id, &who, &Self::extra_rewards_account_id(), amount)?;
+			ExtraRewards::mutate(pool_id, currency_id, |info| {
+				info.remaining_funds = info.remaining_funds.saturating_add(amount);
+			});
+			Self::deposit_event(RawEvent::FundExtraRewards(who, pool_id, currency_id, amount));
+		}
+
+		/// Update the period to release the claimed extra rewards in currency linearly
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `currency_id`: the currency of extra rewards
+		/// - `period`: the vesting period, zero pays the extra rewards at once
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `ExtraRewardVestingPeriods`
+		/// -------------------
+		/// Base Weight: 15 µs
+		/// # </weight>
+		#[weight = 15 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_extra_reward_vesting_period(origin, currency_id: CurrencyId, period: T::BlockNumber) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			<ExtraRewardVestingPeriods<T>>::insert(currency_id, period);
+			Self::deposit_event(RawEvent::ExtraRewardVestingPeriodUpdated(currency_id, period));
+		}
+
+		/// Accumulate rewards to the pools every `AccumulatePeriod` unless paused
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			if !(now % T::AccumulatePeriod::get()).is_zero() {
+				0
+			} else if Self::rewards_paused() {
+				T::DbWeight::get().reads(1)
+			} else {
+				let count = Self::accumulate_emission(now) + Self::accumulate_extra_rewards();
+				T::DbWeight::get().reads_writes(count * 2 + 2, count)
+			}
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_legacy_rewards()
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account of keeper incentive pool
+	pub fn account_id() -> T::AccountId {
+		MODULE_ID.into_account()
+	}
+
+	/// Whether the bad debit of CDP treasury reaches the stress threshold
+	pub fn is_under_stress(config: &AuctionRewardConfig) -> bool {
+		T::CDPTreasury::get_debit_pool().saturating_sub(T::CDPTreasury::get_surplus_pool()) >= config.stress_threshold
+	}
+
+	/// The account holding the deposit and the pot of deposit rewards
+	pub fn deposit_account_id() -> T::AccountId {
+		DEPOSIT_MODULE_ID.into_account()
+	}
+
+	/// The account holding the funds of extra rewards
+	pub fn extra_rewards_account_id() -> T::AccountId {
+		EXTRA_REWARDS_MODULE_ID.into_account()
+	}
+
+	/// Pay out the extra rewards at once, or by vesting if the currency has vesting period,
+	/// the vesting rewards of repeated claims accumulate in one schedule
+	fn pay_extra_rewards(who: &T::AccountId, currency_id: CurrencyId, rewards: Balance) -> DispatchResult {
+		let period = Self::extra_reward_vesting_periods(currency_id);
+		if period.is_zero() {
+			T::Currency::transfer(currency_id, &Self::extra_rewards_account_id(), who, rewards)
+		} else {
+			let now = <system::Module<T>>::block_number();
+			T::Vesting::vested_transfer(
+				currency_id,
+				&Self::extra_rewards_account_id(),
+				who,
+				rewards,
+				now,
+				period,
+			)
+		}
+	}
+
+	/// Accumulate extra rewards to pools from the remaining funds, skip if nobody has share in it.
+	/// Return the count of extra rewards.
+	fn accumulate_extra_rewards() -> u64 {
+		let extra_rewards = ExtraRewards::iter().collect::<Vec<_>>();
+		for (pool_id, currency_id, mut info) in extra_rewards.iter().cloned() {
+			let amount = info.reward_per_period.min(info.remaining_funds);
+			if !amount.is_zero() && !Self::pools(pool_id).total_shares.is_zero() {
+				info.remaining_funds -= amount;
+				info.total_rewards = info.total_rewards.saturating_add(amount);
+				ExtraRewards::insert(pool_id, currency_id, info);
+			}
+		}
+		extra_rewards.len() as u64
+	}
+
+	/// Accumulate rewards to pool, skip if nobody has share in it.
+	fn accumulate_rewards(pool_id: PoolId, amount: Balance) {
+		Pools::mutate(pool_id, |pool| {
+			if !pool.total_shares.is_zero() {
+				let amount = if pool_id == PoolId::Deposit {
+					// limited by the pot not owed to depositors yet
+					let unclaimed = pool.total_rewards.saturating_sub(pool.total_withdrawn_rewards);
+					let pot = T::Currency::free_balance(T::IncentiveCurrencyId::get(), &Self::deposit_account_id());
+					amount.min(pot.saturating_sub(unclaimed))
+				} else {
+					amount
+				};
+				pool.total_rewards = pool.total_rewards.saturating_add(amount);
+			}
+		});
+	}
+
+	/// Accumulate the score by the outstanding debit until `until`
+	fn accrue_score(participation: &mut ParticipationScore<T::BlockNumber>, until: T::BlockNumber) {
+		if until > participation.last_update {
+			let blocks: Balance = (until - participation.last_update).unique_saturated_into();
+			participation.score = participation
+				.score
+				.saturating_add(participation.debit.saturating_mul(blocks));
+			participation.last_update = until;
+		}
+	}
+
+	/// Update the outstanding debit of `who` under the collateral type in early adopter program
+	fn update_early_adopter_score(who: &T::AccountId, currency_id: CurrencyId, new_debit: Balance) {
+		let program = match Self::early_adopter_program() {
+			Some(program) => program,
+			None => return,
+		};
+		let now = <system::Module<T>>::block_number();
+		if now >= program.end {
+			return;
+		}
+
+		<EarlyAdopterScores<T>>::mutate(who, currency_id, |participation| {
+			Self::accrue_score(participation, now);
+			TotalEarlyAdopterScore::<T>::mutate(|total| {
+				Self::accrue_score(total, now);
+				total.debit = total
+					.debit
+					.saturating_sub(participation.debit)
+					.saturating_add(new_debit);
+			});
+			participation.debit = new_debit;
+		});
+	}
+
+	/// The total emission per period at block `now`, decayed by the passed decay periods
+	pub fn emission_amount(now: T::BlockNumber) -> Balance {
+		let schedule = Self::emission_schedule();
+		if now < schedule.start {
+			return Zero::zero();
+		}
+		if schedule.decay_period.is_zero() {
+			return schedule.emission_per_period;
+		}
+
+		// exponentiation by squaring
+		let mut decays: u64 = ((now - schedule.start) / schedule.decay_period).unique_saturated_into();
+		let mut base = schedule.decay_ratio;
+		let mut factor = Ratio::saturating_from_integer(1);
+		while decays > 0 && !factor.is_zero() {
+			if decays & 1 == 1 {
+				factor = factor.saturating_mul(base);
+			}
+			base = base.saturating_mul(base);
+			decays >>= 1;
+		}
+		factor.saturating_mul_int(schedule.emission_per_period)
+	}
+
+	/// Distribute the emission to pools by weights, return the count of pools.
+	fn accumulate_emission(now: T::BlockNumber) -> u64 {
+		let amount = Self::emission_amount(now);
+		if amount.is_zero() {
+			return 0;
+		}
+
+		let weights = PoolWeights::iter().collect::<Vec<_>>();
+		let total_weight = weights
+			.iter()
+			.fold(0u128, |total, (_, weight)| total.saturating_add(*weight as u128));
+		for (pool_id, weight) in weights.iter() {
+			let rewards = Ratio::checked_from_rational(*weight as u128, total_weight)
+				.unwrap_or_default()
+				.saturating_mul_int(amount);
+			Self::accumulate_rewards(*pool_id, rewards);
+		}
+		weights.len() as u64
+	}
+
+	/// Migrate the reward amounts of pools and the liquidity mining of previous version into the emission.
+	/// Their amounts per period at the upgrade become a constant emission shared by weights in parts per
+	/// billion, unless governance has set the emission already, which supersedes them.
+	fn migrate_legacy_rewards() -> Weight {
+		let now = <system::Module<T>>::block_number();
+		let dex_amount = <DexRewardSchedule<T>>::take()
+			.into_iter()
+			.rev()
+			.find(|(start, _)| *start <= now)
+			.map(|(_, amount)| amount)
+			.unwrap_or_default();
+		let dex_weights = DexIncentiveWeights::drain().collect::<Vec<_>>();
+		let total_dex_weight = dex_weights
+			.iter()
+			.fold(0u128, |total, (_, weight)| total.saturating_add(*weight as u128));
+
+		let mut amounts = IncentiveRewardAmount::drain().collect::<BTreeMap<_, _>>();
+		for (currency_id, weight) in dex_weights.iter() {
+			let rewards = Ratio::checked_from_rational(*weight as u128, total_dex_weight)
+				.unwrap_or_default()
+				.saturating_mul_int(dex_amount);
+			let amount = amounts.entry(PoolId::DexIncentive(*currency_id)).or_default();
+			*amount = amount.saturating_add(rewards);
+		}
+		let count = amounts.len() as u64;
+		let total_amount = amounts
+			.values()
+			.fold(Balance::zero(), |total, amount| total.saturating_add(*amount));
+		if total_amount.is_zero() || !Self::emission_schedule().emission_per_period.is_zero() {
+			return T::DbWeight::get().reads_writes(count + 3, count + 3);
+		}
+
+		<EmissionSchedule<T>>::put(EmissionScheduleInfo {
+			start: now,
+			emission_per_period: total_amount,
+			decay_period: Zero::zero(),
+			decay_ratio: Zero::zero(),
+		});
+		for (pool_id, amount) in amounts {
+			let weight: u32 = Ratio::checked_from_rational(amount, total_amount)
+				.unwrap_or_default()
+				.saturating_mul_int(1_000_000_000u128)
+				.unique_saturated_into();
+			if !weight.is_zero() {
+				PoolWeights::insert(pool_id, weight);
+			}
+		}
+		T::DbWeight::get().reads_writes(count + 3, count * 2 + 4)
+	}
+
+	/// Get the rewards of `who` in pool which can be claimed now
+	pub fn pending_rewards(who: &T::AccountId, pool_id: PoolId) -> Balance {
+		let (share, withdrawn_rewards) = Self::share_and_withdrawn_reward(pool_id, who);
+		let pool = Self::pools(pool_id);
+		Ratio::checked_from_rational(share, pool.total_shares)
+			.unwrap_or_default()
+			.saturating_mul_int(pool.total_rewards)
+			.saturating_sub(withdrawn_rewards)
+	}
+
+	/// Get the extra rewards of `who` in pool in the currency which can be claimed now
+	pub fn pending_extra_rewards(who: &T::AccountId, pool_id: PoolId, currency_id: CurrencyId) -> Balance {
+		let (share, _) = Self::share_and_withdrawn_reward(pool_id, who);
+		let info = Self::extra_rewards(pool_id, currency_id);
+		Ratio::checked_from_rational(share, Self::pools(pool_id).total_shares)
+			.unwrap_or_default()
+			.saturating_mul_int(info.total_rewards)
+			.saturating_sub(Self::extra_withdrawn_rewards((pool_id, who.clone()), currency_id))
+	}
+
+	/// Pay out the rewards of `who` in pool in all currencies
+	fn payout_rewards(who: &T::AccountId, pool_id: PoolId) {
+		let rewards = Self::pending_rewards(who, pool_id);
+		if !rewards.is_zero() {
+			// the rewards of deposit are paid from the pot, others are issued.
+			// keep the rewards pending if failed to pay out
+			let incentive_currency_id = T::IncentiveCurrencyId::get();
+			let result = if pool_id == PoolId::Deposit {
+				T::Currency::transfer(incentive_currency_id, &Self::deposit_account_id(), who, rewards)
+			} else {
+				T::Currency::deposit(incentive_currency_id, who, rewards)
+			};
+			if result.is_ok() {
+				<ShareAndWithdrawnReward<T>>::mutate(pool_id, who, |(_, withdrawn_rewards)| {
+					*withdrawn_rewards = withdrawn_rewards.saturating_add(rewards);
+				});
+				Pools::mutate(pool_id, |pool| {
+					pool.total_withdrawn_rewards = pool.total_withdrawn_rewards.saturating_add(rewards);
+				});
+				Self::deposit_event(RawEvent::ClaimRewards(who.clone(), pool_id, rewards));
+			}
+		}
+
+		for (currency_id, _) in ExtraRewards::iter_prefix(pool_id).collect::<Vec<_>>() {
+			let rewards = Self::pending_extra_rewards(who, pool_id, currency_id);
+			if !rewards.is_zero() && Self::pay_extra_rewards(who, currency_id, rewards).is_ok() {
+				<ExtraWithdrawnRewards<T>>::mutate((pool_id, who.clone()), currency_id, |withdrawn_rewards| {
+					*withdrawn_rewards = withdrawn_rewards.saturating_add(rewards);
+				});
+				ExtraRewards::mutate(pool_id, currency_id, |info| {
+					info.total_withdrawn_rewards = info.total_withdrawn_rewards.saturating_add(rewards);
+				});
+				Self::deposit_event(RawEvent::ClaimExtraRewards(who.clone(), pool_id, currency_id, rewards));
+			}
+		}
+	}
+
+	fn add_share(who: &T::AccountId, pool_id: PoolId, amount: Balance) {
+		if amount.is_zero() {
+			return;
+		}
+
+		// inflate the extra rewards in the same way as below
+		let total_shares = Self::pools(pool_id).total_shares;
+		for (currency_id, mut info) in ExtraRewards::iter_prefix(pool_id).collect::<Vec<_>>() {
+			let reward_inflation = Ratio::checked_from_rational(amount, total_shares)
+				.unwrap_or_default()
+				.saturating_mul_int(info.total_rewards);
+			if !reward_inflation.is_zero() {
+				info.total_rewards = info.total_rewards.saturating_add(reward_inflation);
+				info.total_withdrawn_rewards = info.total_withdrawn_rewards.saturating_add(reward_inflation);
+				ExtraRewards::insert(pool_id, currency_id, info);
+				<ExtraWithdrawnRewards<T>>::mutate((pool_id, who.clone()), currency_id, |withdrawn_rewards| {
+					*withdrawn_rewards = withdrawn_rewards.saturating_add(reward_inflation);
+				});
+			}
+		}
+
+		Pools::mutate(pool_id, |pool| {
+			// inflate the rewards for the new share as if it has withdrawn them,
+			// so that it can only get the rewards accumulated afterwards.
+			let reward_inflation = Ratio::checked_from_rational(amount, pool.total_shares)
+				.unwrap_or_default()
+				.saturating_mul_int(pool.total_rewards);
+			pool.total_shares = pool.total_shares.saturating_add(amount);
+			pool.total_rewards = pool.total_rewards.saturating_add(reward_inflation);
+			pool.total_withdrawn_rewards = pool.total_withdrawn_rewards.saturating_add(reward_inflation);
+
+			<ShareAndWithdrawnReward<T>>::mutate(pool_id, who, |(share, withdrawn_rewards)| {
+				*share = share.saturating_add(amount);
+				*withdrawn_rewards = withdrawn_rewards.saturating_add(reward_inflation);
+			});
+		});
+	}
+
+	fn remove_share(who: &T::AccountId, pool_id: PoolId, amount: Balance) {
+		if amount.is_zero() {
+			return;
+		}
+
+		// claim rewards first
+		Self::payout_rewards(who, pool_id);
+
+		let (share, withdrawn_rewards) = Self::share_and_withdrawn_reward(pool_id, who);
+		let remove_amount = amount.min(share);
+		if remove_amount.is_zero() {
+			return;
+		}
+
+		// remove the withdrawn rewards in proportion to the removed share
+		let withdrawn_rewards_to_remove = Ratio::checked_from_rational(remove_amount, share)
+			.unwrap_or_default()
+			.saturating_mul_int(withdrawn_rewards);
+		Pools::mutate(pool_id, |pool| {
+			pool.total_shares = pool.total_shares.saturating_sub(remove_amount);
+			pool.total_rewards = pool.total_rewards.saturating_sub(withdrawn_rewards_to_remove);
+			pool.total_withdrawn_rewards = pool.total_withdrawn_rewards.saturating_sub(withdrawn_rewards_to_remove);
+		});
+
+		let new_share = share - remove_amount;
+		for (currency_id, withdrawn_rewards) in
+			<ExtraWithdrawnRewards<T>>::iter_prefix((pool_id, who.clone())).collect::<Vec<_>>()
+		{
+			let withdrawn_rewards_to_remove = Ratio::checked_from_rational(remove_amount, share)
+				.unwrap_or_default()
+				.saturating_mul_int(withdrawn_rewards);
+			ExtraRewards::mutate(pool_id, currency_id, |info| {
+				info.total_rewards = info.total_rewards.saturating_sub(withdrawn_rewards_to_remove);
+				info.total_withdrawn_rewards = info.total_withdrawn_rewards.saturating_sub(withdrawn_rewards_to_remove);
+			});
+			if new_share.is_zero() {
+				<ExtraWithdrawnRewards<T>>::remove((pool_id, who.clone()), currency_id);
+			} else {
+				<ExtraWithdrawnRewards<T>>::insert(
+					(pool_id, who.clone()),
+					currency_id,
+					withdrawn_rewards.saturating_sub(withdrawn_rewards_to_remove),
+				);
+			}
+		}
+
+		if new_share.is_zero() {
+			<ShareAndWithdrawnReward<T>>::remove(pool_id, who);
+		} else {
+			<ShareAndWithdrawnReward<T>>::insert(
+				pool_id,
+				who,
+				(new_share, withdrawn_rewards.saturating_sub(withdrawn_rewards_to_remove)),
+			);
+		}
+	}
+
+	/// Update the share of `who` in pool to `new_share`
+	fn set_share(who: &T::AccountId, pool_id: PoolId, new_share: Balance) {
+		let (share, _) = Self::share_and_withdrawn_reward(pool_id, who);
+		if new_share > share {
+			Self::add_share(who, pool_id, new_share - share);
+		} else {
+			Self::remove_share(who, pool_id, share - new_share);
+		}
+	}
+}
+
+impl<T: Trait> OnUpdateLoan<T::AccountId, CurrencyId, Amount, Balance> for Module<T> {
+	fn on_update_loan(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		amount_adjustment: Amount,
+		previous_amount: Balance,
+	) {
+		let adjustment_abs = TryInto::<Balance>::try_into(amount_adjustment.saturating_abs()).unwrap_or_default();
+		let new_amount = if amount_adjustment.is_positive() {
+			previous_amount.saturating_add(adjustment_abs)
+		} else {
+			previous_amount.saturating_sub(adjustment_abs)
+		};
+
+		// set the share to the new debit amount, which also takes the loans
+		// created before the incentives into account.
+		Self::set_share(who, PoolId::Loans(currency_id), new_amount);
+		Self::update_early_adopter_score(who, currency_id, new_amount);
+	}
+}
+
+impl<T: Trait> OnKeeperAction<T::AccountId, CurrencyId, Balance> for Module<T> {
+	fn on_keeper_action(keeper: &T::AccountId, currency_id: CurrencyId, debit_value: Balance) {
+		// rewards are limited by the balance of pool not reserved for pending rewards
+		let available = T::Currency::free_balance(T::GetStableCurrencyId::get(), &Self::account_id())
+			.saturating_sub(Self::total_pending_keeper_rewards());
+		let rewards = Self::keeper_reward_rate()
+			.saturating_mul_int(debit_value)
+			.min(available);
+
+		if !rewards.is_zero() {
+			<PendingKeeperRewards<T>>::mutate(keeper, |pending| *pending = pending.saturating_add(rewards));
+			TotalPendingKeeperRewards::mutate(|total| *total = total.saturating_add(rewards));
+			Self::deposit_event(RawEvent::KeeperRewardAccrued(keeper.clone(), currency_id, rewards));
+		}
+	}
+}
+
+impl<T: Trait> OnUpdateLiquidity<T::AccountId, CurrencyId, Balance> for Module<T> {
+	fn on_update_liquidity(who: &T::AccountId, currency_id: CurrencyId, new_share: Balance) {
+		Self::set_share(who, PoolId::DexIncentive(currency_id), new_share);
+	}
+}
+
+impl<T: Trait> OnEmergencyShutdown for Module<T> {
+	fn on_emergency_shutdown() {}
+
+	fn on_cancel_shutdown() {}
+
+	fn on_shutdown_phase_changed(phase: ShutdownPhase) {
+		RewardsPaused::put(phase != ShutdownPhase::Operational);
+	}
+}
+
+impl<T: Trait> OnCollateralAuctionDealt<T::AccountId, CurrencyId, Balance> for Module<T> {
+	fn on_collateral_auction_dealt(
+		refund_recipient: &T::AccountId,
+		currency_id: CurrencyId,
+		target: Balance,
+		winner: (T::AccountId, Balance),
+		runner_up: Option<(T::AccountId, Balance)>,
+	) {
+		let config = Self::auction_rewards();
+		if !Self::is_under_stress(&config) {
+			return;
+		}
+
+		let treasury_account = T::TreasuryAccount::get();
+		let mut remaining = config.max_rewards_per_auction.min(Self::auction_reward_budget());
+		// the winner outbid by itself doesn't count as the runner-up
+		let runner_up = runner_up.filter(|(bidder, _)| *bidder != winner.0);
+		let bids = sp_std::iter::once((winner, config.winner_rate))
+			.chain(runner_up.map(|runner_up| (runner_up, config.runner_up_rate)));
+		for ((bidder, bid_price), rate) in bids {
+			// no rewards for self-dealing
+			if bidder == *refund_recipient || bidder == treasury_account {
+				continue;
+			}
+
+			// the part of bid exceeding the target is refunded in collateral, doesn't count
+			let rewards = rate.saturating_mul_int(bid_price.min(target)).min(remaining);
+			if !rewards.is_zero() && T::Currency::deposit(T::IncentiveCurrencyId::get(), &bidder, rewards).is_ok() {
+				remaining -= rewards;
+				AuctionRewardBudget::mutate(|budget| *budget = budget.saturating_sub(rewards));
+				Self::deposit_event(RawEvent::AuctionRewardPaid(bidder, currency_id, rewards));
+			}
+		}
+	}
+}
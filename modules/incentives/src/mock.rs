@@ -0,0 +1,220 @@
+//! Mocks for the incentives module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types};
+use frame_system::EnsureSignedBy;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchResult, Perbill};
+use sp_std::cell::RefCell;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const CDP_TREASURY: AccountId = 10;
+pub const VESTING: AccountId = 11;
+pub const ACA: CurrencyId = CurrencyId::ACA;
+pub const AUSD: CurrencyId = CurrencyId::AUSD;
+pub const DOT: CurrencyId = CurrencyId::DOT;
+pub const BTC: CurrencyId = CurrencyId::XBTC;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod incentives {
+	pub use super::super::*;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		system<T>,
+		incentives<T>,
+		orml_tokens<T>,
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+}
+pub type System = system::Module<Runtime>;
+
+impl orml_tokens::Trait for Runtime {
+	type Event = TestEvent;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type DustRemoval = ();
+	type OnReceived = ();
+}
+pub type Tokens = orml_tokens::Module<Runtime>;
+
+thread_local! {
+	pub static DEBIT_POOL: RefCell<Balance> = RefCell::new(0);
+	pub static VESTED: RefCell<Vec<(AccountId, CurrencyId, Balance, BlockNumber, BlockNumber)>> = RefCell::new(vec![]);
+}
+
+/// The vesting funds are kept by `VESTING` and the schedules are recorded in `VESTED`
+pub struct MockVesting;
+impl MultiCurrencyVesting<AccountId, CurrencyId, Balance, BlockNumber> for MockVesting {
+	fn vested_transfer(
+		currency_id: CurrencyId,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Balance,
+		start: BlockNumber,
+		period: BlockNumber,
+	) -> DispatchResult {
+		Tokens::transfer(currency_id, from, &VESTING, amount)?;
+		VESTED.with(|v| v.borrow_mut().push((*to, currency_id, amount, start, period)));
+		Ok(())
+	}
+}
+
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		Tokens::free_balance(AUSD, &CDP_TREASURY)
+	}
+
+	fn get_debit_pool() -> Balance {
+		DEBIT_POOL.with(|v| *v.borrow())
+	}
+
+	fn get_total_collaterals(_id: CurrencyId) -> Balance {
+		Default::default()
+	}
+
+	fn on_system_debit(_amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn on_system_surplus(_amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn deposit_backed_debit_to(_who: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn deposit_unbacked_debit_to(_who: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn withdraw_backed_debit_from(_who: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn transfer_surplus_from(_from: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn transfer_surplus_to(to: &AccountId, amount: Balance) -> DispatchResult {
+		Tokens::transfer(AUSD, &CDP_TREASURY, to, amount)
+	}
+
+	fn transfer_collateral_to(_currency_id: CurrencyId, _to: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn transfer_collateral_from(_currency_id: CurrencyId, _from: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn burn_collateral(_currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn get_debit_proportion(_amount: Balance) -> Ratio {
+		Default::default()
+	}
+}
+
+ord_parameter_types! {
+	pub const One: AccountId = 1;
+}
+
+parameter_types! {
+	pub const IncentiveCurrencyId: CurrencyId = ACA;
+	pub const AccumulatePeriod: BlockNumber = 10;
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	pub const VestingPeriod: BlockNumber = 100;
+	pub const TreasuryAccount: AccountId = CDP_TREASURY;
+}
+
+impl Trait for Runtime {
+	type Event = TestEvent;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type Currency = Tokens;
+	type IncentiveCurrencyId = IncentiveCurrencyId;
+	type AccumulatePeriod = AccumulatePeriod;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = MockCDPTreasury;
+	type VestingPeriod = VestingPeriod;
+	type TreasuryAccount = TreasuryAccount;
+	type Vesting = MockVesting;
+}
+pub type IncentivesModule = Module<Runtime>;
+
+pub struct ExtBuilder {
+	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			endowed_accounts: vec![(CDP_TREASURY, AUSD, 10000)],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		orml_tokens::GenesisConfig::<Runtime> {
+			endowed_accounts: self.endowed_accounts,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		t.into()
+	}
+}
@@ -0,0 +1,925 @@
+//! Unit tests for the incentives module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OnInitialize, OnRuntimeUpgrade},
+};
+use mock::{
+	ExtBuilder, IncentivesModule, Origin, Runtime, System, TestEvent, Tokens, ACA, ALICE, AUSD, BOB, BTC, CAROL,
+	CDP_TREASURY, DEBIT_POOL, DOT, VESTED, VESTING,
+};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn on_update_loan_update_share() {
+	ExtBuilder::default().build().execute_with(|| {
+		IncentivesModule::on_update_loan(&ALICE, DOT, 100, 0);
+		assert_eq!(
+			IncentivesModule::share_and_withdrawn_reward(PoolId::Loans(DOT), ALICE),
+			(100, 0)
+		);
+		IncentivesModule::on_update_loan(&ALICE, DOT, -40, 100);
+		assert_eq!(
+			IncentivesModule::share_and_withdrawn_reward(PoolId::Loans(DOT), ALICE),
+			(60, 0)
+		);
+
+		// loan created before incentives
+		IncentivesModule::on_update_loan(&BOB, DOT, 50, 200);
+		assert_eq!(
+			IncentivesModule::share_and_withdrawn_reward(PoolId::Loans(DOT), BOB),
+			(250, 0)
+		);
+		assert_eq!(IncentivesModule::pools(PoolId::Loans(DOT)).total_shares, 310);
+
+		IncentivesModule::on_update_loan(&BOB, DOT, -250, 250);
+		assert_eq!(
+			ShareAndWithdrawnReward::<mock::Runtime>::contains_key(PoolId::Loans(DOT), BOB),
+			false
+		);
+		assert_eq!(IncentivesModule::pools(PoolId::Loans(DOT)).total_shares, 60);
+	});
+}
+
+#[test]
+fn accumulate_rewards_and_claim_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 0,
+				emission_per_period: 2000,
+				decay_period: 0,
+				decay_ratio: Zero::zero(),
+			}
+		));
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::ROOT,
+			vec![(PoolId::Loans(DOT), 1), (PoolId::Loans(BTC), 1)]
+		));
+		IncentivesModule::on_update_loan(&ALICE, DOT, 100, 0);
+
+		IncentivesModule::on_initialize(9);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 0);
+
+		// no share in BTC pool, rewards are not accumulated
+		IncentivesModule::on_initialize(10);
+		assert_eq!(IncentivesModule::pools(PoolId::Loans(DOT)).total_rewards, 1000);
+		assert_eq!(IncentivesModule::pools(PoolId::Loans(BTC)).total_rewards, 0);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 1000);
+
+		// the new share can not get the rewards accumulated before
+		IncentivesModule::on_update_loan(&BOB, DOT, 300, 0);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 1000);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::Loans(DOT)), 0);
+
+		IncentivesModule::on_initialize(20);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 1250);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::Loans(DOT)), 750);
+
+		assert_ok!(IncentivesModule::claim_rewards(
+			Origin::signed(ALICE),
+			PoolId::Loans(DOT)
+		));
+		let claim_event = TestEvent::incentives(RawEvent::ClaimRewards(ALICE, PoolId::Loans(DOT), 1250));
+		assert!(System::events().iter().any(|record| record.event == claim_event));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 1250);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 0);
+
+		// reducing share claims the rewards automatically
+		IncentivesModule::on_update_loan(&BOB, DOT, -300, 300);
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 750);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 0);
+
+		IncentivesModule::on_initialize(30);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 1000);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::Loans(DOT)), 0);
+	});
+}
+
+#[test]
+fn rewards_paused_after_emergency_shutdown() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 0,
+				emission_per_period: 1000,
+				decay_period: 0,
+				decay_ratio: Zero::zero(),
+			}
+		));
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::ROOT,
+			vec![(PoolId::Loans(DOT), 1)]
+		));
+		IncentivesModule::on_update_loan(&ALICE, DOT, 100, 0);
+		IncentivesModule::on_initialize(10);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 1000);
+
+		IncentivesModule::on_shutdown_phase_changed(ShutdownPhase::Frozen);
+		assert_eq!(IncentivesModule::rewards_paused(), true);
+		IncentivesModule::on_initialize(20);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 1000);
+
+		// the accrued rewards can still be claimed
+		assert_ok!(IncentivesModule::claim_rewards(
+			Origin::signed(ALICE),
+			PoolId::Loans(DOT)
+		));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 1000);
+
+		// resume after the shutdown is cancelled
+		IncentivesModule::on_shutdown_phase_changed(ShutdownPhase::Operational);
+		assert_eq!(IncentivesModule::rewards_paused(), false);
+		IncentivesModule::on_initialize(30);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 1000);
+	});
+}
+
+#[test]
+fn set_keeper_reward_rate_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			IncentivesModule::set_keeper_reward_rate(Origin::signed(5), Rate::saturating_from_rational(1, 100)),
+			BadOrigin,
+		);
+		assert_ok!(IncentivesModule::set_keeper_reward_rate(
+			Origin::signed(1),
+			Rate::saturating_from_rational(1, 100)
+		));
+		let update_event = TestEvent::incentives(RawEvent::KeeperRewardRateUpdated(Rate::saturating_from_rational(
+			1, 100,
+		)));
+		assert!(System::events().iter().any(|record| record.event == update_event));
+		assert_eq!(
+			IncentivesModule::keeper_reward_rate(),
+			Rate::saturating_from_rational(1, 100)
+		);
+	});
+}
+
+#[test]
+fn fund_keeper_pool_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(IncentivesModule::fund_keeper_pool(Origin::signed(5), 1000), BadOrigin);
+		assert_ok!(IncentivesModule::fund_keeper_pool(Origin::signed(1), 1000));
+		let fund_event = TestEvent::incentives(RawEvent::FundKeeperPool(1000));
+		assert!(System::events().iter().any(|record| record.event == fund_event));
+		assert_eq!(Tokens::free_balance(AUSD, &IncentivesModule::account_id()), 1000);
+		assert_eq!(Tokens::free_balance(AUSD, &CDP_TREASURY), 9000);
+	});
+}
+
+#[test]
+fn keeper_rewards_accrue_and_claim_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(IncentivesModule::set_keeper_reward_rate(
+			Origin::ROOT,
+			Rate::saturating_from_rational(1, 10)
+		));
+
+		// no rewards when the pool is empty
+		IncentivesModule::on_keeper_action(&ALICE, BTC, 1000);
+		assert_eq!(IncentivesModule::pending_keeper_rewards(ALICE), 0);
+
+		assert_ok!(IncentivesModule::fund_keeper_pool(Origin::ROOT, 150));
+		IncentivesModule::on_keeper_action(&ALICE, BTC, 1000);
+		let accrue_event = TestEvent::incentives(RawEvent::KeeperRewardAccrued(ALICE, BTC, 100));
+		assert!(System::events().iter().any(|record| record.event == accrue_event));
+		assert_eq!(IncentivesModule::pending_keeper_rewards(ALICE), 100);
+
+		// limited by the rewards not reserved
+		IncentivesModule::on_keeper_action(&BOB, DOT, 1000);
+		assert_eq!(IncentivesModule::pending_keeper_rewards(BOB), 50);
+		assert_eq!(IncentivesModule::total_pending_keeper_rewards(), 150);
+
+		assert_ok!(IncentivesModule::claim_keeper_rewards(Origin::signed(ALICE)));
+		let claim_event = TestEvent::incentives(RawEvent::ClaimKeeperRewards(ALICE, 100));
+		assert!(System::events().iter().any(|record| record.event == claim_event));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 100);
+		assert_eq!(IncentivesModule::pending_keeper_rewards(ALICE), 0);
+		assert_eq!(IncentivesModule::total_pending_keeper_rewards(), 50);
+		assert_eq!(Tokens::free_balance(AUSD, &IncentivesModule::account_id()), 50);
+	});
+}
+
+#[test]
+fn dex_liquidity_mining_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 20,
+				emission_per_period: 1000,
+				decay_period: 20,
+				decay_ratio: Ratio::saturating_from_rational(1, 2),
+			}
+		));
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::ROOT,
+			vec![(PoolId::DexIncentive(DOT), 1), (PoolId::DexIncentive(BTC), 3)]
+		));
+		IncentivesModule::on_update_liquidity(&ALICE, DOT, 100);
+		IncentivesModule::on_update_liquidity(&ALICE, BTC, 100);
+		IncentivesModule::on_update_liquidity(&BOB, BTC, 300);
+
+		// before the schedule starts
+		IncentivesModule::on_initialize(10);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::DexIncentive(DOT)), 0);
+
+		IncentivesModule::on_initialize(20);
+		assert_eq!(
+			IncentivesModule::pending_rewards(&ALICE, PoolId::DexIncentive(DOT)),
+			250
+		);
+		assert_eq!(
+			IncentivesModule::pending_rewards(&ALICE, PoolId::DexIncentive(BTC)),
+			187
+		);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::DexIncentive(BTC)), 562);
+
+		// withdraw all liquidity claims the rewards
+		IncentivesModule::on_update_liquidity(&ALICE, DOT, 0);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 250);
+
+		IncentivesModule::on_initialize(40);
+		assert_eq!(IncentivesModule::pools(PoolId::DexIncentive(DOT)).total_rewards, 0);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::DexIncentive(BTC)), 843);
+	});
+}
+
+#[test]
+fn set_early_adopter_program_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			IncentivesModule::set_early_adopter_program(Origin::signed(5), 100, 1000),
+			BadOrigin,
+		);
+		assert_noop!(
+			IncentivesModule::set_early_adopter_program(Origin::signed(1), 1, 1000),
+			Error::<Runtime>::InvalidProgramEnd,
+		);
+		assert_ok!(IncentivesModule::set_early_adopter_program(
+			Origin::signed(1),
+			100,
+			1000
+		));
+		let update_event = TestEvent::incentives(RawEvent::EarlyAdopterProgramUpdated(100, 1000));
+		assert!(System::events().iter().any(|record| record.event == update_event));
+		assert_eq!(
+			IncentivesModule::early_adopter_program(),
+			Some(EarlyAdopterProgramInfo {
+				end: 100,
+				total_rewards: 1000
+			})
+		);
+
+		System::set_block_number(100);
+		assert_noop!(
+			IncentivesModule::set_early_adopter_program(Origin::ROOT, 200, 1000),
+			Error::<Runtime>::ProgramEnded,
+		);
+	});
+}
+
+#[test]
+fn early_adopter_rewards_vesting_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(IncentivesModule::set_early_adopter_program(Origin::ROOT, 101, 1000));
+		IncentivesModule::on_update_loan(&ALICE, DOT, 100, 0);
+		System::set_block_number(51);
+		IncentivesModule::on_update_loan(&BOB, BTC, 100, 0);
+		assert_noop!(
+			IncentivesModule::claim_early_adopter_rewards(Origin::signed(ALICE)),
+			Error::<Runtime>::ProgramNotEnded,
+		);
+
+		// not counted after the program ends
+		System::set_block_number(101);
+		IncentivesModule::on_update_loan(&BOB, BTC, 1000, 100);
+
+		assert_ok!(IncentivesModule::claim_early_adopter_rewards(Origin::signed(ALICE)));
+		let vested_event = TestEvent::incentives(RawEvent::EarlyAdopterRewardsVested(ALICE, 666));
+		assert!(System::events().iter().any(|record| record.event == vested_event));
+		assert_eq!(VESTED.with(|v| v.borrow().clone()), vec![(ALICE, ACA, 666, 101, 100)]);
+		assert_eq!(Tokens::free_balance(ACA, &VESTING), 666);
+		assert_eq!(Tokens::free_balance(ACA, &IncentivesModule::account_id()), 0);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 0);
+		assert_noop!(
+			IncentivesModule::claim_early_adopter_rewards(Origin::signed(ALICE)),
+			Error::<Runtime>::NoEarlyAdopterRewards,
+		);
+
+		// the schedule starts from the end of program when claiming late
+		System::set_block_number(300);
+		assert_ok!(IncentivesModule::claim_early_adopter_rewards(Origin::signed(BOB)));
+		assert_eq!(
+			VESTED.with(|v| v.borrow().clone()),
+			vec![(ALICE, ACA, 666, 101, 100), (BOB, ACA, 333, 101, 100)]
+		);
+	});
+}
+
+#[test]
+fn set_auction_reward_config_and_budget_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let config = AuctionRewardConfig {
+			winner_rate: Rate::saturating_from_rational(1, 10),
+			runner_up_rate: Rate::saturating_from_rational(1, 20),
+			max_rewards_per_auction: 50,
+			stress_threshold: 100,
+		};
+		assert_noop!(
+			IncentivesModule::set_auction_reward_config(Origin::signed(BOB), config.clone()),
+			BadOrigin
+		);
+		assert_ok!(IncentivesModule::set_auction_reward_config(
+			Origin::signed(ALICE),
+			config.clone()
+		));
+		let config_event = TestEvent::incentives(RawEvent::AuctionRewardConfigUpdated(config.clone()));
+		assert!(System::events().iter().any(|record| record.event == config_event));
+		assert_eq!(IncentivesModule::auction_rewards(), config);
+
+		assert_noop!(
+			IncentivesModule::set_auction_reward_budget(Origin::signed(BOB), 1000),
+			BadOrigin
+		);
+		assert_ok!(IncentivesModule::set_auction_reward_budget(Origin::signed(ALICE), 1000));
+		let budget_event = TestEvent::incentives(RawEvent::AuctionRewardBudgetUpdated(1000));
+		assert!(System::events().iter().any(|record| record.event == budget_event));
+		assert_eq!(IncentivesModule::auction_reward_budget(), 1000);
+	});
+}
+
+#[test]
+fn auction_rewards_only_under_stress() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(IncentivesModule::set_auction_reward_config(
+			Origin::signed(ALICE),
+			AuctionRewardConfig {
+				winner_rate: Rate::saturating_from_rational(1, 10),
+				runner_up_rate: Rate::saturating_from_rational(1, 20),
+				max_rewards_per_auction: 50,
+				stress_threshold: 100,
+			}
+		));
+		assert_ok!(IncentivesModule::set_auction_reward_budget(Origin::signed(ALICE), 1000));
+
+		// bad debit is below threshold
+		DEBIT_POOL.with(|v| *v.borrow_mut() = 10050);
+		assert_eq!(
+			IncentivesModule::is_under_stress(&IncentivesModule::auction_rewards()),
+			false
+		);
+		IncentivesModule::on_collateral_auction_dealt(&CAROL, BTC, 500, (ALICE, 200), Some((BOB, 100)));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 0);
+
+		DEBIT_POOL.with(|v| *v.borrow_mut() = 10100);
+		assert_eq!(
+			IncentivesModule::is_under_stress(&IncentivesModule::auction_rewards()),
+			true
+		);
+		IncentivesModule::on_collateral_auction_dealt(&CAROL, BTC, 500, (ALICE, 200), Some((BOB, 100)));
+		let reward_event = TestEvent::incentives(RawEvent::AuctionRewardPaid(ALICE, BTC, 20));
+		assert!(System::events().iter().any(|record| record.event == reward_event));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 20);
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 5);
+		assert_eq!(IncentivesModule::auction_reward_budget(), 975);
+	});
+}
+
+#[test]
+fn auction_rewards_capped_and_exclude_self_dealing() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(IncentivesModule::set_auction_reward_config(
+			Origin::signed(ALICE),
+			AuctionRewardConfig {
+				winner_rate: Rate::saturating_from_rational(1, 10),
+				runner_up_rate: Rate::saturating_from_rational(1, 20),
+				max_rewards_per_auction: 50,
+				stress_threshold: 0,
+			}
+		));
+		assert_ok!(IncentivesModule::set_auction_reward_budget(Origin::signed(ALICE), 80));
+
+		// the bid exceeding the target doesn't count, rewards are capped per auction
+		IncentivesModule::on_collateral_auction_dealt(&CAROL, BTC, 400, (ALICE, 1000), Some((BOB, 400)));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 40);
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 10);
+		assert_eq!(IncentivesModule::auction_reward_budget(), 30);
+
+		// no rewards for the owner of liquidated CDP and CDP treasury
+		IncentivesModule::on_collateral_auction_dealt(&ALICE, BTC, 400, (ALICE, 400), Some((CDP_TREASURY, 300)));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 40);
+		assert_eq!(Tokens::free_balance(ACA, &CDP_TREASURY), 0);
+
+		// the winner gets no runner-up rewards
+		IncentivesModule::on_collateral_auction_dealt(&CAROL, BTC, 100, (BOB, 100), Some((BOB, 80)));
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 20);
+		assert_eq!(IncentivesModule::auction_reward_budget(), 20);
+
+		// rewards are capped by the remaining budget
+		IncentivesModule::on_collateral_auction_dealt(&CAROL, BTC, 400, (BOB, 400), None);
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 40);
+		assert_eq!(IncentivesModule::auction_reward_budget(), 0);
+	});
+}
+
+#[test]
+fn deposit_and_withdraw_with_delay_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Tokens::deposit(AUSD, &ALICE, 1000));
+		assert_noop!(
+			IncentivesModule::set_deposit_withdrawal_delay(Origin::signed(BOB), 20),
+			BadOrigin
+		);
+		assert_ok!(IncentivesModule::set_deposit_withdrawal_delay(
+			Origin::signed(ALICE),
+			20
+		));
+		let delay_event = TestEvent::incentives(RawEvent::DepositWithdrawalDelayUpdated(20));
+		assert!(System::events().iter().any(|record| record.event == delay_event));
+
+		assert_ok!(IncentivesModule::deposit_stable(Origin::signed(ALICE), 600));
+		let deposit_event = TestEvent::incentives(RawEvent::DepositStable(ALICE, 600));
+		assert!(System::events().iter().any(|record| record.event == deposit_event));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 400);
+		assert_eq!(Tokens::free_balance(AUSD, &IncentivesModule::deposit_account_id()), 600);
+		assert_eq!(
+			IncentivesModule::share_and_withdrawn_reward(PoolId::Deposit, ALICE),
+			(600, 0)
+		);
+
+		assert_noop!(
+			IncentivesModule::unlock_deposit(Origin::signed(ALICE), 601),
+			Error::<Runtime>::DepositNotEnough,
+		);
+		assert_noop!(
+			IncentivesModule::withdraw_deposit(Origin::signed(ALICE)),
+			Error::<Runtime>::NoUnlockedDeposit,
+		);
+		assert_ok!(IncentivesModule::unlock_deposit(Origin::signed(ALICE), 200));
+		let unlock_event = TestEvent::incentives(RawEvent::UnlockDeposit(ALICE, 200, 21));
+		assert!(System::events().iter().any(|record| record.event == unlock_event));
+		assert_eq!(
+			IncentivesModule::share_and_withdrawn_reward(PoolId::Deposit, ALICE),
+			(400, 0)
+		);
+
+		// the delay restarts when unlocking more
+		System::set_block_number(11);
+		assert_ok!(IncentivesModule::unlock_deposit(Origin::signed(ALICE), 100));
+		assert_eq!(IncentivesModule::deposit_unlocking(ALICE), Some((300, 31)));
+
+		System::set_block_number(30);
+		assert_noop!(
+			IncentivesModule::withdraw_deposit(Origin::signed(ALICE)),
+			Error::<Runtime>::DepositStillLocked,
+		);
+
+		System::set_block_number(31);
+		assert_ok!(IncentivesModule::withdraw_deposit(Origin::signed(ALICE)));
+		let withdraw_event = TestEvent::incentives(RawEvent::WithdrawDeposit(ALICE, 300));
+		assert!(System::events().iter().any(|record| record.event == withdraw_event));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 700);
+		assert_eq!(IncentivesModule::deposit_unlocking(ALICE), None);
+	});
+}
+
+#[test]
+fn deposit_rewards_paid_from_pot() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Tokens::deposit(AUSD, &ALICE, 1000));
+		assert_ok!(Tokens::deposit(AUSD, &BOB, 1000));
+		assert_ok!(Tokens::deposit(ACA, &CAROL, 1000));
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 0,
+				emission_per_period: 100,
+				decay_period: 0,
+				decay_ratio: Zero::zero(),
+			}
+		));
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::ROOT,
+			vec![(PoolId::Deposit, 1)]
+		));
+
+		assert_ok!(IncentivesModule::fund_deposit_pot(Origin::signed(CAROL), 150));
+		let fund_event = TestEvent::incentives(RawEvent::FundDepositPot(CAROL, 150));
+		assert!(System::events().iter().any(|record| record.event == fund_event));
+		assert_eq!(Tokens::free_balance(ACA, &IncentivesModule::deposit_account_id()), 150);
+
+		assert_ok!(IncentivesModule::deposit_stable(Origin::signed(ALICE), 300));
+		assert_ok!(IncentivesModule::deposit_stable(Origin::signed(BOB), 100));
+		IncentivesModule::on_initialize(10);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Deposit), 75);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::Deposit), 25);
+
+		// limited by the pot not owed yet
+		IncentivesModule::on_initialize(20);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Deposit), 112);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::Deposit), 37);
+
+		// rewards are transferred from the pot instead of issued
+		assert_ok!(IncentivesModule::unlock_deposit(Origin::signed(ALICE), 300));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 112);
+		assert_eq!(Tokens::free_balance(ACA, &IncentivesModule::deposit_account_id()), 38);
+		assert_eq!(Tokens::total_issuance(ACA), 1000);
+
+		IncentivesModule::on_initialize(30);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::Deposit), 38);
+	});
+}
+
+#[test]
+fn set_emission_schedule_and_pool_weights_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let schedule = EmissionScheduleInfo {
+			start: 10,
+			emission_per_period: 1000,
+			decay_period: 100,
+			decay_ratio: Ratio::saturating_from_rational(1, 2),
+		};
+		assert_noop!(
+			IncentivesModule::set_emission_schedule(Origin::signed(BOB), schedule.clone()),
+			BadOrigin
+		);
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::signed(ALICE),
+			schedule.clone()
+		));
+		let schedule_event = TestEvent::incentives(RawEvent::EmissionScheduleUpdated(schedule.clone()));
+		assert!(System::events().iter().any(|record| record.event == schedule_event));
+		assert_eq!(IncentivesModule::emission_schedule(), schedule);
+
+		assert_noop!(
+			IncentivesModule::set_pool_weights(Origin::signed(BOB), vec![(PoolId::Loans(BTC), 1)]),
+			BadOrigin
+		);
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::signed(ALICE),
+			vec![(PoolId::Loans(BTC), 1), (PoolId::Deposit, 2)]
+		));
+		let weight_event = TestEvent::incentives(RawEvent::PoolWeightUpdated(PoolId::Deposit, 2));
+		assert!(System::events().iter().any(|record| record.event == weight_event));
+		assert_eq!(IncentivesModule::pool_weights(PoolId::Loans(BTC)), 1);
+		assert_eq!(IncentivesModule::pool_weights(PoolId::Deposit), 2);
+
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::signed(ALICE),
+			vec![(PoolId::Deposit, 0)]
+		));
+		assert_eq!(IncentivesModule::pool_weights(PoolId::Deposit), 0);
+	});
+}
+
+#[test]
+fn emission_amount_decays() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(IncentivesModule::emission_amount(100), 0);
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 10,
+				emission_per_period: 1000,
+				decay_period: 100,
+				decay_ratio: Ratio::saturating_from_rational(1, 2),
+			}
+		));
+		assert_eq!(IncentivesModule::emission_amount(9), 0);
+		assert_eq!(IncentivesModule::emission_amount(10), 1000);
+		assert_eq!(IncentivesModule::emission_amount(109), 1000);
+		assert_eq!(IncentivesModule::emission_amount(110), 500);
+		assert_eq!(IncentivesModule::emission_amount(310), 125);
+		assert_eq!(IncentivesModule::emission_amount(100_000), 0);
+
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 10,
+				emission_per_period: 1000,
+				decay_period: 0,
+				decay_ratio: Ratio::saturating_from_rational(1, 2),
+			}
+		));
+		assert_eq!(IncentivesModule::emission_amount(100_000), 1000);
+	});
+}
+
+#[test]
+fn accumulate_emission_by_pool_weights() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 0,
+				emission_per_period: 300,
+				decay_period: 20,
+				decay_ratio: Ratio::saturating_from_rational(1, 2),
+			}
+		));
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::ROOT,
+			vec![(PoolId::Loans(BTC), 1), (PoolId::DexIncentive(DOT), 2)]
+		));
+		IncentivesModule::on_update_loan(&ALICE, BTC, 100, 0);
+		IncentivesModule::on_update_liquidity(&BOB, DOT, 100);
+
+		IncentivesModule::on_initialize(10);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(BTC)), 100);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::DexIncentive(DOT)), 200);
+
+		// halved after the decay period
+		IncentivesModule::on_initialize(20);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(BTC)), 150);
+		assert_eq!(IncentivesModule::pending_rewards(&BOB, PoolId::DexIncentive(DOT)), 300);
+	});
+}
+
+#[test]
+fn migrate_legacy_rewards_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(30);
+		IncentiveRewardAmount::insert(PoolId::Loans(DOT), 500);
+		IncentiveRewardAmount::insert(PoolId::Deposit, 100);
+		DexIncentiveWeights::insert(DOT, 1);
+		DexIncentiveWeights::insert(BTC, 3);
+		<DexRewardSchedule<Runtime>>::put(vec![(20, 1600), (40, 800)]);
+
+		IncentivesModule::on_runtime_upgrade();
+		assert_eq!(IncentiveRewardAmount::iter().count(), 0);
+		assert_eq!(DexIncentiveWeights::iter().count(), 0);
+		assert_eq!(DexRewardSchedule::<Runtime>::exists(), false);
+		assert_eq!(
+			IncentivesModule::emission_schedule(),
+			EmissionScheduleInfo {
+				start: 30,
+				emission_per_period: 2200,
+				decay_period: 0,
+				decay_ratio: Zero::zero(),
+			}
+		);
+		assert_eq!(IncentivesModule::pool_weights(PoolId::Loans(DOT)), 227_272_727);
+		assert_eq!(IncentivesModule::pool_weights(PoolId::Deposit), 45_454_545);
+		assert_eq!(IncentivesModule::pool_weights(PoolId::DexIncentive(DOT)), 181_818_181);
+		assert_eq!(IncentivesModule::pool_weights(PoolId::DexIncentive(BTC)), 545_454_545);
+
+		IncentivesModule::on_update_loan(&ALICE, DOT, 100, 0);
+		IncentivesModule::on_initialize(40);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(DOT)), 500);
+	});
+}
+
+#[test]
+fn migrate_legacy_rewards_superseded_by_emission() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(30);
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 0,
+				emission_per_period: 300,
+				decay_period: 0,
+				decay_ratio: Zero::zero(),
+			}
+		));
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::ROOT,
+			vec![(PoolId::Loans(BTC), 1)]
+		));
+		IncentiveRewardAmount::insert(PoolId::Loans(DOT), 500);
+		DexIncentiveWeights::insert(DOT, 1);
+
+		IncentivesModule::on_runtime_upgrade();
+		assert_eq!(IncentiveRewardAmount::contains_key(PoolId::Loans(DOT)), false);
+		assert_eq!(DexIncentiveWeights::contains_key(DOT), false);
+		assert_eq!(IncentivesModule::emission_schedule().emission_per_period, 300);
+		assert_eq!(PoolWeights::contains_key(PoolId::Loans(DOT)), false);
+		assert_eq!(IncentivesModule::pool_weights(PoolId::Loans(BTC)), 1);
+	});
+}
+
+#[test]
+fn set_and_fund_extra_rewards_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			IncentivesModule::set_extra_reward_amount(Origin::signed(BOB), PoolId::Loans(BTC), DOT, 10),
+			BadOrigin
+		);
+		assert_noop!(
+			IncentivesModule::set_extra_reward_amount(Origin::signed(ALICE), PoolId::Loans(BTC), ACA, 10),
+			Error::<Runtime>::InvalidExtraRewardCurrency,
+		);
+		assert_ok!(IncentivesModule::set_extra_reward_amount(
+			Origin::signed(ALICE),
+			PoolId::Loans(BTC),
+			DOT,
+			10
+		));
+		let update_event = TestEvent::incentives(RawEvent::ExtraRewardAmountUpdated(PoolId::Loans(BTC), DOT, 10));
+		assert!(System::events().iter().any(|record| record.event == update_event));
+		assert_eq!(
+			IncentivesModule::extra_rewards(PoolId::Loans(BTC), DOT).reward_per_period,
+			10
+		);
+
+		assert_ok!(Tokens::deposit(DOT, &CAROL, 1000));
+		assert_noop!(
+			IncentivesModule::fund_extra_rewards(Origin::signed(CAROL), PoolId::Loans(BTC), ACA, 100),
+			Error::<Runtime>::InvalidExtraRewardCurrency,
+		);
+		assert_ok!(IncentivesModule::fund_extra_rewards(
+			Origin::signed(CAROL),
+			PoolId::Loans(BTC),
+			DOT,
+			100
+		));
+		let fund_event = TestEvent::incentives(RawEvent::FundExtraRewards(CAROL, PoolId::Loans(BTC), DOT, 100));
+		assert!(System::events().iter().any(|record| record.event == fund_event));
+		assert_eq!(
+			IncentivesModule::extra_rewards(PoolId::Loans(BTC), DOT).remaining_funds,
+			100
+		);
+		assert_eq!(Tokens::free_balance(DOT, &CAROL), 900);
+		assert_eq!(
+			Tokens::free_balance(DOT, &IncentivesModule::extra_rewards_account_id()),
+			100
+		);
+	});
+}
+
+#[test]
+fn accumulate_and_claim_extra_rewards_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(IncentivesModule::set_emission_schedule(
+			Origin::ROOT,
+			EmissionScheduleInfo {
+				start: 0,
+				emission_per_period: 100,
+				decay_period: 0,
+				decay_ratio: Zero::zero(),
+			}
+		));
+		assert_ok!(IncentivesModule::set_pool_weights(
+			Origin::ROOT,
+			vec![(PoolId::Loans(BTC), 1)]
+		));
+		assert_ok!(IncentivesModule::set_extra_reward_amount(
+			Origin::ROOT,
+			PoolId::Loans(BTC),
+			DOT,
+			40
+		));
+		assert_ok!(Tokens::deposit(DOT, &CAROL, 1000));
+		assert_ok!(IncentivesModule::fund_extra_rewards(
+			Origin::signed(CAROL),
+			PoolId::Loans(BTC),
+			DOT,
+			100
+		));
+
+		// no accumulation when the pool is empty
+		IncentivesModule::on_initialize(10);
+		assert_eq!(
+			IncentivesModule::extra_rewards(PoolId::Loans(BTC), DOT).remaining_funds,
+			100
+		);
+
+		IncentivesModule::on_update_loan(&ALICE, BTC, 100, 0);
+		IncentivesModule::on_initialize(20);
+		assert_eq!(IncentivesModule::pending_rewards(&ALICE, PoolId::Loans(BTC)), 100);
+		assert_eq!(
+			IncentivesModule::pending_extra_rewards(&ALICE, PoolId::Loans(BTC), DOT),
+			40
+		);
+
+		// the new share only gets the extra rewards accumulated afterwards
+		IncentivesModule::on_update_loan(&BOB, BTC, 100, 0);
+		assert_eq!(
+			IncentivesModule::pending_extra_rewards(&BOB, PoolId::Loans(BTC), DOT),
+			0
+		);
+		IncentivesModule::on_initialize(30);
+		assert_eq!(
+			IncentivesModule::pending_extra_rewards(&ALICE, PoolId::Loans(BTC), DOT),
+			60
+		);
+		assert_eq!(
+			IncentivesModule::pending_extra_rewards(&BOB, PoolId::Loans(BTC), DOT),
+			20
+		);
+
+		// limited by the remaining funds
+		IncentivesModule::on_initialize(40);
+		assert_eq!(
+			IncentivesModule::extra_rewards(PoolId::Loans(BTC), DOT).remaining_funds,
+			0
+		);
+		assert_eq!(
+			IncentivesModule::pending_extra_rewards(&ALICE, PoolId::Loans(BTC), DOT),
+			70
+		);
+		assert_eq!(
+			IncentivesModule::pending_extra_rewards(&BOB, PoolId::Loans(BTC), DOT),
+			30
+		);
+
+		// claim rewards in all currencies
+		assert_ok!(IncentivesModule::claim_rewards(
+			Origin::signed(ALICE),
+			PoolId::Loans(BTC)
+		));
+		let claim_event = TestEvent::incentives(RawEvent::ClaimExtraRewards(ALICE, PoolId::Loans(BTC), DOT, 70));
+		assert!(System::events().iter().any(|record| record.event == claim_event));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 200);
+		assert_eq!(Tokens::free_balance(DOT, &ALICE), 70);
+		assert_eq!(
+			IncentivesModule::pending_extra_rewards(&ALICE, PoolId::Loans(BTC), DOT),
+			0
+		);
+
+		// rewards are claimed when the share is removed
+		IncentivesModule::on_update_loan(&BOB, BTC, -100, 100);
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 30);
+		assert_eq!(
+			IncentivesModule::extra_withdrawn_rewards((PoolId::Loans(BTC), BOB), DOT),
+			0
+		);
+		assert_eq!(
+			Tokens::free_balance(DOT, &IncentivesModule::extra_rewards_account_id()),
+			0
+		);
+	});
+}
+
+#[test]
+fn vest_claimed_extra_rewards_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			IncentivesModule::set_extra_reward_vesting_period(Origin::signed(BOB), DOT, 100),
+			BadOrigin
+		);
+		assert_ok!(IncentivesModule::set_extra_reward_vesting_period(
+			Origin::signed(ALICE),
+			DOT,
+			100
+		));
+		let update_event = TestEvent::incentives(RawEvent::ExtraRewardVestingPeriodUpdated(DOT, 100));
+		assert!(System::events().iter().any(|record| record.event == update_event));
+		assert_eq!(IncentivesModule::extra_reward_vesting_periods(DOT), 100);
+
+		assert_ok!(IncentivesModule::set_extra_reward_amount(
+			Origin::ROOT,
+			PoolId::Loans(BTC),
+			DOT,
+			40
+		));
+		assert_ok!(Tokens::deposit(DOT, &CAROL, 1000));
+		assert_ok!(IncentivesModule::fund_extra_rewards(
+			Origin::signed(CAROL),
+			PoolId::Loans(BTC),
+			DOT,
+			100
+		));
+		IncentivesModule::on_update_loan(&ALICE, BTC, 100, 0);
+		IncentivesModule::on_initialize(10);
+
+		System::set_block_number(15);
+		assert_ok!(IncentivesModule::claim_rewards(
+			Origin::signed(ALICE),
+			PoolId::Loans(BTC)
+		));
+		let claim_event = TestEvent::incentives(RawEvent::ClaimExtraRewards(ALICE, PoolId::Loans(BTC), DOT, 40));
+		assert!(System::events().iter().any(|record| record.event == claim_event));
+		assert_eq!(Tokens::free_balance(DOT, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(DOT, &VESTING), 40);
+		assert_eq!(VESTED.with(|v| v.borrow().clone()), vec![(ALICE, DOT, 40, 15, 100)]);
+		assert_eq!(
+			IncentivesModule::pending_extra_rewards(&ALICE, PoolId::Loans(BTC), DOT),
+			0
+		);
+	});
+}
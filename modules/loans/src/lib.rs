@@ -6,21 +6,32 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{decl_error, decl_event, decl_module, decl_storage, Parameter};
+use codec::Decode;
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	storage::{generator::StorageDoubleMap as StorageDoubleMapGenerator, StorageDoubleMap},
+	traits::Get,
+	IterableStorageDoubleMap, IterableStorageMap, Parameter,
+};
 use frame_system::{self as system};
 use orml_traits::{
 	arithmetic::{self, Signed},
-	MultiCurrency, MultiCurrencyExtended,
+	MultiCurrency, MultiCurrencyExtended, MultiReservableCurrency,
 };
 use primitives::{Amount, Balance, CurrencyId};
 use sp_runtime::{
 	traits::{
-		AccountIdConversion, AtLeast32Bit, CheckedAdd, CheckedSub, Convert, MaybeSerializeDeserialize, Member, Zero,
+		AccountIdConversion, AtLeast32Bit, CheckedAdd, CheckedSub, Convert, MaybeSerializeDeserialize, Member,
+		Saturating, Zero,
 	},
 	DispatchResult, ModuleId,
 };
-use sp_std::convert::{TryFrom, TryInto};
-use support::{CDPTreasury, RiskManager};
+use sp_std::{
+	collections::btree_map::BTreeMap,
+	convert::{TryFrom, TryInto},
+	prelude::*,
+};
+use support::{CDPTreasury, EmergencyShutdown, OnUpdateCollateral, OnUpdateLoan, RiskManager, ShutdownPhase};
 
 mod mock;
 mod tests;
@@ -34,7 +45,15 @@ pub trait Trait: system::Trait {
 	type Convert: Convert<(CurrencyId, Self::DebitBalance), Balance>;
 
 	/// Currency type for deposit/withdraw collateral assets to/from loans module
-	type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance, Amount = Amount>;
+	type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance, Amount = Amount>
+		+ MultiReservableCurrency<Self::AccountId>;
+
+	/// Native currency id, the position deposit is reserved in native currency
+	type GetNativeCurrencyId: Get<CurrencyId>;
+
+	/// The deposit reserved when a new position is created, and refunded when
+	/// the position is closed, to stop dust positions from bloating storage
+	type PositionDeposit: Get<Balance>;
 
 	/// Risk manager is used to limit the debit size of CDP
 	type RiskManager: RiskManager<Self::AccountId, CurrencyId, Balance, Self::DebitBalance>;
@@ -55,6 +74,15 @@ pub trait Trait: system::Trait {
 
 	/// CDP treasury for issuing/burning stable coin adjust debit value adjustment
 	type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+	/// Emergency shutdown phase, decides which operations of loans are allowed
+	type EmergencyShutdown: EmergencyShutdown;
+
+	/// Hook called before the debit of a loan is updated, e.g. to track the shares of incentive rewards
+	type OnUpdateLoan: OnUpdateLoan<Self::AccountId, CurrencyId, Self::DebitAmount, Self::DebitBalance>;
+
+	/// Hook called when the collateral of a loan is updated, e.g. to track the system collateralization
+	type OnUpdateCollateral: OnUpdateCollateral<Self::AccountId, CurrencyId, Amount>;
 }
 
 decl_storage! {
@@ -74,6 +102,10 @@ decl_storage! {
 		/// The total collateral asset amount, map from
 		/// CollateralType -> TotalCollateralAmount
 		pub TotalCollaterals get(fn total_collaterals): map hasher(twox_64_concat) CurrencyId => Balance;
+
+		/// The native deposit reserved for CDPs, map from
+		/// Owner -> CollateralType -> ReservedDeposit
+		pub PositionDeposits get(fn position_deposits): double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) CurrencyId => Balance;
 	}
 }
 
@@ -103,6 +135,8 @@ decl_error! {
 		CollateralOverflow,
 		CollateralTooLow,
 		AmountConvertFailed,
+		InvalidShutdownPhase,
+		PositionDepositNotEnough,
 	}
 }
 
@@ -110,6 +144,9 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		type Error = Error<T>;
 		fn deposit_event() = default;
+
+		/// The native deposit reserved when a new position is created
+		const PositionDeposit: Balance = T::PositionDeposit::get();
 	}
 }
 
@@ -125,6 +162,13 @@ impl<T: Trait> Module<T> {
 		collateral_confiscate: Balance,
 		debit_decrease: T::DebitBalance,
 	) -> DispatchResult {
+		// confiscation happens on liquidation before shutdown, or on settlement after shutdown
+		let phase = T::EmergencyShutdown::shutdown_phase();
+		ensure!(
+			phase == ShutdownPhase::Operational || phase == ShutdownPhase::Settling,
+			Error::<T>::InvalidShutdownPhase,
+		);
+
 		// balance -> amount
 		let collateral_adjustment =
 			TryInto::<Amount>::try_into(collateral_confiscate).map_err(|_| Error::<T>::AmountConvertFailed)?;
@@ -161,6 +205,13 @@ impl<T: Trait> Module<T> {
 		collateral_adjustment: Amount,
 		debit_adjustment: T::DebitAmount,
 	) -> DispatchResult {
+		// after shutdown, only allowed to withdraw collateral
+		if T::EmergencyShutdown::is_shutdown() {
+			ensure!(
+				debit_adjustment.is_zero() && !collateral_adjustment.is_positive(),
+				Error::<T>::InvalidShutdownPhase,
+			);
+		}
 		Self::check_update_loan_overflow(who, currency_id, collateral_adjustment, debit_adjustment)?;
 
 		let collateral_balance_adjustment =
@@ -192,14 +243,37 @@ impl<T: Trait> Module<T> {
 		// ensure pass risk check
 		T::RiskManager::check_position_valid(currency_id, new_collateral_balance, new_debit_balance)?;
 
+		// ensure the native collateral can still be transferred after the position deposit reserved
+		let native_currency_id = T::GetNativeCurrencyId::get();
+		let position_deposit = Self::required_position_deposit(who, currency_id, collateral_adjustment);
+		if !position_deposit.is_zero() && currency_id == native_currency_id {
+			ensure!(
+				T::Currency::can_reserve(
+					native_currency_id,
+					who,
+					position_deposit.saturating_add(collateral_balance_adjustment)
+				),
+				Error::<T>::PositionDepositNotEnough,
+			);
+		}
+
+		// reserve position deposit when open a new position, before any transfer
+		Self::reserve_position_deposit(who, currency_id, position_deposit)?;
+		let refund_on_error = |e| {
+			Self::refund_position_deposit(who, currency_id);
+			e
+		};
+
 		// update stable coin by Treasury
 		if debit_adjustment.is_positive() {
-			T::CDPTreasury::deposit_backed_debit_to(who, T::Convert::convert((currency_id, debit_balance_adjustment)))?;
+			T::CDPTreasury::deposit_backed_debit_to(who, T::Convert::convert((currency_id, debit_balance_adjustment)))
+				.map_err(refund_on_error)?;
 		} else if debit_adjustment.is_negative() {
 			T::CDPTreasury::withdraw_backed_debit_from(
 				who,
 				T::Convert::convert((currency_id, debit_balance_adjustment)),
-			)?;
+			)
+			.map_err(refund_on_error)?;
 		}
 
 		// update collateral asset
@@ -213,7 +287,7 @@ impl<T: Trait> Module<T> {
 
 		// mutate collateral and debit
 		Self::update_loan(who, currency_id, collateral_adjustment, debit_adjustment)
-			.expect("Will never fail ensured by overflow check");
+			.expect("never failed ensured by overflow check");
 
 		Self::deposit_event(RawEvent::PositionUpdated(
 			who.clone(),
@@ -226,6 +300,8 @@ impl<T: Trait> Module<T> {
 
 	// transfer whole loan of `from` to `to`
 	pub fn transfer_loan(from: &T::AccountId, to: &T::AccountId, currency_id: CurrencyId) -> DispatchResult {
+		ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::InvalidShutdownPhase);
+
 		// get `from` position data
 		let collateral_balance = Self::collaterals(from, currency_id);
 		let debit_balance = Self::debits(currency_id, from);
@@ -242,6 +318,10 @@ impl<T: Trait> Module<T> {
 		let debit_adjustment =
 			TryInto::<T::DebitAmount>::try_into(debit_balance).map_err(|_| Error::<T>::AmountConvertFailed)?;
 
+		// `to` must afford the position deposit if it opens a new position
+		let position_deposit = Self::required_position_deposit(to, currency_id, collateral_adjustment);
+		Self::reserve_position_deposit(to, currency_id, position_deposit)?;
+
 		Self::update_loan(from, currency_id, -collateral_adjustment, -debit_adjustment)?;
 		Self::update_loan(to, currency_id, collateral_adjustment, debit_adjustment)?;
 
@@ -284,6 +364,122 @@ impl<T: Trait> Module<T> {
 		Ok(())
 	}
 
+	/// Enumerate the positions with debit under `currency_id` in storage order, at most `limit` entries
+	/// starting from `start` (inclusive). Returns the (owner, collateral, debit) of positions and the
+	/// start of next page, `None` if there's no more.
+	pub fn positions_page(
+		currency_id: CurrencyId,
+		start: Option<T::AccountId>,
+		limit: u32,
+	) -> (Vec<(T::AccountId, Balance, T::DebitBalance)>, Option<T::AccountId>) {
+		let prefix = <Debits<T> as StorageDoubleMapGenerator<_, _, _>>::storage_double_map_final_key1(currency_id);
+		let mut positions = vec![];
+		let mut next = match start {
+			Some(who) => Some(<Debits<T>>::hashed_key_for(currency_id, who)),
+			None => sp_io::storage::next_key(&prefix),
+		};
+
+		while let Some(key) = next.take().filter(|key| key.starts_with(&prefix)) {
+			// the key of owner is twox_64_concat encoded, skip the 8 bytes hash
+			let who = match key
+				.get(prefix.len() + 8..)
+				.and_then(|mut raw| T::AccountId::decode(&mut raw).ok())
+			{
+				Some(who) => who,
+				None => break,
+			};
+			if positions.len() as u32 >= limit {
+				return (positions, Some(who));
+			}
+
+			let debit = Self::debits(currency_id, &who);
+			if !debit.is_zero() {
+				positions.push((who.clone(), Self::collaterals(&who, currency_id), debit));
+			}
+			next = sp_io::storage::next_key(&key);
+		}
+
+		(positions, None)
+	}
+
+	/// Check the accounting invariants of loans: the total debits and total collaterals equal
+	/// the sum of positions, and the module account holds the total collaterals.
+	pub fn check_invariants() -> Result<(), &'static str> {
+		let mut debits: BTreeMap<CurrencyId, T::DebitBalance> = BTreeMap::new();
+		for (currency_id, _, debit) in <Debits<T>>::iter() {
+			let total = debits.entry(currency_id).or_default();
+			*total = total.saturating_add(debit);
+		}
+		for (currency_id, total_debit) in <TotalDebits<T>>::iter() {
+			ensure!(
+				debits.remove(&currency_id).unwrap_or_default() == total_debit,
+				"total debits not equal to the sum of debits"
+			);
+		}
+		ensure!(
+			debits.values().all(|debit| debit.is_zero()),
+			"total debits not equal to the sum of debits"
+		);
+
+		let mut collaterals: BTreeMap<CurrencyId, Balance> = BTreeMap::new();
+		for (_, currency_id, collateral) in <Collaterals<T>>::iter() {
+			let total = collaterals.entry(currency_id).or_default();
+			*total = total.saturating_add(collateral);
+		}
+		for (currency_id, total_collateral) in TotalCollaterals::iter() {
+			ensure!(
+				collaterals.remove(&currency_id).unwrap_or_default() == total_collateral,
+				"total collaterals not equal to the sum of collaterals"
+			);
+			ensure!(
+				T::Currency::free_balance(currency_id, &Self::account_id()) >= total_collateral,
+				"loans account not holding total collaterals"
+			);
+		}
+		ensure!(
+			collaterals.values().all(|collateral| collateral.is_zero()),
+			"total collaterals not equal to the sum of collaterals"
+		);
+
+		Ok(())
+	}
+
+	/// The position deposit required when adjust collateral of `who`,
+	/// non-zero only when a new position entry will be created
+	pub fn required_position_deposit(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		collateral_adjustment: Amount,
+	) -> Balance {
+		if collateral_adjustment.is_positive() && Self::collaterals(who, currency_id).is_zero() {
+			T::PositionDeposit::get()
+		} else {
+			Zero::zero()
+		}
+	}
+
+	/// Reserve the position deposit of `who` for the new position under the collateral type
+	fn reserve_position_deposit(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		position_deposit: Balance,
+	) -> DispatchResult {
+		if !position_deposit.is_zero() {
+			T::Currency::reserve(T::GetNativeCurrencyId::get(), who, position_deposit)
+				.map_err(|_| Error::<T>::PositionDepositNotEnough)?;
+			<PositionDeposits<T>>::insert(who, currency_id, position_deposit);
+		}
+		Ok(())
+	}
+
+	/// Refund the position deposit of `who` under the collateral type
+	fn refund_position_deposit(who: &T::AccountId, currency_id: CurrencyId) {
+		let position_deposit = <PositionDeposits<T>>::take(who, currency_id);
+		if !position_deposit.is_zero() {
+			T::Currency::unreserve(T::GetNativeCurrencyId::get(), who, position_deposit);
+		}
+	}
+
 	fn update_loan(
 		who: &T::AccountId,
 		currency_id: CurrencyId,
@@ -311,15 +507,22 @@ impl<T: Trait> Module<T> {
 		} else if collateral_adjustment.is_negative() {
 			<Collaterals<T>>::mutate(who, currency_id, |balance| {
 				*balance -= collateral_balance;
-				// decrease account ref for who when has no amount
+				// decrease account ref for who and refund position deposit when has no amount
 				if balance.is_zero() {
 					system::Module::<T>::dec_ref(who);
+					Self::refund_position_deposit(who, currency_id);
 				}
 			});
 			TotalCollaterals::mutate(currency_id, |balance| *balance -= collateral_balance);
 		}
+		if !collateral_adjustment.is_zero() {
+			T::OnUpdateCollateral::on_update_collateral(who, currency_id, collateral_adjustment);
+		}
 
 		// update debit record
+		if !debit_adjustment.is_zero() {
+			T::OnUpdateLoan::on_update_loan(who, currency_id, debit_adjustment, Self::debits(currency_id, who));
+		}
 		if debit_adjustment.is_positive() {
 			<Debits<T>>::mutate(currency_id, who, |balance| *balance += debit_balance);
 			<TotalDebits<T>>::mutate(currency_id, |balance| *balance += debit_balance);
@@ -7,7 +7,8 @@ use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, pa
 use frame_system::EnsureSignedBy;
 use sp_core::H256;
 use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
-use support::{AuctionManager, RiskManager};
+use sp_std::cell::RefCell;
+use support::{AuctionManager, EmergencyShutdown, RiskManager, ShutdownPhase};
 
 pub type AccountId = u128;
 pub type AuctionId = u64;
@@ -170,9 +171,21 @@ impl cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = ();
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = MockEmergencyShutdown;
 }
 pub type CDPTreasuryModule = cdp_treasury::Module<Runtime>;
 
+thread_local! {
+	pub static SHUTDOWN_PHASE: RefCell<ShutdownPhase> = RefCell::new(ShutdownPhase::Operational);
+}
+
+pub struct MockEmergencyShutdown;
+impl EmergencyShutdown for MockEmergencyShutdown {
+	fn shutdown_phase() -> ShutdownPhase {
+		SHUTDOWN_PHASE.with(|v| *v.borrow())
+	}
+}
+
 // mock convert
 pub struct MockConvert;
 impl Convert<(CurrencyId, DebitBalance), Balance> for MockConvert {
@@ -209,6 +222,10 @@ impl RiskManager<AccountId, CurrencyId, Balance, DebitBalance> for MockRiskManag
 	}
 }
 
+parameter_types! {
+	pub const PositionDeposit: Balance = 10;
+}
+
 impl Trait for Runtime {
 	type Event = TestEvent;
 	type Convert = MockConvert;
@@ -217,6 +234,11 @@ impl Trait for Runtime {
 	type DebitBalance = DebitBalance;
 	type DebitAmount = DebitAmount;
 	type CDPTreasury = CDPTreasuryModule;
+	type EmergencyShutdown = MockEmergencyShutdown;
+	type OnUpdateLoan = ();
+	type OnUpdateCollateral = ();
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type PositionDeposit = PositionDeposit;
 }
 pub type LoansModule = Module<Runtime>;
 
@@ -240,6 +262,11 @@ impl Default for ExtBuilder {
 impl ExtBuilder {
 	pub fn build(self) -> sp_io::TestExternalities {
 		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(ALICE, 1000), (BOB, 1000)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
 		orml_tokens::GenesisConfig::<Runtime> {
 			endowed_accounts: self.endowed_accounts,
 		}
@@ -5,7 +5,8 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::{
-	CDPTreasuryModule, Currencies, ExtBuilder, LoansModule, Runtime, System, TestEvent, ALICE, AUSD, BOB, BTC, DOT,
+	CDPTreasuryModule, Currencies, ExtBuilder, LoansModule, Runtime, System, TestEvent, ACA, ALICE, AUSD, BOB, BTC,
+	DOT, SHUTDOWN_PHASE,
 };
 
 #[test]
@@ -153,3 +154,177 @@ fn confiscate_collateral_and_debit_work() {
 		assert!(System::events().iter().any(|record| record.event == confiscate_event));
 	});
 }
+
+#[test]
+fn adjust_position_after_shutdown() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 500, 300));
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
+
+		assert_noop!(
+			LoansModule::adjust_position(&ALICE, BTC, 100, 0),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+		assert_noop!(
+			LoansModule::adjust_position(&ALICE, BTC, 0, -100),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, -100, 0));
+		assert_eq!(LoansModule::collaterals(&ALICE, BTC), 400);
+		assert_noop!(
+			LoansModule::transfer_loan(&ALICE, &BOB, BTC),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+	});
+}
+
+#[test]
+fn confiscate_collateral_and_debit_only_when_operational_or_settling() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 500, 300));
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Frozen);
+		assert_noop!(
+			LoansModule::confiscate_collateral_and_debit(&ALICE, BTC, 300, 200),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::Settling);
+		assert_ok!(LoansModule::confiscate_collateral_and_debit(&ALICE, BTC, 300, 200));
+		assert_eq!(LoansModule::debits(BTC, &ALICE), 100);
+
+		SHUTDOWN_PHASE.with(|v| *v.borrow_mut() = ShutdownPhase::ClaimsOpen);
+		assert_noop!(
+			LoansModule::confiscate_collateral_and_debit(&ALICE, BTC, 100, 100),
+			Error::<Runtime>::InvalidShutdownPhase,
+		);
+	});
+}
+
+#[test]
+fn position_deposit_reserved_and_refunded() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 1000);
+
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 500, 300));
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 990);
+		assert_eq!(Currencies::reserved_balance(ACA, &ALICE), 10);
+		assert_eq!(LoansModule::position_deposits(&ALICE, BTC), 10);
+
+		// only charged once for the same position
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 0));
+		assert_eq!(Currencies::reserved_balance(ACA, &ALICE), 10);
+
+		// refunded when the position is closed
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, -600, -300));
+		assert_eq!(Currencies::free_balance(ACA, &ALICE), 1000);
+		assert_eq!(Currencies::reserved_balance(ACA, &ALICE), 0);
+		assert_eq!(LoansModule::position_deposits(&ALICE, BTC), 0);
+	});
+}
+
+#[test]
+fn position_deposit_not_enough() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Currencies::transfer(Some(ALICE).into(), BOB, ACA, 995));
+		assert_noop!(
+			LoansModule::adjust_position(&ALICE, BTC, 500, 300),
+			Error::<Runtime>::PositionDepositNotEnough,
+		);
+	});
+}
+
+#[test]
+fn transfer_loan_moves_position_deposit() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 500, 300));
+		assert_eq!(Currencies::reserved_balance(ACA, &ALICE), 10);
+
+		assert_ok!(LoansModule::transfer_loan(&ALICE, &BOB, BTC));
+		assert_eq!(Currencies::reserved_balance(ACA, &ALICE), 0);
+		assert_eq!(Currencies::reserved_balance(ACA, &BOB), 10);
+		assert_eq!(LoansModule::position_deposits(&BOB, BTC), 10);
+	});
+}
+
+#[test]
+fn transfer_loan_require_position_deposit() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 500, 300));
+		assert_ok!(Currencies::transfer(Some(BOB).into(), ALICE, ACA, 995));
+		assert_noop!(
+			LoansModule::transfer_loan(&ALICE, &BOB, BTC),
+			Error::<Runtime>::PositionDepositNotEnough,
+		);
+	});
+}
+
+#[test]
+fn positions_page_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(LoansModule::positions_page(BTC, None, 10), (vec![], None));
+
+		for who in 1..=5u128 {
+			Debits::<Runtime>::insert(BTC, who, 100 * who);
+			Collaterals::<Runtime>::insert(who, BTC, 200 * who);
+		}
+		// positions without debit are skipped
+		Debits::<Runtime>::insert(BTC, 6u128, 0);
+		Debits::<Runtime>::insert(DOT, ALICE, 100);
+
+		let mut positions = vec![];
+		let mut start = None;
+		loop {
+			let (page, next) = LoansModule::positions_page(BTC, start, 2);
+			assert!(page.len() <= 2);
+			positions.extend(page);
+			match next {
+				Some(who) => start = Some(who),
+				None => break,
+			}
+		}
+		positions.sort();
+		assert_eq!(
+			positions,
+			(1..=5u128).map(|who| (who, 200 * who, 100 * who)).collect::<Vec<_>>()
+		);
+
+		// the page is stable with the same start
+		let (first_page, next) = LoansModule::positions_page(BTC, None, 2);
+		assert_eq!(LoansModule::positions_page(BTC, None, 2), (first_page, next));
+		let (_, next_of_next) = LoansModule::positions_page(BTC, next, 2);
+		assert_eq!(LoansModule::positions_page(BTC, next, 2).1, next_of_next);
+	});
+}
+
+#[test]
+fn check_invariants_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::check_invariants());
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_ok!(LoansModule::adjust_position(&BOB, BTC, 200, 100));
+		assert_ok!(LoansModule::check_invariants());
+
+		TotalDebits::<Runtime>::insert(BTC, 100);
+		assert_eq!(
+			LoansModule::check_invariants(),
+			Err("total debits not equal to the sum of debits")
+		);
+		TotalDebits::<Runtime>::insert(BTC, 150);
+		assert_ok!(LoansModule::check_invariants());
+
+		TotalCollaterals::insert(BTC, 301);
+		assert_eq!(
+			LoansModule::check_invariants(),
+			Err("total collaterals not equal to the sum of collaterals")
+		);
+		TotalCollaterals::insert(BTC, 300);
+		assert_ok!(LoansModule::check_invariants());
+
+		Collaterals::<Runtime>::insert(ALICE, DOT, 10);
+		TotalCollaterals::insert(DOT, 10);
+		assert_eq!(
+			LoansModule::check_invariants(),
+			Err("loans account not holding total collaterals")
+		);
+	});
+}
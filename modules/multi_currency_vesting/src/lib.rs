@@ -0,0 +1,299 @@
+//! # Multi Currency Vesting Module
+//!
+//! ## Overview
+//!
+//! Vesting schedules of any currency, created through `MultiCurrencyVesting` by other modules such as
+//! incentives and airdrop to pay out rewards over time. The vesting funds are kept in the module account
+//! and released linearly over the period of schedule, the recipient claims the vested amount at any time.
+//! Governance can revoke the schedules of an account: the vested amount is paid to the account and
+//! the unvested amount is returned to the funder of each schedule.
+//!
+//! The vested transfers of the same currency, funder and period to an account accumulate in one schedule,
+//! so that recurring rewards don't run into `MaxVestingSchedules`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{EnsureOrigin, Get},
+	weights::constants::WEIGHT_PER_MICROS,
+};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, UniqueSaturatedInto, Zero},
+	DispatchResult, FixedPointNumber, ModuleId, RuntimeDebug,
+};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+use support::{MultiCurrencyVesting, Ratio};
+
+mod mock;
+mod tests;
+
+const MODULE_ID: ModuleId = ModuleId(*b"aca/vest");
+
+/// The schedule to release `total` amount of currency linearly over `period` blocks from `start`
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct VestingSchedule<AccountId, BlockNumber> {
+	/// The vesting currency
+	pub currency_id: CurrencyId,
+
+	/// The account funded the schedule, the unvested amount is returned to it on revoke
+	pub funder: AccountId,
+
+	/// The block the vesting starts
+	pub start: BlockNumber,
+
+	/// The blocks to release the total amount, zero means vested at `start`
+	pub period: BlockNumber,
+
+	/// Total amount to release
+	pub total: Balance,
+
+	/// The amount claimed already
+	pub claimed: Balance,
+}
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency of vesting funds
+	type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+	/// The maximum number of vesting schedules of an account
+	type MaxVestingSchedules: Get<u32>;
+
+	/// The origin which may revoke vesting schedules. Root can always do this.
+	type RevokeOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		<T as system::Trait>::AccountId,
+		<T as system::Trait>::BlockNumber,
+		CurrencyId = CurrencyId,
+		Balance = Balance,
+	{
+		/// A vesting schedule created (funder, recipient, currency_id, amount, start, period)
+		VestingScheduleAdded(AccountId, AccountId, CurrencyId, Balance, BlockNumber, BlockNumber),
+		/// Vested amount claimed (who, currency_id, amount)
+		Claimed(AccountId, CurrencyId, Balance),
+		/// The vesting schedules of account revoked (who)
+		VestingSchedulesRevoked(AccountId),
+	}
+);
+
+decl_error! {
+	/// Error for multi currency vesting module.
+	pub enum Error for Module<T: Trait> {
+		/// The vesting amount is zero
+		ZeroVestingAmount,
+		/// The account has `MaxVestingSchedules` vesting schedules already
+		TooManyVestingSchedules,
+		/// The account has no vesting schedule
+		NoVestingSchedule,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as MultiCurrencyVesting {
+		/// The vesting schedules of accounts
+		pub VestingSchedules get(fn vesting_schedules): map hasher(twox_64_concat) T::AccountId => Vec<VestingSchedule<T::AccountId, T::BlockNumber>>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// The maximum number of vesting schedules of an account
+		const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+
+		/// Claim the vested amount of all vesting schedules of caller.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// # <weight>
+		/// - Complexity: `O(N)` where N is the number of vesting schedules, which is capped by `MaxVestingSchedules`
+		/// - Db reads: `VestingSchedules`, 2 * N items of orml_currencies
+		/// - Db writes: `VestingSchedules`, 2 * N items of orml_currencies
+		/// -------------------
+		/// Base Weight: 30 + 25 * N µs
+		/// # </weight>
+		#[weight = (30 + 25 * T::MaxVestingSchedules::get() as u64) * WEIGHT_PER_MICROS
+			+ T::DbWeight::get().reads_writes(1 + 2 * T::MaxVestingSchedules::get() as u64, 1 + 2 * T::MaxVestingSchedules::get() as u64)]
+		pub fn claim(origin) {
+			let who = ensure_signed(origin)?;
+			ensure!(<VestingSchedules<T>>::contains_key(&who), Error::<T>::NoVestingSchedule);
+			let now = <system::Module<T>>::block_number();
+
+			let mut schedules = Self::vesting_schedules(&who);
+			for schedule in schedules.iter_mut() {
+				let amount = Self::vested_amount(schedule, now).saturating_sub(schedule.claimed);
+				// keep the amount vesting if failed to pay out
+				if !amount.is_zero() && T::Currency::transfer(schedule.currency_id, &Self::account_id(), &who, amount).is_ok() {
+					schedule.claimed = schedule.claimed.saturating_add(amount);
+					Self::deposit_event(RawEvent::Claimed(who.clone(), schedule.currency_id, amount));
+				}
+			}
+
+			schedules.retain(|schedule| schedule.claimed < schedule.total);
+			if schedules.is_empty() {
+				<VestingSchedules<T>>::remove(&who);
+			} else {
+				<VestingSchedules<T>>::insert(&who, schedules);
+			}
+		}
+
+		/// Revoke all vesting schedules of an account, pay the vested amount to it
+		/// and return the unvested amount to the funders.
+		///
+		/// The dispatch origin of this call must be `RevokeOrigin` or _Root_.
+		///
+		/// - `who`: the account whose vesting schedules to revoke.
+		///
+		/// # <weight>
+		/// - Complexity: `O(N)` where N is the number of vesting schedules, which is capped by `MaxVestingSchedules`
+		/// - Db reads: `VestingSchedules`, 3 * N items of orml_currencies
+		/// - Db writes: `VestingSchedules`, 3 * N items of orml_currencies
+		/// -------------------
+		/// Base Weight: 30 + 40 * N µs
+		/// # </weight>
+		#[weight = (30 + 40 * T::MaxVestingSchedules::get() as u64) * WEIGHT_PER_MICROS
+			+ T::DbWeight::get().reads_writes(1 + 3 * T::MaxVestingSchedules::get() as u64, 1 + 3 * T::MaxVestingSchedules::get() as u64)]
+		pub fn revoke(origin, who: T::AccountId) {
+			T::RevokeOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			let schedules = Self::vesting_schedules(&who);
+			ensure!(!schedules.is_empty(), Error::<T>::NoVestingSchedule);
+			let now = <system::Module<T>>::block_number();
+			let module_account = Self::account_id();
+
+			// check the module account can pay all the schedules before transferring any of them
+			let mut totals: BTreeMap<CurrencyId, Balance> = BTreeMap::new();
+			for schedule in schedules.iter() {
+				let total = totals.entry(schedule.currency_id).or_default();
+				*total = total.saturating_add(schedule.total.saturating_sub(schedule.claimed));
+			}
+			for (currency_id, total) in totals {
+				T::Currency::ensure_can_withdraw(currency_id, &module_account, total)?;
+			}
+
+			for schedule in schedules {
+				let vested = Self::vested_amount(&schedule, now);
+				let unclaimed = vested.saturating_sub(schedule.claimed);
+				let unvested = schedule.total.saturating_sub(vested);
+				T::Currency::transfer(schedule.currency_id, &module_account, &who, unclaimed)?;
+				T::Currency::transfer(schedule.currency_id, &module_account, &schedule.funder, unvested)?;
+			}
+			<VestingSchedules<T>>::remove(&who);
+
+			Self::deposit_event(RawEvent::VestingSchedulesRevoked(who));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	pub fn account_id() -> T::AccountId {
+		MODULE_ID.into_account()
+	}
+
+	/// The amount of vesting schedule vested at block `now`, including the claimed amount
+	pub fn vested_amount(schedule: &VestingSchedule<T::AccountId, T::BlockNumber>, now: T::BlockNumber) -> Balance {
+		if now < schedule.start {
+			return Zero::zero();
+		}
+		let elapsed = now.saturating_sub(schedule.start);
+		if elapsed >= schedule.period {
+			return schedule.total;
+		}
+
+		let elapsed: Balance = elapsed.unique_saturated_into();
+		let period: Balance = schedule.period.unique_saturated_into();
+		Ratio::checked_from_rational(elapsed, period)
+			.unwrap_or_default()
+			.saturating_mul_int(schedule.total)
+	}
+
+	/// The amount of all vesting schedules of `who` in `currency_id` can be claimed now
+	pub fn claimable_amount(who: &T::AccountId, currency_id: CurrencyId) -> Balance {
+		let now = <system::Module<T>>::block_number();
+		Self::vesting_schedules(who)
+			.iter()
+			.filter(|schedule| schedule.currency_id == currency_id)
+			.fold(Zero::zero(), |amount: Balance, schedule| {
+				amount.saturating_add(Self::vested_amount(schedule, now).saturating_sub(schedule.claimed))
+			})
+	}
+}
+
+impl<T: Trait> MultiCurrencyVesting<T::AccountId, CurrencyId, Balance, T::BlockNumber> for Module<T> {
+	/// Merge into the schedule of the same currency, funder and period if any: its vested amount is paid
+	/// out, and the unvested amount vests again together with `amount` from `start`.
+	fn vested_transfer(
+		currency_id: CurrencyId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: Balance,
+		start: T::BlockNumber,
+		period: T::BlockNumber,
+	) -> DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::ZeroVestingAmount);
+		let mut schedules = Self::vesting_schedules(to);
+		let merged = schedules.iter().position(|schedule| {
+			schedule.currency_id == currency_id && schedule.funder == *from && schedule.period == period
+		});
+		ensure!(
+			merged.is_some() || (schedules.len() as u32) < T::MaxVestingSchedules::get(),
+			Error::<T>::TooManyVestingSchedules,
+		);
+
+		let module_account = Self::account_id();
+		T::Currency::transfer(currency_id, from, &module_account, amount)?;
+		if let Some(index) = merged {
+			let schedule = &mut schedules[index];
+			let vested = Self::vested_amount(schedule, <system::Module<T>>::block_number());
+			let mut unclaimed = vested.saturating_sub(schedule.claimed);
+			// keep the amount vesting if failed to pay out
+			if !unclaimed.is_zero() && T::Currency::transfer(currency_id, &module_account, to, unclaimed).is_ok() {
+				Self::deposit_event(RawEvent::Claimed(to.clone(), currency_id, unclaimed));
+			} else {
+				unclaimed = Zero::zero();
+			}
+
+			schedule.total = schedule
+				.total
+				.saturating_sub(schedule.claimed)
+				.saturating_sub(unclaimed)
+				.saturating_add(amount);
+			schedule.claimed = Zero::zero();
+			schedule.start = start;
+		} else {
+			schedules.push(VestingSchedule {
+				currency_id,
+				funder: from.clone(),
+				start,
+				period,
+				total: amount,
+				claimed: Zero::zero(),
+			});
+		}
+		<VestingSchedules<T>>::insert(to, schedules);
+
+		Self::deposit_event(RawEvent::VestingScheduleAdded(
+			from.clone(),
+			to.clone(),
+			currency_id,
+			amount,
+			start,
+			period,
+		));
+		Ok(())
+	}
+}
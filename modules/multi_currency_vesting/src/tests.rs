@@ -0,0 +1,183 @@
+//! Unit tests for the multi currency vesting module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{
+	ExtBuilder, MultiCurrencyVestingModule, Origin, Runtime, System, TestEvent, Tokens, ALICE, AUSD, BOB, CAROL, DOT,
+};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn vested_transfer_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			MultiCurrencyVestingModule::vested_transfer(AUSD, &ALICE, &BOB, 0, 10, 100),
+			Error::<Runtime>::ZeroVestingAmount,
+		);
+		assert!(MultiCurrencyVestingModule::vested_transfer(AUSD, &ALICE, &BOB, 2000, 10, 100).is_err());
+
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			AUSD, &ALICE, &BOB, 500, 10, 100
+		));
+		let added_event =
+			TestEvent::multi_currency_vesting(RawEvent::VestingScheduleAdded(ALICE, BOB, AUSD, 500, 10, 100));
+		assert!(System::events().iter().any(|record| record.event == added_event));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 500);
+		assert_eq!(
+			Tokens::free_balance(AUSD, &MultiCurrencyVestingModule::account_id()),
+			500
+		);
+		assert_eq!(
+			MultiCurrencyVestingModule::vesting_schedules(BOB),
+			vec![VestingSchedule {
+				currency_id: AUSD,
+				funder: ALICE,
+				start: 10,
+				period: 100,
+				total: 500,
+				claimed: 0,
+			}]
+		);
+
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			DOT, &ALICE, &BOB, 100, 10, 0
+		));
+		assert_noop!(
+			MultiCurrencyVestingModule::vested_transfer(DOT, &ALICE, &BOB, 100, 10, 1),
+			Error::<Runtime>::TooManyVestingSchedules,
+		);
+	});
+}
+
+#[test]
+fn vested_transfer_merge_into_existing_schedule() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			AUSD, &ALICE, &BOB, 500, 10, 100
+		));
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			DOT, &ALICE, &BOB, 100, 10, 100
+		));
+
+		// 100 vested and claimed
+		System::set_block_number(30);
+		assert_ok!(MultiCurrencyVestingModule::claim(Origin::signed(BOB)));
+		System::set_block_number(50);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 100);
+
+		// the vested 100 is paid out, the unvested 300 vests again with the new 100
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			AUSD, &ALICE, &BOB, 100, 50, 100
+		));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 200);
+		assert_eq!(
+			Tokens::free_balance(AUSD, &MultiCurrencyVestingModule::account_id()),
+			400
+		);
+		assert_eq!(MultiCurrencyVestingModule::vesting_schedules(BOB).len(), 2);
+		assert_eq!(
+			MultiCurrencyVestingModule::vesting_schedules(BOB)[0],
+			VestingSchedule {
+				currency_id: AUSD,
+				funder: ALICE,
+				start: 50,
+				period: 100,
+				total: 400,
+				claimed: 0,
+			}
+		);
+
+		System::set_block_number(150);
+		assert_ok!(MultiCurrencyVestingModule::claim(Origin::signed(BOB)));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 600);
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 100);
+	});
+}
+
+#[test]
+fn claim_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			MultiCurrencyVestingModule::claim(Origin::signed(BOB)),
+			Error::<Runtime>::NoVestingSchedule,
+		);
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			AUSD, &ALICE, &BOB, 500, 10, 100
+		));
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			DOT, &ALICE, &BOB, 100, 20, 0
+		));
+
+		// not started
+		assert_ok!(MultiCurrencyVestingModule::claim(Origin::signed(BOB)));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 0);
+
+		System::set_block_number(30);
+		assert_eq!(MultiCurrencyVestingModule::claimable_amount(&BOB, AUSD), 100);
+		assert_eq!(MultiCurrencyVestingModule::claimable_amount(&BOB, DOT), 100);
+		assert_ok!(MultiCurrencyVestingModule::claim(Origin::signed(BOB)));
+		let claimed_event = TestEvent::multi_currency_vesting(RawEvent::Claimed(BOB, AUSD, 100));
+		assert!(System::events().iter().any(|record| record.event == claimed_event));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 100);
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 100);
+		assert_eq!(MultiCurrencyVestingModule::vesting_schedules(BOB).len(), 1);
+		assert_eq!(MultiCurrencyVestingModule::vesting_schedules(BOB)[0].claimed, 100);
+
+		System::set_block_number(200);
+		assert_ok!(MultiCurrencyVestingModule::claim(Origin::signed(BOB)));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 500);
+		assert_eq!(Tokens::free_balance(AUSD, &MultiCurrencyVestingModule::account_id()), 0);
+		assert!(!<VestingSchedules<Runtime>>::contains_key(BOB));
+	});
+}
+
+#[test]
+fn revoke_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			AUSD, &ALICE, &BOB, 500, 10, 100
+		));
+		assert_noop!(MultiCurrencyVestingModule::revoke(Origin::signed(BOB), BOB), BadOrigin);
+		assert_noop!(
+			MultiCurrencyVestingModule::revoke(Origin::signed(ALICE), CAROL),
+			Error::<Runtime>::NoVestingSchedule,
+		);
+
+		System::set_block_number(30);
+		assert_ok!(MultiCurrencyVestingModule::claim(Origin::signed(BOB)));
+		System::set_block_number(50);
+		assert_ok!(MultiCurrencyVestingModule::revoke(Origin::signed(ALICE), BOB));
+		let revoked_event = TestEvent::multi_currency_vesting(RawEvent::VestingSchedulesRevoked(BOB));
+		assert!(System::events().iter().any(|record| record.event == revoked_event));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 200);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 800);
+		assert_eq!(Tokens::free_balance(AUSD, &MultiCurrencyVestingModule::account_id()), 0);
+		assert!(!<VestingSchedules<Runtime>>::contains_key(BOB));
+	});
+}
+
+#[test]
+fn revoke_nothing_when_module_account_cannot_pay() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			AUSD, &ALICE, &BOB, 500, 10, 100
+		));
+		assert_ok!(MultiCurrencyVestingModule::vested_transfer(
+			DOT, &ALICE, &BOB, 100, 10, 100
+		));
+		assert_ok!(Tokens::withdraw(DOT, &MultiCurrencyVestingModule::account_id(), 1));
+
+		System::set_block_number(50);
+		assert_noop!(
+			MultiCurrencyVestingModule::revoke(Origin::signed(ALICE), BOB),
+			orml_tokens::Error::<Runtime>::BalanceTooLow,
+		);
+		assert_eq!(MultiCurrencyVestingModule::vesting_schedules(BOB).len(), 2);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 0);
+	});
+}
@@ -3,18 +3,18 @@
 use codec::{Decode, Encode};
 use frame_support::{
 	decl_error, decl_module, decl_storage, ensure,
-	traits::{Get, LockIdentifier},
+	traits::{EnsureOrigin, Get, LockIdentifier},
 	IterableStorageMap, Parameter,
 };
-use frame_system::{self as system, ensure_signed};
+use frame_system::{self as system, ensure_root, ensure_signed};
 use orml_traits::{BasicCurrency, BasicLockableCurrency};
 use primitives::{Balance, EraIndex};
 use sp_runtime::{
 	traits::{MaybeDisplay, MaybeSerializeDeserialize, Member, Zero},
-	RuntimeDebug,
+	FixedPointNumber, RuntimeDebug,
 };
 use sp_std::{fmt::Debug, prelude::*};
-use support::{NomineesProvider, OnNewEra};
+use support::{NomineesProvider, OnNewEra, Ratio};
 
 mod mock;
 mod tests;
@@ -93,6 +93,18 @@ impl BondingLedger {
 	}
 }
 
+/// The strategy to select nominees from the validators voted by bonders.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, Default)]
+pub struct NominationStrategy {
+	/// Only the validators in whitelist can be nominated.
+	pub whitelist_only: bool,
+	/// The validators whose performance is below this are rotated out.
+	pub min_performance: Ratio,
+	/// The max votes counted for every validator, `None` means no cap. Validators are ranked
+	/// by the capped votes weighted by performance.
+	pub max_votes_per_validator: Option<Balance>,
+}
+
 pub trait Trait: system::Trait {
 	type Currency: BasicLockableCurrency<Self::AccountId, Moment = Self::BlockNumber, Balance = Balance>;
 	type PolkadotAccountId: Parameter + Member + MaybeSerializeDeserialize + Debug + MaybeDisplay + Ord + Default;
@@ -100,6 +112,10 @@ pub trait Trait: system::Trait {
 	type BondingDuration: Get<EraIndex>;
 	type NominateesCount: Get<usize>;
 	type MaxUnlockingChunks: Get<usize>;
+
+	/// The origin which may update the nomination strategy, whitelist and validator performance.
+	/// Root can always do this.
+	type UpdateOrigin: EnsureOrigin<Self::Origin>;
 }
 
 decl_error! {
@@ -120,6 +136,15 @@ decl_storage! {
 		pub Votes get(fn votes): map hasher(twox_64_concat) T::PolkadotAccountId => Balance;
 		pub Nominees get(fn nominees): Vec<T::PolkadotAccountId>;
 		pub CurrentEra get(fn current_era): EraIndex;
+
+		/// The strategy to select nominees
+		pub Strategy get(fn strategy): NominationStrategy;
+
+		/// The whitelist of validators
+		pub Whitelist get(fn whitelist): map hasher(twox_64_concat) T::PolkadotAccountId => bool;
+
+		/// The latest performance of validators, e.g. the ratio of era points to the average
+		pub ValidatorPerformance get(fn validator_performance): map hasher(twox_64_concat) T::PolkadotAccountId => Option<Ratio>;
 	}
 }
 
@@ -246,6 +271,50 @@ decl_module! {
 			Self::update_votes(old_active, &old_nominations, Zero::zero(), &[]);
 			<Nominations<T>>::remove(&who);
 		}
+
+		/// Update the strategy to select nominees.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		#[weight = 10_000]
+		pub fn set_nomination_strategy(origin, strategy: NominationStrategy) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			Strategy::put(strategy);
+		}
+
+		/// Add validators to and remove validators from whitelist.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		#[weight = 10_000]
+		pub fn update_whitelist(origin, add: Vec<T::PolkadotAccountId>, remove: Vec<T::PolkadotAccountId>) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			for validator in add {
+				<Whitelist<T>>::insert(validator, true);
+			}
+			for validator in remove {
+				<Whitelist<T>>::remove(validator);
+			}
+		}
+
+		/// Update the performance of validators, `None` removes the record.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		#[weight = 10_000]
+		pub fn set_validator_performance(origin, updates: Vec<(T::PolkadotAccountId, Option<Ratio>)>) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			for (validator, performance) in updates {
+				if let Some(performance) = performance {
+					<ValidatorPerformance<T>>::insert(validator, performance);
+				} else {
+					<ValidatorPerformance<T>>::remove(validator);
+				}
+			}
+		}
 	}
 }
 
@@ -280,8 +349,27 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
+	/// Whether the validator can be nominated under the strategy, the validator without
+	/// performance record is considered to be qualified.
+	fn is_eligible(strategy: &NominationStrategy, validator: &T::PolkadotAccountId) -> bool {
+		(!strategy.whitelist_only || Self::whitelist(validator))
+			&& Self::validator_performance(validator)
+				.map_or(true, |performance| performance >= strategy.min_performance)
+	}
+
 	fn rebalance() {
-		let mut voters = <Votes<T>>::iter().collect::<Vec<(T::PolkadotAccountId, Balance)>>();
+		let strategy = Self::strategy();
+		let mut voters = <Votes<T>>::iter()
+			.filter(|(validator, _)| Self::is_eligible(&strategy, validator))
+			.map(|(validator, votes)| {
+				// rank by the capped votes weighted by performance
+				let votes = strategy.max_votes_per_validator.map_or(votes, |cap| votes.min(cap));
+				let score = Self::validator_performance(&validator)
+					.unwrap_or_else(Ratio::one)
+					.saturating_mul_int(votes);
+				(validator, score)
+			})
+			.collect::<Vec<(T::PolkadotAccountId, Balance)>>();
 
 		voters.sort_by(|a, b| b.1.cmp(&a.1));
 
@@ -109,6 +109,7 @@ impl Trait for Runtime {
 	type BondingDuration = BondingDuration;
 	type NominateesCount = NominateesCount;
 	type MaxUnlockingChunks = MaxUnlockingChunks;
+	type UpdateOrigin = system::EnsureRoot<AccountId>;
 }
 pub type NomineesElectionModule = Module<Runtime>;
 
@@ -5,6 +5,7 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::{ExtBuilder, LDOTCurrency, NomineesElectionModule, Origin, Runtime, ALICE, BOB};
+use sp_runtime::{traits::BadOrigin, FixedPointNumber};
 
 #[test]
 fn bond_below_min_bond_threshold() {
@@ -236,3 +237,86 @@ fn update_votes_work() {
 		assert_eq!(NomineesElectionModule::votes(4), 10);
 	});
 }
+
+#[test]
+fn update_nomination_strategy_require_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			NomineesElectionModule::set_nomination_strategy(Origin::signed(ALICE), Default::default()),
+			BadOrigin,
+		);
+		assert_noop!(
+			NomineesElectionModule::update_whitelist(Origin::signed(ALICE), vec![1], vec![]),
+			BadOrigin,
+		);
+		assert_noop!(
+			NomineesElectionModule::set_validator_performance(Origin::signed(ALICE), vec![(1, None)]),
+			BadOrigin,
+		);
+
+		assert_ok!(NomineesElectionModule::update_whitelist(
+			Origin::ROOT,
+			vec![1, 2],
+			vec![]
+		));
+		assert_ok!(NomineesElectionModule::update_whitelist(Origin::ROOT, vec![], vec![2]));
+		assert_eq!(NomineesElectionModule::whitelist(1), true);
+		assert_eq!(NomineesElectionModule::whitelist(2), false);
+	});
+}
+
+#[test]
+fn rebalance_by_nomination_strategy() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(ALICE), 500));
+		assert_ok!(NomineesElectionModule::nominate(Origin::signed(ALICE), vec![1, 2, 3]));
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(BOB), 600));
+		assert_ok!(NomineesElectionModule::nominate(Origin::signed(BOB), vec![3, 4]));
+		NomineesElectionModule::rebalance();
+		assert_eq!(NomineesElectionModule::nominees().len(), 4);
+
+		// only whitelist
+		assert_ok!(NomineesElectionModule::update_whitelist(
+			Origin::ROOT,
+			vec![1, 3, 4],
+			vec![]
+		));
+		assert_ok!(NomineesElectionModule::set_nomination_strategy(
+			Origin::ROOT,
+			NominationStrategy {
+				whitelist_only: true,
+				min_performance: Ratio::saturating_from_rational(50, 100),
+				max_votes_per_validator: None,
+			}
+		));
+		NomineesElectionModule::rebalance();
+		assert_eq!(NomineesElectionModule::nominees(), vec![3, 4, 1]);
+
+		// rotate out the validator with poor performance
+		assert_ok!(NomineesElectionModule::set_validator_performance(
+			Origin::ROOT,
+			vec![
+				(3, Some(Ratio::saturating_from_rational(10, 100))),
+				(4, Some(Ratio::saturating_from_rational(90, 100)))
+			]
+		));
+		NomineesElectionModule::rebalance();
+		assert_eq!(NomineesElectionModule::nominees(), vec![4, 1]);
+
+		// cap the votes of every validator, rank by the capped votes weighted by performance
+		assert_ok!(NomineesElectionModule::set_validator_performance(
+			Origin::ROOT,
+			vec![(3, None), (2, Some(Ratio::saturating_from_rational(80, 100)))]
+		));
+		assert_ok!(NomineesElectionModule::set_nomination_strategy(
+			Origin::ROOT,
+			NominationStrategy {
+				whitelist_only: false,
+				min_performance: Default::default(),
+				max_votes_per_validator: Some(550),
+			}
+		));
+		NomineesElectionModule::rebalance();
+		assert_eq!(NomineesElectionModule::nominees(), vec![3, 1, 4, 2]);
+	});
+}
@@ -0,0 +1,362 @@
+//! # Oracle Operator Module
+//!
+//! ## Overview
+//!
+//! Oracle operators are required to bond a stake before they can feed
+//! values, which is slashed by governance or by the deviation report when
+//! the raw values they fed are wildly inconsistent with the accepted value.
+//! This module sits between the operator membership and the oracle: only
+//! operators whose bond is not less than `MinOperatorBond` are the feeders
+//! of the oracle.
+//!
+//! The module also provides `ExpiringCombineData` for the oracle, fed values
+//! are treated as absent after the validity period of the currency.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{ChangeMembers, Currency, EnsureOrigin, Get, InitializeMembers, OnUnbalanced, ReservableCurrency, Time},
+};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use orml_oracle::TimestampedValueOf;
+use orml_traits::{CombineData, DataProvider};
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{CheckedDiv, Saturating, Zero},
+	FixedPointNumber,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+use support::{OperatorDataProvider, Price, Ratio};
+
+mod mock;
+mod tests;
+
+type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+type MomentOf<T> = <<T as Trait>::Time as Time>::Moment;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency used to bond by operators
+	type Currency: ReservableCurrency<Self::AccountId, Balance = Balance>;
+
+	/// The minimum bond required for an operator to feed values
+	type MinOperatorBond: Get<Balance>;
+
+	/// The blocks that unbonded stake are still slashable before withdrawn
+	type UnbondingPeriod: Get<Self::BlockNumber>;
+
+	/// The origin which may slash operators. Root can always do this.
+	type SlashOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Handler for the slashed bonds
+	type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The accepted values of oracle
+	type Source: DataProvider<CurrencyId, Price>;
+
+	/// The raw values fed by each operator
+	type RawSource: OperatorDataProvider<Self::AccountId, CurrencyId, Price, MomentOf<Self>>;
+
+	/// The max deviation of raw value from the accepted value before the
+	/// operator can be reported
+	type MaxDeviation: Get<Ratio>;
+
+	/// The ratio of total bond to slash when the deviation report succeeded
+	type DeviationSlashRatio: Get<Ratio>;
+
+	/// The receiver of the feeders set
+	type FeedersInitialized: InitializeMembers<Self::AccountId>;
+
+	/// The receiver of the changes of feeders set
+	type FeedersChanged: ChangeMembers<Self::AccountId>;
+
+	/// The origin which may update the validity period of fed values. Root can always do this.
+	type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Time used for the expiry of fed values
+	type Time: Time;
+
+	/// The validity period of fed values if not set for the currency
+	type DefaultValueExpiry: Get<MomentOf<Self>>;
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		<T as system::Trait>::AccountId,
+		<T as system::Trait>::BlockNumber,
+		Balance = Balance,
+		Moment = MomentOf<T>,
+	{
+		/// Operator bonded stake (operator, amount)
+		Bond(AccountId, Balance),
+		/// Operator unbonded stake, which can be withdrawn at the block (operator, amount, unlock_at)
+		Unbond(AccountId, Balance, BlockNumber),
+		/// Operator withdrawn unbonded stake (operator, amount)
+		WithdrawUnbonded(AccountId, Balance),
+		/// Operator has been slashed (operator, amount)
+		Slash(AccountId, Balance),
+		/// The raw value of operator deviated from the accepted value (operator, currency_id, raw_value, accepted_value)
+		DeviationReported(AccountId, CurrencyId, Price, Price),
+		/// The feeders of oracle has been changed (new_feeders)
+		FeedersChanged(Vec<AccountId>),
+		/// The validity period of fed values has been updated, `None` means default (currency_id, expiry)
+		ValueExpiryUpdated(CurrencyId, Option<Moment>),
+	}
+);
+
+decl_error! {
+	/// Error for oracle operator module.
+	pub enum Error for Module<T: Trait> {
+		/// The bonded stake is not enough
+		BondNotEnough,
+		/// No unbonded stake can be withdrawn
+		NoWithdrawableUnbonded,
+		/// No raw value or accepted value to compare
+		NoValue,
+		/// The raw value has expired and is no longer combined into the accepted value
+		ValueExpired,
+		/// The deviation of raw value is within the limit
+		DeviationWithinLimit,
+		/// The raw value has been reported
+		AlreadyReported,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as OracleOperator {
+		/// The sorted operators set from membership
+		pub Operators get(fn operators): Vec<T::AccountId>;
+
+		/// The sorted operators who have bonded enough and are feeding oracle
+		pub Feeders get(fn feeders): Vec<T::AccountId>;
+
+		/// The active bond of operators
+		pub Bonded get(fn bonded): map hasher(twox_64_concat) T::AccountId => Balance;
+
+		/// The unbonding stake of operators and the block to be unlocked
+		pub Unbonding get(fn unbonding): map hasher(twox_64_concat) T::AccountId => (Balance, T::BlockNumber);
+
+		/// The last raw value of operator that has been slashed for deviation
+		pub ReportedValues get(fn reported_values): double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) CurrencyId => Option<Price>;
+
+		/// The validity period of fed values of the currency
+		pub ValueExpiries get(fn value_expiries): map hasher(twox_64_concat) CurrencyId => Option<MomentOf<T>>;
+	}
+
+	add_extra_genesis {
+		config(bonds): Vec<(T::AccountId, Balance)>;
+		build(|config: &GenesisConfig<T>| {
+			for (who, amount) in &config.bonds {
+				T::Currency::reserve(who, *amount).expect("operator must be able to bond at genesis");
+				<Bonded<T>>::mutate(who, |bonded| *bonded = bonded.saturating_add(*amount));
+			}
+		})
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+		fn deposit_event() = default;
+
+		const MinOperatorBond: Balance = T::MinOperatorBond::get();
+		const UnbondingPeriod: T::BlockNumber = T::UnbondingPeriod::get();
+		const MaxDeviation: Ratio = T::MaxDeviation::get();
+		const DeviationSlashRatio: Ratio = T::DeviationSlashRatio::get();
+		const DefaultValueExpiry: MomentOf<T> = T::DefaultValueExpiry::get();
+
+		#[weight = 10_000]
+		pub fn bond(origin, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			T::Currency::reserve(&who, amount)?;
+			<Bonded<T>>::mutate(&who, |bonded| *bonded = bonded.saturating_add(amount));
+			Self::update_feeders();
+			Self::deposit_event(RawEvent::Bond(who, amount));
+		}
+
+		/// Unbond stake, which is still slashable until withdrawn after `UnbondingPeriod`.
+		#[weight = 10_000]
+		pub fn unbond(origin, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			let remaining = Self::bonded(&who).checked_sub(amount).ok_or(Error::<T>::BondNotEnough)?;
+			let unlock_at = <system::Module<T>>::block_number() + T::UnbondingPeriod::get();
+
+			<Bonded<T>>::insert(&who, remaining);
+			<Unbonding<T>>::mutate(&who, |(unbonding, at)| {
+				*unbonding = unbonding.saturating_add(amount);
+				*at = unlock_at;
+			});
+			Self::update_feeders();
+			Self::deposit_event(RawEvent::Unbond(who, amount, unlock_at));
+		}
+
+		#[weight = 10_000]
+		pub fn withdraw_unbonded(origin) {
+			let who = ensure_signed(origin)?;
+			let (amount, unlock_at) = Self::unbonding(&who);
+			ensure!(
+				!amount.is_zero() && unlock_at <= <system::Module<T>>::block_number(),
+				Error::<T>::NoWithdrawableUnbonded,
+			);
+
+			T::Currency::unreserve(&who, amount);
+			<Unbonding<T>>::remove(&who);
+			Self::deposit_event(RawEvent::WithdrawUnbonded(who, amount));
+		}
+
+		#[weight = 10_000]
+		pub fn slash(origin, who: T::AccountId, #[compact] amount: Balance) {
+			T::SlashOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			Self::do_slash(&who, amount);
+		}
+
+		/// Report the raw value of `who` deviated from the accepted value over `MaxDeviation`,
+		/// `DeviationSlashRatio` of its total bond will be slashed. Only unexpired raw values,
+		/// which are combined into the accepted value, can be reported.
+		#[weight = 10_000]
+		pub fn report_deviation(origin, who: T::AccountId, currency_id: CurrencyId) {
+			let _ = ensure_signed(origin)?;
+			let (raw_value, timestamp) = T::RawSource::raw_value(&who, &currency_id).ok_or(Error::<T>::NoValue)?;
+			ensure!(Self::is_unexpired(currency_id, timestamp), Error::<T>::ValueExpired);
+			let accepted_value = T::Source::get(&currency_id).ok_or(Error::<T>::NoValue)?;
+			ensure!(
+				Self::reported_values(&who, currency_id) != Some(raw_value),
+				Error::<T>::AlreadyReported,
+			);
+
+			let deviation = raw_value
+				.max(accepted_value)
+				.saturating_sub(raw_value.min(accepted_value))
+				.checked_div(&accepted_value)
+				.unwrap_or_default();
+			ensure!(deviation > T::MaxDeviation::get(), Error::<T>::DeviationWithinLimit);
+
+			<ReportedValues<T>>::insert(&who, currency_id, raw_value);
+			let total_bond = Self::bonded(&who).saturating_add(Self::unbonding(&who).0);
+			Self::do_slash(&who, T::DeviationSlashRatio::get().saturating_mul_int(total_bond));
+			Self::deposit_event(RawEvent::DeviationReported(who, currency_id, raw_value, accepted_value));
+		}
+
+		#[weight = 10_000]
+		pub fn set_value_expiry(origin, currency_id: CurrencyId, expiry: Option<MomentOf<T>>) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			if let Some(expiry) = expiry {
+				<ValueExpiries<T>>::insert(currency_id, expiry);
+			} else {
+				<ValueExpiries<T>>::remove(currency_id);
+			}
+			Self::deposit_event(RawEvent::ValueExpiryUpdated(currency_id, expiry));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	pub fn value_expiry(currency_id: CurrencyId) -> MomentOf<T> {
+		Self::value_expiries(currency_id).unwrap_or_else(T::DefaultValueExpiry::get)
+	}
+
+	/// Whether the value of `currency_id` fed at `timestamp` is not expired yet
+	pub fn is_unexpired(currency_id: CurrencyId, timestamp: MomentOf<T>) -> bool {
+		timestamp.saturating_add(Self::value_expiry(currency_id)) > T::Time::now()
+	}
+
+	/// Slash the bond of `who`, the active bond is slashed before the unbonding.
+	fn do_slash(who: &T::AccountId, amount: Balance) {
+		let bonded = Self::bonded(who);
+		let (unbonding, _) = Self::unbonding(who);
+		let amount = amount.min(bonded.saturating_add(unbonding));
+		if amount.is_zero() {
+			return;
+		}
+
+		let (imbalance, remaining) = T::Currency::slash_reserved(who, amount);
+		T::Slash::on_unbalanced(imbalance);
+		let slashed = amount.saturating_sub(remaining);
+
+		let slashed_bonded = slashed.min(bonded);
+		<Bonded<T>>::insert(who, bonded - slashed_bonded);
+		let slashed_unbonding = slashed - slashed_bonded;
+		if !slashed_unbonding.is_zero() {
+			<Unbonding<T>>::mutate(who, |(unbonding, _)| {
+				*unbonding = unbonding.saturating_sub(slashed_unbonding)
+			});
+		}
+
+		Self::update_feeders();
+		Self::deposit_event(RawEvent::Slash(who.clone(), slashed));
+	}
+
+	fn eligible_feeders(operators: &[T::AccountId]) -> Vec<T::AccountId> {
+		let min_bond = T::MinOperatorBond::get();
+		operators
+			.iter()
+			.filter(|who| Self::bonded(who) >= min_bond)
+			.cloned()
+			.collect()
+	}
+
+	fn update_feeders() {
+		let old_feeders = Self::feeders();
+		let new_feeders = Self::eligible_feeders(&Self::operators());
+
+		if new_feeders != old_feeders {
+			T::FeedersChanged::set_members_sorted(&new_feeders, &old_feeders);
+			<Feeders<T>>::put(&new_feeders);
+			Self::deposit_event(RawEvent::FeedersChanged(new_feeders));
+		}
+	}
+}
+
+impl<T: Trait> InitializeMembers<T::AccountId> for Module<T> {
+	fn initialize_members(members: &[T::AccountId]) {
+		let mut operators = members.to_vec();
+		operators.sort();
+		let feeders = Self::eligible_feeders(&operators);
+
+		<Operators<T>>::put(operators);
+		<Feeders<T>>::put(&feeders);
+		T::FeedersInitialized::initialize_members(&feeders);
+	}
+}
+
+impl<T: Trait> ChangeMembers<T::AccountId> for Module<T> {
+	fn change_members_sorted(_incoming: &[T::AccountId], _outgoing: &[T::AccountId], sorted_new: &[T::AccountId]) {
+		<Operators<T>>::put(sorted_new.to_vec());
+		Self::update_feeders();
+	}
+}
+
+/// Combine the fed values by median. The values older than the expiry of the
+/// currency are treated as absent, and the previous combined value is dropped
+/// once expired rather than kept forever.
+pub struct ExpiringCombineData<T, MinimumCount>(PhantomData<(T, MinimumCount)>);
+
+impl<T, MinimumCount> CombineData<CurrencyId, TimestampedValueOf<T>> for ExpiringCombineData<T, MinimumCount>
+where
+	T: Trait + orml_oracle::Trait<OracleKey = CurrencyId, Time = <T as Trait>::Time>,
+	MinimumCount: Get<u32>,
+{
+	fn combine_data(
+		key: &CurrencyId,
+		values: Vec<TimestampedValueOf<T>>,
+		prev_value: Option<TimestampedValueOf<T>>,
+	) -> Option<TimestampedValueOf<T>> {
+		let is_valid = |x: &TimestampedValueOf<T>| <Module<T>>::is_unexpired(*key, x.timestamp);
+
+		let mut valid_values = values.into_iter().filter(|x| is_valid(x)).collect::<Vec<_>>();
+		let count = valid_values.len() as u32;
+		if count < MinimumCount::get() || count.is_zero() {
+			return prev_value.filter(|x| is_valid(x));
+		}
+
+		valid_values.sort_by(|a, b| a.value.cmp(&b.value));
+		Some(valid_values[count as usize / 2].clone())
+	}
+}
@@ -0,0 +1,233 @@
+//! Mocks for the oracle operator module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types};
+use frame_system::EnsureSignedBy;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, UintAuthorityId},
+	traits::IdentityLookup,
+	Perbill,
+};
+use sp_std::cell::RefCell;
+use std::collections::BTreeMap;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const DOT: CurrencyId = CurrencyId::DOT;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod oracle_operator {
+	pub use super::super::*;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		system<T>,
+		oracle_operator<T>,
+		pallet_balances<T>,
+		orml_oracle<T>,
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+}
+pub type System = system::Module<Runtime>;
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Trait for Runtime {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = TestEvent;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+pub type PalletBalances = pallet_balances::Module<Runtime>;
+
+thread_local! {
+	pub static FEEDERS: RefCell<Vec<AccountId>> = RefCell::new(vec![]);
+	static ACCEPTED_VALUES: RefCell<BTreeMap<CurrencyId, Price>> = RefCell::new(BTreeMap::new());
+	static RAW_VALUES: RefCell<BTreeMap<(AccountId, CurrencyId), (Price, u64)>> = RefCell::new(BTreeMap::new());
+	static NOW: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct MockTime;
+impl MockTime {
+	pub fn set_now(now: u64) {
+		NOW.with(|v| *v.borrow_mut() = now);
+	}
+}
+impl Time for MockTime {
+	type Moment = u64;
+
+	fn now() -> u64 {
+		NOW.with(|v| *v.borrow())
+	}
+}
+
+pub struct MockFeeders;
+impl InitializeMembers<AccountId> for MockFeeders {
+	fn initialize_members(members: &[AccountId]) {
+		FEEDERS.with(|v| *v.borrow_mut() = members.to_vec());
+	}
+}
+impl ChangeMembers<AccountId> for MockFeeders {
+	fn change_members_sorted(_incoming: &[AccountId], _outgoing: &[AccountId], sorted_new: &[AccountId]) {
+		FEEDERS.with(|v| *v.borrow_mut() = sorted_new.to_vec());
+	}
+}
+
+pub struct MockSource;
+impl MockSource {
+	pub fn set_value(currency_id: CurrencyId, value: Price) {
+		ACCEPTED_VALUES.with(|v| v.borrow_mut().insert(currency_id, value));
+	}
+
+	pub fn set_raw_value(who: AccountId, currency_id: CurrencyId, value: Price) {
+		RAW_VALUES.with(|v| v.borrow_mut().insert((who, currency_id), (value, MockTime::now())));
+	}
+}
+impl DataProvider<CurrencyId, Price> for MockSource {
+	fn get(currency_id: &CurrencyId) -> Option<Price> {
+		ACCEPTED_VALUES.with(|v| v.borrow().get(currency_id).copied())
+	}
+}
+impl OperatorDataProvider<AccountId, CurrencyId, Price, u64> for MockSource {
+	fn raw_value(who: &AccountId, currency_id: &CurrencyId) -> Option<(Price, u64)> {
+		RAW_VALUES.with(|v| v.borrow().get(&(*who, *currency_id)).copied())
+	}
+}
+
+ord_parameter_types! {
+	pub const SlashAccount: AccountId = 100;
+}
+
+parameter_types! {
+	pub const MinOperatorBond: Balance = 100;
+	pub const UnbondingPeriod: BlockNumber = 10;
+	pub MaxDeviation: Ratio = Ratio::saturating_from_rational(1, 10);
+	pub DeviationSlashRatio: Ratio = Ratio::saturating_from_rational(1, 2);
+	pub const DefaultValueExpiry: u64 = 1000;
+}
+
+impl Trait for Runtime {
+	type Event = TestEvent;
+	type Currency = PalletBalances;
+	type MinOperatorBond = MinOperatorBond;
+	type UnbondingPeriod = UnbondingPeriod;
+	type SlashOrigin = EnsureSignedBy<SlashAccount, AccountId>;
+	type Slash = ();
+	type Source = MockSource;
+	type RawSource = MockSource;
+	type MaxDeviation = MaxDeviation;
+	type DeviationSlashRatio = DeviationSlashRatio;
+	type FeedersInitialized = MockFeeders;
+	type FeedersChanged = MockFeeders;
+	type UpdateOrigin = EnsureSignedBy<SlashAccount, AccountId>;
+	type Time = MockTime;
+	type DefaultValueExpiry = DefaultValueExpiry;
+}
+pub type OracleOperatorModule = Module<Runtime>;
+
+parameter_types! {
+	pub const MinimumCount: u32 = 2;
+	pub const UnsignedPriority: u64 = 1 << 20;
+}
+
+impl orml_oracle::Trait for Runtime {
+	type Event = TestEvent;
+	type OnNewData = ();
+	type CombineData = ExpiringCombineData<Runtime, MinimumCount>;
+	type Time = MockTime;
+	type OracleKey = CurrencyId;
+	type OracleValue = Price;
+	type UnsignedPriority = UnsignedPriority;
+	type AuthorityId = UintAuthorityId;
+}
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, Balance)>,
+	bonds: Vec<(AccountId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![(ALICE, 1000), (BOB, 1000), (CAROL, 1000)],
+			bonds: vec![(ALICE, 100)],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		FEEDERS.with(|v| v.borrow_mut().clear());
+		ACCEPTED_VALUES.with(|v| v.borrow_mut().clear());
+		RAW_VALUES.with(|v| v.borrow_mut().clear());
+		MockTime::set_now(0);
+
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		GenesisConfig::<Runtime> { bonds: self.bonds }
+			.assimilate_storage(&mut t)
+			.unwrap();
+
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			OracleOperatorModule::initialize_members(&[ALICE, BOB, CAROL]);
+		});
+		ext
+	}
+}
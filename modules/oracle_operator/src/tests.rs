@@ -0,0 +1,213 @@
+//! Unit tests for the oracle operator module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{
+	ExtBuilder, MinimumCount, MockSource, MockTime, OracleOperatorModule, Origin, PalletBalances, Runtime, System,
+	TestEvent, ALICE, BOB, CAROL, DOT, FEEDERS,
+};
+use sp_runtime::traits::BadOrigin;
+
+fn feeders() -> Vec<u128> {
+	FEEDERS.with(|v| v.borrow().clone())
+}
+
+#[test]
+fn only_bonded_operators_are_feeders() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 100);
+		assert_eq!(OracleOperatorModule::operators(), vec![ALICE, BOB, CAROL]);
+		assert_eq!(OracleOperatorModule::feeders(), vec![ALICE]);
+		assert_eq!(feeders(), vec![ALICE]);
+
+		assert_ok!(OracleOperatorModule::bond(Origin::signed(BOB), 99));
+		assert_eq!(feeders(), vec![ALICE]);
+		assert_ok!(OracleOperatorModule::bond(Origin::signed(BOB), 1));
+		assert_eq!(PalletBalances::reserved_balance(BOB), 100);
+		assert_eq!(feeders(), vec![ALICE, BOB]);
+
+		let feeders_changed_event = TestEvent::oracle_operator(RawEvent::FeedersChanged(vec![ALICE, BOB]));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == feeders_changed_event));
+	});
+}
+
+#[test]
+fn membership_change_updates_feeders() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(OracleOperatorModule::bond(Origin::signed(BOB), 100));
+		assert_eq!(feeders(), vec![ALICE, BOB]);
+
+		OracleOperatorModule::change_members_sorted(&[], &[ALICE], &[BOB, CAROL]);
+		assert_eq!(OracleOperatorModule::operators(), vec![BOB, CAROL]);
+		assert_eq!(feeders(), vec![BOB]);
+		// the bond of removed operator is kept until unbonded
+		assert_eq!(OracleOperatorModule::bonded(ALICE), 100);
+	});
+}
+
+#[test]
+fn unbond_and_withdraw_unbonded_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			OracleOperatorModule::unbond(Origin::signed(ALICE), 101),
+			Error::<Runtime>::BondNotEnough,
+		);
+		assert_ok!(OracleOperatorModule::unbond(Origin::signed(ALICE), 40));
+		assert_eq!(OracleOperatorModule::bonded(ALICE), 60);
+		assert_eq!(OracleOperatorModule::unbonding(ALICE), (40, 11));
+		assert_eq!(feeders(), Vec::<u128>::new());
+
+		assert_noop!(
+			OracleOperatorModule::withdraw_unbonded(Origin::signed(ALICE)),
+			Error::<Runtime>::NoWithdrawableUnbonded,
+		);
+		System::set_block_number(11);
+		assert_ok!(OracleOperatorModule::withdraw_unbonded(Origin::signed(ALICE)));
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 60);
+		assert_eq!(OracleOperatorModule::unbonding(ALICE), (0, 0));
+	});
+}
+
+#[test]
+fn slash_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(OracleOperatorModule::unbond(Origin::signed(ALICE), 40));
+		assert_noop!(OracleOperatorModule::slash(Origin::signed(BOB), ALICE, 80), BadOrigin,);
+
+		assert_ok!(OracleOperatorModule::slash(Origin::signed(100), ALICE, 80));
+		assert_eq!(OracleOperatorModule::bonded(ALICE), 0);
+		assert_eq!(OracleOperatorModule::unbonding(ALICE).0, 20);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 20);
+		assert_eq!(PalletBalances::total_balance(&ALICE), 920);
+
+		// cannot slash more than the bond
+		assert_ok!(OracleOperatorModule::slash(Origin::ROOT, ALICE, 1000));
+		assert_eq!(PalletBalances::total_balance(&ALICE), 900);
+
+		let slash_event = TestEvent::oracle_operator(RawEvent::Slash(ALICE, 20));
+		assert!(System::events().iter().any(|record| record.event == slash_event));
+	});
+}
+
+#[test]
+fn report_deviation_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			OracleOperatorModule::report_deviation(Origin::signed(BOB), ALICE, DOT),
+			Error::<Runtime>::NoValue,
+		);
+
+		MockSource::set_value(DOT, Price::saturating_from_integer(100));
+		MockSource::set_raw_value(ALICE, DOT, Price::saturating_from_integer(110));
+		assert_noop!(
+			OracleOperatorModule::report_deviation(Origin::signed(BOB), ALICE, DOT),
+			Error::<Runtime>::DeviationWithinLimit,
+		);
+
+		MockSource::set_raw_value(ALICE, DOT, Price::saturating_from_integer(80));
+		assert_ok!(OracleOperatorModule::report_deviation(Origin::signed(BOB), ALICE, DOT));
+		assert_eq!(OracleOperatorModule::bonded(ALICE), 50);
+		assert_eq!(PalletBalances::total_balance(&ALICE), 950);
+		assert_eq!(feeders(), Vec::<u128>::new());
+
+		let report_event = TestEvent::oracle_operator(RawEvent::DeviationReported(
+			ALICE,
+			DOT,
+			Price::saturating_from_integer(80),
+			Price::saturating_from_integer(100),
+		));
+		assert!(System::events().iter().any(|record| record.event == report_event));
+
+		// the same raw value cannot be reported twice
+		assert_noop!(
+			OracleOperatorModule::report_deviation(Origin::signed(BOB), ALICE, DOT),
+			Error::<Runtime>::AlreadyReported,
+		);
+	});
+}
+
+#[test]
+fn expired_raw_value_cannot_be_reported() {
+	ExtBuilder::default().build().execute_with(|| {
+		MockSource::set_value(DOT, Price::saturating_from_integer(100));
+		MockSource::set_raw_value(ALICE, DOT, Price::saturating_from_integer(80));
+
+		// the accepted value moved on after the raw value expired
+		MockTime::set_now(1000);
+		assert_noop!(
+			OracleOperatorModule::report_deviation(Origin::signed(BOB), ALICE, DOT),
+			Error::<Runtime>::ValueExpired,
+		);
+		assert_eq!(OracleOperatorModule::bonded(ALICE), 100);
+
+		MockTime::set_now(999);
+		assert_ok!(OracleOperatorModule::report_deviation(Origin::signed(BOB), ALICE, DOT));
+	});
+}
+
+#[test]
+fn set_value_expiry_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(OracleOperatorModule::value_expiry(DOT), 1000);
+		assert_noop!(
+			OracleOperatorModule::set_value_expiry(Origin::signed(ALICE), DOT, Some(500)),
+			BadOrigin,
+		);
+		assert_ok!(OracleOperatorModule::set_value_expiry(
+			Origin::signed(100),
+			DOT,
+			Some(500)
+		));
+		assert_eq!(OracleOperatorModule::value_expiry(DOT), 500);
+
+		let update_event = TestEvent::oracle_operator(RawEvent::ValueExpiryUpdated(DOT, Some(500)));
+		assert!(System::events().iter().any(|record| record.event == update_event));
+
+		assert_ok!(OracleOperatorModule::set_value_expiry(Origin::ROOT, DOT, None));
+		assert_eq!(OracleOperatorModule::value_expiry(DOT), 1000);
+	});
+}
+
+#[test]
+fn expired_values_are_treated_as_absent() {
+	ExtBuilder::default().build().execute_with(|| {
+		type Combine = ExpiringCombineData<Runtime, MinimumCount>;
+		let value = |price: u128, timestamp: u64| TimestampedValueOf::<Runtime> {
+			value: Price::saturating_from_integer(price),
+			timestamp,
+		};
+
+		MockTime::set_now(1500);
+		// the value fed at 400 is expired, not enough valid values
+		assert_eq!(
+			Combine::combine_data(&DOT, vec![value(100, 400), value(110, 600)], Some(value(90, 600))),
+			Some(value(90, 600))
+		);
+		assert_eq!(
+			Combine::combine_data(&DOT, vec![value(100, 600), value(110, 700), value(120, 800)], None),
+			Some(value(110, 700))
+		);
+
+		// the previous value is also dropped when expired
+		MockTime::set_now(1600);
+		assert_eq!(
+			Combine::combine_data(&DOT, vec![value(100, 600), value(110, 700)], Some(value(90, 600))),
+			None
+		);
+
+		// the expiry is configurable per currency
+		assert_ok!(OracleOperatorModule::set_value_expiry(
+			Origin::signed(100),
+			DOT,
+			Some(2000)
+		));
+		assert_eq!(
+			Combine::combine_data(&DOT, vec![value(100, 600), value(110, 700)], None),
+			Some(value(110, 700))
+		);
+	});
+}
@@ -14,6 +14,9 @@ use support::{
 	PolkadotUnlockChunk, Rate,
 };
 
+mod mock;
+mod tests;
+
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	type DOTCurrency: BasicCurrency<Self::AccountId, Balance = Balance>;
@@ -0,0 +1,138 @@
+//! Unit tests for polkadot bridge module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize};
+use mock::{DOTCurrency, ExtBuilder, Origin, PolkadotBridgeModule, Runtime, System, ALICE, BOB, NEW_ERAS};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn transfer_to_bridge_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(DOTCurrency::free_balance(&ALICE), 1000);
+		assert_eq!(PolkadotBridgeModule::available(), 0);
+		assert_ok!(PolkadotBridgeModule::transfer_to_bridge(&ALICE, 300));
+		assert_eq!(DOTCurrency::free_balance(&ALICE), 700);
+		assert_eq!(PolkadotBridgeModule::available(), 300);
+		assert!(PolkadotBridgeModule::transfer_to_bridge(&ALICE, 1000).is_err());
+	});
+}
+
+#[test]
+fn receive_from_bridge_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PolkadotBridgeModule::transfer_to_bridge(&ALICE, 300));
+		assert_noop!(
+			PolkadotBridgeModule::receive_from_bridge(&BOB, 301),
+			Error::<Runtime>::NotEnough,
+		);
+		assert_ok!(PolkadotBridgeModule::receive_from_bridge(&BOB, 100));
+		assert_eq!(DOTCurrency::free_balance(&BOB), 1100);
+		assert_eq!(PolkadotBridgeModule::available(), 200);
+	});
+}
+
+#[test]
+fn bond_extra_and_unbond_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PolkadotBridgeModule::transfer_to_bridge(&ALICE, 500));
+		assert_noop!(PolkadotBridgeModule::bond_extra(501), Error::<Runtime>::NotEnough);
+		assert_ok!(PolkadotBridgeModule::bond_extra(400));
+		assert_eq!(PolkadotBridgeModule::bonded(), 400);
+		assert_eq!(PolkadotBridgeModule::available(), 100);
+
+		assert_noop!(PolkadotBridgeModule::unbond(401), Error::<Runtime>::NotEnough);
+		assert_ok!(PolkadotBridgeModule::unbond(150));
+		assert_eq!(PolkadotBridgeModule::bonded(), 250);
+		assert_eq!(PolkadotBridgeModule::unbonding(), vec![(150, 2)]);
+
+		let ledger = PolkadotBridgeModule::ledger();
+		assert_eq!(ledger.total, 400);
+		assert_eq!(ledger.active, 250);
+		assert_eq!(ledger.unlocking, vec![PolkadotUnlockChunk { value: 150, era: 2 }]);
+		assert_eq!(PolkadotBridgeModule::balance(), 500);
+	});
+}
+
+#[test]
+fn rebond_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PolkadotBridgeModule::transfer_to_bridge(&ALICE, 500));
+		assert_ok!(PolkadotBridgeModule::bond_extra(500));
+		assert_ok!(PolkadotBridgeModule::unbond(100));
+		assert_ok!(PolkadotBridgeModule::unbond(200));
+		assert_noop!(PolkadotBridgeModule::rebond(301), Error::<Runtime>::NotEnough);
+
+		assert_ok!(PolkadotBridgeModule::rebond(250));
+		assert_eq!(PolkadotBridgeModule::bonded(), 450);
+		assert_eq!(PolkadotBridgeModule::unbonding(), vec![(50, 2)]);
+		assert_eq!(PolkadotBridgeModule::balance(), 500);
+	});
+}
+
+#[test]
+fn withdraw_unbonded_after_bonding_duration() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PolkadotBridgeModule::transfer_to_bridge(&ALICE, 500));
+		assert_ok!(PolkadotBridgeModule::bond_extra(500));
+		assert_ok!(PolkadotBridgeModule::unbond(100));
+
+		PolkadotBridgeModule::on_finalize(10);
+		assert_eq!(PolkadotBridgeModule::current_era(), 1);
+		PolkadotBridgeModule::withdraw_unbonded();
+		assert_eq!(PolkadotBridgeModule::available(), 0);
+		assert_eq!(PolkadotBridgeModule::unbonding(), vec![(100, 2)]);
+
+		PolkadotBridgeModule::on_finalize(20);
+		assert_eq!(PolkadotBridgeModule::current_era(), 2);
+		PolkadotBridgeModule::withdraw_unbonded();
+		assert_eq!(PolkadotBridgeModule::available(), 100);
+		assert_eq!(PolkadotBridgeModule::unbonding(), vec![]);
+	});
+}
+
+#[test]
+fn payout_nominator_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PolkadotBridgeModule::transfer_to_bridge(&ALICE, 500));
+		assert_ok!(PolkadotBridgeModule::bond_extra(400));
+		PolkadotBridgeModule::payout_nominator();
+		assert_eq!(PolkadotBridgeModule::available(), 100);
+
+		assert_noop!(
+			PolkadotBridgeModule::set_mock_reward_rate(
+				Origin::signed(ALICE),
+				Some(Rate::saturating_from_rational(1, 10))
+			),
+			BadOrigin,
+		);
+		assert_ok!(PolkadotBridgeModule::set_mock_reward_rate(
+			Origin::ROOT,
+			Some(Rate::saturating_from_rational(1, 10))
+		));
+		PolkadotBridgeModule::payout_nominator();
+		assert_eq!(PolkadotBridgeModule::available(), 140);
+		assert_eq!(PolkadotBridgeModule::balance(), 540);
+	});
+}
+
+#[test]
+fn new_era_on_era_length_or_forced() {
+	ExtBuilder::default().build().execute_with(|| {
+		PolkadotBridgeModule::on_finalize(9);
+		assert_eq!(PolkadotBridgeModule::current_era(), 0);
+		PolkadotBridgeModule::on_finalize(10);
+		assert_eq!(PolkadotBridgeModule::current_era(), 1);
+		assert_eq!(PolkadotBridgeModule::era_start_block_number(), 10);
+
+		System::set_block_number(12);
+		assert_noop!(PolkadotBridgeModule::force_era(Origin::signed(ALICE), 13), BadOrigin);
+		assert_ok!(PolkadotBridgeModule::force_era(Origin::ROOT, 13));
+		assert_eq!(PolkadotBridgeModule::forced_era(), Some(13));
+		PolkadotBridgeModule::on_finalize(13);
+		assert_eq!(PolkadotBridgeModule::current_era(), 2);
+		assert_eq!(PolkadotBridgeModule::forced_era(), None);
+		NEW_ERAS.with(|v| assert_eq!(*v.borrow(), vec![1, 2]));
+	});
+}
@@ -0,0 +1,36 @@
+//! Runtime API definition for prices module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_std::prelude::*;
+use support::Price;
+
+pub use module_prices::PriceSource;
+
+#[derive(Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PriceInfo<BlockNumber> {
+	/// The price currently in effect
+	pub price: Price,
+	/// Where the price comes from
+	pub source: PriceSource,
+	/// The block number of the latest oracle feed, `None` if the price is not fed by oracle
+	pub last_updated_at: Option<BlockNumber>,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait PricesApi<CurrencyId, BlockNumber> where
+		CurrencyId: Codec,
+		BlockNumber: Codec,
+	{
+		fn get_price(currency_id: CurrencyId) -> Option<PriceInfo<BlockNumber>>;
+
+		fn get_relative_price(base_currency_id: CurrencyId, quote_currency_id: CurrencyId) -> Option<Price>;
+	}
+}
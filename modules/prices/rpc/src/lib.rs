@@ -0,0 +1,102 @@
+//! RPC interface for the prices module.
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use module_prices_rpc_runtime_api::PriceInfo;
+use module_support::Price;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::sync::Arc;
+
+pub use self::gen_client::Client as PricesClient;
+pub use module_prices_rpc_runtime_api::PricesApi as PricesRuntimeApi;
+
+#[rpc]
+pub trait PricesApi<BlockHash, CurrencyId, ResponseType> {
+	#[rpc(name = "prices_getPrice")]
+	fn get_price(&self, currency_id: CurrencyId, at: Option<BlockHash>) -> Result<Option<ResponseType>>;
+
+	#[rpc(name = "prices_getRelativePrice")]
+	fn get_relative_price(
+		&self,
+		base_currency_id: CurrencyId,
+		quote_currency_id: CurrencyId,
+		at: Option<BlockHash>,
+	) -> Result<Option<Price>>;
+}
+
+/// A struct that implements the [`PricesApi`].
+pub struct Prices<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Prices<C, B> {
+	/// Create new `Prices` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Prices {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+pub enum Error {
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, CurrencyId, BlockNumber> PricesApi<<Block as BlockT>::Hash, CurrencyId, PriceInfo<BlockNumber>>
+	for Prices<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: PricesRuntimeApi<Block, CurrencyId, BlockNumber>,
+	CurrencyId: Codec,
+	BlockNumber: Codec,
+{
+	fn get_price(
+		&self,
+		currency_id: CurrencyId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<PriceInfo<BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.get_price(&at, currency_id).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to get price.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+
+	fn get_relative_price(
+		&self,
+		base_currency_id: CurrencyId,
+		quote_currency_id: CurrencyId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Price>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.get_relative_price(&at, base_currency_id, quote_currency_id)
+			.map_err(|e| RpcError {
+				code: ErrorCode::ServerError(Error::RuntimeError.into()),
+				message: "Unable to get relative price.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})
+	}
+}
@@ -1,48 +1,173 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
 use frame_support::{
-	decl_event, decl_module, decl_storage,
+	decl_error, decl_event, decl_module, decl_storage, ensure,
 	traits::{EnsureOrigin, Get},
+	IterableStorageMap,
 };
 use frame_system::{self as system, ensure_root};
-use orml_traits::{DataProvider, DataProviderExtended};
+use orml_traits::{DataProvider, DataProviderExtended, OnNewData};
 use primitives::CurrencyId;
-use sp_runtime::traits::{CheckedDiv, CheckedMul};
-use support::{ExchangeRateProvider, Price, PriceProvider};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{
+	traits::{CheckedDiv, CheckedMul, Saturating, Zero},
+	FixedPointNumber, RuntimeDebug,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+use support::{DerivativeAsset, ExchangeRate, ExchangeRateProvider, OnEmergencyShutdown, Price, PriceProvider, Ratio};
 
 mod mock;
 mod tests;
 
+/// The source of the price currently in effect
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum PriceSource {
+	/// The fixed price of stable currency
+	Fixed,
+	/// The price locked by governance or emergency shutdown
+	Locked,
+	/// The emergency price override set by governance
+	Emergency,
+	/// The price fed by oracle
+	Oracle,
+	/// The price derived from the underlying asset
+	Derivative,
+}
+
+/// The rule to aggregate the price of oracle `Source` and `ExternalSource`
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum AggregationRule {
+	/// Only use the price of `Source`
+	SourceOnly,
+	/// Use the price of `Source`, fallback to `ExternalSource` if absent
+	Fallback,
+	/// The weighted average of both prices, the ratio is the weight of `Source`.
+	/// Use the available one if either is absent.
+	Weighted(Ratio),
+}
+
+impl Default for AggregationRule {
+	fn default() -> Self {
+		AggregationRule::SourceOnly
+	}
+}
+
 pub trait Trait: system::Trait {
-	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	type Source: DataProviderExtended<CurrencyId, Price, Self::AccountId>;
+
+	/// The price provider of an external oracle network, aggregated with `Source`
+	/// by the aggregation rule of currency
+	type ExternalSource: DataProvider<CurrencyId, Price>;
+
+	/// The dispersion of source prices across feeders, relative to the aggregated price.
+	/// The confidence of oracle price is `1 - dispersion`.
+	type Dispersion: DataProvider<CurrencyId, Ratio>;
 	type GetStableCurrencyId: Get<CurrencyId>;
 	type StableCurrencyFixedPrice: Get<Price>;
-	type GetStakingCurrencyId: Get<CurrencyId>;
-	type GetLiquidCurrencyId: Get<CurrencyId>;
 	type LockOrigin: EnsureOrigin<Self::Origin>;
-	type LiquidStakingExchangeRateProvider: ExchangeRateProvider;
+
+	/// The derivative assets whose price is derived from their underlying assets
+	type DerivativeAssets: DerivativeAsset<CurrencyId>;
+
+	/// The origin which may override prices in emergency. Root can always do this.
+	type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The maximum number of blocks an emergency price can stay in effect
+	type MaxEmergencyPriceDuration: Get<Self::BlockNumber>;
+
+	/// The list of collateral currency types whose price will be locked at emergency shutdown,
+	/// their feeds are also monitored by heartbeat
+	type CollateralCurrencyIds: Get<Vec<CurrencyId>>;
+
+	/// The number of blocks without new feed after which the feed is considered stale
+	type HeartbeatThreshold: Get<Self::BlockNumber>;
+
+	/// The maximum number of historical prices kept for each collateral currency
+	type PriceHistoryDepth: Get<u32>;
 }
 
 decl_event!(
-	pub enum Event {
+	pub enum Event<T> where
+		<T as system::Trait>::BlockNumber,
+	{
 		LockPrice(CurrencyId, Price),
 		UnlockPrice(CurrencyId),
+		/// Emergency price override set (currency_id, price, expire_at)
+		EmergencyPriceSet(CurrencyId, Price, BlockNumber),
+		/// Emergency price override cancelled by governance (currency_id)
+		EmergencyPriceCancelled(CurrencyId),
+		/// Emergency price override expired (currency_id)
+		EmergencyPriceExpired(CurrencyId),
+		/// The price source of stable currency switched (use_market_price)
+		StableCurrencyPriceSourceUpdated(bool),
+		/// No new feed received within heartbeat threshold (currency_id, last_updated_at)
+		PriceFeedStale(CurrencyId, BlockNumber),
+		/// Feed received again after being stale (currency_id)
+		PriceFeedRecovered(CurrencyId),
+		/// The aggregation rule of oracle sources updated (currency_id, rule)
+		AggregationRuleUpdated(CurrencyId, AggregationRule),
 	}
 );
 
+decl_error! {
+	/// Error for prices module.
+	pub enum Error for Module<T: Trait> {
+		/// The duration of emergency price is zero or exceeds the limit
+		InvalidEmergencyPriceDuration,
+		/// Can not override the price of this currency
+		InvalidCurrencyId,
+		/// The weight of aggregation rule is greater than one
+		InvalidWeight,
+		/// The emergency price is zero
+		ZeroEmergencyPrice,
+	}
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Prices {
 		LockedPrice get(fn locked_price): map hasher(twox_64_concat) CurrencyId => Option<Price>;
+
+		/// Emergency price overrides set by governance, map from
+		/// CurrencyId -> (Price, ExpireAt)
+		EmergencyPrice get(fn emergency_price): map hasher(twox_64_concat) CurrencyId => Option<(Price, T::BlockNumber)>;
+
+		/// Whether to use the market price of stable currency instead of the fixed peg
+		UseStableCurrencyMarketPrice get(fn use_stable_currency_market_price): bool;
+
+		/// The block number of the latest feed of currencies
+		LastFeedUpdatedAt get(fn last_feed_updated_at): map hasher(twox_64_concat) CurrencyId => T::BlockNumber;
+
+		/// The currencies whose feed is stale
+		StaleFeeds get(fn is_feed_stale): map hasher(twox_64_concat) CurrencyId => bool;
+
+		/// Recent prices in effect of collateral currencies, the entry is recorded when the price changes,
+		/// map from CurrencyId -> Vec<(BlockNumber, Price)> ordered by block number
+		PriceHistory get(fn price_history): map hasher(twox_64_concat) CurrencyId => Vec<(T::BlockNumber, Price)>;
+
+		/// The rule to aggregate the prices of oracle sources for currencies
+		AggregationRules get(fn aggregation_rule): map hasher(twox_64_concat) CurrencyId => AggregationRule;
+
+		/// The currencies whose prices are locked by emergency shutdown, the prices already locked
+		/// by others are not included
+		ShutdownLockedPrices get(fn shutdown_locked_prices): Vec<CurrencyId>;
 	}
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
 		fn deposit_event() = default;
 
 		const GetStableCurrencyId: CurrencyId = T::GetStableCurrencyId::get();
 		const StableCurrencyFixedPrice: Price = T::StableCurrencyFixedPrice::get();
+		const MaxEmergencyPriceDuration: T::BlockNumber = T::MaxEmergencyPriceDuration::get();
+		const HeartbeatThreshold: T::BlockNumber = T::HeartbeatThreshold::get();
+		const PriceHistoryDepth: u32 = T::PriceHistoryDepth::get();
 
 		#[weight = 10_000]
 		fn lock_price(origin, currency_id: CurrencyId) {
@@ -61,10 +186,229 @@ decl_module! {
 
 			<Module<T> as PriceProvider<CurrencyId>>::unlock_price(currency_id);
 		}
+
+		/// Pin the price of `currency_id` to `price` for the next `duration` blocks,
+		/// the override expires automatically afterwards.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `currency_id`: currency type.
+		/// - `price`: the price to pin, can't be zero.
+		/// - `duration`: the number of blocks the override stays in effect.
+		#[weight = 10_000]
+		fn set_emergency_price(origin, currency_id: CurrencyId, price: Price, duration: T::BlockNumber) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(
+				T::DerivativeAssets::get_underlying(currency_id).is_none()
+					&& (currency_id != T::GetStableCurrencyId::get() || Self::use_stable_currency_market_price()),
+				Error::<T>::InvalidCurrencyId,
+			);
+			ensure!(!price.is_zero(), Error::<T>::ZeroEmergencyPrice);
+			ensure!(
+				!duration.is_zero() && duration <= T::MaxEmergencyPriceDuration::get(),
+				Error::<T>::InvalidEmergencyPriceDuration,
+			);
+
+			let expire_at = <system::Module<T>>::block_number() + duration;
+			<EmergencyPrice<T>>::insert(currency_id, (price, expire_at));
+			Self::deposit_event(RawEvent::EmergencyPriceSet(currency_id, price, expire_at));
+		}
+
+		/// Cancel the emergency price override of `currency_id`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `currency_id`: currency type.
+		#[weight = 10_000]
+		fn cancel_emergency_price(origin, currency_id: CurrencyId) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			if <EmergencyPrice<T>>::take(currency_id).is_some() {
+				Self::deposit_event(RawEvent::EmergencyPriceCancelled(currency_id));
+			}
+		}
+
+		/// Switch the price source of stable currency between the fixed peg and the market price.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `use_market_price`: if true, stable currency is priced by oracle, otherwise by the fixed peg.
+		#[weight = 10_000]
+		fn set_stable_currency_price_source(origin, use_market_price: bool) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			UseStableCurrencyMarketPrice::put(use_market_price);
+			Self::deposit_event(RawEvent::StableCurrencyPriceSourceUpdated(use_market_price));
+		}
+
+		/// Set the rule to aggregate the prices of oracle `Source` and `ExternalSource` for `currency_id`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `currency_id`: currency type.
+		/// - `rule`: the aggregation rule.
+		#[weight = 10_000]
+		fn set_aggregation_rule(origin, currency_id: CurrencyId, rule: AggregationRule) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			if let AggregationRule::Weighted(weight) = rule {
+				ensure!(weight <= Ratio::one(), Error::<T>::InvalidWeight);
+			}
+
+			AggregationRules::insert(currency_id, rule);
+			Self::deposit_event(RawEvent::AggregationRuleUpdated(currency_id, rule));
+		}
+
+		/// Remove expired emergency price overrides, check feed heartbeat and record price history when block end
+		fn on_finalize(now: T::BlockNumber) {
+			let expired = <EmergencyPrice<T>>::iter()
+				.filter(|(_, (_, expire_at))| *expire_at <= now)
+				.map(|(currency_id, _)| currency_id)
+				.collect::<Vec<_>>();
+
+			for currency_id in expired {
+				<EmergencyPrice<T>>::remove(currency_id);
+				Self::deposit_event(RawEvent::EmergencyPriceExpired(currency_id));
+			}
+
+			Self::check_feed_heartbeat(now);
+			Self::record_price_history(now);
+		}
 	}
 }
 
-impl<T: Trait> Module<T> {}
+impl<T: Trait> Module<T> {
+	/// Mark feeds of collateral currencies stale if no new feed received within heartbeat threshold.
+	fn check_feed_heartbeat(now: T::BlockNumber) {
+		for currency_id in T::CollateralCurrencyIds::get() {
+			// the price of derivative asset is derived, it has no feed
+			if T::DerivativeAssets::get_underlying(currency_id).is_some() || Self::is_feed_stale(currency_id) {
+				continue;
+			}
+
+			let last_updated_at = Self::last_feed_updated_at(currency_id);
+			if now.saturating_sub(last_updated_at) >= T::HeartbeatThreshold::get() {
+				StaleFeeds::insert(currency_id, true);
+				Self::deposit_event(RawEvent::PriceFeedStale(currency_id, last_updated_at));
+			}
+		}
+	}
+
+	/// Record the price in effect of collateral currencies if it changes,
+	/// the oldest entry is dropped once the history exceeds the depth.
+	fn record_price_history(now: T::BlockNumber) {
+		let depth = T::PriceHistoryDepth::get() as usize;
+		if depth.is_zero() {
+			return;
+		}
+
+		for currency_id in T::CollateralCurrencyIds::get() {
+			if let Some(price) = <Module<T> as PriceProvider<CurrencyId>>::get_price(currency_id) {
+				<PriceHistory<T>>::mutate(currency_id, |history| {
+					if history.last().map(|(_, last_price)| *last_price) != Some(price) {
+						history.push((now, price));
+						if history.len() > depth {
+							let overflow = history.len() - depth;
+							history.drain(..overflow);
+						}
+					}
+				});
+			}
+		}
+	}
+
+	/// Get the recorded price in effect at block `at`,
+	/// `None` if `at` is earlier than the oldest recorded entry.
+	pub fn historical_price(currency_id: CurrencyId, at: T::BlockNumber) -> Option<Price> {
+		Self::price_history(currency_id)
+			.into_iter()
+			.rev()
+			.find(|(recorded_at, _)| *recorded_at <= at)
+			.map(|(_, price)| price)
+	}
+
+	/// Get the price currently in effect and its source, this is exactly the price
+	/// consumers see through `PriceProvider::get_price`.
+	pub fn get_price_and_source(currency_id: CurrencyId) -> Option<(Price, PriceSource)> {
+		if currency_id == T::GetStableCurrencyId::get() {
+			// if is stable currency, return fix price or market price
+			Some(Self::get_stable_currency_price(currency_id))
+		} else if let Some(locked_price) = Self::locked_price(currency_id) {
+			// if there's locked price return it
+			Some((locked_price, PriceSource::Locked))
+		} else if let Some((underlying_currency_id, exchange_rate)) = T::DerivativeAssets::get_underlying(currency_id) {
+			// if is derivative asset, return the price derived from underlying asset
+			Self::get_derivative_price(underlying_currency_id, exchange_rate)
+				.map(|price| (price, PriceSource::Derivative))
+		} else {
+			// get emergency price or latest price from oracle
+			Self::get_source_price(currency_id)
+		}
+	}
+
+	/// Get the price from the emergency override if it's still in effect, otherwise from oracle.
+	fn get_source_price(currency_id: CurrencyId) -> Option<(Price, PriceSource)> {
+		match Self::emergency_price(currency_id) {
+			Some((price, expire_at)) if expire_at > <system::Module<T>>::block_number() => {
+				Some((price, PriceSource::Emergency))
+			}
+			_ => Self::get_aggregated_price(currency_id).map(|price| (price, PriceSource::Oracle)),
+		}
+	}
+
+	/// Get the price aggregated from oracle sources by the aggregation rule of `currency_id`.
+	fn get_aggregated_price(currency_id: CurrencyId) -> Option<Price> {
+		match Self::aggregation_rule(currency_id) {
+			AggregationRule::SourceOnly => T::Source::get(&currency_id),
+			AggregationRule::Fallback => T::Source::get(&currency_id).or_else(|| T::ExternalSource::get(&currency_id)),
+			AggregationRule::Weighted(weight) => {
+				match (T::Source::get(&currency_id), T::ExternalSource::get(&currency_id)) {
+					(Some(price), Some(external_price)) => Some(
+						price
+							.saturating_mul(weight)
+							.saturating_add(external_price.saturating_mul(Ratio::one().saturating_sub(weight))),
+					),
+					(price, external_price) => price.or(external_price),
+				}
+			}
+		}
+	}
+
+	/// Get the confidence of oracle price, price without dispersion measure is treated as fully trusted.
+	fn get_oracle_confidence(currency_id: CurrencyId) -> Ratio {
+		T::Dispersion::get(&currency_id)
+			.map(|dispersion| Ratio::one().saturating_sub(dispersion))
+			.unwrap_or_else(Ratio::one)
+	}
+
+	/// Get the price of stable currency, return the market price if it's enabled and available,
+	/// otherwise return the fixed price.
+	fn get_stable_currency_price(currency_id: CurrencyId) -> (Price, PriceSource) {
+		let fixed_price = (T::StableCurrencyFixedPrice::get(), PriceSource::Fixed);
+		if Self::use_stable_currency_market_price() {
+			Self::locked_price(currency_id)
+				.map(|price| (price, PriceSource::Locked))
+				.or_else(|| Self::get_source_price(currency_id))
+				.unwrap_or(fixed_price)
+		} else {
+			fixed_price
+		}
+	}
+
+	/// Get the price of derivative asset,
+	/// which is the product of underlying asset price and derivative/underlying exchange rate.
+	fn get_derivative_price(underlying_currency_id: CurrencyId, exchange_rate: ExchangeRate) -> Option<Price> {
+		let underlying_price = Self::get_price(underlying_currency_id)?;
+		underlying_price.checked_mul(&exchange_rate)
+	}
+}
 
 impl<T: Trait> PriceProvider<CurrencyId> for Module<T> {
 	fn get_relative_price(base_currency_id: CurrencyId, quote_currency_id: CurrencyId) -> Option<Price> {
@@ -78,41 +422,113 @@ impl<T: Trait> PriceProvider<CurrencyId> for Module<T> {
 	}
 
 	fn get_price(currency_id: CurrencyId) -> Option<Price> {
-		if currency_id == T::GetStableCurrencyId::get() {
-			// if is stable currency, return fix price
-			Some(T::StableCurrencyFixedPrice::get())
-		} else if currency_id == T::GetLiquidCurrencyId::get() {
-			// if is homa liquid currency,
-			// return the product of staking currency price and liquid/staking exchange rate.
-			if let Some(staking_currency_price) = Self::get_price(T::GetStakingCurrencyId::get()) {
-				let exchange_rate: Price = T::LiquidStakingExchangeRateProvider::get_exchange_rate();
-				staking_currency_price.checked_mul(&exchange_rate)
-			} else {
-				None
-			}
-		} else {
-			// if locked price exists, return it,
-			// otherwise return the price get from oracle.
-			if let Some(locked_price) = Self::locked_price(currency_id) {
-				// if there's locked price return it
-				Some(locked_price)
-			} else {
-				// get latest price from oracle
-				T::Source::get(&currency_id)
-			}
-		}
+		Self::get_price_and_source(currency_id).map(|(price, _)| price)
+	}
+
+	fn get_price_with_confidence(currency_id: CurrencyId) -> Option<(Price, Ratio)> {
+		let (price, source) = Self::get_price_and_source(currency_id)?;
+		let confidence = match source {
+			// fixed price, locked price and emergency price are fully trusted
+			PriceSource::Fixed | PriceSource::Locked | PriceSource::Emergency => Ratio::one(),
+			PriceSource::Oracle => Self::get_oracle_confidence(currency_id),
+			// derived price has the same confidence as the underlying asset
+			PriceSource::Derivative => T::DerivativeAssets::get_underlying(currency_id)
+				.and_then(|(underlying_currency_id, _)| Self::get_price_with_confidence(underlying_currency_id))
+				.map(|(_, confidence)| confidence)
+				.unwrap_or_else(Ratio::one),
+		};
+
+		Some((price, confidence))
 	}
 
 	fn lock_price(currency_id: CurrencyId) {
+		let price =
+			if let Some((underlying_currency_id, exchange_rate)) = T::DerivativeAssets::get_underlying(currency_id) {
+				Self::get_derivative_price(underlying_currency_id, exchange_rate)
+			} else {
+				Self::get_source_price(currency_id).map(|(price, _)| price)
+			};
+
 		// lock price when get valid price from source
-		if let Some(val) = T::Source::get(&currency_id) {
+		if let Some(val) = price {
 			LockedPrice::insert(currency_id, val);
-			<Module<T>>::deposit_event(Event::LockPrice(currency_id, val));
+			<Module<T>>::deposit_event(RawEvent::LockPrice(currency_id, val));
 		}
 	}
 
 	fn unlock_price(currency_id: CurrencyId) {
 		LockedPrice::remove(currency_id);
-		<Module<T>>::deposit_event(Event::UnlockPrice(currency_id));
+		<Module<T>>::deposit_event(RawEvent::UnlockPrice(currency_id));
+	}
+}
+
+/// Derivative asset adapter, the derivative asset is priced by
+/// the product of underlying asset price and the exchange rate provided by `RateProvider`,
+/// e.g. homa liquid currency LDOT derived from DOT with the liquid exchange rate of staking pool.
+pub struct DerivativeAssetAdapter<GetDerivativeCurrencyId, GetUnderlyingCurrencyId, RateProvider>(
+	PhantomData<(GetDerivativeCurrencyId, GetUnderlyingCurrencyId, RateProvider)>,
+);
+
+impl<GetDerivativeCurrencyId, GetUnderlyingCurrencyId, RateProvider> DerivativeAsset<CurrencyId>
+	for DerivativeAssetAdapter<GetDerivativeCurrencyId, GetUnderlyingCurrencyId, RateProvider>
+where
+	GetDerivativeCurrencyId: Get<CurrencyId>,
+	GetUnderlyingCurrencyId: Get<CurrencyId>,
+	RateProvider: ExchangeRateProvider,
+{
+	fn get_underlying(currency_id: CurrencyId) -> Option<(CurrencyId, ExchangeRate)> {
+		if currency_id == GetDerivativeCurrencyId::get() {
+			Some((GetUnderlyingCurrencyId::get(), RateProvider::get_exchange_rate()))
+		} else {
+			None
+		}
+	}
+}
+
+/// The external source for chains without an external oracle network, provides no price.
+pub struct NoExternalSource;
+
+impl DataProvider<CurrencyId, Price> for NoExternalSource {
+	fn get(_currency_id: &CurrencyId) -> Option<Price> {
+		None
+	}
+}
+
+impl<T: Trait> OnNewData<T::AccountId, CurrencyId, Price> for Module<T> {
+	fn on_new_data(_who: &T::AccountId, currency_id: &CurrencyId, _price: &Price) {
+		<LastFeedUpdatedAt<T>>::insert(currency_id, <system::Module<T>>::block_number());
+		if StaleFeeds::take(currency_id) {
+			Self::deposit_event(RawEvent::PriceFeedRecovered(*currency_id));
+		}
+	}
+}
+
+impl<T: Trait> OnEmergencyShutdown for Module<T> {
+	fn on_emergency_shutdown() {
+		// lock price for every collateral, so that all settlements use consistent prices,
+		// the market price of stable currency can drift as well
+		let mut currency_ids = T::CollateralCurrencyIds::get();
+		if Self::use_stable_currency_market_price() {
+			currency_ids.push(T::GetStableCurrencyId::get());
+		}
+
+		// keep the prices locked by governance or retired collateral, and record the prices locked here
+		let mut locked_currency_ids = Self::shutdown_locked_prices();
+		for currency_id in currency_ids {
+			if Self::locked_price(currency_id).is_none() {
+				<Module<T> as PriceProvider<CurrencyId>>::lock_price(currency_id);
+				if Self::locked_price(currency_id).is_some() {
+					locked_currency_ids.push(currency_id);
+				}
+			}
+		}
+		ShutdownLockedPrices::put(locked_currency_ids);
+	}
+
+	fn on_cancel_shutdown() {
+		// only unlock the prices locked by emergency shutdown
+		for currency_id in ShutdownLockedPrices::take() {
+			<Module<T> as PriceProvider<CurrencyId>>::unlock_price(currency_id);
+		}
 	}
 }
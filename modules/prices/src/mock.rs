@@ -28,7 +28,7 @@ mod prices {
 impl_outer_event! {
 	pub enum TestEvent for Runtime {
 		system<T>,
-		prices,
+		prices<T>,
 	}
 }
 
@@ -89,6 +89,29 @@ impl DataProviderExtended<CurrencyId, Price, AccountId> for MockDataProvider {
 	}
 }
 
+pub struct MockExternalSource;
+impl DataProvider<CurrencyId, Price> for MockExternalSource {
+	fn get(currency_id: &CurrencyId) -> Option<Price> {
+		match currency_id {
+			&BTC => Some(Price::saturating_from_integer(6000)),
+			&DOT => Some(Price::saturating_from_integer(120)),
+			&ACA => Some(Price::saturating_from_integer(2)),
+			_ => None,
+		}
+	}
+}
+
+pub struct MockDispersion;
+impl DataProvider<CurrencyId, Ratio> for MockDispersion {
+	fn get(currency_id: &CurrencyId) -> Option<Ratio> {
+		match currency_id {
+			&BTC => Some(Ratio::saturating_from_rational(5, 100)),
+			&DOT => Some(Ratio::saturating_from_rational(20, 100)),
+			_ => None,
+		}
+	}
+}
+
 pub struct MockLiquidStakingExchangeProvider;
 impl ExchangeRateProvider for MockLiquidStakingExchangeProvider {
 	fn get_exchange_rate() -> ExchangeRate {
@@ -105,17 +128,27 @@ parameter_types! {
 	pub const GetStakingCurrencyId: CurrencyId = DOT;
 	pub const GetLiquidCurrencyId: CurrencyId = LDOT;
 	pub StableCurrencyFixedPrice: Price = Price::saturating_from_integer(1);
+	pub const MaxEmergencyPriceDuration: BlockNumber = 100;
+	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![BTC, DOT, LDOT];
+	pub const HeartbeatThreshold: BlockNumber = 10;
+	pub const PriceHistoryDepth: u32 = 3;
 }
 
 impl Trait for Runtime {
 	type Event = TestEvent;
 	type Source = MockDataProvider;
+	type ExternalSource = MockExternalSource;
+	type Dispersion = MockDispersion;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type StableCurrencyFixedPrice = StableCurrencyFixedPrice;
-	type GetStakingCurrencyId = GetStakingCurrencyId;
-	type GetLiquidCurrencyId = GetLiquidCurrencyId;
 	type LockOrigin = EnsureSignedBy<One, AccountId>;
-	type LiquidStakingExchangeRateProvider = MockLiquidStakingExchangeProvider;
+	type DerivativeAssets =
+		DerivativeAssetAdapter<GetLiquidCurrencyId, GetStakingCurrencyId, MockLiquidStakingExchangeProvider>;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type MaxEmergencyPriceDuration = MaxEmergencyPriceDuration;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type HeartbeatThreshold = HeartbeatThreshold;
+	type PriceHistoryDepth = PriceHistoryDepth;
 }
 pub type PricesModule = Module<Runtime>;
 
@@ -3,8 +3,11 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
-use mock::{ExtBuilder, Origin, PricesModule, System, TestEvent, ACA, AUSD, BTC, DOT, LDOT};
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize};
+use mock::{
+	ExtBuilder, GetLiquidCurrencyId, GetStakingCurrencyId, MockLiquidStakingExchangeProvider, Origin, PricesModule,
+	Runtime, System, TestEvent, ACA, AUSD, BTC, DOT, LDOT,
+};
 use sp_runtime::{traits::BadOrigin, FixedPointNumber};
 
 #[test]
@@ -69,7 +72,7 @@ fn lock_price_call_work() {
 		assert_noop!(PricesModule::lock_price(Origin::signed(5), BTC), BadOrigin,);
 		assert_ok!(PricesModule::lock_price(Origin::ROOT, BTC));
 
-		let lock_price_event = TestEvent::prices(Event::LockPrice(BTC, Price::saturating_from_integer(5000)));
+		let lock_price_event = TestEvent::prices(RawEvent::LockPrice(BTC, Price::saturating_from_integer(5000)));
 		assert!(System::events().iter().any(|record| record.event == lock_price_event));
 
 		assert_eq!(
@@ -87,9 +90,580 @@ fn unlock_price_call_work() {
 		assert_noop!(PricesModule::unlock_price(Origin::signed(5), BTC), BadOrigin,);
 		assert_ok!(PricesModule::unlock_price(Origin::signed(1), BTC));
 
-		let unlock_price_event = TestEvent::prices(Event::UnlockPrice(BTC));
+		let unlock_price_event = TestEvent::prices(RawEvent::UnlockPrice(BTC));
 		assert!(System::events().iter().any(|record| record.event == unlock_price_event));
 
 		assert_eq!(PricesModule::locked_price(BTC), None);
 	});
 }
+
+#[test]
+fn set_emergency_price_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			PricesModule::set_emergency_price(Origin::signed(5), BTC, Price::saturating_from_integer(4000), 10),
+			BadOrigin,
+		);
+		assert_noop!(
+			PricesModule::set_emergency_price(Origin::signed(1), AUSD, Price::saturating_from_integer(4000), 10),
+			Error::<Runtime>::InvalidCurrencyId,
+		);
+		assert_noop!(
+			PricesModule::set_emergency_price(Origin::signed(1), BTC, Price::saturating_from_integer(4000), 0),
+			Error::<Runtime>::InvalidEmergencyPriceDuration,
+		);
+		assert_noop!(
+			PricesModule::set_emergency_price(Origin::signed(1), BTC, Price::zero(), 10),
+			Error::<Runtime>::ZeroEmergencyPrice,
+		);
+		assert_noop!(
+			PricesModule::set_emergency_price(Origin::signed(1), BTC, Price::saturating_from_integer(4000), 101),
+			Error::<Runtime>::InvalidEmergencyPriceDuration,
+		);
+
+		assert_ok!(PricesModule::set_emergency_price(
+			Origin::signed(1),
+			BTC,
+			Price::saturating_from_integer(4000),
+			10
+		));
+		let set_event = TestEvent::prices(RawEvent::EmergencyPriceSet(
+			BTC,
+			Price::saturating_from_integer(4000),
+			11,
+		));
+		assert!(System::events().iter().any(|record| record.event == set_event));
+		assert_eq!(
+			PricesModule::emergency_price(BTC),
+			Some((Price::saturating_from_integer(4000), 11))
+		);
+		assert_eq!(PricesModule::get_price(BTC), Some(Price::saturating_from_integer(4000)));
+	});
+}
+
+#[test]
+fn locked_price_take_precedence_over_emergency_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PricesModule::set_emergency_price(
+			Origin::ROOT,
+			BTC,
+			Price::saturating_from_integer(4000),
+			10
+		));
+		LockedPrice::insert(BTC, Price::saturating_from_integer(8000));
+		assert_eq!(PricesModule::get_price(BTC), Some(Price::saturating_from_integer(8000)));
+	});
+}
+
+#[test]
+fn lock_price_snapshot_emergency_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PricesModule::set_emergency_price(
+			Origin::ROOT,
+			BTC,
+			Price::saturating_from_integer(4000),
+			10
+		));
+		assert_ok!(PricesModule::lock_price(Origin::ROOT, BTC));
+		assert_eq!(
+			PricesModule::locked_price(BTC),
+			Some(Price::saturating_from_integer(4000))
+		);
+	});
+}
+
+#[test]
+fn cancel_emergency_price_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PricesModule::set_emergency_price(
+			Origin::ROOT,
+			BTC,
+			Price::saturating_from_integer(4000),
+			10
+		));
+		assert_noop!(PricesModule::cancel_emergency_price(Origin::signed(5), BTC), BadOrigin,);
+		assert_ok!(PricesModule::cancel_emergency_price(Origin::signed(1), BTC));
+
+		let cancel_event = TestEvent::prices(RawEvent::EmergencyPriceCancelled(BTC));
+		assert!(System::events().iter().any(|record| record.event == cancel_event));
+		assert_eq!(PricesModule::emergency_price(BTC), None);
+		assert_eq!(PricesModule::get_price(BTC), Some(Price::saturating_from_integer(5000)));
+	});
+}
+
+#[test]
+fn emergency_price_expire_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PricesModule::set_emergency_price(
+			Origin::ROOT,
+			BTC,
+			Price::saturating_from_integer(4000),
+			10
+		));
+
+		PricesModule::on_finalize(10);
+		assert_eq!(
+			PricesModule::emergency_price(BTC),
+			Some((Price::saturating_from_integer(4000), 11))
+		);
+
+		System::set_block_number(11);
+		assert_eq!(PricesModule::get_price(BTC), Some(Price::saturating_from_integer(5000)));
+
+		PricesModule::on_finalize(11);
+		let expire_event = TestEvent::prices(RawEvent::EmergencyPriceExpired(BTC));
+		assert!(System::events().iter().any(|record| record.event == expire_event));
+		assert_eq!(PricesModule::emergency_price(BTC), None);
+	});
+}
+
+#[test]
+fn get_price_of_liquid_currency_use_locked_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(PricesModule::get_price(LDOT), Some(Price::saturating_from_integer(50)));
+		LockedPrice::insert(LDOT, Price::saturating_from_integer(40));
+		assert_eq!(PricesModule::get_price(LDOT), Some(Price::saturating_from_integer(40)));
+	});
+}
+
+#[test]
+fn on_emergency_shutdown_lock_collateral_prices() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(PricesModule::locked_price(BTC), None);
+		assert_eq!(PricesModule::locked_price(DOT), None);
+		assert_eq!(PricesModule::locked_price(LDOT), None);
+
+		PricesModule::on_emergency_shutdown();
+		assert_eq!(
+			PricesModule::locked_price(BTC),
+			Some(Price::saturating_from_integer(5000))
+		);
+		assert_eq!(
+			PricesModule::locked_price(DOT),
+			Some(Price::saturating_from_integer(100))
+		);
+		assert_eq!(
+			PricesModule::locked_price(LDOT),
+			Some(Price::saturating_from_integer(50))
+		);
+		assert_eq!(PricesModule::locked_price(ACA), None);
+
+		let lock_ldot_event = TestEvent::prices(RawEvent::LockPrice(LDOT, Price::saturating_from_integer(50)));
+		assert!(System::events().iter().any(|record| record.event == lock_ldot_event));
+	});
+}
+
+#[test]
+fn on_emergency_shutdown_lock_stable_currency_market_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		PricesModule::on_emergency_shutdown();
+		assert_eq!(PricesModule::locked_price(AUSD), None);
+
+		assert_ok!(PricesModule::set_stable_currency_price_source(Origin::signed(1), true));
+		PricesModule::on_emergency_shutdown();
+		assert_eq!(
+			PricesModule::locked_price(AUSD),
+			Some(Price::saturating_from_rational(99, 100))
+		);
+		assert_eq!(
+			PricesModule::get_price(AUSD),
+			Some(Price::saturating_from_rational(99, 100))
+		);
+	});
+}
+
+#[test]
+fn on_cancel_shutdown_unlock_collateral_prices() {
+	ExtBuilder::default().build().execute_with(|| {
+		PricesModule::on_emergency_shutdown();
+		assert_eq!(
+			PricesModule::locked_price(BTC),
+			Some(Price::saturating_from_integer(5000))
+		);
+
+		PricesModule::on_cancel_shutdown();
+		assert_eq!(PricesModule::locked_price(BTC), None);
+		assert_eq!(PricesModule::locked_price(DOT), None);
+		assert_eq!(PricesModule::locked_price(LDOT), None);
+		assert_eq!(PricesModule::shutdown_locked_prices(), vec![]);
+	});
+}
+
+#[test]
+fn on_cancel_shutdown_keep_prices_locked_by_others() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PricesModule::lock_price(Origin::signed(1), DOT));
+		LockedPrice::insert(BTC, Price::saturating_from_integer(4000));
+
+		PricesModule::on_emergency_shutdown();
+		assert_eq!(
+			PricesModule::locked_price(BTC),
+			Some(Price::saturating_from_integer(4000))
+		);
+		assert_eq!(PricesModule::shutdown_locked_prices(), vec![LDOT]);
+
+		PricesModule::on_cancel_shutdown();
+		assert_eq!(
+			PricesModule::locked_price(BTC),
+			Some(Price::saturating_from_integer(4000))
+		);
+		assert_eq!(
+			PricesModule::locked_price(DOT),
+			Some(Price::saturating_from_integer(100))
+		);
+		assert_eq!(PricesModule::locked_price(LDOT), None);
+	});
+}
+
+#[test]
+fn get_price_with_confidence_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(
+			PricesModule::get_price_with_confidence(BTC),
+			Some((
+				Price::saturating_from_integer(5000),
+				Ratio::saturating_from_rational(95, 100)
+			))
+		);
+		assert_eq!(
+			PricesModule::get_price_with_confidence(ACA),
+			Some((Price::saturating_from_integer(0), Ratio::one()))
+		);
+		assert_eq!(
+			PricesModule::get_price_with_confidence(AUSD),
+			Some((Price::saturating_from_integer(1), Ratio::one()))
+		);
+		assert_eq!(
+			PricesModule::get_price_with_confidence(LDOT),
+			Some((
+				Price::saturating_from_integer(50),
+				Ratio::saturating_from_rational(80, 100)
+			))
+		);
+
+		LockedPrice::insert(BTC, Price::saturating_from_integer(8000));
+		assert_eq!(
+			PricesModule::get_price_with_confidence(BTC),
+			Some((Price::saturating_from_integer(8000), Ratio::one()))
+		);
+	});
+}
+
+#[test]
+fn get_relative_price_with_confidence_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(
+			PricesModule::get_relative_price_with_confidence(BTC, DOT),
+			Some((
+				Price::saturating_from_integer(50),
+				Ratio::saturating_from_rational(80, 100)
+			))
+		);
+		assert_eq!(
+			PricesModule::get_relative_price_with_confidence(BTC, AUSD),
+			Some((
+				Price::saturating_from_integer(5000),
+				Ratio::saturating_from_rational(95, 100)
+			))
+		);
+	});
+}
+
+#[test]
+fn emergency_price_has_full_confidence() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PricesModule::set_emergency_price(
+			Origin::ROOT,
+			BTC,
+			Price::saturating_from_integer(4000),
+			10
+		));
+		assert_eq!(
+			PricesModule::get_price_with_confidence(BTC),
+			Some((Price::saturating_from_integer(4000), Ratio::one()))
+		);
+	});
+}
+
+#[test]
+fn set_stable_currency_price_source_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			PricesModule::set_stable_currency_price_source(Origin::signed(5), true),
+			BadOrigin,
+		);
+		assert_eq!(PricesModule::use_stable_currency_market_price(), false);
+		assert_eq!(PricesModule::get_price(AUSD), Some(Price::saturating_from_integer(1)));
+
+		assert_ok!(PricesModule::set_stable_currency_price_source(Origin::signed(1), true));
+		let update_event = TestEvent::prices(RawEvent::StableCurrencyPriceSourceUpdated(true));
+		assert!(System::events().iter().any(|record| record.event == update_event));
+		assert_eq!(PricesModule::use_stable_currency_market_price(), true);
+		assert_eq!(
+			PricesModule::get_price(AUSD),
+			Some(Price::saturating_from_rational(99, 100))
+		);
+		assert_eq!(
+			PricesModule::get_relative_price(BTC, AUSD),
+			Price::saturating_from_integer(5000).checked_div(&Price::saturating_from_rational(99, 100))
+		);
+
+		assert_ok!(PricesModule::set_stable_currency_price_source(Origin::ROOT, false));
+		assert_eq!(PricesModule::get_price(AUSD), Some(Price::saturating_from_integer(1)));
+	});
+}
+
+#[test]
+fn emergency_price_of_stable_currency_require_market_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			PricesModule::set_emergency_price(Origin::ROOT, AUSD, Price::saturating_from_rational(95, 100), 10),
+			Error::<Runtime>::InvalidCurrencyId,
+		);
+		assert_ok!(PricesModule::set_stable_currency_price_source(Origin::ROOT, true));
+		assert_ok!(PricesModule::set_emergency_price(
+			Origin::ROOT,
+			AUSD,
+			Price::saturating_from_rational(95, 100),
+			10
+		));
+		assert_eq!(
+			PricesModule::get_price(AUSD),
+			Some(Price::saturating_from_rational(95, 100))
+		);
+	});
+}
+
+#[test]
+fn feed_heartbeat_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		PricesModule::on_new_data(&1, &BTC, &Price::saturating_from_integer(5000));
+		PricesModule::on_new_data(&1, &DOT, &Price::saturating_from_integer(100));
+		assert_eq!(PricesModule::last_feed_updated_at(BTC), 1);
+
+		PricesModule::on_finalize(10);
+		assert_eq!(PricesModule::is_feed_stale(BTC), false);
+		assert_eq!(PricesModule::is_feed_stale(DOT), false);
+
+		System::set_block_number(5);
+		PricesModule::on_new_data(&1, &DOT, &Price::saturating_from_integer(100));
+
+		PricesModule::on_finalize(11);
+		assert_eq!(PricesModule::is_feed_stale(BTC), true);
+		assert_eq!(PricesModule::is_feed_stale(DOT), false);
+		assert_eq!(PricesModule::is_feed_stale(LDOT), false);
+		let stale_event = TestEvent::prices(RawEvent::PriceFeedStale(BTC, 1));
+		assert!(System::events().iter().any(|record| record.event == stale_event));
+
+		System::set_block_number(12);
+		PricesModule::on_new_data(&1, &BTC, &Price::saturating_from_integer(5000));
+		assert_eq!(PricesModule::is_feed_stale(BTC), false);
+		assert_eq!(PricesModule::last_feed_updated_at(BTC), 12);
+		let recovered_event = TestEvent::prices(RawEvent::PriceFeedRecovered(BTC));
+		assert!(System::events().iter().any(|record| record.event == recovered_event));
+	});
+}
+
+#[test]
+fn derivative_asset_adapter_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		type Adapter =
+			DerivativeAssetAdapter<GetLiquidCurrencyId, GetStakingCurrencyId, MockLiquidStakingExchangeProvider>;
+		assert_eq!(
+			Adapter::get_underlying(LDOT),
+			Some((DOT, ExchangeRate::saturating_from_rational(1, 2)))
+		);
+		assert_eq!(Adapter::get_underlying(DOT), None);
+		assert_eq!(
+			<(Adapter, Adapter)>::get_underlying(LDOT),
+			Adapter::get_underlying(LDOT)
+		);
+		assert_eq!(<()>::get_underlying(LDOT), None);
+	});
+}
+
+#[test]
+fn get_price_and_source_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(
+			PricesModule::get_price_and_source(AUSD),
+			Some((Price::saturating_from_integer(1), PriceSource::Fixed))
+		);
+		assert_eq!(
+			PricesModule::get_price_and_source(BTC),
+			Some((Price::saturating_from_integer(5000), PriceSource::Oracle))
+		);
+		assert_eq!(
+			PricesModule::get_price_and_source(LDOT),
+			Some((Price::saturating_from_integer(50), PriceSource::Derivative))
+		);
+
+		assert_ok!(PricesModule::set_emergency_price(
+			Origin::ROOT,
+			BTC,
+			Price::saturating_from_integer(4000),
+			10
+		));
+		assert_eq!(
+			PricesModule::get_price_and_source(BTC),
+			Some((Price::saturating_from_integer(4000), PriceSource::Emergency))
+		);
+
+		LockedPrice::insert(BTC, Price::saturating_from_integer(8000));
+		assert_eq!(
+			PricesModule::get_price_and_source(BTC),
+			Some((Price::saturating_from_integer(8000), PriceSource::Locked))
+		);
+		assert_eq!(
+			PricesModule::get_price_and_source(BTC).map(|(price, _)| price),
+			PricesModule::get_price(BTC)
+		);
+	});
+}
+
+#[test]
+fn record_price_history_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		PricesModule::on_finalize(1);
+		assert_eq!(
+			PricesModule::price_history(BTC),
+			vec![(1, Price::saturating_from_integer(5000))]
+		);
+
+		// unchanged price is not recorded
+		PricesModule::on_finalize(2);
+		assert_eq!(
+			PricesModule::price_history(BTC),
+			vec![(1, Price::saturating_from_integer(5000))]
+		);
+
+		LockedPrice::insert(BTC, Price::saturating_from_integer(6000));
+		PricesModule::on_finalize(3);
+		LockedPrice::insert(BTC, Price::saturating_from_integer(7000));
+		PricesModule::on_finalize(4);
+		assert_eq!(
+			PricesModule::price_history(BTC),
+			vec![
+				(1, Price::saturating_from_integer(5000)),
+				(3, Price::saturating_from_integer(6000)),
+				(4, Price::saturating_from_integer(7000))
+			]
+		);
+
+		// the oldest entry is dropped when exceeds the depth
+		LockedPrice::insert(BTC, Price::saturating_from_integer(8000));
+		PricesModule::on_finalize(6);
+		assert_eq!(
+			PricesModule::price_history(BTC),
+			vec![
+				(3, Price::saturating_from_integer(6000)),
+				(4, Price::saturating_from_integer(7000)),
+				(6, Price::saturating_from_integer(8000))
+			]
+		);
+
+		assert_eq!(PricesModule::historical_price(BTC, 2), None);
+		assert_eq!(
+			PricesModule::historical_price(BTC, 3),
+			Some(Price::saturating_from_integer(6000))
+		);
+		assert_eq!(
+			PricesModule::historical_price(BTC, 5),
+			Some(Price::saturating_from_integer(7000))
+		);
+		assert_eq!(
+			PricesModule::historical_price(BTC, 10),
+			Some(Price::saturating_from_integer(8000))
+		);
+		assert_eq!(PricesModule::price_history(ACA), vec![]);
+	});
+}
+
+#[test]
+fn set_aggregation_rule_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(PricesModule::aggregation_rule(BTC), AggregationRule::SourceOnly);
+		assert_noop!(
+			PricesModule::set_aggregation_rule(Origin::signed(5), BTC, AggregationRule::Fallback),
+			BadOrigin,
+		);
+		assert_noop!(
+			PricesModule::set_aggregation_rule(
+				Origin::signed(1),
+				BTC,
+				AggregationRule::Weighted(Ratio::saturating_from_rational(3, 2))
+			),
+			Error::<Runtime>::InvalidWeight,
+		);
+
+		assert_ok!(PricesModule::set_aggregation_rule(
+			Origin::signed(1),
+			BTC,
+			AggregationRule::Fallback
+		));
+		let update_event = TestEvent::prices(RawEvent::AggregationRuleUpdated(BTC, AggregationRule::Fallback));
+		assert!(System::events().iter().any(|record| record.event == update_event));
+		assert_eq!(PricesModule::aggregation_rule(BTC), AggregationRule::Fallback);
+
+		assert_ok!(PricesModule::set_aggregation_rule(
+			Origin::ROOT,
+			BTC,
+			AggregationRule::SourceOnly
+		));
+		assert_eq!(PricesModule::aggregation_rule(BTC), AggregationRule::SourceOnly);
+	});
+}
+
+#[test]
+fn get_aggregated_price_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(PricesModule::get_price(BTC), Some(Price::saturating_from_integer(5000)));
+
+		// source price takes precedence when available
+		assert_ok!(PricesModule::set_aggregation_rule(
+			Origin::ROOT,
+			BTC,
+			AggregationRule::Fallback
+		));
+		assert_eq!(PricesModule::get_price(BTC), Some(Price::saturating_from_integer(5000)));
+
+		assert_ok!(PricesModule::set_aggregation_rule(
+			Origin::ROOT,
+			BTC,
+			AggregationRule::Weighted(Ratio::saturating_from_rational(1, 4))
+		));
+		assert_eq!(PricesModule::get_price(BTC), Some(Price::saturating_from_integer(5750)));
+		assert_eq!(
+			PricesModule::get_price_and_source(BTC),
+			Some((Price::saturating_from_integer(5750), PriceSource::Oracle))
+		);
+
+		assert_ok!(PricesModule::set_aggregation_rule(
+			Origin::ROOT,
+			DOT,
+			AggregationRule::Weighted(Ratio::one())
+		));
+		assert_eq!(PricesModule::get_price(DOT), Some(Price::saturating_from_integer(100)));
+
+		// locked price is not affected by the aggregation rule
+		assert_ok!(PricesModule::lock_price(Origin::ROOT, BTC));
+		assert_ok!(PricesModule::set_aggregation_rule(
+			Origin::ROOT,
+			BTC,
+			AggregationRule::Weighted(Ratio::zero())
+		));
+		assert_eq!(PricesModule::get_price(BTC), Some(Price::saturating_from_integer(5750)));
+	});
+}
@@ -9,11 +9,14 @@ use serde::{Deserialize, Serialize};
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum CurrencyId {
-	ACA = 0,
-	AUSD = 1,
-	DOT = 2,
-	XBTC = 3,
-	LDOT = 4,
+	// the encoded index of variants must not change, append new variants to the end
+	ACA,
+	AUSD,
+	DOT,
+	XBTC,
+	LDOT,
+	/// Asset registered in the asset registry, e.g. bridged or locally issued tokens
+	Registered(AssetId),
 }
 
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
@@ -23,6 +26,9 @@ pub enum AirDropCurrencyId {
 	ACA,
 }
 
+/// Identifier of assets registered in the asset registry.
+pub type AssetId = u32;
+
 /// Counter for the number of eras that have passed.
 pub type EraIndex = u32;
 
@@ -0,0 +1,241 @@
+//! # RenVM Bridge Module
+//!
+//! ## Overview
+//!
+//! Mints and burns the wrapped BTC backed by an external custody, e.g. the RenVM darknodes.
+//! After receiving a BTC deposit the custody signs a mint request, anyone can submit the signed
+//! request as an unsigned transaction to mint the wrapped BTC to the recipient. Burning the wrapped
+//! BTC deposits an event with the BTC destination address, then the custody releases the BTC to it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Encode;
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{EnsureOrigin, Get},
+	weights::constants::WEIGHT_PER_MICROS,
+};
+use frame_system::{self as system, ensure_none, ensure_root, ensure_signed};
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_core::ecdsa;
+use sp_runtime::{
+	traits::{Verify, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+	},
+	DispatchResult,
+};
+use sp_std::prelude::*;
+
+mod mock;
+mod tests;
+
+/// The maximum length of destination address, the longest bech32 address has 90 characters
+pub const MAX_DESTINATION_LENGTH: usize = 90;
+
+/// The domain of the message signed by the custody for mint requests
+const MINT_MESSAGE_DOMAIN: &[u8] = b"acala/renvm-bridge/mint";
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Currency to mint and burn the wrapped asset
+	type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+	/// The currency id of the wrapped asset
+	type BridgedCurrencyId: Get<CurrencyId>;
+
+	/// The origin which may update the public key of the custody. Root can always do this.
+	type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// A configuration for base priority of unsigned transactions.
+	///
+	/// This is exposed so that it can be tuned for particular runtime, when
+	/// multiple modules send unsigned transactions.
+	type UnsignedPriority: Get<TransactionPriority>;
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		<T as system::Trait>::AccountId,
+		Balance = Balance,
+	{
+		/// Minted the wrapped asset (recipient, amount, n_hash)
+		Minted(AccountId, Balance, [u8; 32]),
+		/// Burnt the wrapped asset to release the asset to the destination (who, destination, amount)
+		Burnt(AccountId, Vec<u8>, Balance),
+		/// The public key of the custody updated (new_key)
+		CustodyKeyUpdated(ecdsa::Public),
+	}
+);
+
+decl_error! {
+	/// Error for renvm bridge module.
+	pub enum Error for Module<T: Trait> {
+		/// The public key of the custody is not set
+		CustodyKeyNotSet,
+		/// The signature of mint request is not signed by the custody
+		InvalidSignature,
+		/// The deposit has already been minted
+		AlreadyMinted,
+		/// The destination address is empty or too long
+		InvalidDestination,
+		/// The amount is zero
+		ZeroAmount,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as RenVmBridge {
+		/// The public key of the custody which signs the mint requests
+		pub CustodyKey get(fn custody_key): Option<ecdsa::Public>;
+
+		/// The deposits already minted, identified by the nonce hash of mint request
+		pub MintedDeposits get(fn minted_deposits): map hasher(twox_64_concat) [u8; 32] => bool;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// The currency id of the wrapped asset
+		const BridgedCurrencyId: CurrencyId = T::BridgedCurrencyId::get();
+
+		/// Update the public key of the custody.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `key`: the new public key.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `CustodyKey`
+		/// -------------------
+		/// Base Weight: 10 µs
+		/// # </weight>
+		#[weight = 10 * WEIGHT_PER_MICROS + T::DbWeight::get().writes(1)]
+		pub fn set_custody_key(origin, key: ecdsa::Public) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			CustodyKey::put(key.clone());
+			Self::deposit_event(RawEvent::CustodyKeyUpdated(key));
+		}
+
+		/// Mint the wrapped asset by the mint request signed by the custody.
+		///
+		/// The dispatch origin of this call must be _None_.
+		///
+		/// - `who`: the recipient.
+		/// - `p_hash`: the hash of the payload of deposit.
+		/// - `amount`: the amount to mint.
+		/// - `n_hash`: the nonce hash which identifies the deposit.
+		/// - `signature`: the signature of custody on the mint request.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `MintedDeposits`, `CustodyKey`, 1 items of orml_tokens
+		/// - Db writes: `MintedDeposits`, 2 items of orml_tokens
+		/// -------------------
+		/// Base Weight: 80 µs
+		/// # </weight>
+		#[weight = 80 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(3, 3)]
+		pub fn mint(
+			origin,
+			who: T::AccountId,
+			p_hash: [u8; 32],
+			#[compact] amount: Balance,
+			n_hash: [u8; 32],
+			signature: ecdsa::Signature,
+		) {
+			ensure_none(origin)?;
+			Self::check_mint_request(&who, &p_hash, amount, &n_hash, &signature)?;
+
+			T::Currency::deposit(T::BridgedCurrencyId::get(), &who, amount)?;
+			MintedDeposits::insert(n_hash, true);
+			Self::deposit_event(RawEvent::Minted(who, amount, n_hash));
+		}
+
+		/// Burn the wrapped asset to release the asset to `to` by the custody.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `to`: the destination address.
+		/// - `amount`: the amount to burn.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: 1 items of orml_tokens
+		/// - Db writes: 2 items of orml_tokens
+		/// -------------------
+		/// Base Weight: 40 µs
+		/// # </weight>
+		#[weight = 40 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 2)]
+		pub fn burn(origin, to: Vec<u8>, #[compact] amount: Balance) {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				!to.is_empty() && to.len() <= MAX_DESTINATION_LENGTH,
+				Error::<T>::InvalidDestination,
+			);
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+
+			T::Currency::withdraw(T::BridgedCurrencyId::get(), &who, amount)?;
+			Self::deposit_event(RawEvent::Burnt(who, to, amount));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The message signed by the custody for the mint request, bound to the genesis hash so that
+	/// the signature cannot be replayed on another chain.
+	pub fn mint_message(who: &T::AccountId, p_hash: &[u8; 32], amount: Balance, n_hash: &[u8; 32]) -> Vec<u8> {
+		let genesis_hash = <system::Module<T>>::block_hash(T::BlockNumber::zero());
+		(MINT_MESSAGE_DOMAIN, genesis_hash, p_hash, amount, who, n_hash).encode()
+	}
+
+	fn check_mint_request(
+		who: &T::AccountId,
+		p_hash: &[u8; 32],
+		amount: Balance,
+		n_hash: &[u8; 32],
+		signature: &ecdsa::Signature,
+	) -> DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+		ensure!(!Self::minted_deposits(n_hash), Error::<T>::AlreadyMinted);
+		let key = Self::custody_key().ok_or(Error::<T>::CustodyKeyNotSet)?;
+		let message = Self::mint_message(who, p_hash, amount, n_hash);
+		ensure!(signature.verify(&message[..], &key), Error::<T>::InvalidSignature);
+		Ok(())
+	}
+}
+
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		match call {
+			Call::mint(who, p_hash, amount, n_hash, signature) => {
+				if Self::minted_deposits(n_hash) {
+					return InvalidTransaction::Stale.into();
+				}
+				if Self::check_mint_request(who, p_hash, *amount, n_hash, signature).is_err() {
+					return InvalidTransaction::BadProof.into();
+				}
+
+				ValidTransaction::with_tag_prefix("RenVmBridgeMint")
+					.priority(T::UnsignedPriority::get())
+					.and_provides(n_hash)
+					.longevity(64_u64)
+					.propagate(true)
+					.build()
+			}
+			_ => InvalidTransaction::Call.into(),
+		}
+	}
+}
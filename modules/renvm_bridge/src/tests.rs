@@ -0,0 +1,169 @@
+//! Unit tests for the renvm bridge module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, unsigned::ValidateUnsigned};
+use mock::{
+	custody, AccountId, ExtBuilder, Origin, RenVmBridgeModule, Runtime, System, TestEvent, Tokens, ALICE, BOB, RENBTC,
+};
+use sp_core::{Pair, H256};
+use sp_runtime::{traits::BadOrigin, transaction_validity::TransactionValidityError};
+
+const P_HASH: [u8; 32] = [1u8; 32];
+const N_HASH: [u8; 32] = [2u8; 32];
+
+fn sign_mint(signer: &ecdsa::Pair, who: AccountId, amount: Balance, n_hash: &[u8; 32]) -> ecdsa::Signature {
+	signer.sign(&RenVmBridgeModule::mint_message(&who, &P_HASH, amount, n_hash))
+}
+
+#[test]
+fn set_custody_key_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			RenVmBridgeModule::set_custody_key(Origin::signed(BOB), custody().public()),
+			BadOrigin
+		);
+		assert_ok!(RenVmBridgeModule::set_custody_key(
+			Origin::signed(ALICE),
+			custody().public()
+		));
+		let updated_event = TestEvent::renvm_bridge(RawEvent::CustodyKeyUpdated(custody().public()));
+		assert!(System::events().iter().any(|record| record.event == updated_event));
+		assert_eq!(RenVmBridgeModule::custody_key(), Some(custody().public()));
+	});
+}
+
+#[test]
+fn mint_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let signature = sign_mint(&custody(), ALICE, 1000, &N_HASH);
+		assert_noop!(
+			RenVmBridgeModule::mint(Origin::NONE, ALICE, P_HASH, 1000, N_HASH, signature.clone()),
+			Error::<Runtime>::CustodyKeyNotSet,
+		);
+		assert_ok!(RenVmBridgeModule::set_custody_key(Origin::ROOT, custody().public()));
+
+		assert_noop!(
+			RenVmBridgeModule::mint(Origin::signed(ALICE), ALICE, P_HASH, 1000, N_HASH, signature.clone()),
+			BadOrigin,
+		);
+		assert_noop!(
+			RenVmBridgeModule::mint(Origin::NONE, BOB, P_HASH, 1000, N_HASH, signature.clone()),
+			Error::<Runtime>::InvalidSignature,
+		);
+		assert_noop!(
+			RenVmBridgeModule::mint(Origin::NONE, ALICE, P_HASH, 2000, N_HASH, signature.clone()),
+			Error::<Runtime>::InvalidSignature,
+		);
+		let forged = sign_mint(&ecdsa::Pair::from_seed(&[2u8; 32]), ALICE, 1000, &N_HASH);
+		assert_noop!(
+			RenVmBridgeModule::mint(Origin::NONE, ALICE, P_HASH, 1000, N_HASH, forged),
+			Error::<Runtime>::InvalidSignature,
+		);
+
+		// the signature for another chain cannot be replayed
+		let genesis_hash = System::block_hash(0);
+		<frame_system::BlockHash<Runtime>>::insert(0, H256::repeat_byte(1));
+		let replayed = sign_mint(&custody(), ALICE, 1000, &N_HASH);
+		<frame_system::BlockHash<Runtime>>::insert(0, genesis_hash);
+		assert_noop!(
+			RenVmBridgeModule::mint(Origin::NONE, ALICE, P_HASH, 1000, N_HASH, replayed),
+			Error::<Runtime>::InvalidSignature,
+		);
+
+		assert_ok!(RenVmBridgeModule::mint(
+			Origin::NONE,
+			ALICE,
+			P_HASH,
+			1000,
+			N_HASH,
+			signature.clone()
+		));
+		let minted_event = TestEvent::renvm_bridge(RawEvent::Minted(ALICE, 1000, N_HASH));
+		assert!(System::events().iter().any(|record| record.event == minted_event));
+		assert_eq!(Tokens::free_balance(RENBTC, &ALICE), 1000);
+		assert!(RenVmBridgeModule::minted_deposits(N_HASH));
+
+		assert_noop!(
+			RenVmBridgeModule::mint(Origin::NONE, ALICE, P_HASH, 1000, N_HASH, signature),
+			Error::<Runtime>::AlreadyMinted,
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_mint_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(RenVmBridgeModule::set_custody_key(Origin::ROOT, custody().public()));
+		let signature = sign_mint(&custody(), ALICE, 1000, &N_HASH);
+
+		assert_eq!(
+			RenVmBridgeModule::validate_unsigned(
+				TransactionSource::External,
+				&Call::mint(BOB, P_HASH, 1000, N_HASH, signature.clone())
+			),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::BadProof)),
+		);
+		assert!(RenVmBridgeModule::validate_unsigned(
+			TransactionSource::External,
+			&Call::mint(ALICE, P_HASH, 1000, N_HASH, signature.clone())
+		)
+		.is_ok());
+
+		assert_ok!(RenVmBridgeModule::mint(
+			Origin::NONE,
+			ALICE,
+			P_HASH,
+			1000,
+			N_HASH,
+			signature.clone()
+		));
+		assert_eq!(
+			RenVmBridgeModule::validate_unsigned(
+				TransactionSource::External,
+				&Call::mint(ALICE, P_HASH, 1000, N_HASH, signature)
+			),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale)),
+		);
+	});
+}
+
+#[test]
+fn burn_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(RenVmBridgeModule::set_custody_key(Origin::ROOT, custody().public()));
+		let signature = sign_mint(&custody(), ALICE, 1000, &N_HASH);
+		assert_ok!(RenVmBridgeModule::mint(
+			Origin::NONE,
+			ALICE,
+			P_HASH,
+			1000,
+			N_HASH,
+			signature
+		));
+
+		let to = b"bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_vec();
+		assert_noop!(
+			RenVmBridgeModule::burn(Origin::signed(ALICE), vec![], 100),
+			Error::<Runtime>::InvalidDestination,
+		);
+		assert_noop!(
+			RenVmBridgeModule::burn(Origin::signed(ALICE), vec![b'1'; MAX_DESTINATION_LENGTH + 1], 100),
+			Error::<Runtime>::InvalidDestination,
+		);
+		assert_noop!(
+			RenVmBridgeModule::burn(Origin::signed(ALICE), to.clone(), 0),
+			Error::<Runtime>::ZeroAmount,
+		);
+		assert!(RenVmBridgeModule::burn(Origin::signed(ALICE), to.clone(), 1001).is_err());
+
+		assert_ok!(RenVmBridgeModule::burn(Origin::signed(ALICE), to.clone(), 400));
+		let burnt_event = TestEvent::renvm_bridge(RawEvent::Burnt(ALICE, to, 400));
+		assert!(System::events().iter().any(|record| record.event == burnt_event));
+		assert_eq!(Tokens::free_balance(RENBTC, &ALICE), 600);
+	});
+}
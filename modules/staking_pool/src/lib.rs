@@ -36,7 +36,8 @@ pub trait Trait: system::Trait {
 	type DefaultExchangeRate: Get<ExchangeRate>;
 	type ClaimFeeReturnRatio: Get<Ratio>;
 
-	// TODO: add RewardFeeRatio
+	/// The ratio of staking reward taken as commission, which is issued as liquid currency to `OnCommission`
+	type RewardFeeRatio: Get<Ratio>;
 }
 
 decl_event!(
@@ -49,6 +50,16 @@ decl_event!(
 		RedeemByUnbond(AccountId, Balance),
 		RedeemByFreeUnbonded(AccountId, Balance, Balance, Balance),
 		RedeemByClaimUnbonding(AccountId, EraIndex, Balance, Balance, Balance),
+		/// Received unbonded from bridge at rebalance (era, claimed_unbonded_added, free_unbonded_added)
+		ReceiveUnbonded(EraIndex, Balance, Balance),
+		/// Issued liquid currency as the commission of staking reward (staking_reward, liquid_commission)
+		RewardCommission(Balance, Balance),
+		/// Schedule to unbond communal bonded to free pool at next era, as bond ratio is over MaxBondRatio (amount)
+		ScheduleUnbondToFree(Balance),
+		/// Bond free unbonded to bridge, as bond ratio is under MinBondRatio (amount)
+		BondFreeUnbonded(Balance),
+		/// Unbond from bridge and put in the unbonding queue (unbonded_era, total_unbond, claimed_unbond)
+		UnbondAtEra(EraIndex, Balance, Balance),
 	}
 );
 
@@ -58,6 +69,7 @@ decl_error! {
 		StakingCurrencyNotEnough,
 		LiquidCurrencyNotEnough,
 		InvalidEra,
+		ClaimedUnbondNotEnough,
 	}
 }
 
@@ -89,6 +101,7 @@ decl_module! {
 		const MaxClaimFee: Rate = T::MaxClaimFee::get();
 		const DefaultExchangeRate: ExchangeRate = T::DefaultExchangeRate::get();
 		const ClaimFeeReturnRatio: Ratio = T::ClaimFeeReturnRatio::get();
+		const RewardFeeRatio: Ratio = T::RewardFeeRatio::get();
 	}
 }
 
@@ -346,6 +359,29 @@ impl<T: Trait> Module<T> {
 		Ok(())
 	}
 
+	pub fn transfer_claimed_unbond(
+		from: &T::AccountId,
+		to: &T::AccountId,
+		era: EraIndex,
+		amount: Balance,
+	) -> DispatchResult {
+		if from == to || amount.is_zero() {
+			return Ok(());
+		}
+
+		let from_claimed = Self::claimed_unbond(from, era)
+			.checked_sub(amount)
+			.ok_or(Error::<T>::ClaimedUnbondNotEnough)?;
+		if from_claimed.is_zero() {
+			<ClaimedUnbond<T>>::remove(from, era);
+		} else {
+			<ClaimedUnbond<T>>::insert(from, era, from_claimed);
+		}
+		<ClaimedUnbond<T>>::mutate(to, era, |balance| *balance += amount);
+
+		Ok(())
+	}
+
 	pub fn unbond_and_update(era: EraIndex) {
 		let (total_to_unbond, claimed_to_unbond) = Self::next_era_unbond();
 		let bonding_duration = <<T as Trait>::Bridge as PolkadotBridgeType<_, _>>::BondingDuration::get();
@@ -356,16 +392,21 @@ impl<T: Trait> Module<T> {
 			TotalBonded::mutate(|bonded| *bonded -= total_to_unbond);
 			Unbonding::insert(unbonded_era_index, (total_to_unbond, claimed_to_unbond));
 			UnbondingToFree::mutate(|unbonding| *unbonding += total_to_unbond - claimed_to_unbond);
+			<Module<T>>::deposit_event(RawEvent::UnbondAtEra(
+				unbonded_era_index,
+				total_to_unbond,
+				claimed_to_unbond,
+			));
 		}
 	}
 
 	pub fn rebalance(era: EraIndex) {
-		// #1: bridge withdraw unbonded and withdraw payout
+		// #1: bridge withdraw unbonded and withdraw payout,
+		// record the balances of bridge before and after payout to get the staking reward
 		T::Bridge::withdraw_unbonded();
-
-		// TODO: record the balances of bridge before and after do payout_nominator,
-		// and oncommision to homa treasury according to RewardFeeRatio
+		let balance_before_payout = T::Bridge::balance();
 		T::Bridge::payout_nominator();
+		let staking_reward = T::Bridge::balance().saturating_sub(balance_before_payout);
 
 		// #2: update staking pool by bridge ledger
 		// TODO: adjust the amount of this era unbond by the slash situation in last era
@@ -386,6 +427,25 @@ impl<T: Trait> Module<T> {
 			}
 			UnbondingToFree::mutate(|balance| *balance = balance.saturating_sub(total_unbonded - claimed_unbonded));
 			Unbonding::remove(era);
+			if !bridge_available.is_zero() {
+				<Module<T>>::deposit_event(RawEvent::ReceiveUnbonded(
+					era,
+					claimed_unbonded_added,
+					free_unbonded_added,
+				));
+			}
+		}
+
+		// issue liquid currency as the commission of staking reward according to RewardFeeRatio,
+		// the reward has been counted in the exchange rate
+		let reward_commission = T::RewardFeeRatio::get().saturating_mul_int(staking_reward);
+		if !reward_commission.is_zero() {
+			let liquid_commission = ExchangeRate::saturating_from_integer(1)
+				.checked_div(&Self::liquid_exchange_rate())
+				.unwrap_or_default()
+				.saturating_mul_int(reward_commission);
+			T::OnCommission::on_commission(T::LiquidCurrencyId::get(), liquid_commission);
+			<Module<T>>::deposit_event(RawEvent::RewardCommission(staking_reward, liquid_commission));
 		}
 
 		// #4: according to the communal_bonded_ratio, decide to
@@ -403,6 +463,7 @@ impl<T: Trait> Module<T> {
 
 			if !unbond_to_free.is_zero() {
 				NextEraUnbond::mutate(|(unbond, _)| *unbond += unbond_to_free);
+				<Module<T>>::deposit_event(RawEvent::ScheduleUnbondToFree(unbond_to_free));
 			}
 		} else if communal_bonded_ratio < min_bond_ratio {
 			// bond more
@@ -415,6 +476,7 @@ impl<T: Trait> Module<T> {
 				FreeUnbonded::mutate(|balance| *balance -= bond_amount);
 				if T::Bridge::bond_extra(bond_amount).is_ok() {
 					TotalBonded::mutate(|bonded| *bonded += bond_amount);
+					<Module<T>>::deposit_event(RawEvent::BondFreeUnbonded(bond_amount));
 				}
 			}
 		}
@@ -458,4 +520,17 @@ impl<T: Trait> HomaProtocol<T::AccountId, Balance, EraIndex> for Module<T> {
 	fn withdraw_redemption(who: &T::AccountId) -> sp_std::result::Result<Self::Balance, DispatchError> {
 		Self::withdraw_unbonded(who)
 	}
+
+	fn claimed_unbonding(who: &T::AccountId, era: EraIndex) -> Self::Balance {
+		Self::claimed_unbond(who, era)
+	}
+
+	fn transfer_claimed_unbonding(
+		from: &T::AccountId,
+		to: &T::AccountId,
+		era: EraIndex,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		Self::transfer_claimed_unbond(from, to, era, amount)
+	}
 }
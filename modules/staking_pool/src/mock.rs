@@ -7,6 +7,7 @@ use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
 use primitives::Amount;
 use sp_core::H256;
 use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_std::cell::RefCell;
 use support::PolkadotStakingLedger;
 
 pub type AccountId = u128;
@@ -117,9 +118,17 @@ impl NomineesProvider<PolkadotAccountId> for MockNomineesProvider {
 	}
 }
 
+thread_local! {
+	pub static COMMISSION: RefCell<Vec<(CurrencyId, Balance)>> = RefCell::new(vec![]);
+	pub static REWARD_TO_PAYOUT: RefCell<Balance> = RefCell::new(0);
+	static BRIDGE_REWARD: RefCell<Balance> = RefCell::new(0);
+}
+
 pub struct MockOnCommission;
 impl OnCommission<Balance, CurrencyId> for MockOnCommission {
-	fn on_commission(_currency_id: CurrencyId, _amount: Balance) {}
+	fn on_commission(currency_id: CurrencyId, amount: Balance) {
+		COMMISSION.with(|v| v.borrow_mut().push((currency_id, amount)));
+	}
 }
 
 pub struct MockBridge;
@@ -152,13 +161,20 @@ impl PolkadotBridgeCall<AccountId, BlockNumber, Balance, EraIndex> for MockBridg
 
 	fn nominate(_targets: Vec<Self::PolkadotAccountId>) {}
 
-	fn payout_nominator() {}
+	fn payout_nominator() {
+		let reward = REWARD_TO_PAYOUT.with(|v| v.replace(0));
+		BRIDGE_REWARD.with(|v| *v.borrow_mut() += reward);
+	}
 
 	fn transfer_to_bridge(from: &AccountId, amount: Balance) -> DispatchResult {
 		CurrenciesModule::withdraw(DOT, from, amount)
 	}
 
 	fn receive_from_bridge(to: &AccountId, amount: Balance) -> DispatchResult {
+		BRIDGE_REWARD.with(|v| {
+			let mut reward = v.borrow_mut();
+			*reward = reward.saturating_sub(amount);
+		});
 		CurrenciesModule::deposit(DOT, to, amount)
 	}
 }
@@ -173,7 +189,9 @@ impl PolkadotBridgeState<Balance, EraIndex> for MockBridge {
 	}
 
 	fn balance() -> Balance {
-		StakingPoolModule::total_bonded() + StakingPoolModule::unbonding(StakingPoolModule::current_era()).0
+		StakingPoolModule::total_bonded()
+			+ StakingPoolModule::unbonding(StakingPoolModule::current_era()).0
+			+ BRIDGE_REWARD.with(|v| *v.borrow())
 	}
 
 	fn current_era() -> EraIndex {
@@ -191,6 +209,7 @@ parameter_types! {
 	pub MaxClaimFee: Rate = Rate::saturating_from_rational(10, 100);	// 10%
 	pub DefaultExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(10, 100);	// 1 : 10
 	pub ClaimFeeReturnRatio: Ratio = Ratio::saturating_from_rational(80, 100);	// 80%
+	pub RewardFeeRatio: Ratio = Ratio::saturating_from_rational(10, 100);	// 10%
 }
 
 impl Trait for Runtime {
@@ -206,6 +225,7 @@ impl Trait for Runtime {
 	type MaxClaimFee = MaxClaimFee;
 	type DefaultExchangeRate = DefaultExchangeRate;
 	type ClaimFeeReturnRatio = ClaimFeeReturnRatio;
+	type RewardFeeRatio = RewardFeeRatio;
 }
 pub type StakingPoolModule = Module<Runtime>;
 
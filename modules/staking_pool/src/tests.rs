@@ -5,7 +5,8 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::{
-	BondingDuration, CurrenciesModule, ExtBuilder, Runtime, StakingPoolModule, System, TestEvent, ALICE, BOB, DOT, LDOT,
+	BondingDuration, CurrenciesModule, ExtBuilder, Runtime, StakingPoolModule, System, TestEvent, ALICE, BOB,
+	COMMISSION, DOT, LDOT, REWARD_TO_PAYOUT,
 };
 
 #[test]
@@ -55,6 +56,24 @@ fn withdraw_unbonded_work() {
 	});
 }
 
+#[test]
+fn transfer_claimed_unbond_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		<ClaimedUnbond<Runtime>>::insert(ALICE, 3, 200);
+		assert_noop!(
+			StakingPoolModule::transfer_claimed_unbond(&ALICE, &BOB, 3, 201),
+			Error::<Runtime>::ClaimedUnbondNotEnough,
+		);
+		assert_ok!(StakingPoolModule::transfer_claimed_unbond(&ALICE, &BOB, 3, 150));
+		assert_eq!(StakingPoolModule::claimed_unbond(&ALICE, 3), 50);
+		assert_eq!(StakingPoolModule::claimed_unbond(&BOB, 3), 150);
+
+		assert_ok!(StakingPoolModule::transfer_claimed_unbond(&ALICE, &BOB, 3, 50));
+		assert_eq!(<ClaimedUnbond<Runtime>>::contains_key(ALICE, 3), false);
+		assert_eq!(StakingPoolModule::claimed_unbond(&BOB, 3), 200);
+	});
+}
+
 #[test]
 fn redeem_by_unbond_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -178,6 +197,7 @@ fn redeem_by_claim_unbonding_work() {
 #[test]
 fn rebalance_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		TotalBonded::put(20000);
 		Unbonding::insert(1, (20000, 10000));
 		UnbondingToFree::put(10000);
@@ -206,5 +226,32 @@ fn rebalance_work() {
 		assert_eq!(StakingPoolModule::unbonding(1), (0, 0));
 		assert_eq!(StakingPoolModule::unbonding(1 + BondingDuration::get()), (5000, 5000));
 		assert_eq!(StakingPoolModule::next_era_unbond(), (0, 0));
+
+		let receive_unbonded_event = TestEvent::staking_pool(RawEvent::ReceiveUnbonded(1, 10000, 10000));
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == receive_unbonded_event));
+		let unbond_event = TestEvent::staking_pool(RawEvent::UnbondAtEra(1 + BondingDuration::get(), 5000, 5000));
+		assert!(System::events().iter().any(|record| record.event == unbond_event));
+	});
+}
+
+#[test]
+fn rebalance_issue_reward_commission() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StakingPoolModule::bond(&ALICE, 100));
+		assert_eq!(CurrenciesModule::free_balance(LDOT, &ALICE), 1000);
+		REWARD_TO_PAYOUT.with(|v| *v.borrow_mut() = 10);
+
+		CurrentEra::put(1);
+		StakingPoolModule::rebalance(1);
+
+		assert_eq!(StakingPoolModule::free_unbonded(), 10);
+		assert_eq!(
+			CurrenciesModule::free_balance(DOT, &StakingPoolModule::account_id()),
+			10
+		);
+		// commission = 10 * 10% = 1 DOT, exchange rate = (100 + 10) / 1000
+		COMMISSION.with(|v| assert_eq!(*v.borrow(), vec![(LDOT, 9)]));
 	});
 }
@@ -73,4 +73,6 @@ pub trait HomaProtocol<AccountId, Balance, EraIndex> {
 	fn redeem_by_free_unbonded(who: &AccountId, amount: Balance) -> DispatchResult;
 	fn redeem_by_claim_unbonding(who: &AccountId, amount: Balance, target_era: EraIndex) -> DispatchResult;
 	fn withdraw_redemption(who: &AccountId) -> sp_std::result::Result<Balance, DispatchError>;
+	fn claimed_unbonding(who: &AccountId, era: EraIndex) -> Balance;
+	fn transfer_claimed_unbonding(from: &AccountId, to: &AccountId, era: EraIndex, amount: Balance) -> DispatchResult;
 }
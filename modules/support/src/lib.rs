@@ -1,7 +1,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode, FullCodec, HasCompact};
-use sp_runtime::{DispatchError, DispatchResult, FixedU128};
+use sp_runtime::{DispatchError, DispatchResult, FixedPointNumber, FixedU128, RuntimeDebug};
 use sp_std::{
 	cmp::{Eq, PartialEq},
 	fmt::Debug,
@@ -51,6 +51,130 @@ impl<AccountId, CurrencyId, Balance: Default, DebitBalance> RiskManager<AccountI
 	}
 }
 
+/// Hook called before the debit amount of a loan is updated.
+pub trait OnUpdateLoan<AccountId, CurrencyId, Amount, Balance> {
+	/// `amount_adjustment` is the signed change of debit amount, `previous_amount`
+	/// is the debit amount before the change.
+	fn on_update_loan(who: &AccountId, currency_id: CurrencyId, amount_adjustment: Amount, previous_amount: Balance);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, CurrencyId: Copy, Amount: Copy, Balance: Copy> OnUpdateLoan<AccountId, CurrencyId, Amount, Balance>
+	for Tuple
+{
+	fn on_update_loan(who: &AccountId, currency_id: CurrencyId, amount_adjustment: Amount, previous_amount: Balance) {
+		for_tuples!( #( Tuple::on_update_loan(who, currency_id, amount_adjustment, previous_amount); )* );
+	}
+}
+
+/// Hook called when the collateral amount of a loan is updated.
+pub trait OnUpdateCollateral<AccountId, CurrencyId, Amount> {
+	/// `amount_adjustment` is the signed change of collateral amount.
+	fn on_update_collateral(who: &AccountId, currency_id: CurrencyId, amount_adjustment: Amount);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, CurrencyId: Copy, Amount: Copy> OnUpdateCollateral<AccountId, CurrencyId, Amount> for Tuple {
+	fn on_update_collateral(who: &AccountId, currency_id: CurrencyId, amount_adjustment: Amount) {
+		for_tuples!( #( Tuple::on_update_collateral(who, currency_id, amount_adjustment); )* );
+	}
+}
+
+/// Hook called after the liquidity share of an account in a DEX trading pair is updated.
+pub trait OnUpdateLiquidity<AccountId, CurrencyId, Share> {
+	/// `currency_id` is the other currency of the trading pair with base currency.
+	fn on_update_liquidity(who: &AccountId, currency_id: CurrencyId, new_share: Share);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, CurrencyId: Copy, Share: Copy> OnUpdateLiquidity<AccountId, CurrencyId, Share> for Tuple {
+	fn on_update_liquidity(who: &AccountId, currency_id: CurrencyId, new_share: Share) {
+		for_tuples!( #( Tuple::on_update_liquidity(who, currency_id, new_share); )* );
+	}
+}
+
+/// Hook called after a keeper's transaction triggered a successful liquidation or settlement.
+pub trait OnKeeperAction<AccountId, CurrencyId, Balance> {
+	/// `debit_value` is the debit value of the liquidated or settled CDP.
+	fn on_keeper_action(keeper: &AccountId, currency_id: CurrencyId, debit_value: Balance);
+}
+
+impl<AccountId, CurrencyId, Balance> OnKeeperAction<AccountId, CurrencyId, Balance> for () {
+	fn on_keeper_action(_keeper: &AccountId, _currency_id: CurrencyId, _debit_value: Balance) {}
+}
+
+/// Hook called after a collateral auction is dealt with its bidders.
+pub trait OnCollateralAuctionDealt<AccountId, CurrencyId, Balance> {
+	/// `refund_recipient` is the owner of the liquidated CDP, `winner` and `runner_up` are
+	/// the winning bid and the last outbid bid of another bidder, as (bidder, bid_price).
+	fn on_collateral_auction_dealt(
+		refund_recipient: &AccountId,
+		currency_id: CurrencyId,
+		target: Balance,
+		winner: (AccountId, Balance),
+		runner_up: Option<(AccountId, Balance)>,
+	);
+}
+
+impl<AccountId, CurrencyId, Balance> OnCollateralAuctionDealt<AccountId, CurrencyId, Balance> for () {
+	fn on_collateral_auction_dealt(
+		_refund_recipient: &AccountId,
+		_currency_id: CurrencyId,
+		_target: Balance,
+		_winner: (AccountId, Balance),
+		_runner_up: Option<(AccountId, Balance)>,
+	) {
+	}
+}
+
+/// Create vesting schedules of any currency.
+pub trait MultiCurrencyVesting<AccountId, CurrencyId, Balance, BlockNumber> {
+	/// Transfer `amount` of `currency_id` from `from` and release it to `to` linearly
+	/// over `period` blocks from `start`.
+	fn vested_transfer(
+		currency_id: CurrencyId,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Balance,
+		start: BlockNumber,
+		period: BlockNumber,
+	) -> DispatchResult;
+}
+
+/// Tell whether a call strictly reduces the risk of the caller's positions,
+/// e.g. repaying debit or adding collateral.
+pub trait IsRiskReducing<Call> {
+	fn is_risk_reducing(call: &Call) -> bool;
+}
+
+impl<Call> IsRiskReducing<Call> for () {
+	fn is_risk_reducing(_call: &Call) -> bool {
+		false
+	}
+}
+
+/// Tell whether a call is a basic operation, which can use the free transaction quota.
+pub trait IsBasicOperation<Call> {
+	fn is_basic_operation(call: &Call) -> bool;
+}
+
+impl<Call> IsBasicOperation<Call> for () {
+	fn is_basic_operation(_call: &Call) -> bool {
+		false
+	}
+}
+
+/// Tell whether the fee of a call can be paid by the sponsor of the caller.
+pub trait IsSponsoredCall<Call> {
+	fn is_sponsored_call(call: &Call) -> bool;
+}
+
+impl<Call> IsSponsoredCall<Call> for () {
+	fn is_sponsored_call(_call: &Call) -> bool {
+		false
+	}
+}
+
 pub trait AuctionManager<AccountId> {
 	type CurrencyId;
 	type Balance;
@@ -98,6 +222,35 @@ pub trait DEXManager<AccountId, CurrencyId, Balance> {
 		target_currency_id: CurrencyId,
 		supply_amount: Balance,
 	) -> Option<Ratio>;
+
+	/// The liquidity pool of the currency paired with base currency (currency_amount, base_currency_amount)
+	fn get_liquidity_pool(currency_id: CurrencyId) -> (Balance, Balance);
+
+	/// Get the target amount of swapping the exact supply amount along the trading path,
+	/// `None` means the path is invalid or the liquidity is not enough
+	fn get_swap_target_amount(path: &[CurrencyId], supply_amount: Balance) -> Option<Balance>;
+
+	/// Get the supply amount needed to swap for the exact target amount along the trading path,
+	/// `None` means the path is invalid or the liquidity is not enough
+	fn get_swap_supply_amount(path: &[CurrencyId], target_amount: Balance) -> Option<Balance>;
+
+	/// Swap the exact supply amount along the trading path, the target amount must be at least
+	/// `min_target_amount`. Return the actual target amount.
+	fn swap_with_exact_supply(
+		who: &AccountId,
+		path: &[CurrencyId],
+		supply_amount: Balance,
+		min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError>;
+
+	/// Swap for the exact target amount along the trading path, the supply amount must be at most
+	/// `max_supply_amount`. Return the actual supply amount.
+	fn swap_with_exact_target(
+		who: &AccountId,
+		path: &[CurrencyId],
+		target_amount: Balance,
+		max_supply_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError>;
 }
 
 impl<AccountId, CurrencyId, Balance> DEXManager<AccountId, CurrencyId, Balance> for ()
@@ -137,6 +290,36 @@ where
 	) -> Option<Ratio> {
 		None
 	}
+
+	fn get_liquidity_pool(_currency_id: CurrencyId) -> (Balance, Balance) {
+		Default::default()
+	}
+
+	fn get_swap_target_amount(_path: &[CurrencyId], _supply_amount: Balance) -> Option<Balance> {
+		None
+	}
+
+	fn get_swap_supply_amount(_path: &[CurrencyId], _target_amount: Balance) -> Option<Balance> {
+		None
+	}
+
+	fn swap_with_exact_supply(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_supply_amount: Balance,
+		_min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(Default::default())
+	}
+
+	fn swap_with_exact_target(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_target_amount: Balance,
+		_max_supply_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(Default::default())
+	}
 }
 
 pub trait CDPTreasury<AccountId> {
@@ -155,22 +338,37 @@ pub trait CDPTreasury<AccountId> {
 	fn withdraw_backed_debit_from(who: &AccountId, amount: Self::Balance) -> DispatchResult;
 
 	fn transfer_surplus_from(from: &AccountId, amount: Self::Balance) -> DispatchResult;
+	fn transfer_surplus_to(to: &AccountId, amount: Self::Balance) -> DispatchResult;
 	fn transfer_collateral_to(currency_id: Self::CurrencyId, to: &AccountId, amount: Self::Balance) -> DispatchResult;
 	fn transfer_collateral_from(
 		currency_id: Self::CurrencyId,
 		from: &AccountId,
 		amount: Self::Balance,
 	) -> DispatchResult;
+	fn burn_collateral(currency_id: Self::CurrencyId, amount: Self::Balance) -> DispatchResult;
 
 	fn get_debit_proportion(amount: Self::Balance) -> Ratio;
 }
 
 pub trait CDPTreasuryExtended<AccountId>: CDPTreasury<AccountId> {
-	fn swap_collateral_to_stable(
+	/// Swap the exact collateral amount to stable currency along the trading path, which must start
+	/// with the collateral and end with stable currency. Return the actual stable amount.
+	fn swap_exact_collateral_to_stable(
 		currency_id: Self::CurrencyId,
 		supply_amount: Self::Balance,
+		min_target_amount: Self::Balance,
+		path: &[Self::CurrencyId],
+	) -> sp_std::result::Result<Self::Balance, DispatchError>;
+
+	/// Swap the collateral for the exact stable amount along the trading path, which must start
+	/// with the collateral and end with stable currency. Return the actual collateral amount.
+	fn swap_collateral_to_exact_stable(
+		currency_id: Self::CurrencyId,
+		max_supply_amount: Self::Balance,
 		target_amount: Self::Balance,
+		path: &[Self::CurrencyId],
 	) -> sp_std::result::Result<Self::Balance, DispatchError>;
+
 	fn create_collateral_auctions(
 		currency_id: Self::CurrencyId,
 		amount: Self::Balance,
@@ -184,13 +382,102 @@ pub trait PriceProvider<CurrencyId> {
 	fn get_price(currency_id: CurrencyId) -> Option<Price>;
 	fn lock_price(currency_id: CurrencyId);
 	fn unlock_price(currency_id: CurrencyId);
+
+	/// Get the price together with its confidence, which is in range [0, 1]
+	/// and `1` means full confidence.
+	fn get_price_with_confidence(currency_id: CurrencyId) -> Option<(Price, Ratio)> {
+		Self::get_price(currency_id).map(|price| (price, Ratio::one()))
+	}
+
+	/// Get the relative price together with its confidence, the confidence
+	/// is the lower one of base and quote.
+	fn get_relative_price_with_confidence(base: CurrencyId, quote: CurrencyId) -> Option<(Price, Ratio)> {
+		let price = Self::get_relative_price(base, quote)?;
+		let (_, base_confidence) = Self::get_price_with_confidence(base)?;
+		let (_, quote_confidence) = Self::get_price_with_confidence(quote)?;
+		Some((price, base_confidence.min(quote_confidence)))
+	}
 }
 
 pub trait ExchangeRateProvider {
 	fn get_exchange_rate() -> ExchangeRate;
 }
 
+/// The raw values fed by individual oracle operators, before combined.
+pub trait OperatorDataProvider<AccountId, Key, Value, Moment> {
+	/// The last value fed by `who` and the time it was fed
+	fn raw_value(who: &AccountId, key: &Key) -> Option<(Value, Moment)>;
+}
+
+impl<AccountId, Key, Value, Moment> OperatorDataProvider<AccountId, Key, Value, Moment> for () {
+	fn raw_value(_who: &AccountId, _key: &Key) -> Option<(Value, Moment)> {
+		None
+	}
+}
+
+/// A derivative asset whose price is derived from its underlying asset
+pub trait DerivativeAsset<CurrencyId> {
+	/// Return the underlying currency and the exchange rate of derivative/underlying,
+	/// `None` if `currency_id` is not a derivative asset.
+	fn get_underlying(currency_id: CurrencyId) -> Option<(CurrencyId, ExchangeRate)>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<CurrencyId: Copy> DerivativeAsset<CurrencyId> for Tuple {
+	fn get_underlying(currency_id: CurrencyId) -> Option<(CurrencyId, ExchangeRate)> {
+		for_tuples!( #(
+			if let Some(underlying) = Tuple::get_underlying(currency_id) {
+				return Some(underlying);
+			}
+		)* );
+		None
+	}
+}
+
 #[impl_trait_for_tuples::impl_for_tuples(30)]
 pub trait OnEmergencyShutdown {
 	fn on_emergency_shutdown();
+
+	/// Revert the operations done by `on_emergency_shutdown` when the shutdown is cancelled.
+	fn on_cancel_shutdown();
+
+	/// Called after every transition of shutdown phase, including the ones
+	/// to `Frozen` and back to `Operational`, for phase-specific operations.
+	fn on_shutdown_phase_changed(_phase: ShutdownPhase) {}
+}
+
+/// The phases of emergency shutdown, always advanced in order, only `Frozen` and
+/// `Settling` can be cancelled back to `Operational`.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShutdownPhase {
+	/// System works normally
+	Operational,
+	/// Shutdown triggered, user entries are closed and prices are locked
+	Frozen,
+	/// CDPs are being settled and active auctions are being cancelled
+	Settling,
+	/// Settlement completed, stable coin holders can claim collaterals
+	ClaimsOpen,
+	/// Claims window closed, nothing can be changed anymore
+	Finalized,
+}
+
+impl Default for ShutdownPhase {
+	fn default() -> Self {
+		ShutdownPhase::Operational
+	}
+}
+
+pub trait EmergencyShutdown {
+	fn shutdown_phase() -> ShutdownPhase;
+
+	fn is_shutdown() -> bool {
+		Self::shutdown_phase() != ShutdownPhase::Operational
+	}
+}
+
+impl EmergencyShutdown for () {
+	fn shutdown_phase() -> ShutdownPhase {
+		ShutdownPhase::Operational
+	}
 }
@@ -0,0 +1,208 @@
+//! # Surplus Spending Module
+//!
+//! ## Overview
+//!
+//! Anyone can propose to spend the surplus of CDP treasury by reserving `ProposalBond` of native currency,
+//! the spending is paid in stable currency to the beneficiary. Governance approves or rejects the proposal:
+//! approval requires the spending not exceeding the spendable surplus, which is the surplus above the buffer
+//! and not used by the system, pays out the spending and returns the bond; rejection slashes the bond.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{EnsureOrigin, Get},
+	weights::constants::WEIGHT_PER_MICROS,
+};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use orml_traits::MultiReservableCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	RuntimeDebug,
+};
+use support::CDPTreasury;
+
+mod mock;
+mod tests;
+
+pub type ProposalIndex = u32;
+
+/// Pending proposal to spend the surplus
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct SpendingProposal<AccountId> {
+	/// The proposer who reserved the bond
+	pub proposer: AccountId,
+
+	/// The amount of stable currency to spend
+	pub value: Balance,
+
+	/// The account to receive the spending
+	pub beneficiary: AccountId,
+
+	/// The amount of reserved bond
+	pub bond: Balance,
+}
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Currency to reserve the proposal bond
+	type Currency: MultiReservableCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+	/// Native currency id, the proposal bond is reserved in it
+	type GetNativeCurrencyId: Get<CurrencyId>;
+
+	/// The amount of bond to reserve when propose a spending
+	type ProposalBond: Get<Balance>;
+
+	/// The origin which may approve or reject the proposals. Root can always do this.
+	type ApproveOrigin: EnsureOrigin<Self::Origin>;
+
+	/// CDP treasury to pay out the spending from its surplus pool
+	type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+	/// The amount of surplus which can be spent
+	type SpendableSurplus: Get<Balance>;
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		<T as system::Trait>::AccountId,
+		Balance = Balance,
+	{
+		/// A spending proposed (proposal_index, proposer, value, beneficiary)
+		SpendingProposed(ProposalIndex, AccountId, Balance, AccountId),
+		/// The proposal approved and the spending paid out (proposal_index, value, beneficiary)
+		SpendingApproved(ProposalIndex, Balance, AccountId),
+		/// The proposal rejected and its bond slashed (proposal_index, slashed_bond)
+		SpendingRejected(ProposalIndex, Balance),
+	}
+);
+
+decl_error! {
+	/// Error for surplus spending module.
+	pub enum Error for Module<T: Trait> {
+		/// The value of spending is zero
+		ZeroValue,
+		/// The proposal does not exist
+		ProposalNotFound,
+		/// The spendable surplus is not enough for the spending
+		SpendableSurplusNotEnough,
+		/// No available proposal index
+		NoAvailableProposalIndex,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as SurplusSpending {
+		/// The index of next proposal
+		pub ProposalCount get(fn proposal_count): ProposalIndex;
+
+		/// Mapping from proposal index to its pending proposal
+		pub Proposals get(fn proposals): map hasher(twox_64_concat) ProposalIndex => Option<SpendingProposal<T::AccountId>>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// The amount of bond to reserve when propose a spending
+		const ProposalBond: Balance = T::ProposalBond::get();
+
+		/// Propose to spend the surplus by reserving `ProposalBond`.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `value`: the amount of stable currency to spend.
+		/// - `beneficiary`: the account to receive the spending.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `ProposalCount`, 1 items of orml_tokens
+		/// - Db writes: `ProposalCount`, `Proposals`, 1 items of orml_tokens
+		/// -------------------
+		/// Base Weight: 40 µs
+		/// # </weight>
+		#[weight = 40 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 3)]
+		pub fn propose_spending(origin, value: Balance, beneficiary: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			ensure!(!value.is_zero(), Error::<T>::ZeroValue);
+
+			let index = Self::proposal_count();
+			let next_index = index.checked_add(1).ok_or(Error::<T>::NoAvailableProposalIndex)?;
+			let bond = T::ProposalBond::get();
+			T::Currency::reserve(T::GetNativeCurrencyId::get(), &who, bond)?;
+			ProposalCount::put(next_index);
+			<Proposals<T>>::insert(index, SpendingProposal {
+				proposer: who.clone(),
+				value,
+				beneficiary: beneficiary.clone(),
+				bond,
+			});
+
+			Self::deposit_event(RawEvent::SpendingProposed(index, who, value, beneficiary));
+		}
+
+		/// Approve the proposal, pay out the spending from the surplus and return the bond to the proposer.
+		///
+		/// The dispatch origin of this call must be `ApproveOrigin` or _Root_.
+		///
+		/// - `proposal_index`: the index of the proposal.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `Proposals`, 3 items of module_cdp_treasury, 1 items of module_auction_manager
+		/// - Db writes: `Proposals`, 1 items of module_cdp_treasury, 3 items of orml_tokens
+		/// -------------------
+		/// Base Weight: 70 µs
+		/// # </weight>
+		#[weight = 70 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(5, 5)]
+		pub fn approve_spending(origin, #[compact] proposal_index: ProposalIndex) {
+			T::ApproveOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			let proposal = Self::proposals(proposal_index).ok_or(Error::<T>::ProposalNotFound)?;
+			ensure!(
+				proposal.value <= T::SpendableSurplus::get(),
+				Error::<T>::SpendableSurplusNotEnough,
+			);
+
+			T::CDPTreasury::transfer_surplus_to(&proposal.beneficiary, proposal.value)?;
+			<Proposals<T>>::remove(proposal_index);
+			T::Currency::unreserve(T::GetNativeCurrencyId::get(), &proposal.proposer, proposal.bond);
+
+			Self::deposit_event(RawEvent::SpendingApproved(proposal_index, proposal.value, proposal.beneficiary));
+		}
+
+		/// Reject the proposal and slash the bond.
+		///
+		/// The dispatch origin of this call must be `ApproveOrigin` or _Root_.
+		///
+		/// - `proposal_index`: the index of the proposal.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `Proposals`, 1 items of orml_tokens
+		/// - Db writes: `Proposals`, 1 items of orml_tokens
+		/// -------------------
+		/// Base Weight: 35 µs
+		/// # </weight>
+		#[weight = 35 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 2)]
+		pub fn reject_spending(origin, #[compact] proposal_index: ProposalIndex) {
+			T::ApproveOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			let proposal = <Proposals<T>>::take(proposal_index).ok_or(Error::<T>::ProposalNotFound)?;
+			let remaining = T::Currency::slash_reserved(T::GetNativeCurrencyId::get(), &proposal.proposer, proposal.bond);
+			let slashed = proposal.bond.saturating_sub(remaining);
+
+			Self::deposit_event(RawEvent::SpendingRejected(proposal_index, slashed));
+		}
+	}
+}
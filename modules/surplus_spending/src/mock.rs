@@ -0,0 +1,206 @@
+//! Mocks for the surplus spending module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types};
+use frame_system::EnsureSignedBy;
+use orml_traits::MultiCurrency;
+use primitives::Amount;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchResult, Perbill};
+use sp_std::cell::RefCell;
+use support::Ratio;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const ACA: CurrencyId = CurrencyId::ACA;
+pub const AUSD: CurrencyId = CurrencyId::AUSD;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod surplus_spending {
+	pub use super::super::*;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		system<T>,
+		orml_tokens<T>,
+		surplus_spending<T>,
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+}
+pub type System = system::Module<Runtime>;
+
+impl orml_tokens::Trait for Runtime {
+	type Event = TestEvent;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type DustRemoval = ();
+	type OnReceived = ();
+}
+pub type Tokens = orml_tokens::Module<Runtime>;
+
+thread_local! {
+	pub static SURPLUS_POOL: RefCell<Balance> = RefCell::new(0);
+}
+
+/// The surplus pool is `SURPLUS_POOL`, the spending is issued to the receiver
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		SURPLUS_POOL.with(|v| *v.borrow())
+	}
+
+	fn get_debit_pool() -> Balance {
+		Default::default()
+	}
+
+	fn get_total_collaterals(_: CurrencyId) -> Balance {
+		Default::default()
+	}
+
+	fn on_system_debit(_: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn on_system_surplus(_: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn deposit_backed_debit_to(_: &AccountId, _: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn deposit_unbacked_debit_to(_: &AccountId, _: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn withdraw_backed_debit_from(_: &AccountId, _: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn transfer_surplus_from(_: &AccountId, _: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn transfer_surplus_to(to: &AccountId, amount: Balance) -> DispatchResult {
+		SURPLUS_POOL.with(|v| *v.borrow_mut() -= amount);
+		Tokens::deposit(AUSD, to, amount)
+	}
+
+	fn transfer_collateral_to(_: CurrencyId, _: &AccountId, _: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn transfer_collateral_from(_: CurrencyId, _: &AccountId, _: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn burn_collateral(_: CurrencyId, _: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_debit_proportion(_: Balance) -> Ratio {
+		Default::default()
+	}
+}
+
+/// The surplus above 100 is spendable
+pub struct MockSpendableSurplus;
+impl Get<Balance> for MockSpendableSurplus {
+	fn get() -> Balance {
+		MockCDPTreasury::get_surplus_pool().saturating_sub(100)
+	}
+}
+
+ord_parameter_types! {
+	pub const One: AccountId = 1;
+}
+
+parameter_types! {
+	pub const GetNativeCurrencyId: CurrencyId = ACA;
+	pub const ProposalBond: Balance = 100;
+}
+
+impl Trait for Runtime {
+	type Event = TestEvent;
+	type Currency = Tokens;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type ProposalBond = ProposalBond;
+	type ApproveOrigin = EnsureSignedBy<One, AccountId>;
+	type CDPTreasury = MockCDPTreasury;
+	type SpendableSurplus = MockSpendableSurplus;
+}
+pub type SurplusSpendingModule = Module<Runtime>;
+
+pub struct ExtBuilder {
+	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			endowed_accounts: vec![(ALICE, ACA, 1000), (BOB, ACA, 50)],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		orml_tokens::GenesisConfig::<Runtime> {
+			endowed_accounts: self.endowed_accounts,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		SURPLUS_POOL.with(|v| *v.borrow_mut() = 1000);
+		t.into()
+	}
+}
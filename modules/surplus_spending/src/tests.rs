@@ -0,0 +1,110 @@
+//! Unit tests for the surplus spending module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{
+	ExtBuilder, MockCDPTreasury, Origin, Runtime, SurplusSpendingModule, System, TestEvent, Tokens, ACA, ALICE, AUSD,
+	BOB, CAROL,
+};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn propose_spending_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			SurplusSpendingModule::propose_spending(Origin::signed(ALICE), 0, CAROL),
+			Error::<Runtime>::ZeroValue,
+		);
+		assert!(SurplusSpendingModule::propose_spending(Origin::signed(BOB), 500, CAROL).is_err());
+
+		assert_ok!(SurplusSpendingModule::propose_spending(
+			Origin::signed(ALICE),
+			500,
+			CAROL
+		));
+		let proposed_event = TestEvent::surplus_spending(RawEvent::SpendingProposed(0, ALICE, 500, CAROL));
+		assert!(System::events().iter().any(|record| record.event == proposed_event));
+		assert_eq!(Tokens::reserved_balance(ACA, &ALICE), 100);
+		assert_eq!(SurplusSpendingModule::proposal_count(), 1);
+		assert_eq!(
+			SurplusSpendingModule::proposals(0),
+			Some(SpendingProposal {
+				proposer: ALICE,
+				value: 500,
+				beneficiary: CAROL,
+				bond: 100,
+			})
+		);
+	});
+}
+
+#[test]
+fn approve_spending_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(SurplusSpendingModule::propose_spending(
+			Origin::signed(ALICE),
+			1000,
+			CAROL
+		));
+		assert_ok!(SurplusSpendingModule::propose_spending(
+			Origin::signed(ALICE),
+			500,
+			CAROL
+		));
+		assert_noop!(
+			SurplusSpendingModule::approve_spending(Origin::signed(BOB), 1),
+			BadOrigin
+		);
+		assert_noop!(
+			SurplusSpendingModule::approve_spending(Origin::signed(ALICE), 2),
+			Error::<Runtime>::ProposalNotFound,
+		);
+		// the spendable surplus is 900
+		assert_noop!(
+			SurplusSpendingModule::approve_spending(Origin::signed(ALICE), 0),
+			Error::<Runtime>::SpendableSurplusNotEnough,
+		);
+
+		assert_ok!(SurplusSpendingModule::approve_spending(Origin::signed(ALICE), 1));
+		let approved_event = TestEvent::surplus_spending(RawEvent::SpendingApproved(1, 500, CAROL));
+		assert!(System::events().iter().any(|record| record.event == approved_event));
+		assert_eq!(SurplusSpendingModule::proposals(1), None);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 500);
+		assert_eq!(Tokens::free_balance(AUSD, &CAROL), 500);
+		assert_eq!(Tokens::reserved_balance(ACA, &ALICE), 100);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 900);
+	});
+}
+
+#[test]
+fn reject_spending_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(SurplusSpendingModule::propose_spending(
+			Origin::signed(ALICE),
+			500,
+			CAROL
+		));
+		assert_noop!(
+			SurplusSpendingModule::reject_spending(Origin::signed(BOB), 0),
+			BadOrigin
+		);
+
+		assert_ok!(SurplusSpendingModule::reject_spending(Origin::ROOT, 0));
+		let rejected_event = TestEvent::surplus_spending(RawEvent::SpendingRejected(0, 100));
+		assert!(System::events().iter().any(|record| record.event == rejected_event));
+		assert_eq!(SurplusSpendingModule::proposals(0), None);
+		assert_eq!(Tokens::reserved_balance(ACA, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(AUSD, &CAROL), 0);
+		assert_noop!(
+			SurplusSpendingModule::reject_spending(Origin::ROOT, 0),
+			Error::<Runtime>::ProposalNotFound,
+		);
+	});
+}
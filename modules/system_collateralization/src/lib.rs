@@ -0,0 +1,235 @@
+//! # System Collateralization Module
+//!
+//! ## Overview
+//!
+//! Track the collateralization of the whole system, the total collateral value versus the total debit value
+//! of all CDPs, and emit events when it crosses the thresholds set by governance, which is the signal for
+//! other safety automation.
+//!
+//! The prices of collateral types are locked at checkpoints every `CheckpointPeriod` blocks, when all
+//! collateral types are revalued. Between checkpoints, the collateral types whose debits or collaterals are
+//! updated are revalued with the checkpoint prices at the end of block, so the estimate follows the position changes
+//! without reading the prices every block.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{EnsureOrigin, Get},
+	weights::constants::WEIGHT_PER_MICROS,
+};
+use frame_system::{self as system, ensure_root};
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{traits::Zero, FixedPointNumber};
+use sp_std::prelude::*;
+use support::{OnUpdateCollateral, OnUpdateLoan, Price, PriceProvider, Ratio};
+
+mod mock;
+mod tests;
+
+/// The maximum number of thresholds
+pub const MAX_THRESHOLDS: usize = 10;
+
+/// The total positions of the CDPs under a collateral type
+pub trait TotalPositions {
+	/// Return the total collateral amount and the total debit value in stable currency
+	fn total_positions(currency_id: CurrencyId) -> (Balance, Balance);
+}
+
+pub trait Trait: system::Trait {
+	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// The origin which may update the thresholds. Root can always do this.
+	type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The list of valid collateral currency types
+	type CollateralCurrencyIds: Get<Vec<CurrencyId>>;
+
+	/// Stablecoin currency id, the collateral is valued in it
+	type GetStableCurrencyId: Get<CurrencyId>;
+
+	/// The price source of collateral types
+	type PriceSource: PriceProvider<CurrencyId>;
+
+	/// The total positions of collateral types
+	type TotalPositions: TotalPositions;
+
+	/// The blocks between the checkpoints to lock prices and revalue all collateral types
+	type CheckpointPeriod: Get<Self::BlockNumber>;
+}
+
+decl_event!(
+	pub enum Event {
+		/// The thresholds updated (new_thresholds)
+		ThresholdsUpdated(Vec<Ratio>),
+		/// The system collateral ratio fell below the threshold (threshold, collateral_ratio)
+		ThresholdCrossedBelow(Ratio, Ratio),
+		/// The system collateral ratio rose to or above the threshold (threshold, collateral_ratio),
+		/// `None` means the system has no debit
+		ThresholdCrossedAbove(Ratio, Option<Ratio>),
+	}
+);
+
+decl_error! {
+	/// Error for system collateralization module.
+	pub enum Error for Module<T: Trait> {
+		/// The thresholds are not in ascending order or more than `MAX_THRESHOLDS`
+		InvalidThresholds,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as SystemCollateralization {
+		/// The thresholds of system collateral ratio in ascending order
+		pub Thresholds get(fn thresholds): Vec<Ratio>;
+
+		/// The prices of collateral types locked at the last checkpoint
+		pub CheckpointPrices get(fn checkpoint_prices): map hasher(twox_64_concat) CurrencyId => Option<Price>;
+
+		/// The valuation of collateral types, map from
+		/// CollateralType -> (CollateralValue, DebitValue)
+		pub Valuations get(fn valuations): map hasher(twox_64_concat) CurrencyId => (Balance, Balance);
+
+		/// The total collateral value of system
+		pub TotalCollateralValue get(fn total_collateral_value): Balance;
+
+		/// The total debit value of system
+		pub TotalDebitValue get(fn total_debit_value): Balance;
+
+		/// The collateral types whose loans are updated in current block
+		pub PendingRevaluations get(fn pending_revaluations): Vec<CurrencyId>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// The blocks between the checkpoints
+		const CheckpointPeriod: T::BlockNumber = T::CheckpointPeriod::get();
+
+		/// Update the thresholds of system collateral ratio
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `thresholds`: the new thresholds in ascending order
+		///
+		/// # <weight>
+		/// - Complexity: `O(N)` where N is the number of thresholds, which is capped by `MAX_THRESHOLDS`
+		/// - Db reads:
+		/// - Db writes: `Thresholds`
+		/// -------------------
+		/// Base Weight: 15 µs
+		/// # </weight>
+		#[weight = 15 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(0, 1)]
+		pub fn set_thresholds(origin, thresholds: Vec<Ratio>) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(
+				thresholds.len() <= MAX_THRESHOLDS && thresholds.windows(2).all(|w| w[0] < w[1]),
+				Error::<T>::InvalidThresholds,
+			);
+			Thresholds::put(thresholds.clone());
+			Self::deposit_event(Event::ThresholdsUpdated(thresholds));
+		}
+
+		/// Revalue the collateral types at checkpoint or whose loans are updated
+		fn on_finalize(now: T::BlockNumber) {
+			let previous_ratio = Self::system_collateral_ratio();
+			let pending = PendingRevaluations::take();
+
+			if (now % T::CheckpointPeriod::get()).is_zero() {
+				for currency_id in T::CollateralCurrencyIds::get() {
+					// keep the last checkpoint price if no price now
+					if let Some(price) = T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get()) {
+						CheckpointPrices::insert(currency_id, price);
+					}
+					Self::revalue(currency_id);
+				}
+			} else if !pending.is_empty() {
+				for currency_id in pending {
+					Self::revalue(currency_id);
+				}
+			} else {
+				return;
+			}
+
+			Self::check_thresholds(previous_ratio, Self::system_collateral_ratio());
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The system collateral ratio, `None` means the system has no debit
+	pub fn system_collateral_ratio() -> Option<Ratio> {
+		Ratio::checked_from_rational(Self::total_collateral_value(), Self::total_debit_value())
+	}
+
+	/// Revalue the collateral type at the end of block
+	fn schedule_revaluation(currency_id: CurrencyId) {
+		PendingRevaluations::mutate(|pending| {
+			if !pending.contains(&currency_id) {
+				pending.push(currency_id);
+			}
+		});
+	}
+
+	/// Revalue the collateral type with its checkpoint price and update the total values
+	fn revalue(currency_id: CurrencyId) {
+		let (collateral_amount, debit_value) = T::TotalPositions::total_positions(currency_id);
+		// lock the current price if the collateral type has no checkpoint yet
+		let price = Self::checkpoint_prices(currency_id)
+			.or_else(|| {
+				let price = T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get());
+				if let Some(price) = price {
+					CheckpointPrices::insert(currency_id, price);
+				}
+				price
+			})
+			.unwrap_or_default();
+		let collateral_value = price.saturating_mul_int(collateral_amount);
+
+		let (previous_collateral_value, previous_debit_value) = Self::valuations(currency_id);
+		TotalCollateralValue::mutate(|value| {
+			*value = value
+				.saturating_sub(previous_collateral_value)
+				.saturating_add(collateral_value)
+		});
+		TotalDebitValue::mutate(|value| {
+			*value = value.saturating_sub(previous_debit_value).saturating_add(debit_value)
+		});
+		Valuations::insert(currency_id, (collateral_value, debit_value));
+	}
+
+	/// Emit events for the thresholds crossed from `previous` to `current` ratio,
+	/// `None` ratio is above all thresholds.
+	fn check_thresholds(previous: Option<Ratio>, current: Option<Ratio>) {
+		let is_below = |ratio: Option<Ratio>, threshold: Ratio| ratio.map_or(false, |ratio| ratio < threshold);
+		for threshold in Self::thresholds() {
+			match (is_below(previous, threshold), is_below(current, threshold)) {
+				(false, true) => {
+					Self::deposit_event(Event::ThresholdCrossedBelow(threshold, current.unwrap_or_default()))
+				}
+				(true, false) => Self::deposit_event(Event::ThresholdCrossedAbove(threshold, current)),
+				_ => {}
+			}
+		}
+	}
+}
+
+impl<T: Trait, AccountId, Amount, DebitBalance> OnUpdateLoan<AccountId, CurrencyId, Amount, DebitBalance>
+	for Module<T>
+{
+	fn on_update_loan(_who: &AccountId, currency_id: CurrencyId, _adjustment: Amount, _previous_amount: DebitBalance) {
+		Self::schedule_revaluation(currency_id);
+	}
+}
+
+impl<T: Trait, AccountId, Amount> OnUpdateCollateral<AccountId, CurrencyId, Amount> for Module<T> {
+	fn on_update_collateral(_who: &AccountId, currency_id: CurrencyId, _adjustment: Amount) {
+		Self::schedule_revaluation(currency_id);
+	}
+}
@@ -0,0 +1,144 @@
+//! Mocks for the system collateralization module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types};
+use frame_system::EnsureSignedBy;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_std::{cell::RefCell, collections::btree_map::BTreeMap};
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const AUSD: CurrencyId = CurrencyId::AUSD;
+pub const BTC: CurrencyId = CurrencyId::XBTC;
+pub const DOT: CurrencyId = CurrencyId::DOT;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod system_collateralization {
+	pub use super::super::*;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		system<T>,
+		system_collateralization,
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl system::Trait for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+}
+pub type System = system::Module<Runtime>;
+
+thread_local! {
+	static PRICES: RefCell<BTreeMap<CurrencyId, Price>> = RefCell::new(BTreeMap::new());
+	static POSITIONS: RefCell<BTreeMap<CurrencyId, (Balance, Balance)>> = RefCell::new(BTreeMap::new());
+}
+
+pub fn set_price(currency_id: CurrencyId, price: Price) {
+	PRICES.with(|v| v.borrow_mut().insert(currency_id, price));
+}
+
+pub fn set_positions(currency_id: CurrencyId, collateral: Balance, debit_value: Balance) {
+	POSITIONS.with(|v| v.borrow_mut().insert(currency_id, (collateral, debit_value)));
+}
+
+/// The prices set by `set_price` relative to any currency
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_relative_price(base: CurrencyId, _quote: CurrencyId) -> Option<Price> {
+		Self::get_price(base)
+	}
+
+	fn get_price(currency_id: CurrencyId) -> Option<Price> {
+		PRICES.with(|v| v.borrow().get(&currency_id).cloned())
+	}
+
+	fn lock_price(_currency_id: CurrencyId) {}
+
+	fn unlock_price(_currency_id: CurrencyId) {}
+}
+
+/// The total positions set by `set_positions`
+pub struct MockTotalPositions;
+impl TotalPositions for MockTotalPositions {
+	fn total_positions(currency_id: CurrencyId) -> (Balance, Balance) {
+		POSITIONS.with(|v| v.borrow().get(&currency_id).cloned().unwrap_or_default())
+	}
+}
+
+ord_parameter_types! {
+	pub const One: AccountId = 1;
+}
+
+parameter_types! {
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![BTC, DOT];
+	pub const CheckpointPeriod: BlockNumber = 10;
+}
+
+impl Trait for Runtime {
+	type Event = TestEvent;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type PriceSource = MockPriceSource;
+	type TotalPositions = MockTotalPositions;
+	type CheckpointPeriod = CheckpointPeriod;
+}
+pub type SystemCollateralizationModule = Module<Runtime>;
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		ExtBuilder
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		t.into()
+	}
+}
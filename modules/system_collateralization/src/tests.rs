@@ -0,0 +1,128 @@
+//! Unit tests for the system collateralization module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize};
+use mock::{
+	set_positions, set_price, ExtBuilder, Origin, Runtime, System, SystemCollateralizationModule, TestEvent, ALICE,
+	BOB, BTC, DOT,
+};
+use sp_runtime::traits::BadOrigin;
+
+fn ratio(n: u128, d: u128) -> Ratio {
+	Ratio::saturating_from_rational(n, d)
+}
+
+#[test]
+fn set_thresholds_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			SystemCollateralizationModule::set_thresholds(Origin::signed(BOB), vec![ratio(3, 2)]),
+			BadOrigin
+		);
+		assert_noop!(
+			SystemCollateralizationModule::set_thresholds(Origin::signed(ALICE), vec![ratio(2, 1), ratio(3, 2)]),
+			Error::<Runtime>::InvalidThresholds,
+		);
+		assert_noop!(
+			SystemCollateralizationModule::set_thresholds(
+				Origin::signed(ALICE),
+				(1..=MAX_THRESHOLDS as u128 + 1).map(|n| ratio(n, 1)).collect()
+			),
+			Error::<Runtime>::InvalidThresholds,
+		);
+
+		assert_ok!(SystemCollateralizationModule::set_thresholds(
+			Origin::signed(ALICE),
+			vec![ratio(3, 2), ratio(2, 1)]
+		));
+		let update_event =
+			TestEvent::system_collateralization(Event::ThresholdsUpdated(vec![ratio(3, 2), ratio(2, 1)]));
+		assert!(System::events().iter().any(|record| record.event == update_event));
+		assert_eq!(
+			SystemCollateralizationModule::thresholds(),
+			vec![ratio(3, 2), ratio(2, 1)]
+		);
+	});
+}
+
+#[test]
+fn track_collateralization_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(SystemCollateralizationModule::set_thresholds(
+			Origin::ROOT,
+			vec![ratio(3, 2), ratio(2, 1)]
+		));
+		set_price(BTC, ratio(2, 1));
+		set_price(DOT, ratio(1, 1));
+		set_positions(BTC, 100, 100);
+		assert_eq!(SystemCollateralizationModule::system_collateral_ratio(), None);
+
+		// revalue all collateral types at checkpoint
+		SystemCollateralizationModule::on_finalize(10);
+		assert_eq!(SystemCollateralizationModule::total_collateral_value(), 200);
+		assert_eq!(SystemCollateralizationModule::total_debit_value(), 100);
+		assert_eq!(
+			SystemCollateralizationModule::system_collateral_ratio(),
+			Some(ratio(2, 1))
+		);
+		assert_eq!(SystemCollateralizationModule::checkpoint_prices(BTC), Some(ratio(2, 1)));
+
+		// revalue the updated collateral type with checkpoint price
+		set_price(BTC, ratio(1, 1));
+		set_positions(DOT, 100, 100);
+		SystemCollateralizationModule::on_update_loan(&ALICE, DOT, 100i64, 0u128);
+		assert_eq!(SystemCollateralizationModule::pending_revaluations(), vec![DOT]);
+		SystemCollateralizationModule::on_finalize(11);
+		assert_eq!(SystemCollateralizationModule::pending_revaluations(), vec![]);
+		assert_eq!(SystemCollateralizationModule::total_collateral_value(), 300);
+		assert_eq!(SystemCollateralizationModule::total_debit_value(), 200);
+		assert_eq!(SystemCollateralizationModule::valuations(DOT), (100, 100));
+		let crossed_event = TestEvent::system_collateralization(Event::ThresholdCrossedBelow(ratio(2, 1), ratio(3, 2)));
+		assert!(System::events().iter().any(|record| record.event == crossed_event));
+
+		// the new price is used at next checkpoint
+		SystemCollateralizationModule::on_finalize(20);
+		assert_eq!(SystemCollateralizationModule::total_collateral_value(), 200);
+		assert_eq!(
+			SystemCollateralizationModule::system_collateral_ratio(),
+			Some(ratio(1, 1))
+		);
+		let crossed_event = TestEvent::system_collateralization(Event::ThresholdCrossedBelow(ratio(3, 2), ratio(1, 1)));
+		assert!(System::events().iter().any(|record| record.event == crossed_event));
+
+		// all debits are repaid
+		System::reset_events();
+		set_positions(BTC, 100, 0);
+		set_positions(DOT, 100, 0);
+		SystemCollateralizationModule::on_finalize(30);
+		assert_eq!(SystemCollateralizationModule::system_collateral_ratio(), None);
+		let crossed_event = TestEvent::system_collateralization(Event::ThresholdCrossedAbove(ratio(3, 2), None));
+		assert!(System::events().iter().any(|record| record.event == crossed_event));
+		let crossed_event = TestEvent::system_collateralization(Event::ThresholdCrossedAbove(ratio(2, 1), None));
+		assert!(System::events().iter().any(|record| record.event == crossed_event));
+	});
+}
+
+#[test]
+fn track_collateral_updates_without_debit_changes() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_price(DOT, ratio(1, 1));
+		set_positions(DOT, 100, 100);
+		SystemCollateralizationModule::on_finalize(10);
+		assert_eq!(SystemCollateralizationModule::total_collateral_value(), 100);
+
+		set_positions(DOT, 150, 100);
+		SystemCollateralizationModule::on_update_collateral(&ALICE, DOT, 50i128);
+		assert_eq!(SystemCollateralizationModule::pending_revaluations(), vec![DOT]);
+		SystemCollateralizationModule::on_finalize(11);
+		assert_eq!(SystemCollateralizationModule::total_collateral_value(), 150);
+		assert_eq!(
+			SystemCollateralizationModule::system_collateral_ratio(),
+			Some(ratio(3, 2))
+		);
+	});
+}
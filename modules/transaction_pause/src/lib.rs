@@ -0,0 +1,212 @@
+//! # Transaction Pause Module
+//!
+//! ## Overview
+//!
+//! Lets governance pause and unpause specific calls of pallets, e.g. `Honzon::adjust_loan`,
+//! for incident response that is less drastic than emergency shutdown. The paused calls are
+//! rejected by the `CheckPausedTransaction` signed extension for transactions, and by the
+//! `PausedTransactionFilter` call filter for calls dispatched by other calls, e.g. utility batch.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{CallMetadata, EnsureOrigin, Filter, Get, GetCallMetadata},
+	weights::constants::WEIGHT_PER_MICROS,
+};
+use frame_system::{self as system, ensure_root};
+use sp_runtime::{
+	traits::{DispatchInfoOf, SignedExtension},
+	transaction_validity::{InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction},
+};
+use sp_std::{marker, prelude::*};
+
+mod mock;
+mod tests;
+
+/// The name of this module in runtime, its calls can't be paused
+pub const MODULE_NAME: &[u8] = b"TransactionPause";
+
+pub trait Trait: system::Trait {
+	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// The origin which may pause and unpause transactions. Root can always do this.
+	type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The names of pallets whose calls can't be paused, e.g. the governance pallets
+	type UnpausablePallets: Get<Vec<Vec<u8>>>;
+}
+
+decl_event!(
+	pub enum Event {
+		/// The transaction paused (pallet_name, function_name)
+		TransactionPaused(Vec<u8>, Vec<u8>),
+		/// The transaction unpaused (pallet_name, function_name)
+		TransactionUnpaused(Vec<u8>, Vec<u8>),
+	}
+);
+
+decl_error! {
+	/// Error for transaction pause module.
+	pub enum Error for Module<T: Trait> {
+		/// The calls of this module or the unpausable pallets can't be paused
+		CannotPause,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as TransactionPause {
+		/// The paused transactions, identified by (pallet_name, function_name)
+		pub PausedTransactions get(fn paused_transactions): map hasher(twox_64_concat) (Vec<u8>, Vec<u8>) => bool;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// The names of pallets whose calls can't be paused
+		const UnpausablePallets: Vec<Vec<u8>> = T::UnpausablePallets::get();
+
+		/// Pause the call of pallet.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `pallet_name`: the name of pallet in runtime, e.g. `Honzon`.
+		/// - `function_name`: the name of call, e.g. `adjust_loan`.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads:
+		/// - Db writes: `PausedTransactions`
+		/// -------------------
+		/// Base Weight: 10 µs
+		/// # </weight>
+		#[weight = 10 * WEIGHT_PER_MICROS + T::DbWeight::get().writes(1)]
+		pub fn pause_transaction(origin, pallet_name: Vec<u8>, function_name: Vec<u8>) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(
+				pallet_name != MODULE_NAME && !T::UnpausablePallets::get().contains(&pallet_name),
+				Error::<T>::CannotPause,
+			);
+
+			PausedTransactions::insert((pallet_name.clone(), function_name.clone()), true);
+			Self::deposit_event(Event::TransactionPaused(pallet_name, function_name));
+		}
+
+		/// Unpause the call of pallet.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin` or _Root_.
+		///
+		/// - `pallet_name`: the name of pallet in runtime, e.g. `Honzon`.
+		/// - `function_name`: the name of call, e.g. `adjust_loan`.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		/// - Db reads: `PausedTransactions`
+		/// - Db writes: `PausedTransactions`
+		/// -------------------
+		/// Base Weight: 10 µs
+		/// # </weight>
+		#[weight = 10 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn unpause_transaction(origin, pallet_name: Vec<u8>, function_name: Vec<u8>) {
+			T::UpdateOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			if PausedTransactions::take((pallet_name.clone(), function_name.clone())) {
+				Self::deposit_event(Event::TransactionUnpaused(pallet_name, function_name));
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether the call is paused.
+	pub fn is_paused(call: &impl GetCallMetadata) -> bool {
+		let CallMetadata {
+			pallet_name,
+			function_name,
+		} = call.get_call_metadata();
+		Self::paused_transactions((pallet_name.as_bytes().to_vec(), function_name.as_bytes().to_vec()))
+	}
+}
+
+/// The call filter which filters out the paused calls
+pub struct PausedTransactionFilter<T>(marker::PhantomData<T>);
+
+impl<T: Trait> Filter<T::Call> for PausedTransactionFilter<T>
+where
+	T::Call: GetCallMetadata,
+{
+	fn filter(call: &T::Call) -> bool {
+		!<Module<T>>::is_paused(call)
+	}
+}
+
+/// Reject the transactions of paused calls.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckPausedTransaction<T: Trait + Send + Sync>(marker::PhantomData<T>);
+
+impl<T: Trait + Send + Sync> CheckPausedTransaction<T> {
+	pub fn new() -> Self {
+		Self(marker::PhantomData)
+	}
+}
+
+impl<T: Trait + Send + Sync> Default for CheckPausedTransaction<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Trait + Send + Sync> sp_std::fmt::Debug for CheckPausedTransaction<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "CheckPausedTransaction")
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for CheckPausedTransaction<T>
+where
+	T::Call: GetCallMetadata,
+{
+	const IDENTIFIER: &'static str = "CheckPausedTransaction";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		if <Module<T>>::is_paused(call) {
+			return InvalidTransaction::Call.into();
+		}
+		Ok(ValidTransaction::default())
+	}
+
+	fn validate_unsigned(call: &Self::Call, _info: &DispatchInfoOf<Self::Call>, _len: usize) -> TransactionValidity {
+		if <Module<T>>::is_paused(call) {
+			return InvalidTransaction::Call.into();
+		}
+		Ok(ValidTransaction::default())
+	}
+}
@@ -0,0 +1,117 @@
+//! Unit tests for the transaction pause module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, weights::DispatchInfo};
+use mock::{Call as RuntimeCall, ExtBuilder, Origin, Runtime, System, TestEvent, TransactionPause, ALICE, BOB};
+use sp_runtime::traits::BadOrigin;
+
+fn remark_call() -> RuntimeCall {
+	RuntimeCall::System(system::Call::remark(vec![]))
+}
+
+#[test]
+fn pause_transaction_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TransactionPause::pause_transaction(Origin::signed(BOB), b"System".to_vec(), b"remark".to_vec()),
+			BadOrigin
+		);
+		assert_noop!(
+			TransactionPause::pause_transaction(
+				Origin::signed(ALICE),
+				MODULE_NAME.to_vec(),
+				b"unpause_transaction".to_vec()
+			),
+			Error::<Runtime>::CannotPause
+		);
+		assert_noop!(
+			TransactionPause::pause_transaction(Origin::signed(ALICE), b"Sudo".to_vec(), b"sudo".to_vec()),
+			Error::<Runtime>::CannotPause
+		);
+
+		assert!(!TransactionPause::is_paused(&remark_call()));
+		assert_ok!(TransactionPause::pause_transaction(
+			Origin::signed(ALICE),
+			b"System".to_vec(),
+			b"remark".to_vec()
+		));
+		let paused_event =
+			TestEvent::transaction_pause(Event::TransactionPaused(b"System".to_vec(), b"remark".to_vec()));
+		assert!(System::events().iter().any(|record| record.event == paused_event));
+		assert!(TransactionPause::paused_transactions((
+			b"System".to_vec(),
+			b"remark".to_vec()
+		)));
+		assert!(TransactionPause::is_paused(&remark_call()));
+		assert!(!TransactionPause::is_paused(&RuntimeCall::System(
+			system::Call::set_heap_pages(1)
+		)));
+	});
+}
+
+#[test]
+fn unpause_transaction_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TransactionPause::pause_transaction(
+			Origin::ROOT,
+			b"System".to_vec(),
+			b"remark".to_vec()
+		));
+		assert_noop!(
+			TransactionPause::unpause_transaction(Origin::signed(BOB), b"System".to_vec(), b"remark".to_vec()),
+			BadOrigin
+		);
+
+		assert_ok!(TransactionPause::unpause_transaction(
+			Origin::signed(ALICE),
+			b"System".to_vec(),
+			b"remark".to_vec()
+		));
+		let unpaused_event =
+			TestEvent::transaction_pause(Event::TransactionUnpaused(b"System".to_vec(), b"remark".to_vec()));
+		assert!(System::events().iter().any(|record| record.event == unpaused_event));
+		assert!(!TransactionPause::is_paused(&remark_call()));
+	});
+}
+
+#[test]
+fn paused_transaction_filter_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(PausedTransactionFilter::<Runtime>::filter(&remark_call()));
+		assert_ok!(TransactionPause::pause_transaction(
+			Origin::ROOT,
+			b"System".to_vec(),
+			b"remark".to_vec()
+		));
+		assert!(!PausedTransactionFilter::<Runtime>::filter(&remark_call()));
+	});
+}
+
+#[test]
+fn check_paused_transaction_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let info = DispatchInfo::default();
+		assert!(CheckPausedTransaction::<Runtime>::new()
+			.validate(&ALICE, &remark_call(), &info, 0)
+			.is_ok());
+		assert!(CheckPausedTransaction::<Runtime>::validate_unsigned(&remark_call(), &info, 0).is_ok());
+
+		assert_ok!(TransactionPause::pause_transaction(
+			Origin::ROOT,
+			b"System".to_vec(),
+			b"remark".to_vec()
+		));
+		assert_eq!(
+			CheckPausedTransaction::<Runtime>::new().validate(&ALICE, &remark_call(), &info, 0),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
+		);
+		assert_eq!(
+			CheckPausedTransaction::<Runtime>::validate_unsigned(&remark_call(), &info, 0),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
+		);
+	});
+}
@@ -10,7 +10,7 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
-use codec::Encode;
+use codec::{Decode, Encode};
 use sp_api::impl_runtime_apis;
 use sp_core::{
 	crypto::KeyTypeId,
@@ -18,14 +18,15 @@ use sp_core::{
 	OpaqueMetadata,
 };
 use sp_runtime::traits::{
-	BlakeTwo256, Block as BlockT, Convert, NumberFor, OpaqueKeys, SaturatedConversion, Saturating, StaticLookup,
+	BlakeTwo256, Block as BlockT, CheckedDiv, Convert, NumberFor, OpaqueKeys, SaturatedConversion, Saturating,
+	StaticLookup, Zero,
 };
 use sp_runtime::{
 	create_runtime_str,
 	curve::PiecewiseLinear,
 	generic, impl_opaque_keys,
 	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, FixedPointNumber, ModuleId,
+	ApplyExtrinsicResult, DispatchResult, FixedPointNumber, ModuleId, RuntimeDebug,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -34,19 +35,21 @@ use sp_version::RuntimeVersion;
 
 use frame_system::{self as system};
 use orml_currencies::{BasicCurrencyAdapter, Currency};
+use orml_traits::Change;
 use pallet_grandpa::fg_primitives;
 use pallet_grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use pallet_session::historical as pallet_session_historical;
 
 pub use frame_support::{
 	construct_runtime, debug, parameter_types,
-	traits::{Contains, ContainsLengthBound, Filter, KeyOwnerProofSystem, Randomness},
+	traits::{Contains, ContainsLengthBound, Filter, Get, InstanceFilter, KeyOwnerProofSystem, Randomness},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	IterableStorageDoubleMap, StorageValue,
 };
+pub use module_asset_registry::{AssetClass, AssetMetadata};
 pub use orml_oracle::AuthorityId as OracleId;
 pub use pallet_staking::StakerStatus;
 pub use pallet_timestamp::Call as TimestampCall;
@@ -66,10 +69,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("acala"),
 	impl_name: create_runtime_str!("acala"),
 	authoring_version: 1,
-	spec_version: 404,
+	spec_version: 405,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 /// The version infromation used to identify this runtime when compiled natively.
@@ -335,19 +338,47 @@ impl pallet_membership::Trait<OperatorMembershipInstance> for Runtime {
 	type SwapOrigin = pallet_collective::EnsureProportionMoreThan<_1, _3, AccountId, GeneralCouncilInstance>;
 	type ResetOrigin = pallet_collective::EnsureProportionMoreThan<_1, _3, AccountId, GeneralCouncilInstance>;
 	type PrimeOrigin = pallet_collective::EnsureProportionMoreThan<_1, _3, AccountId, GeneralCouncilInstance>;
-	type MembershipInitialized = Oracle;
-	type MembershipChanged = Oracle;
+	type MembershipInitialized = OracleOperator;
+	type MembershipChanged = OracleOperator;
 }
 
 pub struct BaseFilter;
 impl Filter<Call> for BaseFilter {
-	fn filter(_call: &Call) -> bool {
-		true
+	fn filter(call: &Call) -> bool {
+		module_transaction_pause::PausedTransactionFilter::<Runtime>::filter(call)
 	}
 }
 pub struct IsCallable;
 frame_support::impl_filter_stack!(IsCallable, BaseFilter, Call, is_callable);
 
+parameter_types! {
+	pub UnpausablePallets: Vec<Vec<u8>> = vec![
+		// inherents and consensus, pausing them halts the chain
+		b"System".to_vec(),
+		b"Timestamp".to_vec(),
+		b"Babe".to_vec(),
+		b"Grandpa".to_vec(),
+		b"Session".to_vec(),
+		// governance, pausing them makes the pauses irreversible
+		b"Sudo".to_vec(),
+		b"Democracy".to_vec(),
+		b"GeneralCouncil".to_vec(),
+		b"GeneralCouncilMembership".to_vec(),
+		b"HonzonCouncil".to_vec(),
+		b"HonzonCouncilMembership".to_vec(),
+		b"HomaCouncil".to_vec(),
+		b"HomaCouncilMembership".to_vec(),
+		b"TechnicalCouncil".to_vec(),
+		b"TechnicalCouncilMembership".to_vec(),
+	];
+}
+
+impl module_transaction_pause::Trait for Runtime {
+	type Event = Event;
+	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, TechnicalCouncilInstance>;
+	type UnpausablePallets = UnpausablePallets;
+}
+
 impl pallet_utility::Trait for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -370,6 +401,60 @@ impl pallet_multisig::Trait for Runtime {
 	type IsCallable = IsCallable;
 }
 
+parameter_types! {
+	pub const ProxyDepositBase: Balance = 500 * MILLICENTS;
+	pub const ProxyDepositFactor: Balance = 100 * MILLICENTS;
+	pub const MaxProxies: u16 = 32;
+}
+
+/// The kinds of calls a proxy account is allowed to make on behalf of the proxied account.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug)]
+pub enum ProxyType {
+	/// All calls
+	Any,
+	/// Managing the loans positions, the withdrawn collaterals and issued stable coins go to the proxied account
+	Loan,
+	/// Trading in DEX
+	Swap,
+	/// Bidding in auctions
+	Auction,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl InstanceFilter<Call> for ProxyType {
+	fn filter(&self, c: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// `authorize` is excluded as the authorized account could take the loans away
+			ProxyType::Loan => matches!(
+				c,
+				Call::Honzon(module_honzon::Call::adjust_loan(..))
+					| Call::Honzon(module_honzon::Call::transfer_loan_from(..))
+					| Call::Honzon(module_honzon::Call::unauthorize(..))
+					| Call::Honzon(module_honzon::Call::unauthorize_all(..))
+			),
+			ProxyType::Swap => matches!(c, Call::Dex(module_dex::Call::swap_currency(..))),
+			ProxyType::Auction => matches!(c, Call::Auction(orml_auction::Call::bid(..))),
+		}
+	}
+}
+
+impl pallet_proxy::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+	type IsCallable = IsCallable;
+}
+
 pub struct GeneralCouncilProvider;
 impl Contains<AccountId> for GeneralCouncilProvider {
 	fn contains(who: &AccountId) -> bool {
@@ -548,8 +633,8 @@ parameter_types! {
 
 impl orml_oracle::Trait for Runtime {
 	type Event = Event;
-	type OnNewData = ();
-	type CombineData = orml_oracle::DefaultCombineData<Runtime, MinimumCount, ExpiresIn>;
+	type OnNewData = Prices;
+	type CombineData = module_oracle_operator::ExpiringCombineData<Runtime, MinimumCount>;
 	type Time = Timestamp;
 	type OracleKey = CurrencyId;
 	type OracleValue = Price;
@@ -559,6 +644,39 @@ impl orml_oracle::Trait for Runtime {
 
 pub type TimeStampedPrice = orml_oracle::TimestampedValueOf<Runtime>;
 
+parameter_types! {
+	pub const MinOperatorBond: Balance = 1_000 * DOLLARS;
+	pub const OperatorUnbondingPeriod: BlockNumber = 7 * DAYS;
+	pub MaxOracleDeviation: Ratio = Ratio::saturating_from_rational(50, 100);
+	pub DeviationSlashRatio: Ratio = Ratio::saturating_from_rational(10, 100);
+}
+
+impl module_oracle_operator::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type MinOperatorBond = MinOperatorBond;
+	type UnbondingPeriod = OperatorUnbondingPeriod;
+	type SlashOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, GeneralCouncilInstance>;
+	type Slash = PalletTreasury;
+	type Source = Oracle;
+	type RawSource = OracleRawValues;
+	type MaxDeviation = MaxOracleDeviation;
+	type DeviationSlashRatio = DeviationSlashRatio;
+	type FeedersInitialized = Oracle;
+	type FeedersChanged = Oracle;
+	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, GeneralCouncilInstance>;
+	type Time = Timestamp;
+	type DefaultValueExpiry = ExpiresIn;
+}
+
+/// The raw values fed by each oracle operator.
+pub struct OracleRawValues;
+impl module_support::OperatorDataProvider<AccountId, CurrencyId, Price, Moment> for OracleRawValues {
+	fn raw_value(who: &AccountId, currency_id: &CurrencyId) -> Option<(Price, Moment)> {
+		Oracle::raw_values(who, currency_id).map(|x| (x.value, x.timestamp))
+	}
+}
+
 impl orml_tokens::Trait for Runtime {
 	type Event = Event;
 	type Balance = Balance;
@@ -570,17 +688,46 @@ impl orml_tokens::Trait for Runtime {
 
 parameter_types! {
 	pub StableCurrencyFixedPrice: Price = Price::saturating_from_rational(1, 1);
+	pub const MaxEmergencyPriceDuration: BlockNumber = 2 * HOURS;
+	pub const PriceFeedHeartbeatThreshold: BlockNumber = 30 * MINUTES;
+	pub const PriceHistoryDepth: u32 = 32;
 }
 
 impl module_prices::Trait for Runtime {
 	type Event = Event;
 	type Source = Oracle;
+	type ExternalSource = module_prices::NoExternalSource;
+	type Dispersion = OracleDispersion;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type StableCurrencyFixedPrice = StableCurrencyFixedPrice;
-	type GetStakingCurrencyId = GetStakingCurrencyId;
-	type GetLiquidCurrencyId = GetLiquidCurrencyId;
 	type LockOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, GeneralCouncilInstance>;
-	type LiquidStakingExchangeRateProvider = LiquidStakingExchangeRateProvider;
+	type DerivativeAssets = module_prices::DerivativeAssetAdapter<
+		GetLiquidCurrencyId,
+		GetStakingCurrencyId,
+		LiquidStakingExchangeRateProvider,
+	>;
+	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, GeneralCouncilInstance>;
+	type MaxEmergencyPriceDuration = MaxEmergencyPriceDuration;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type HeartbeatThreshold = PriceFeedHeartbeatThreshold;
+	type PriceHistoryDepth = PriceHistoryDepth;
+}
+
+/// The spread between the highest and lowest raw values of oracle feeders,
+/// relative to the combined oracle price. Only the unexpired values, which are
+/// combined into the oracle price, are measured.
+pub struct OracleDispersion;
+impl orml_traits::DataProvider<CurrencyId, Ratio> for OracleDispersion {
+	fn get(currency_id: &CurrencyId) -> Option<Ratio> {
+		let price = <Oracle as orml_traits::DataProvider<CurrencyId, Price>>::get(currency_id)?;
+		let raw_values = Oracle::read_raw_values(currency_id)
+			.into_iter()
+			.filter(|x| OracleOperator::is_unexpired(*currency_id, x.timestamp))
+			.collect::<Vec<_>>();
+		let max = raw_values.iter().map(|x| x.value).max()?;
+		let min = raw_values.iter().map(|x| x.value).min()?;
+		max.saturating_sub(min).checked_div(&price)
+	}
 }
 
 pub struct LiquidStakingExchangeRateProvider;
@@ -602,6 +749,37 @@ impl orml_currencies::Trait for Runtime {
 	type GetNativeCurrencyId = GetNativeCurrencyId;
 }
 
+parameter_types! {
+	pub const MaxBatchTransfers: u32 = 50;
+}
+
+/// The withdrawable balance of native currency in balances, and of the other currencies in tokens.
+pub struct WithdrawableBalances;
+impl module_currencies_transfer::WithdrawableBalance<AccountId> for WithdrawableBalances {
+	fn withdrawable_balance(currency_id: CurrencyId, who: &AccountId) -> Balance {
+		if currency_id == GetNativeCurrencyId::get() {
+			// the account referenced by other modules can't be reaped
+			let account = System::account(who);
+			let kept = if account.refcount.is_zero() {
+				Zero::zero()
+			} else {
+				AcaExistentialDeposit::get()
+			};
+			account.data.free.saturating_sub(account.data.misc_frozen.max(kept))
+		} else {
+			let account = Tokens::accounts(who, currency_id);
+			account.free.saturating_sub(account.frozen)
+		}
+	}
+}
+
+impl module_currencies_transfer::Trait for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type MaxBatchTransfers = MaxBatchTransfers;
+	type WithdrawableBalance = WithdrawableBalances;
+}
+
 impl orml_vesting::Trait for Runtime {
 	type Event = Event;
 	type Currency = pallet_balances::Module<Runtime>;
@@ -632,6 +810,7 @@ parameter_types! {
 	pub MinimumIncrementSize: Rate = Rate::saturating_from_rational(2, 100);
 	pub const AuctionTimeToClose: BlockNumber = 15 * MINUTES;
 	pub const AuctionDurationSoftCap: BlockNumber = 2 * HOURS;
+	pub const MinimumRunnerUpBidDuration: BlockNumber = 5 * MINUTES;
 	pub GetAmountAdjustment: Rate = Rate::saturating_from_rational(20, 100);
 	pub const AuctionManagerUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
 }
@@ -650,6 +829,13 @@ impl module_auction_manager::Trait for Runtime {
 	type DEX = Dex;
 	type PriceSource = Prices;
 	type UnsignedPriority = AuctionManagerUnsignedPriority;
+	type EmergencyShutdown = EmergencyShutdown;
+	type OnCollateralAuctionDealt = Incentives;
+	type MinimumRunnerUpBidDuration = MinimumRunnerUpBidDuration;
+}
+
+parameter_types! {
+	pub const PositionDeposit: Balance = 10 * CENTS;
 }
 
 impl module_loans::Trait for Runtime {
@@ -660,6 +846,41 @@ impl module_loans::Trait for Runtime {
 	type DebitBalance = Balance;
 	type DebitAmount = Amount;
 	type CDPTreasury = CdpTreasury;
+	type EmergencyShutdown = EmergencyShutdown;
+	type OnUpdateLoan = (Incentives, SystemCollateralization);
+	type OnUpdateCollateral = SystemCollateralization;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type PositionDeposit = PositionDeposit;
+}
+
+parameter_types! {
+	pub const AccumulatePeriod: BlockNumber = MINUTES;
+	pub const IncentivesVestingPeriod: BlockNumber = 90 * DAYS;
+	pub CDPTreasuryAccount: AccountId = CdpTreasury::account_id();
+}
+
+impl module_incentives::Trait for Runtime {
+	type Event = Event;
+	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, HonzonCouncilInstance>;
+	type Currency = Currencies;
+	type IncentiveCurrencyId = GetNativeCurrencyId;
+	type AccumulatePeriod = AccumulatePeriod;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = CdpTreasury;
+	type VestingPeriod = IncentivesVestingPeriod;
+	type TreasuryAccount = CDPTreasuryAccount;
+	type Vesting = MultiCurrencyVesting;
+}
+
+parameter_types! {
+	pub const MaxVestingSchedules: u32 = 20;
+}
+
+impl module_multi_currency_vesting::Trait for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type MaxVestingSchedules = MaxVestingSchedules;
+	type RevokeOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, GeneralCouncilInstance>;
 }
 
 impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
@@ -692,6 +913,7 @@ where
 			system::CheckGenesis::<Runtime>::new(),
 			system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
 			system::CheckNonce::<Runtime>::from(nonce),
+			module_transaction_pause::CheckPausedTransaction::<Runtime>::new(),
 			system::CheckWeight::<Runtime>::new(),
 			module_accounts::ChargeTransactionPayment::<Runtime>::from(tip),
 		);
@@ -721,12 +943,13 @@ where
 }
 
 parameter_types! {
-	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![CurrencyId::DOT, CurrencyId::XBTC, CurrencyId::LDOT];
+	pub BaseCollateralCurrencyIds: Vec<CurrencyId> = vec![CurrencyId::DOT, CurrencyId::XBTC, CurrencyId::LDOT];
 	pub DefaultLiquidationRatio: Ratio = Ratio::saturating_from_rational(110, 100);
 	pub DefaultDebitExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(1, 10);
 	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(5, 100);
 	pub const MinimumDebitValue: Balance = DOLLARS;
 	pub MaxSlippageSwapWithDEX: Ratio = Ratio::saturating_from_rational(5, 100);
+	pub MinimumPriceConfidence: Ratio = Ratio::saturating_from_rational(95, 100);
 	pub const CdpEngineUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
 }
 
@@ -741,23 +964,154 @@ impl module_cdp_engine::Trait for Runtime {
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CdpTreasury;
 	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, HonzonCouncilInstance>;
+	type LiquidationParamsUpdateOrigin =
+		pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, GeneralCouncilInstance>;
+	type EmergencyVetoOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, TechnicalCouncilInstance>;
 	type MaxSlippageSwapWithDEX = MaxSlippageSwapWithDEX;
+	type MinimumPriceConfidence = MinimumPriceConfidence;
 	type DEX = Dex;
 	type UnsignedPriority = CdpEngineUnsignedPriority;
+	type OnKeeperAction = Incentives;
+}
+
+/// The collateral types configured in runtime and onboarded by proposals
+pub type CollateralCurrencyIds = module_collateral_onboarding::CollateralCurrencyIds<Runtime>;
+
+parameter_types! {
+	pub const CollateralizationCheckpointPeriod: BlockNumber = 10 * MINUTES;
+}
+
+impl module_system_collateralization::Trait for Runtime {
+	type Event = Event;
+	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, HonzonCouncilInstance>;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type PriceSource = Prices;
+	type TotalPositions = LoansTotalPositions;
+	type CheckpointPeriod = CollateralizationCheckpointPeriod;
+}
+
+/// The total collateral amount and the total debit value of the loans under a collateral type.
+pub struct LoansTotalPositions;
+impl module_system_collateralization::TotalPositions for LoansTotalPositions {
+	fn total_positions(currency_id: CurrencyId) -> (Balance, Balance) {
+		(
+			Loans::total_collaterals(currency_id),
+			CdpEngine::get_debit_value(currency_id, Loans::total_debits(currency_id)),
+		)
+	}
+}
+
+parameter_types! {
+	pub const CollateralProposalBond: Balance = 10_000 * DOLLARS;
+}
+
+impl module_collateral_onboarding::Trait for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type ProposalBond = CollateralProposalBond;
+	type BaseCollateralCurrencyIds = BaseCollateralCurrencyIds;
+	type ApproveOrigin = pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, GeneralCouncilInstance>;
+	type DEX = Dex;
+	type OnCollateralOnboarded = ConfigureOnboardedCollateral;
+}
+
+/// Configure the risk management params and the loans incentive pool of the onboarded collateral type.
+pub struct ConfigureOnboardedCollateral;
+impl module_collateral_onboarding::OnCollateralOnboarded for ConfigureOnboardedCollateral {
+	fn ensure_can_onboard(
+		currency_id: CurrencyId,
+		params: &module_collateral_onboarding::CollateralOnboardingParams,
+	) -> DispatchResult {
+		let collateral_params = module_cdp_engine::RiskManagementParams {
+			maximum_total_debit_value: params.maximum_total_debit_value,
+			stability_fee: params.stability_fee,
+			liquidation_ratio: params.liquidation_ratio,
+			liquidation_penalty: params.liquidation_penalty,
+			required_collateral_ratio: params.required_collateral_ratio,
+		};
+		frame_support::ensure!(
+			CdpEngine::collateral_params_bounds(currency_id).contains(&collateral_params),
+			module_cdp_engine::Error::<Runtime>::ParamOutOfBounds
+		);
+		Ok(())
+	}
+
+	fn on_collateral_onboarded(
+		currency_id: CurrencyId,
+		params: &module_collateral_onboarding::CollateralOnboardingParams,
+	) -> DispatchResult {
+		CdpEngine::set_collateral_params(
+			system::RawOrigin::Root.into(),
+			currency_id,
+			Change::NewValue(params.stability_fee),
+			Change::NewValue(params.liquidation_ratio),
+			Change::NewValue(params.liquidation_penalty),
+			Change::NewValue(params.required_collateral_ratio),
+			Change::NewValue(params.maximum_total_debit_value),
+		)?;
+		if !params.loans_incentive_weight.is_zero() {
+			Incentives::set_pool_weights(
+				system::RawOrigin::Root.into(),
+				vec![(
+					module_incentives::PoolId::Loans(currency_id),
+					params.loans_incentive_weight,
+				)],
+			)?;
+		}
+		Ok(())
+	}
+}
+
+impl module_asset_registry::Trait for Runtime {
+	type Event = Event;
+	type RegisterOrigin = pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, GeneralCouncilInstance>;
+}
+
+parameter_types! {
+	// renBTC is the first asset registered in the genesis of asset registry
+	pub const RenBtcCurrencyId: CurrencyId = CurrencyId::Registered(0);
+	pub const RenVmBridgeUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+
+impl module_renvm_bridge::Trait for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type BridgedCurrencyId = RenBtcCurrencyId;
+	type UpdateOrigin = pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, GeneralCouncilInstance>;
+	type UnsignedPriority = RenVmBridgeUnsignedPriority;
 }
 
 impl module_honzon::Trait for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const ClaimsPeriod: BlockNumber = 30 * DAYS;
+}
+
 impl module_emergency_shutdown::Trait for Runtime {
 	type Event = Event;
 	type CollateralCurrencyIds = CollateralCurrencyIds;
-	type PriceSource = Prices;
+	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CdpTreasury;
 	type AuctionManagerHandler = AuctionManager;
-	type OnShutdown = (CdpTreasury, CdpEngine, Honzon, Dex);
+	type OnShutdown = (Prices, Honzon, Dex, AuctionManager, Incentives);
 	type ShutdownOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, GeneralCouncilInstance>;
+	type CancelShutdownOrigin = pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, GeneralCouncilInstance>;
+	type PriceSource = Prices;
+	type SystemCollateralRatio = TrackedSystemCollateralRatio;
+	type ClaimsPeriod = ClaimsPeriod;
+}
+
+/// The system collateral ratio tracked by system collateralization.
+pub struct TrackedSystemCollateralRatio;
+impl Get<Option<Ratio>> for TrackedSystemCollateralRatio {
+	fn get() -> Option<Ratio> {
+		SystemCollateralization::system_collateral_ratio()
+	}
 }
 
 parameter_types! {
@@ -774,6 +1128,7 @@ impl module_dex::Trait for Runtime {
 	type GetExchangeFee = GetExchangeFee;
 	type CDPTreasury = CdpTreasury;
 	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, HonzonCouncilInstance>;
+	type OnUpdateLiquidity = Incentives;
 }
 
 parameter_types! {
@@ -788,24 +1143,103 @@ impl module_cdp_treasury::Trait for Runtime {
 	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, HonzonCouncilInstance>;
 	type DEX = Dex;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type EmergencyShutdown = EmergencyShutdown;
+}
+
+parameter_types! {
+	pub const SpendingProposalBond: Balance = 1_000 * DOLLARS;
+}
+
+impl module_surplus_spending::Trait for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type ProposalBond = SpendingProposalBond;
+	type ApproveOrigin = pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, HonzonCouncilInstance>;
+	type CDPTreasury = CdpTreasury;
+	type SpendableSurplus = module_cdp_treasury::SpendableSurplus<Runtime>;
 }
 
 parameter_types! {
 	pub const FreeTransferCount: u8 = 3;
 	pub const FreeTransferPeriod: BlockNumber = DAYS;
 	pub const FreeTransferDeposit: Balance = DOLLARS;
+	pub AllNonNativeCurrencyIds: Vec<CurrencyId> = vec![CurrencyId::AUSD, CurrencyId::DOT, CurrencyId::XBTC, CurrencyId::LDOT];
+}
+
+pub struct RiskReducingCalls;
+impl module_support::IsRiskReducing<Call> for RiskReducingCalls {
+	fn is_risk_reducing(call: &Call) -> bool {
+		match call {
+			// only deposit collateral and/or payback debit
+			Call::Honzon(module_honzon::Call::adjust_loan(_, collateral_adjustment, debit_adjustment)) => {
+				*collateral_adjustment >= 0
+					&& *debit_adjustment <= 0
+					&& (*collateral_adjustment > 0 || *debit_adjustment < 0)
+			}
+			_ => false,
+		}
+	}
+}
+
+pub struct BasicOperations;
+impl module_support::IsBasicOperation<Call> for BasicOperations {
+	fn is_basic_operation(call: &Call) -> bool {
+		match call {
+			Call::Honzon(module_honzon::Call::adjust_loan(..))
+			| Call::Honzon(module_honzon::Call::authorize(..))
+			| Call::Honzon(module_honzon::Call::unauthorize(..)) => true,
+			_ => false,
+		}
+	}
+}
+
+pub struct SponsoredCalls;
+impl module_support::IsSponsoredCall<Call> for SponsoredCalls {
+	fn is_sponsored_call(call: &Call) -> bool {
+		match call {
+			Call::Honzon(module_honzon::Call::adjust_loan(..))
+			| Call::Honzon(module_honzon::Call::authorize(..))
+			| Call::Honzon(module_honzon::Call::unauthorize(..))
+			| Call::Honzon(module_honzon::Call::unauthorize_all(..)) => true,
+			_ => false,
+		}
+	}
+}
+
+parameter_types! {
+	pub const FreeQuotaCount: u32 = 3;
+	pub const FreeQuotaPeriod: BlockNumber = DAYS;
+	pub const FreeQuotaDeposit: Balance = DOLLARS;
+	pub const SponsorPeriod: BlockNumber = DAYS;
+	pub const MaxSponsoredAccounts: u32 = 100;
 }
 
 impl module_accounts::Trait for Runtime {
+	type Event = Event;
 	type FreeTransferCount = FreeTransferCount;
 	type FreeTransferPeriod = FreeTransferPeriod;
 	type FreeTransferDeposit = FreeTransferDeposit;
 	type Time = Timestamp;
 	type DepositCurrency = Balances;
+	type AllNonNativeCurrencyIds = AllNonNativeCurrencyIds;
+	type MaxSlippageSwapWithDEX = MaxSlippageSwapWithDEX;
+	type DEX = Dex;
+	type RiskReducingCalls = RiskReducingCalls;
+	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, HonzonCouncilInstance>;
+	type FreeQuotaCount = FreeQuotaCount;
+	type FreeQuotaPeriod = FreeQuotaPeriod;
+	type FreeQuotaDeposit = FreeQuotaDeposit;
+	type BasicOperations = BasicOperations;
+	type SponsoredCalls = SponsoredCalls;
+	type SponsorPeriod = SponsorPeriod;
+	type SponsorCurrency = Balances;
+	type MaxSponsoredAccounts = MaxSponsoredAccounts;
 }
 
 impl module_airdrop::Trait for Runtime {
 	type Event = Event;
+	type Vesting = MultiCurrencyVesting;
 }
 
 parameter_types! {
@@ -830,6 +1264,7 @@ parameter_types! {
 	pub MaxClaimFee: Rate = Rate::saturating_from_rational(5, 100);	// 5%
 	pub DefaultExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(10, 100);	// 1 : 10
 	pub ClaimFeeReturnRatio: Ratio = Ratio::saturating_from_rational(98, 100); // 98%
+	pub RewardFeeRatio: Ratio = Ratio::saturating_from_rational(10, 100); // 10%
 }
 
 impl module_staking_pool::Trait for Runtime {
@@ -838,17 +1273,21 @@ impl module_staking_pool::Trait for Runtime {
 	type StakingCurrencyId = GetStakingCurrencyId;
 	type LiquidCurrencyId = GetLiquidCurrencyId;
 	type Nominees = NomineesElection;
-	type OnCommission = ();
+	type OnCommission = HomaTreasury;
 	type Bridge = PolkadotBridge;
 	type MaxBondRatio = MaxBondRatio;
 	type MinBondRatio = MinBondRatio;
 	type MaxClaimFee = MaxClaimFee;
 	type DefaultExchangeRate = DefaultExchangeRate;
 	type ClaimFeeReturnRatio = ClaimFeeReturnRatio;
+	type RewardFeeRatio = RewardFeeRatio;
 }
 
 impl module_homa::Trait for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
 	type Homa = StakingPool;
+	type StakingCurrencyId = GetStakingCurrencyId;
 }
 
 parameter_types! {
@@ -865,6 +1304,7 @@ impl module_nominees_election::Trait for Runtime {
 	type BondingDuration = NomineesElectionBondingDuration;
 	type NominateesCount = NominateesCount;
 	type MaxUnlockingChunks = MaxUnlockingChunks;
+	type UpdateOrigin = pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, HomaCouncilInstance>;
 }
 
 impl module_homa_treasury::Trait for Runtime {
@@ -892,6 +1332,8 @@ construct_runtime!(
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
 		Utility: pallet_utility::{Module, Call, Event},
 		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
+		Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
+		TransactionPause: module_transaction_pause::{Module, Call, Storage, Event},
 		PalletTreasury: pallet_treasury::{Module, Call, Storage, Config, Event<T>},
 		Staking: pallet_staking::{Module, Call, Config<T>, Storage, Event<T>},
 		Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
@@ -910,12 +1352,16 @@ construct_runtime!(
 
 		// oracle
 		Oracle: orml_oracle::{Module, Storage, Call, Config<T>, Event<T>, ValidateUnsigned},
-		// OperatorMembership must be placed after Oracle or else will have race condition on initialization
+		// OracleOperator and OperatorMembership must be placed after Oracle or else will have race condition on initialization,
+		// and OracleOperator must be placed before OperatorMembership to bond operators before initializing feeders
+		OracleOperator: module_oracle_operator::{Module, Call, Storage, Event<T>, Config<T>},
 		OperatorMembership: pallet_membership::<Instance5>::{Module, Call, Storage, Event<T>, Config<T>},
 
 		// acala modules
 		Currencies: orml_currencies::{Module, Call, Event<T>},
-		Prices: module_prices::{Module, Storage, Call, Event},
+		CurrenciesTransfer: module_currencies_transfer::{Module, Call, Event<T>},
+		AssetRegistry: module_asset_registry::{Module, Storage, Call, Event, Config},
+		Prices: module_prices::{Module, Storage, Call, Event<T>},
 		Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
 		Vesting: orml_vesting::{Module, Storage, Call, Event<T>, Config<T>},
 		ScheduleUpdate: orml_schedule_update::{Module, Storage, Call, Event<T>},
@@ -926,14 +1372,20 @@ construct_runtime!(
 		Honzon: module_honzon::{Module, Storage, Call, Event<T>},
 		Dex: module_dex::{Module, Storage, Call, Config, Event<T>},
 		CdpTreasury: module_cdp_treasury::{Module, Storage, Call, Config, Event},
+		SurplusSpending: module_surplus_spending::{Module, Storage, Call, Event<T>},
 		CdpEngine: module_cdp_engine::{Module, Storage, Call, Event<T>, Config, ValidateUnsigned},
 		EmergencyShutdown: module_emergency_shutdown::{Module, Storage, Call, Event<T>},
-		Accounts: module_accounts::{Module, Call, Storage},
+		SystemCollateralization: module_system_collateralization::{Module, Storage, Call, Event},
+		Incentives: module_incentives::{Module, Storage, Call, Event<T>},
+		MultiCurrencyVesting: module_multi_currency_vesting::{Module, Storage, Call, Event<T>},
+		CollateralOnboarding: module_collateral_onboarding::{Module, Storage, Call, Event<T>},
+		Accounts: module_accounts::{Module, Call, Storage, Event<T>},
 		AirDrop: module_airdrop::{Module, Call, Storage, Event<T>, Config<T>},
-		Homa: module_homa::{Module, Call},
+		Homa: module_homa::{Module, Call, Storage, Event<T>},
 		NomineesElection: module_nominees_election::{Module, Call, Storage},
 		StakingPool: module_staking_pool::{Module, Call, Storage, Event<T>},
 		PolkadotBridge: module_polkadot_bridge::{Module, Call, Storage, Event<T>, Config},
+		RenVmBridge: module_renvm_bridge::{Module, Call, Storage, Event<T>, ValidateUnsigned},
 		HomaTreasury: module_homa_treasury::{Module},
 	}
 );
@@ -955,6 +1407,7 @@ pub type SignedExtra = (
 	system::CheckGenesis<Runtime>,
 	system::CheckEra<Runtime>,
 	system::CheckNonce<Runtime>,
+	module_transaction_pause::CheckPausedTransaction<Runtime>,
 	system::CheckWeight<Runtime>,
 	module_accounts::ChargeTransactionPayment<Runtime>,
 );
@@ -967,6 +1420,107 @@ pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExt
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Runtime, AllModules>;
 
+/// Check the accounting invariants of Honzon modules, e.g. on a state snapshot or in tests.
+pub fn check_honzon_invariants() -> Result<(), &'static str> {
+	Loans::check_invariants()?;
+	CdpTreasury::check_invariants()?;
+	AuctionManager::check_invariants()?;
+	CdpEngine::check_invariants()?;
+
+	// the debit value of loans and the debit pool of treasury are all issued as stable currency,
+	// rounding leaves at most dust behind. Refunds after shutdown burn the stable currency only.
+	if !EmergencyShutdown::is_shutdown() {
+		let total_debit_value =
+			CollateralCurrencyIds::get()
+				.into_iter()
+				.fold(CdpTreasury::debit_pool(), |total, currency_id| {
+					total.saturating_add(CdpEngine::get_debit_value(
+						currency_id,
+						Loans::total_debits(currency_id),
+					))
+				});
+		let issuance =
+			<Currencies as orml_traits::MultiCurrency<AccountId>>::total_issuance(GetStableCurrencyId::get());
+		if total_debit_value > issuance.saturating_add(CENTS) {
+			return Err("loans and debit pool not reconciled with stable currency issuance");
+		}
+	}
+
+	Ok(())
+}
+
+/// The position info of `who` under `currency_id` evaluated at current price.
+fn position_info(
+	who: &AccountId,
+	currency_id: CurrencyId,
+) -> module_honzon_rpc_runtime_api::PositionInfo<CurrencyId, Balance> {
+	let collateral = Loans::collaterals(who, currency_id);
+	let debit = Loans::debits(currency_id, who);
+	let collateral_ratio = if debit.is_zero() {
+		None
+	} else {
+		<Prices as module_support::PriceProvider<CurrencyId>>::get_relative_price(
+			currency_id,
+			GetStableCurrencyId::get(),
+		)
+		.map(|price| CdpEngine::calculate_collateral_ratio(currency_id, collateral, debit, price))
+	};
+
+	module_honzon_rpc_runtime_api::PositionInfo {
+		currency_id,
+		collateral,
+		debit,
+		debit_value: CdpEngine::get_debit_value(currency_id, debit),
+		collateral_ratio,
+		liquidation_ratio: CdpEngine::get_liquidation_ratio(currency_id),
+		required_collateral_ratio: CdpEngine::required_collateral_ratio(currency_id),
+		liquidation_price: CdpEngine::calculate_liquidation_price(currency_id, collateral, debit),
+	}
+}
+
+/// The aggregate statistics of positions under `currency_id` evaluated at current price.
+fn collateral_statistics(
+	currency_id: CurrencyId,
+) -> module_honzon_rpc_runtime_api::CollateralStatistics<CurrencyId, Balance> {
+	let price = <Prices as module_support::PriceProvider<CurrencyId>>::get_relative_price(
+		currency_id,
+		GetStableCurrencyId::get(),
+	);
+	let total_collateral = Loans::total_collaterals(currency_id);
+	let total_locked_value = price.map(|price| price.saturating_mul_int(total_collateral));
+	let total_debit_value = CdpEngine::get_debit_value(currency_id, Loans::total_debits(currency_id));
+	let maximum_total_debit_value = CdpEngine::maximum_total_debit_value(currency_id);
+	let required_collateral_ratio = CdpEngine::required_collateral_ratio(currency_id);
+
+	let (mut positions, mut positions_at_risk) = (0u32, 0u32);
+	for (who, debit) in module_loans::Debits::<Runtime>::iter_prefix(currency_id) {
+		if debit.is_zero() {
+			continue;
+		}
+		positions = positions.saturating_add(1);
+
+		if let (Some(price), Some(required_ratio)) = (price, required_collateral_ratio) {
+			let collateral = Loans::collaterals(&who, currency_id);
+			if CdpEngine::calculate_collateral_ratio(currency_id, collateral, debit, price) < required_ratio {
+				positions_at_risk = positions_at_risk.saturating_add(1);
+			}
+		}
+	}
+
+	module_honzon_rpc_runtime_api::CollateralStatistics {
+		currency_id,
+		total_collateral,
+		total_locked_value,
+		total_debit_value,
+		maximum_total_debit_value,
+		utilization: Ratio::checked_from_rational(total_debit_value, maximum_total_debit_value),
+		average_collateral_ratio: total_locked_value
+			.and_then(|locked_value| Ratio::checked_from_rational(locked_value, total_debit_value)),
+		positions,
+		positions_at_risk,
+	}
+}
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -1145,6 +1699,81 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl module_emergency_shutdown_rpc_runtime_api::EmergencyShutdownApi<Block> for Runtime {
+		fn solvency_report() -> Option<module_emergency_shutdown_rpc_runtime_api::SolvencyReport> {
+			EmergencyShutdown::solvency_report()
+		}
+	}
+
+	impl module_prices_rpc_runtime_api::PricesApi<
+		Block,
+		CurrencyId,
+		BlockNumber,
+	> for Runtime {
+		fn get_price(currency_id: CurrencyId) -> Option<module_prices_rpc_runtime_api::PriceInfo<BlockNumber>> {
+			Prices::get_price_and_source(currency_id).map(|(price, source)| {
+				module_prices_rpc_runtime_api::PriceInfo {
+					price,
+					source,
+					last_updated_at: match source {
+						module_prices::PriceSource::Oracle => Some(Prices::last_feed_updated_at(currency_id)),
+						_ => None,
+					},
+				}
+			})
+		}
+
+		fn get_relative_price(base_currency_id: CurrencyId, quote_currency_id: CurrencyId) -> Option<Price> {
+			<Prices as module_support::PriceProvider<CurrencyId>>::get_relative_price(base_currency_id, quote_currency_id)
+		}
+	}
+
+	impl module_honzon_rpc_runtime_api::HonzonApi<
+		Block,
+		AccountId,
+		CurrencyId,
+		Balance,
+	> for Runtime {
+		fn get_positions(who: AccountId) -> Vec<module_honzon_rpc_runtime_api::PositionInfo<CurrencyId, Balance>> {
+			CollateralCurrencyIds::get()
+				.into_iter()
+				.filter(|currency_id| {
+					!Loans::collaterals(&who, *currency_id).is_zero() || !Loans::debits(*currency_id, &who).is_zero()
+				})
+				.map(|currency_id| position_info(&who, currency_id))
+				.collect()
+		}
+
+		fn get_positions_page(
+			currency_id: CurrencyId,
+			start: Option<AccountId>,
+			limit: u32,
+		) -> module_honzon_rpc_runtime_api::PositionsPage<AccountId, module_honzon_rpc_runtime_api::PositionInfo<CurrencyId, Balance>> {
+			let (positions, next) = Loans::positions_page(currency_id, start, limit);
+			module_honzon_rpc_runtime_api::PositionsPage {
+				positions: positions
+					.into_iter()
+					.map(|(who, _, _)| {
+						let position = position_info(&who, currency_id);
+						(who, position)
+					})
+					.collect(),
+				next,
+			}
+		}
+
+		fn get_collateral_statistics() -> Vec<module_honzon_rpc_runtime_api::CollateralStatistics<CurrencyId, Balance>> {
+			CollateralCurrencyIds::get()
+				.into_iter()
+				.map(collateral_statistics)
+				.collect()
+		}
+
+		fn check_invariants() -> Result<(), Vec<u8>> {
+			check_honzon_invariants().map_err(|e| e.as_bytes().to_vec())
+		}
+	}
+
 	impl module_staking_pool_rpc_runtime_api::StakingPoolApi<
 		Block,
 		AccountId,
@@ -4,13 +4,13 @@ mod tests {
 	use acala_runtime::{AccountId, Balance, CurrencyId, Runtime};
 	use frame_support::{
 		assert_noop, assert_ok,
-		traits::{OnFinalize, OnInitialize},
+		traits::{Get, OnFinalize, OnInitialize},
 	};
 	use module_cdp_engine::LiquidationStrategy;
 	use module_support::CDPTreasury;
-	use module_support::{Price, Rate, Ratio, RiskManager};
+	use module_support::{Price, PriceProvider, Rate, Ratio, RiskManager};
 	use orml_traits::{Change, MultiCurrency};
-	use sp_runtime::{DispatchResult, FixedPointNumber};
+	use sp_runtime::{traits::BadOrigin, DispatchResult, FixedPointNumber};
 
 	const ORACLE1: [u8; 32] = [0u8; 32];
 	const ORACLE2: [u8; 32] = [1u8; 32];
@@ -28,6 +28,10 @@ mod tests {
 	pub type EmergencyShutdownModule = module_emergency_shutdown::Module<Runtime>;
 	pub type AuctionManagerModule = module_auction_manager::Module<Runtime>;
 	pub type Currencies = orml_currencies::Module<Runtime>;
+	pub type PricesModule = module_prices::Module<Runtime>;
+	pub type OperatorMembershipModule = pallet_membership::Module<Runtime, pallet_membership::Instance5>;
+	pub type OracleOperatorModule = module_oracle_operator::Module<Runtime>;
+	pub type TransactionPauseModule = module_transaction_pause::Module<Runtime>;
 
 	pub struct ExtBuilder {
 		endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
@@ -52,8 +56,33 @@ mod tests {
 				.build_storage::<Runtime>()
 				.unwrap();
 
+			let native_currency_id = acala_runtime::GetNativeCurrencyId::get();
+			let operator_bond = acala_runtime::MinOperatorBond::get();
+			let operators = vec![
+				AccountId::from(ORACLE1),
+				AccountId::from(ORACLE2),
+				AccountId::from(ORACLE3),
+			];
+
+			pallet_balances::GenesisConfig::<Runtime> {
+				balances: self
+					.endowed_accounts
+					.clone()
+					.into_iter()
+					.filter(|(_, currency_id, _)| *currency_id == native_currency_id)
+					.map(|(account_id, _, initial_balance)| (account_id, initial_balance))
+					.chain(operators.iter().cloned().map(|operator| (operator, operator_bond)))
+					.collect::<Vec<_>>(),
+			}
+			.assimilate_storage(&mut t)
+			.unwrap();
+
 			orml_tokens::GenesisConfig::<Runtime> {
-				endowed_accounts: self.endowed_accounts,
+				endowed_accounts: self
+					.endowed_accounts
+					.into_iter()
+					.filter(|(_, currency_id, _)| *currency_id != native_currency_id)
+					.collect::<Vec<_>>(),
 			}
 			.assimilate_storage(&mut t)
 			.unwrap();
@@ -64,12 +93,18 @@ mod tests {
 			.assimilate_storage(&mut t)
 			.unwrap();
 
+			module_oracle_operator::GenesisConfig::<Runtime> {
+				bonds: operators
+					.iter()
+					.cloned()
+					.map(|operator| (operator, operator_bond))
+					.collect::<Vec<_>>(),
+			}
+			.assimilate_storage(&mut t)
+			.unwrap();
+
 			pallet_membership::GenesisConfig::<Runtime, pallet_membership::Instance5> {
-				members: vec![
-					AccountId::from(ORACLE1),
-					AccountId::from(ORACLE2),
-					AccountId::from(ORACLE3),
-				],
+				members: operators,
 				phantom: Default::default(),
 			}
 			.assimilate_storage(&mut t)
@@ -185,6 +220,8 @@ mod tests {
 				(AccountId::from(BOB), CurrencyId::AUSD, amount(1_000_000)),
 				(AccountId::from(ALICE), CurrencyId::XBTC, amount(10)),
 				(AccountId::from(BOB), CurrencyId::XBTC, amount(101)),
+				(AccountId::from(ALICE), CurrencyId::ACA, amount(1)),
+				(AccountId::from(BOB), CurrencyId::ACA, amount(1)),
 			])
 			.build()
 			.execute_with(|| {
@@ -297,9 +334,243 @@ mod tests {
 				assert_eq!(LoansModule::collaterals(AccountId::from(BOB), CurrencyId::XBTC), 0);
 				assert_eq!(CdpTreasuryModule::debit_pool(), amount(55_000));
 				assert!(CdpTreasuryModule::surplus_pool() >= amount(5_000));
+				assert_ok!(acala_runtime::check_honzon_invariants());
+			});
+	}
+
+	#[test]
+	fn liquidate_ldot_cdp() {
+		ExtBuilder::default()
+			.balances(vec![
+				(AccountId::from(BOB), CurrencyId::AUSD, amount(1_000_000)),
+				(AccountId::from(ALICE), CurrencyId::LDOT, amount(1_000)),
+				(AccountId::from(BOB), CurrencyId::LDOT, amount(10_100)),
+				(AccountId::from(ALICE), CurrencyId::ACA, amount(1)),
+				(AccountId::from(BOB), CurrencyId::ACA, amount(1)),
+			])
+			.build()
+			.execute_with(|| {
+				SystemModule::set_block_number(1);
+				// LDOT is priced by DOT price and the liquid exchange rate (default 1 : 10)
+				assert_ok!(set_oracle_price(vec![(
+					CurrencyId::DOT,
+					Price::saturating_from_rational(100, 1)
+				)])); // 100 usd
+				assert_eq!(
+					PricesModule::get_price(CurrencyId::LDOT),
+					Some(Price::saturating_from_rational(10, 1))
+				);
+
+				assert_ok!(DexModule::add_liquidity(
+					origin_of(AccountId::from(BOB)),
+					CurrencyId::LDOT,
+					amount(10_000),
+					amount(100_000)
+				));
+
+				assert_ok!(CdpEngineModule::set_collateral_params(
+					<acala_runtime::Runtime as frame_system::Trait>::Origin::ROOT,
+					CurrencyId::LDOT,
+					Change::NewValue(Some(Rate::saturating_from_integer(0))),
+					Change::NewValue(Some(Ratio::saturating_from_rational(200, 100))),
+					Change::NewValue(Some(Rate::saturating_from_rational(20, 100))),
+					Change::NewValue(Some(Ratio::saturating_from_rational(200, 100))),
+					Change::NewValue(amount(1000000)),
+				));
+
+				assert_ok!(CdpEngineModule::adjust_position(
+					&AccountId::from(ALICE),
+					CurrencyId::LDOT,
+					amount(1_000) as i128,
+					amount(50_000) as i128
+				));
+
+				assert_ok!(CdpEngineModule::adjust_position(
+					&AccountId::from(BOB),
+					CurrencyId::LDOT,
+					amount(100) as i128,
+					amount(5_000) as i128
+				));
+
+				assert_eq!(
+					LoansModule::debits(CurrencyId::LDOT, AccountId::from(ALICE)),
+					amount(50_000)
+				);
+				assert_eq!(
+					LoansModule::collaterals(AccountId::from(ALICE), CurrencyId::LDOT),
+					amount(1_000)
+				);
+				assert_eq!(CdpTreasuryModule::debit_pool(), 0);
+				assert_eq!(AuctionManagerModule::collateral_auctions(0), None);
+
+				assert_ok!(CdpEngineModule::set_collateral_params(
+					<acala_runtime::Runtime as frame_system::Trait>::Origin::ROOT,
+					CurrencyId::LDOT,
+					Change::NoChange,
+					Change::NewValue(Some(Ratio::saturating_from_rational(400, 100))),
+					Change::NoChange,
+					Change::NewValue(Some(Ratio::saturating_from_rational(400, 100))),
+					Change::NoChange,
+				));
+
+				// too large to swap with DEX, liquidated by collateral auction
+				assert_ok!(CdpEngineModule::liquidate_unsafe_cdp(
+					AccountId::from(ALICE),
+					CurrencyId::LDOT
+				));
+
+				let liquidate_alice_ldot_cdp_event =
+					acala_runtime::Event::module_cdp_engine(module_cdp_engine::RawEvent::LiquidateUnsafeCDP(
+						CurrencyId::LDOT,
+						AccountId::from(ALICE),
+						amount(1_000),
+						amount(5_000),
+						LiquidationStrategy::Auction,
+					));
+				assert!(SystemModule::events()
+					.iter()
+					.any(|record| record.event == liquidate_alice_ldot_cdp_event));
+
+				assert_eq!(LoansModule::debits(CurrencyId::LDOT, AccountId::from(ALICE)), 0);
+				assert_eq!(LoansModule::collaterals(AccountId::from(ALICE), CurrencyId::LDOT), 0);
+				assert_eq!(AuctionManagerModule::collateral_auctions(0).is_some(), true);
+				assert_eq!(CdpTreasuryModule::debit_pool(), amount(5_000));
+
+				// liquidated by swap with DEX
+				assert_ok!(CdpEngineModule::liquidate_unsafe_cdp(
+					AccountId::from(BOB),
+					CurrencyId::LDOT
+				));
+
+				let liquidate_bob_ldot_cdp_event =
+					acala_runtime::Event::module_cdp_engine(module_cdp_engine::RawEvent::LiquidateUnsafeCDP(
+						CurrencyId::LDOT,
+						AccountId::from(BOB),
+						amount(100),
+						amount(500),
+						LiquidationStrategy::Exchange,
+					));
+				assert!(SystemModule::events()
+					.iter()
+					.any(|record| record.event == liquidate_bob_ldot_cdp_event));
+
+				assert_eq!(LoansModule::debits(CurrencyId::LDOT, AccountId::from(BOB)), 0);
+				assert_eq!(LoansModule::collaterals(AccountId::from(BOB), CurrencyId::LDOT), 0);
+				assert_eq!(CdpTreasuryModule::debit_pool(), amount(5_500));
+				assert!(CdpTreasuryModule::surplus_pool() >= amount(500));
 			});
 	}
 
+	#[test]
+	fn feed_prices_of_multiple_currencies_in_one_submission() {
+		ExtBuilder::default().build().execute_with(|| {
+			SystemModule::set_block_number(1);
+			assert_eq!(PricesModule::get_price(CurrencyId::DOT), None);
+			assert_eq!(PricesModule::get_price(CurrencyId::XBTC), None);
+
+			// each operator submits the prices of all collaterals with a single signed payload
+			assert_ok!(set_oracle_price(vec![
+				(CurrencyId::DOT, Price::saturating_from_rational(100, 1)),
+				(CurrencyId::XBTC, Price::saturating_from_rational(10_000, 1)),
+			]));
+			assert_eq!(
+				PricesModule::get_price(CurrencyId::DOT),
+				Some(Price::saturating_from_rational(100, 1))
+			);
+			assert_eq!(
+				PricesModule::get_price(CurrencyId::XBTC),
+				Some(Price::saturating_from_rational(10_000, 1))
+			);
+			assert_eq!(
+				PricesModule::get_price(CurrencyId::LDOT),
+				Some(Price::saturating_from_rational(10, 1))
+			);
+		});
+	}
+
+	#[test]
+	fn oracle_operators_managed_by_general_council() {
+		ExtBuilder::default()
+			.balances(vec![
+				(AccountId::from(ALICE), CurrencyId::ACA, amount(10_000)),
+				(AccountId::from(BOB), CurrencyId::ACA, amount(10_000)),
+			])
+			.build()
+			.execute_with(|| {
+				SystemModule::set_block_number(1);
+				let council_origin: acala_runtime::Origin =
+					pallet_collective::RawOrigin::<AccountId, pallet_collective::Instance1>::Members(1, 1).into();
+
+				assert!(OracleModule::members().contains(&AccountId::from(ORACLE3)));
+				assert_noop!(
+					OperatorMembershipModule::add_member(origin_of(AccountId::from(ALICE)), AccountId::from(ALICE)),
+					BadOrigin,
+				);
+
+				// add a new operator, who feeds after bonded
+				assert_ok!(OperatorMembershipModule::add_member(
+					council_origin.clone(),
+					AccountId::from(ALICE)
+				));
+				assert!(!OracleModule::members().contains(&AccountId::from(ALICE)));
+				assert_ok!(OracleOperatorModule::bond(
+					origin_of(AccountId::from(ALICE)),
+					acala_runtime::MinOperatorBond::get()
+				));
+				assert!(OracleModule::members().contains(&AccountId::from(ALICE)));
+				let member_added_event =
+					acala_runtime::Event::pallet_membership_Instance5(pallet_membership::RawEvent::MemberAdded);
+				assert!(SystemModule::events()
+					.iter()
+					.any(|record| record.event == member_added_event));
+
+				// rotate the account of an operator
+				assert_ok!(OracleOperatorModule::bond(
+					origin_of(AccountId::from(BOB)),
+					acala_runtime::MinOperatorBond::get()
+				));
+				assert_ok!(OperatorMembershipModule::swap_member(
+					council_origin.clone(),
+					AccountId::from(ORACLE3),
+					AccountId::from(BOB)
+				));
+				assert!(!OracleModule::members().contains(&AccountId::from(ORACLE3)));
+				assert!(OracleModule::members().contains(&AccountId::from(BOB)));
+
+				// remove an operator
+				assert_ok!(OperatorMembershipModule::remove_member(
+					council_origin,
+					AccountId::from(ALICE)
+				));
+				assert!(!OracleModule::members().contains(&AccountId::from(ALICE)));
+			});
+	}
+
+	#[test]
+	fn oracle_operator_slashed_by_general_council() {
+		ExtBuilder::default().build().execute_with(|| {
+			let council_origin: acala_runtime::Origin =
+				pallet_collective::RawOrigin::<AccountId, pallet_collective::Instance1>::Members(1, 1).into();
+			let operator_bond = acala_runtime::MinOperatorBond::get();
+
+			assert!(OracleModule::members().contains(&AccountId::from(ORACLE1)));
+			assert_noop!(
+				OracleOperatorModule::slash(origin_of(AccountId::from(ALICE)), AccountId::from(ORACLE1), 1),
+				BadOrigin,
+			);
+			assert_ok!(OracleOperatorModule::slash(
+				council_origin,
+				AccountId::from(ORACLE1),
+				operator_bond / 2
+			));
+			assert_eq!(
+				OracleOperatorModule::bonded(AccountId::from(ORACLE1)),
+				operator_bond - operator_bond / 2
+			);
+			assert!(!OracleModule::members().contains(&AccountId::from(ORACLE1)));
+		});
+	}
+
 	#[test]
 	fn test_dex_module() {
 		ExtBuilder::default()
@@ -396,7 +667,10 @@ mod tests {
 	#[test]
 	fn test_honzon_module() {
 		ExtBuilder::default()
-			.balances(vec![(AccountId::from(ALICE), CurrencyId::XBTC, amount(1_000))])
+			.balances(vec![
+				(AccountId::from(ALICE), CurrencyId::XBTC, amount(1_000)),
+				(AccountId::from(ALICE), CurrencyId::ACA, amount(1)),
+			])
 			.build()
 			.execute_with(|| {
 				assert_ok!(set_oracle_price(vec![(
@@ -478,6 +752,7 @@ mod tests {
 			.balances(vec![
 				(AccountId::from(ALICE), CurrencyId::AUSD, amount(1000)),
 				(AccountId::from(ALICE), CurrencyId::XBTC, amount(1000)),
+				(AccountId::from(ALICE), CurrencyId::ACA, amount(1)),
 			])
 			.build()
 			.execute_with(|| {
@@ -586,4 +861,51 @@ mod tests {
 				);
 			});
 	}
+
+	#[test]
+	fn proxy_type_filter_calls() {
+		use acala_runtime::{Call, InstanceFilter, ProxyType};
+
+		let adjust_loan = Call::Honzon(module_honzon::Call::adjust_loan(CurrencyId::XBTC, 100, 100));
+		let authorize = Call::Honzon(module_honzon::Call::authorize(CurrencyId::XBTC, AccountId::from(BOB)));
+		let swap = Call::Dex(module_dex::Call::swap_currency(
+			CurrencyId::XBTC,
+			100,
+			CurrencyId::AUSD,
+			0,
+		));
+		let bid = Call::Auction(orml_auction::Call::bid(0, 100));
+
+		for call in &[&adjust_loan, &authorize, &swap, &bid] {
+			assert!(ProxyType::Any.filter(call));
+		}
+		assert!(ProxyType::Loan.filter(&adjust_loan));
+		assert!(!ProxyType::Loan.filter(&authorize));
+		assert!(!ProxyType::Loan.filter(&swap));
+		assert!(ProxyType::Swap.filter(&swap));
+		assert!(!ProxyType::Swap.filter(&adjust_loan));
+		assert!(!ProxyType::Swap.filter(&bid));
+		assert!(ProxyType::Auction.filter(&bid));
+		assert!(!ProxyType::Auction.filter(&swap));
+	}
+
+	#[test]
+	fn inherent_calls_cannot_be_paused() {
+		ExtBuilder::default().build().execute_with(|| {
+			for pallet_name in &[&b"Timestamp"[..], b"Babe", b"Grandpa", b"Session"] {
+				assert_noop!(
+					TransactionPauseModule::pause_transaction(
+						<Runtime as frame_system::Trait>::Origin::root(),
+						pallet_name.to_vec(),
+						b"set".to_vec()
+					),
+					module_transaction_pause::Error::<Runtime>::CannotPause
+				);
+			}
+			assert_eq!(
+				TransactionPauseModule::paused_transactions((b"Timestamp".to_vec(), b"set".to_vec())),
+				false
+			);
+		});
+	}
 }
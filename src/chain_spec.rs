@@ -2,10 +2,11 @@
 
 use hex_literal::hex;
 use runtime::{
-	opaque::SessionKeys, AccountId, AirDropConfig, AirDropCurrencyId, BabeConfig, Balance, BalancesConfig, Block,
-	CdpEngineConfig, CdpTreasuryConfig, CurrencyId, DexConfig, GeneralCouncilMembershipConfig, GenesisConfig,
-	GrandpaConfig, HomaCouncilMembershipConfig, HonzonCouncilMembershipConfig, IndicesConfig, OperatorMembershipConfig,
-	OracleConfig, OracleId, PolkadotBridgeConfig, SessionConfig, Signature, StakerStatus, StakingConfig, SudoConfig,
+	opaque::SessionKeys, AccountId, AirDropConfig, AirDropCurrencyId, AssetClass, AssetMetadata, AssetRegistryConfig,
+	BabeConfig, Balance, BalancesConfig, Block, CdpEngineConfig, CdpTreasuryConfig, CurrencyId, DexConfig,
+	GeneralCouncilMembershipConfig, GenesisConfig, GrandpaConfig, HomaCouncilMembershipConfig,
+	HonzonCouncilMembershipConfig, IndicesConfig, OperatorMembershipConfig, OracleConfig, OracleId,
+	OracleOperatorConfig, PolkadotBridgeConfig, SessionConfig, Signature, StakerStatus, StakingConfig, SudoConfig,
 	SystemConfig, TechnicalCouncilMembershipConfig, TokensConfig, VestingConfig, CENTS, DOLLARS, WASM_BINARY,
 };
 use sc_chain_spec::ChainSpecExtension;
@@ -232,6 +233,7 @@ pub fn latest_mandala_testnet_config() -> ChainSpec {
 
 const INITIAL_BALANCE: u128 = 1_000_000 * DOLLARS;
 const INITIAL_STAKING: u128 = 100_000 * DOLLARS;
+const INITIAL_OPERATOR_BOND: u128 = 1_000 * DOLLARS;
 
 fn testnet_genesis(
 	initial_authorities: Vec<(AccountId, AccountId, GrandpaId, BabeId)>,
@@ -293,7 +295,7 @@ fn testnet_genesis(
 			phantom: Default::default(),
 		}),
 		pallet_membership_Instance5: Some(OperatorMembershipConfig {
-			members: vec![root_key],
+			members: vec![root_key.clone()],
 			phantom: Default::default(),
 		}),
 		pallet_treasury: Some(Default::default()),
@@ -358,6 +360,9 @@ fn testnet_genesis(
 		module_polkadot_bridge: Some(PolkadotBridgeConfig {
 			mock_reward_rate: FixedU128::saturating_from_rational(1, 100_000_000),
 		}),
+		module_asset_registry: Some(AssetRegistryConfig {
+			assets: vec![ren_btc_metadata()],
+		}),
 		module_airdrop: Some(AirDropConfig {
 			airdrop_accounts: vec![],
 		}),
@@ -365,6 +370,9 @@ fn testnet_genesis(
 			members: Default::default(), // initialized by OperatorMembership
 			session_keys: oracle_session_keys,
 		}),
+		module_oracle_operator: Some(OracleOperatorConfig {
+			bonds: vec![(root_key, INITIAL_OPERATOR_BOND)],
+		}),
 	}
 }
 
@@ -497,6 +505,9 @@ fn mandala_genesis(
 		module_polkadot_bridge: Some(PolkadotBridgeConfig {
 			mock_reward_rate: FixedU128::saturating_from_rational(5, 10000), // 20% APR
 		}),
+		module_asset_registry: Some(AssetRegistryConfig {
+			assets: vec![ren_btc_metadata()],
+		}),
 		module_airdrop: Some(AirDropConfig {
 			airdrop_accounts: {
 				let airdrop_accounts_json = &include_bytes!("../resources/mandala-airdrop-accounts.json")[..];
@@ -509,5 +520,23 @@ fn mandala_genesis(
 			members: Default::default(), // initialized by OperatorMembership
 			session_keys: oracle_session_keys,
 		}),
+		module_oracle_operator: Some(OracleOperatorConfig {
+			bonds: endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (k, INITIAL_OPERATOR_BOND))
+				.collect(),
+		}),
+	}
+}
+
+/// The metadata of renBTC, it must be the first asset registered in genesis
+/// as the runtime uses `CurrencyId::Registered(0)` for it.
+fn ren_btc_metadata() -> AssetMetadata {
+	AssetMetadata {
+		symbol: b"renBTC".to_vec(),
+		decimals: 8,
+		existential_deposit: 1_000,
+		class: AssetClass::Bridged,
 	}
 }
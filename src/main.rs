@@ -18,6 +18,7 @@ mod chain_spec;
 mod cli;
 mod command;
 mod executor;
+mod metrics;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
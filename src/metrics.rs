@@ -0,0 +1,274 @@
+//! Prometheus metrics of Honzon activity.
+//!
+//! The metrics are collected from the events of every new best block imported, and the status
+//! the offchain workers record in the offchain storage. Blocks retracted by reorgs are not
+//! subtracted, so the counters are approximate around reorgs.
+
+use std::{collections::HashMap, sync::Arc};
+
+use codec::Decode;
+use futures::prelude::*;
+use log::warn;
+use module_auction_manager::{AuctionType, RawEvent as AuctionManagerEvent};
+use module_cdp_engine::RawEvent as CDPEngineEvent;
+use prometheus_endpoint::{register, CounterVec, GaugeVec, Opts, PrometheusError, Registry, F64, U64};
+use runtime::{opaque::Block, BlockNumber, CurrencyId, Event, Hash, DOLLARS};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sp_core::{offchain::OffchainStorage, storage::StorageKey, twox_128};
+use sp_runtime::generic::BlockId;
+use utilities::OffchainWorkerStatus;
+
+type EventRecord = frame_system::EventRecord<Event, Hash>;
+
+/// The metrics of Honzon activity.
+#[derive(Clone)]
+pub struct HonzonMetrics {
+	liquidations: CounterVec<U64>,
+	settlements: CounterVec<U64>,
+	auctions_opened: CounterVec<U64>,
+	auctions_closed: CounterVec<U64>,
+	offchain_worker_runs: CounterVec<U64>,
+	offchain_worker_scanned: CounterVec<U64>,
+	offchain_worker_submitted: CounterVec<U64>,
+	offchain_worker_submission_failures: CounterVec<U64>,
+	stability_fee_accrued: GaugeVec<F64>,
+	stability_fee_accrued_total: CounterVec<F64>,
+}
+
+impl HonzonMetrics {
+	/// Create the metrics and register them in `registry`.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(HonzonMetrics {
+			liquidations: register(
+				CounterVec::new(
+					Opts::new("honzon_liquidations_total", "Number of unsafe CDPs liquidated"),
+					&["currency", "strategy"],
+				)?,
+				registry,
+			)?,
+			settlements: register(
+				CounterVec::new(
+					Opts::new(
+						"honzon_settlements_total",
+						"Number of CDPs settled after emergency shutdown",
+					),
+					&["currency"],
+				)?,
+				registry,
+			)?,
+			auctions_opened: register(
+				CounterVec::new(
+					Opts::new("honzon_auctions_opened_total", "Number of auctions opened"),
+					&["type"],
+				)?,
+				registry,
+			)?,
+			auctions_closed: register(
+				CounterVec::new(
+					Opts::new("honzon_auctions_closed_total", "Number of auctions closed"),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
+			offchain_worker_runs: register(
+				CounterVec::new(
+					Opts::new("honzon_offchain_worker_runs_total", "Number of offchain worker runs"),
+					&["worker", "outcome"],
+				)?,
+				registry,
+			)?,
+			offchain_worker_scanned: register(
+				CounterVec::new(
+					Opts::new(
+						"honzon_offchain_worker_scanned_total",
+						"Number of items scanned by offchain workers",
+					),
+					&["worker"],
+				)?,
+				registry,
+			)?,
+			offchain_worker_submitted: register(
+				CounterVec::new(
+					Opts::new(
+						"honzon_offchain_worker_submitted_total",
+						"Number of transactions submitted by offchain workers",
+					),
+					&["worker"],
+				)?,
+				registry,
+			)?,
+			offchain_worker_submission_failures: register(
+				CounterVec::new(
+					Opts::new(
+						"honzon_offchain_worker_submission_failures_total",
+						"Number of transactions offchain workers failed to submit",
+					),
+					&["worker"],
+				)?,
+				registry,
+			)?,
+			stability_fee_accrued: register(
+				GaugeVec::new(
+					Opts::new(
+						"honzon_stability_fee_accrued",
+						"Stability fee accrued in the last best block, in stable currency units",
+					),
+					&["currency"],
+				)?,
+				registry,
+			)?,
+			stability_fee_accrued_total: register(
+				CounterVec::new(
+					Opts::new(
+						"honzon_stability_fee_accrued_total",
+						"Total stability fee accrued, in stable currency units",
+					),
+					&["currency"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	fn observe_events(&self, events: Vec<EventRecord>) {
+		self.stability_fee_accrued.reset();
+		for record in events {
+			match record.event {
+				Event::module_cdp_engine(CDPEngineEvent::LiquidateUnsafeCDP(currency_id, _, _, _, strategy)) => self
+					.liquidations
+					.with_label_values(&[&format!("{:?}", currency_id), &format!("{:?}", strategy)])
+					.inc(),
+				Event::module_cdp_engine(CDPEngineEvent::SettleCDPInDebit(currency_id, _)) => self
+					.settlements
+					.with_label_values(&[&format!("{:?}", currency_id)])
+					.inc(),
+				Event::module_cdp_engine(CDPEngineEvent::StabilityFeeAccrued(currency_id, amount)) => {
+					let label = format!("{:?}", currency_id);
+					let amount = amount as f64 / DOLLARS as f64;
+					self.stability_fee_accrued.with_label_values(&[&label]).add(amount);
+					self.stability_fee_accrued_total
+						.with_label_values(&[&label])
+						.inc_by(amount);
+				}
+				Event::module_auction_manager(event) => match event {
+					AuctionManagerEvent::NewCollateralAuction(..) => {
+						self.auctions_opened.with_label_values(&["collateral"]).inc()
+					}
+					AuctionManagerEvent::NewDebitAuction(..) => {
+						self.auctions_opened.with_label_values(&["debit"]).inc()
+					}
+					AuctionManagerEvent::NewSurplusAuction(..) => {
+						self.auctions_opened.with_label_values(&["surplus"]).inc()
+					}
+					AuctionManagerEvent::CollateralAuctionDealed(..) => {
+						self.auctions_closed.with_label_values(&["collateral_dealt"]).inc()
+					}
+					AuctionManagerEvent::SurplusAuctionDealed(..) => {
+						self.auctions_closed.with_label_values(&["surplus_dealt"]).inc()
+					}
+					AuctionManagerEvent::DebitAuctionDealed(..) => {
+						self.auctions_closed.with_label_values(&["debit_dealt"]).inc()
+					}
+					AuctionManagerEvent::DEXTakeCollateralAuction(..) => {
+						self.auctions_closed.with_label_values(&["dex_taken"]).inc()
+					}
+					AuctionManagerEvent::CancelAuction(..) => {
+						self.auctions_closed.with_label_values(&["cancelled"]).inc()
+					}
+				},
+				_ => {}
+			}
+		}
+	}
+
+	fn observe_worker_status<Scope>(&self, worker: &str, status: &OffchainWorkerStatus<BlockNumber, Scope>) {
+		let outcome = if status.error.is_some() { "error" } else { "completed" };
+		self.offchain_worker_runs.with_label_values(&[worker, outcome]).inc();
+		self.offchain_worker_scanned
+			.with_label_values(&[worker])
+			.inc_by(status.scanned.into());
+		self.offchain_worker_submitted
+			.with_label_values(&[worker])
+			.inc_by(status.submitted.into());
+		self.offchain_worker_submission_failures
+			.with_label_values(&[worker])
+			.inc_by(status.failed.into());
+	}
+}
+
+/// The storage key of `System::Events`.
+fn events_key() -> StorageKey {
+	StorageKey([twox_128(b"System"), twox_128(b"Events")].concat())
+}
+
+/// The block numbers of the last offchain worker runs observed, each run is counted once.
+#[derive(Default)]
+struct ObservedRuns(HashMap<&'static str, BlockNumber>);
+
+impl ObservedRuns {
+	fn observe<S: OffchainStorage, Scope: Decode>(
+		&mut self,
+		metrics: &HonzonMetrics,
+		storage: &S,
+		worker: &'static str,
+		key: &[u8],
+	) {
+		let status = match storage.get(sp_offchain::STORAGE_PREFIX, key) {
+			Some(raw) => match OffchainWorkerStatus::<BlockNumber, Scope>::decode(&mut &raw[..]) {
+				Ok(status) => status,
+				Err(e) => {
+					warn!("Unable to decode the status of {} offchain worker: {:?}", worker, e);
+					return;
+				}
+			},
+			None => return,
+		};
+		if self.0.insert(worker, status.block_number) != Some(status.block_number) {
+			metrics.observe_worker_status(worker, &status);
+		}
+	}
+}
+
+/// Update the metrics on every new best block imported by `client`.
+pub async fn run<B, C, S>(metrics: HonzonMetrics, client: Arc<C>, offchain_storage: Option<S>)
+where
+	B: Backend<Block>,
+	C: BlockchainEvents<Block> + StorageProvider<Block, B> + Send + Sync,
+	S: OffchainStorage,
+{
+	let events_key = events_key();
+	let mut observed_runs = ObservedRuns::default();
+
+	client
+		.import_notification_stream()
+		.filter(|notification| future::ready(notification.is_new_best))
+		.for_each(|notification| {
+			match client.storage(&BlockId::Hash(notification.hash), &events_key) {
+				Ok(Some(raw)) => match Vec::<EventRecord>::decode(&mut &raw.0[..]) {
+					Ok(events) => metrics.observe_events(events),
+					Err(e) => warn!("Unable to decode the events of block {}: {:?}", notification.hash, e),
+				},
+				Ok(None) => {}
+				Err(e) => warn!("Unable to read the events of block {}: {:?}", notification.hash, e),
+			}
+
+			// the offchain workers run after import, the status of the last run is observed here
+			if let Some(storage) = &offchain_storage {
+				observed_runs.observe::<_, CurrencyId>(
+					&metrics,
+					storage,
+					"cdp_engine",
+					module_cdp_engine::OFFCHAIN_WORKER_STATUS_KEY,
+				);
+				observed_runs.observe::<_, AuctionType>(
+					&metrics,
+					storage,
+					"auction_manager",
+					module_auction_manager::OFFCHAIN_WORKER_STATUS_KEY,
+				);
+			}
+
+			future::ready(())
+		})
+		.await
+}
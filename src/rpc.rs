@@ -5,13 +5,14 @@ use std::{fmt, sync::Arc};
 use runtime::{
 	opaque::Block, AccountId, Balance, BlockNumber, CurrencyId, Hash, Index, TimeStampedPrice, UncheckedExtrinsic,
 };
+use sc_client_api::BlockchainEvents;
 use sc_consensus_babe::{Config, Epoch};
 use sc_consensus_babe_rpc::BabeRpcHandler;
 use sc_consensus_epochs::SharedEpochChanges;
 use sc_finality_grandpa::{SharedAuthoritySet, SharedVoterState};
 use sc_finality_grandpa_rpc::GrandpaRpcHandler;
 use sc_keystore::KeyStorePtr;
-use sc_rpc_api::DenyUnsafe;
+use sc_rpc_api::{DenyUnsafe, Metadata, Subscriptions};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_consensus::SelectChain;
@@ -50,9 +51,11 @@ pub struct GrandpaDeps {
 }
 
 /// Full client dependencies.
-pub struct FullDeps<C, P, SC> {
+pub struct FullDeps<C, P, SC, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend instance to use.
+	pub backend: Arc<B>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// The SelectChain Strategy
@@ -63,26 +66,38 @@ pub struct FullDeps<C, P, SC> {
 	pub babe: BabeDeps,
 	/// GRANDPA specific dependencies.
 	pub grandpa: GrandpaDeps,
+	/// Manager of RPC subscriptions.
+	pub subscriptions: Subscriptions,
 }
 
 /// Instantiate all Full RPC extensions.
-pub fn create_full<C, P, M, SC>(deps: FullDeps<C, P, SC>) -> jsonrpc_core::IoHandler<M>
+pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> jsonrpc_core::IoHandler<Metadata>
 where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
+	C: BlockchainEvents<Block>,
 	C: Send + Sync + 'static,
 	C::Api: AccountNonceApi<Block, AccountId, Index>,
 	C::Api: BabeApi<Block>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance, UncheckedExtrinsic>,
 	C::Api: orml_oracle_rpc::OracleRuntimeApi<Block, CurrencyId, TimeStampedPrice>,
 	C::Api: module_dex_rpc::DexRuntimeApi<Block, CurrencyId, Balance>,
+	C::Api: module_emergency_shutdown_rpc::EmergencyShutdownRuntimeApi<Block>,
+	C::Api: module_honzon_rpc::HonzonRuntimeApi<Block, AccountId, CurrencyId, Balance>,
+	C::Api: module_prices_rpc::PricesRuntimeApi<Block, CurrencyId, BlockNumber>,
 	C::Api: module_staking_pool_rpc::StakingPoolRuntimeApi<Block, AccountId, Balance>,
 	<C::Api as sp_api::ApiErrorExt>::Error: fmt::Debug,
 	P: TransactionPool + 'static,
-	M: jsonrpc_core::Metadata + Default,
 	SC: SelectChain<Block> + 'static,
+	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+	B::OffchainStorage: 'static,
 {
 	use module_dex_rpc::{Dex, DexApi};
+	use module_emergency_shutdown_rpc::{EmergencyShutdown, EmergencyShutdownApi};
+	use module_honzon_rpc::{
+		Honzon, HonzonApi, HonzonRiskAlert, HonzonRiskAlertApi, OffchainWorkerStatusApi, OffchainWorkers,
+	};
+	use module_prices_rpc::{Prices, PricesApi};
 	use module_staking_pool_rpc::{StakingPool, StakingPoolApi};
 	use orml_oracle_rpc::{Oracle, OracleApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
@@ -91,11 +106,13 @@ where
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
+		backend,
 		pool,
 		select_chain,
 		deny_unsafe,
 		babe,
 		grandpa,
+		subscriptions,
 	} = deps;
 	let BabeDeps {
 		keystore,
@@ -124,6 +141,19 @@ where
 	)));
 	io.extend_with(OracleApi::to_delegate(Oracle::new(client.clone())));
 	io.extend_with(DexApi::to_delegate(Dex::new(client.clone())));
+	io.extend_with(EmergencyShutdownApi::to_delegate(EmergencyShutdown::new(client.clone())));
+	io.extend_with(HonzonApi::to_delegate(Honzon::new(client.clone())));
+	io.extend_with(HonzonRiskAlertApi::to_delegate(HonzonRiskAlert::new(
+		client.clone(),
+		subscriptions,
+		deny_unsafe,
+	)));
+	if let Some(offchain_storage) = backend.offchain_storage() {
+		io.extend_with(OffchainWorkerStatusApi::<BlockNumber, CurrencyId>::to_delegate(
+			OffchainWorkers::new(offchain_storage, deny_unsafe),
+		));
+	}
+	io.extend_with(PricesApi::to_delegate(Prices::new(client.clone())));
 	io.extend_with(StakingPoolApi::to_delegate(StakingPool::new(client)));
 
 	io
@@ -90,16 +90,19 @@ macro_rules! new_full_start {
 			let shared_epoch_changes = babe_link.epoch_changes().clone();
 
 			let client = builder.client().clone();
+			let backend = builder.backend().clone();
 			let pool = builder.pool().clone();
 			let select_chain = builder
 				.select_chain()
 				.cloned()
 				.expect("SelectChain is present for full services or set up failed; qed.");
 			let keystore = builder.keystore().clone();
+			let subscriptions = sc_rpc::Subscriptions::new(Arc::new(builder.spawn_handle()));
 
 			Ok(move |deny_unsafe| {
 				let deps = crate::rpc::FullDeps {
 					client: client.clone(),
+					backend: backend.clone(),
 					pool: pool.clone(),
 					select_chain: select_chain.clone(),
 					deny_unsafe,
@@ -112,6 +115,7 @@ macro_rules! new_full_start {
 						shared_voter_state: shared_voter_state.clone(),
 						shared_authority_set: shared_authority_set.clone(),
 					},
+					subscriptions: subscriptions.clone(),
 				};
 
 				crate::rpc::create_full(deps)
@@ -128,7 +132,7 @@ macro_rules! new_full_start {
 /// concrete types instead.
 macro_rules! new_full {
 	($config:expr, $with_startup_data: expr) => {{
-		use sc_client_api::ExecutorProvider;
+		use sc_client_api::{Backend, ExecutorProvider};
 
 		let (role, force_authoring, name, disable_grandpa) = (
 			$config.role.clone(),
@@ -138,6 +142,7 @@ macro_rules! new_full {
 			);
 
 		let (builder, mut import_setup, inherent_data_providers, mut rpc_setup) = new_full_start!($config);
+		let offchain_storage = builder.backend().offchain_storage();
 
 		let service = builder
 			.with_finality_proof_provider(|client, backend| {
@@ -155,6 +160,15 @@ macro_rules! new_full {
 			.take()
 			.expect("The SharedVoterState is present for Full Services or setup failed before. qed");
 
+		if let Some(registry) = service.prometheus_registry() {
+			let metrics = crate::metrics::HonzonMetrics::register(&registry)
+				.map_err(|e| sc_service::Error::Other(format!("Failed to register Honzon metrics: {}", e)))?;
+			service.spawn_task(
+				"honzon-metrics",
+				crate::metrics::run(metrics, service.client(), offchain_storage),
+			);
+		}
+
 		let with_startup_data_closure = $with_startup_data;
 		with_startup_data_closure(&block_import, &babe_link);
 
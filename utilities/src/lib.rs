@@ -1,11 +1,21 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+pub mod offchain_iterator;
 pub mod offchain_lock;
+pub mod offchain_status;
 
+pub use offchain_iterator::OffchainStorageIterator;
 pub use offchain_lock::{LockItem, OffchainLock};
+pub use offchain_status::OffchainWorkerStatus;
 
 /// Error which may occur while executing the off-chain code.
-#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(any(test, feature = "std"), derive(PartialEq, Eq))]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy)]
 pub enum OffchainErr {
 	OffchainStore,
 	SubmitTransaction,
@@ -0,0 +1,62 @@
+use codec::Decode;
+use rstd::prelude::Vec;
+use sp_runtime::offchain::storage::StorageValueRef;
+
+/// Resumable iteration over the storage items under a prefix for offchain workers.
+///
+/// Each run iterates at most `budget` items and persists the last iterated key as
+/// cursor in offchain persistent storage, the next run resumes after the cursor.
+/// The cursor is cleared once the end of the prefix is reached.
+pub struct OffchainStorageIterator {
+	prefix: Vec<u8>,
+	cursor_key: Vec<u8>,
+	budget: u32,
+}
+
+impl OffchainStorageIterator {
+	pub fn new(prefix: Vec<u8>, cursor_key: Vec<u8>, budget: u32) -> Self {
+		OffchainStorageIterator {
+			prefix,
+			cursor_key,
+			budget,
+		}
+	}
+
+	/// Call `f` with the key suffix after the prefix and the decoded value of each item
+	/// following the cursor, until `budget` items are iterated or the end is reached.
+	/// Items whose value fails to decode are skipped, but still count toward the budget.
+	///
+	/// Returns the count of iterated items.
+	pub fn iterate<V, F>(&self, mut f: F) -> u32
+	where
+		V: Decode,
+		F: FnMut(&[u8], V),
+	{
+		let mut cursor = StorageValueRef::persistent(&self.cursor_key);
+		let mut last_key = cursor
+			.get::<Vec<u8>>()
+			.flatten()
+			.filter(|key| key.starts_with(&self.prefix))
+			.unwrap_or_else(|| self.prefix.clone());
+		let mut iterated = 0u32;
+
+		while iterated < self.budget {
+			match runtime_io::storage::next_key(&last_key).filter(|key| key.starts_with(&self.prefix)) {
+				Some(key) => {
+					if let Some(value) = runtime_io::storage::get(&key).and_then(|raw| V::decode(&mut &raw[..]).ok()) {
+						f(&key[self.prefix.len()..], value);
+					}
+					iterated += 1;
+					last_key = key;
+				}
+				None => {
+					cursor.clear();
+					return iterated;
+				}
+			}
+		}
+
+		cursor.set(&last_key);
+		iterated
+	}
+}
@@ -25,6 +25,15 @@ impl OffchainLock {
 		OffchainLock { key }
 	}
 
+	/// The lock of `shard` under `prefix`, the locks of different shards are independent
+	/// so that workers can hold them concurrently.
+	pub fn new_sharded<K: Encode>(prefix: &[u8], shard: &K) -> Self {
+		let mut key = prefix.to_vec();
+		key.extend_from_slice(b"lock/");
+		shard.using_encoded(|encoded| key.extend_from_slice(encoded));
+		OffchainLock { key }
+	}
+
 	#[allow(clippy::option_option)] // the substrate offchain worker api requires an option option pattern
 	pub fn acquire_offchain_lock<T, F>(&self, f: F) -> Result<LockItem<T>, OffchainErr>
 	where
@@ -37,7 +46,7 @@ impl OffchainLock {
 				expire_timestamp: runtime_io::offchain::timestamp().add(Duration::from_millis(LOCK_EXPIRE_DURATION)),
 				extra_data: f(None),
 			}),
-			Some(Some(item)) => Ok(LockItem {
+			Some(Some(item)) if item.expire_timestamp <= runtime_io::offchain::timestamp() => Ok(LockItem {
 				expire_timestamp: runtime_io::offchain::timestamp().add(Duration::from_millis(LOCK_EXPIRE_DURATION)),
 				extra_data: f(Some(item.extra_data)),
 			}),
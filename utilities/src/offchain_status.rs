@@ -0,0 +1,40 @@
+use super::*;
+use sp_runtime::{offchain::storage::StorageValueRef, RuntimeDebug};
+
+/// The status of an offchain worker run, recorded in offchain persistent storage
+/// so that operators can monitor whether the worker is functioning.
+#[cfg_attr(feature = "std", derive(PartialEq, Eq, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[derive(Encode, Decode, Clone, RuntimeDebug)]
+pub struct OffchainWorkerStatus<BlockNumber, Scope> {
+	/// The block number at which the worker ran
+	pub block_number: BlockNumber,
+	/// What the worker processed in this run, e.g. the collateral type
+	pub scope: Option<Scope>,
+	/// The count of items scanned
+	pub scanned: u32,
+	/// The count of transactions submitted
+	pub submitted: u32,
+	/// The count of transactions failed to submit
+	pub failed: u32,
+	/// The error which stopped the worker, `None` if the run completed
+	pub error: Option<OffchainErr>,
+}
+
+impl<BlockNumber: Encode, Scope: Encode> OffchainWorkerStatus<BlockNumber, Scope> {
+	pub fn new(block_number: BlockNumber) -> Self {
+		OffchainWorkerStatus {
+			block_number,
+			scope: None,
+			scanned: 0,
+			submitted: 0,
+			failed: 0,
+			error: None,
+		}
+	}
+
+	/// Record the status under `key` of offchain persistent storage.
+	pub fn record(&self, key: &[u8]) {
+		StorageValueRef::persistent(key).set(self);
+	}
+}